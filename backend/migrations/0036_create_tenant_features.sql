@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS features (
+  code TEXT PRIMARY KEY,
+  label TEXT NOT NULL,
+  default_enabled BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+INSERT INTO features (code, label, default_enabled) VALUES
+  ('financial', 'Financeiro', TRUE),
+  ('pix', 'Cobrança via PIX', TRUE),
+  ('public_reports', 'Boletins públicos para responsáveis', TRUE),
+  ('whatsapp', 'Notificações via WhatsApp', FALSE)
+ON CONFLICT (code) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS tenant_features (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  feature_code TEXT NOT NULL REFERENCES features(code),
+  enabled BOOLEAN NOT NULL,
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (tenant_id, feature_code)
+);
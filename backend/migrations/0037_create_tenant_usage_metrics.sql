@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS login_events (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_events_tenant_created
+  ON login_events (tenant_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS tenant_usage_snapshots (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  snapshot_date DATE NOT NULL,
+  active_students INT NOT NULL DEFAULT 0,
+  classes_total INT NOT NULL DEFAULT 0,
+  users_total INT NOT NULL DEFAULT 0,
+  logins_30d INT NOT NULL DEFAULT 0,
+  attendance_records_30d INT NOT NULL DEFAULT 0,
+  grade_entries_30d INT NOT NULL DEFAULT 0,
+  contracts_total INT NOT NULL DEFAULT 0,
+  receivables_amount_30d NUMERIC(14, 2) NOT NULL DEFAULT 0,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (tenant_id, snapshot_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_usage_snapshots_date
+  ON tenant_usage_snapshots (snapshot_date DESC);
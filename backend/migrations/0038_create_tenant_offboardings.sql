@@ -0,0 +1,29 @@
+-- Sem FK para tenants: o registro precisa sobreviver à exclusão definitiva da escola.
+CREATE TABLE IF NOT EXISTS tenant_offboardings (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL,
+  tenant_name TEXT NOT NULL,
+  tenant_slug TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'scheduled'
+    CHECK (status IN ('scheduled', 'cancelled', 'deleted', 'failed')),
+  export_file TEXT NOT NULL,
+  export_sha256 TEXT NOT NULL,
+  manifest JSONB NOT NULL,
+  delete_after TIMESTAMP NOT NULL,
+  requested_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  deleted_at TIMESTAMP NULL,
+  deleted_row_counts JSONB NULL,
+  deletion_verified BOOLEAN NULL,
+  error_message TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_offboardings_tenant
+  ON tenant_offboardings (tenant_id, requested_at DESC);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tenant_offboardings_one_scheduled
+  ON tenant_offboardings (tenant_id)
+  WHERE status = 'scheduled';
+
+CREATE INDEX IF NOT EXISTS idx_tenant_offboardings_due
+  ON tenant_offboardings (delete_after)
+  WHERE status = 'scheduled';
@@ -0,0 +1,43 @@
+-- Identidade global: um e-mail + senha (+ 2FA) com vínculo (users) em várias escolas.
+-- O índice global de e-mail em users nunca chegou a valer; o vínculo continua UNIQUE (tenant_id, email).
+DROP INDEX IF EXISTS users_email_unique;
+
+CREATE TABLE IF NOT EXISTS identities (
+  id UUID PRIMARY KEY,
+  email TEXT NOT NULL,
+  password_hash TEXT NOT NULL,
+  totp_secret TEXT NULL,
+  totp_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_identities_email
+  ON identities (lower(email));
+
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS identity_id UUID NULL REFERENCES identities(id) ON DELETE RESTRICT;
+
+-- Uma identidade por e-mail; quando o mesmo e-mail existe em várias escolas,
+-- vale a senha do vínculo mais recente.
+INSERT INTO identities (id, email, password_hash, created_at)
+SELECT DISTINCT ON (lower(u.email))
+  u.id, lower(u.email), u.password_hash, u.created_at
+FROM users u
+ORDER BY lower(u.email), u.created_at DESC
+ON CONFLICT DO NOTHING;
+
+UPDATE users u
+SET identity_id = i.id
+FROM identities i
+WHERE u.identity_id IS NULL
+  AND lower(u.email) = lower(i.email);
+
+ALTER TABLE users ALTER COLUMN identity_id SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_identity_tenant
+  ON users (identity_id, tenant_id);
+
+-- A senha passa a morar na identidade. O hash antigo de cada vínculo fica em
+-- users.password_hash como alternativa até o primeiro login bem-sucedido, que o
+-- migra para a identidade e limpa os demais (ver check_identity_credentials).
+ALTER TABLE users ALTER COLUMN password_hash DROP NOT NULL;
@@ -0,0 +1,33 @@
+-- SSO por escola via OpenID Connect (Google Workspace, Microsoft 365, ...)
+CREATE TABLE IF NOT EXISTS tenant_sso_configs (
+  tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+  issuer TEXT NOT NULL,
+  client_id TEXT NOT NULL,
+  client_secret TEXT NOT NULL,
+  redirect_uri TEXT NOT NULL,
+  allowed_domains TEXT[] NOT NULL DEFAULT '{}',
+  enabled BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Estado do fluxo authorization code + PKCE entre o redirect e o callback
+CREATE TABLE IF NOT EXISTS sso_login_states (
+  state TEXT PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  code_verifier TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sso_login_states_expires_at
+  ON sso_login_states (expires_at);
+
+-- Vínculo estável com a conta do provedor (claim `sub`), preenchido no primeiro login SSO
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS sso_subject TEXT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_tenant_sso_subject
+  ON users (tenant_id, sso_subject)
+  WHERE sso_subject IS NOT NULL;
@@ -0,0 +1,51 @@
+-- Avaliações (provas, trabalhos, participação) por turma, disciplina e período
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS grade_formula TEXT NOT NULL DEFAULT 'weighted';
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'tenants_grade_formula_check'
+  ) THEN
+    ALTER TABLE tenants
+      ADD CONSTRAINT tenants_grade_formula_check
+      CHECK (grade_formula IN ('weighted', 'arithmetic'));
+  END IF;
+END
+$$;
+
+CREATE TABLE IF NOT EXISTS assessments (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE RESTRICT,
+  term_id UUID NOT NULL REFERENCES academic_terms(id) ON DELETE RESTRICT,
+  name TEXT NOT NULL,
+  assessment_date DATE NULL,
+  weight NUMERIC(6,2) NOT NULL DEFAULT 1 CHECK (weight > 0),
+  max_score NUMERIC(6,2) NOT NULL DEFAULT 10 CHECK (max_score > 0),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_assessments_class_term_subject
+  ON assessments (tenant_id, class_id, term_id, subject_id);
+
+CREATE TABLE IF NOT EXISTS assessment_scores (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  assessment_id UUID NOT NULL REFERENCES assessments(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  score NUMERIC(6,2) NOT NULL CHECK (score >= 0),
+  comments TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (assessment_id, student_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_assessment_scores_student
+  ON assessment_scores (tenant_id, student_id);
+
+-- A nota do período passa a ser derivada das avaliações (fica nula enquanto não houver nenhuma).
+ALTER TABLE student_grades ALTER COLUMN score DROP NOT NULL;
@@ -0,0 +1,33 @@
+-- Recuperação paralela por período e prova final
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS recovery_rule TEXT NOT NULL DEFAULT 'max',
+  ADD COLUMN IF NOT EXISTS final_exam_min_grade NUMERIC(5,2) NOT NULL DEFAULT 5.00;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'tenants_recovery_rule_check'
+  ) THEN
+    ALTER TABLE tenants
+      ADD CONSTRAINT tenants_recovery_rule_check
+      CHECK (recovery_rule IN ('max', 'average', 'replace'));
+  END IF;
+END
+$$;
+
+ALTER TABLE student_grades
+  ADD COLUMN IF NOT EXISTS recovery_score NUMERIC(5,2) NULL;
+
+CREATE TABLE IF NOT EXISTS student_final_exams (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE RESTRICT,
+  score NUMERIC(5,2) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, class_id, student_id, subject_id)
+);
@@ -0,0 +1,26 @@
+-- Frequência mínima para aprovação (LDB art. 24, VI: 75%), configurável por escola
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS min_attendance_percent NUMERIC(5,2) NOT NULL DEFAULT 75.00;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'tenants_min_attendance_percent_check'
+  ) THEN
+    ALTER TABLE tenants
+      ADD CONSTRAINT tenants_min_attendance_percent_check
+      CHECK (min_attendance_percent >= 0 AND min_attendance_percent <= 100);
+  END IF;
+END
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_attendance_student_subject
+  ON student_attendance (tenant_id, class_id, student_id, subject_id);
+
+-- A 0019 tentou remover a unicidade por dia pelo nome completo, mas o Postgres
+-- trunca identificadores em 63 caracteres; sem isto a chamada de uma segunda
+-- disciplina no mesmo dia viola a constraint antiga.
+ALTER TABLE student_attendance
+  DROP CONSTRAINT IF EXISTS student_attendance_tenant_id_class_id_student_id_attendance_key;
@@ -0,0 +1,31 @@
+-- Escalas de avaliação por série: numérica (faixa/casas decimais) ou conceitual
+CREATE TABLE IF NOT EXISTS grading_scales (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  name TEXT NOT NULL,
+  scale_type TEXT NOT NULL CHECK (scale_type IN ('numeric', 'conceptual')),
+  min_value NUMERIC(5,2) NOT NULL,
+  max_value NUMERIC(5,2) NOT NULL,
+  decimals INT NOT NULL CHECK (decimals BETWEEN 0 AND 2),
+  passing_value NUMERIC(5,2) NOT NULL,
+  -- conceitual: do melhor para o pior; o melhor vale N-1 e o pior 0
+  concepts TEXT[] NOT NULL DEFAULT '{}',
+  passing_concept TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, name),
+  CHECK (min_value < max_value),
+  CHECK (passing_value BETWEEN min_value AND max_value)
+);
+
+CREATE TABLE IF NOT EXISTS grading_scale_assignments (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  -- mesmo texto de classes.grade (ex.: "1 ano", "Infantil 4")
+  class_grade TEXT NOT NULL,
+  scale_id UUID NOT NULL REFERENCES grading_scales(id) ON DELETE RESTRICT,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (tenant_id, class_grade)
+);
+
+CREATE INDEX IF NOT EXISTS idx_grading_scale_assignments_scale
+  ON grading_scale_assignments (scale_id);
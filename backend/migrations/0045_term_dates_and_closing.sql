@@ -0,0 +1,40 @@
+-- Datas do período e encerramento (trava diário e chamada)
+ALTER TABLE academic_terms
+  ADD COLUMN IF NOT EXISTS start_date DATE NULL,
+  ADD COLUMN IF NOT EXISTS end_date DATE NULL,
+  ADD COLUMN IF NOT EXISTS closed_at TIMESTAMP NULL,
+  ADD COLUMN IF NOT EXISTS closed_by UUID NULL;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'academic_terms_dates_check'
+  ) THEN
+    ALTER TABLE academic_terms
+      ADD CONSTRAINT academic_terms_dates_check
+      CHECK (start_date IS NULL OR end_date IS NULL OR start_date <= end_date);
+  END IF;
+END
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_terms_tenant_dates
+  ON academic_terms (tenant_id, start_date, end_date);
+
+-- Correções justificadas em períodos encerrados (e reaberturas)
+CREATE TABLE IF NOT EXISTS term_corrections (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  term_id UUID NOT NULL REFERENCES academic_terms(id) ON DELETE CASCADE,
+  class_id UUID NULL REFERENCES classes(id) ON DELETE CASCADE,
+  -- sem FK: o registro da correção sobrevive à remoção do usuário
+  user_id UUID NULL,
+  kind TEXT NOT NULL,
+  justification TEXT NOT NULL,
+  payload JSONB NOT NULL DEFAULT '{}'::jsonb,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_term_corrections_term
+  ON term_corrections (tenant_id, term_id, created_at DESC);
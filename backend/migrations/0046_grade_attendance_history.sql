@@ -0,0 +1,107 @@
+-- Histórico de notas e chamadas: toda inclusão/alteração vira uma linha com valor
+-- anterior, valor novo, autor e horário. Triggers garantem que nenhum caminho de
+-- escrita (diário, recuperação, avaliações) fique de fora; o autor vem de
+-- `app.user_id`, definido pela API na transação.
+CREATE TABLE IF NOT EXISTS student_grade_history (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  grade_id UUID NOT NULL,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  term_id UUID NULL,
+  subject_id UUID NULL,
+  operation TEXT NOT NULL CHECK (operation IN ('insert', 'update')),
+  old_score NUMERIC(5,2) NULL,
+  new_score NUMERIC(5,2) NULL,
+  old_recovery_score NUMERIC(5,2) NULL,
+  new_recovery_score NUMERIC(5,2) NULL,
+  old_absences INT NULL,
+  new_absences INT NULL,
+  old_comments TEXT NULL,
+  new_comments TEXT NULL,
+  -- sem FK: o histórico sobrevive à remoção do usuário
+  changed_by UUID NULL,
+  changed_at TIMESTAMP NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX IF NOT EXISTS idx_grade_history_student
+  ON student_grade_history (tenant_id, student_id, term_id, subject_id, changed_at);
+
+CREATE TABLE IF NOT EXISTS student_attendance_history (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  attendance_id UUID NOT NULL,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  subject_id UUID NULL,
+  attendance_date DATE NOT NULL,
+  operation TEXT NOT NULL CHECK (operation IN ('insert', 'update')),
+  old_present BOOLEAN NULL,
+  new_present BOOLEAN NULL,
+  old_notes TEXT NULL,
+  new_notes TEXT NULL,
+  changed_by UUID NULL,
+  changed_at TIMESTAMP NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX IF NOT EXISTS idx_attendance_history_student
+  ON student_attendance_history (tenant_id, student_id, subject_id, attendance_date);
+
+CREATE OR REPLACE FUNCTION history_change_author() RETURNS UUID AS $$
+  SELECT NULLIF(current_setting('app.user_id', true), '')::uuid
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION log_student_grade_change() RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'UPDATE'
+     AND OLD.score IS NOT DISTINCT FROM NEW.score
+     AND OLD.recovery_score IS NOT DISTINCT FROM NEW.recovery_score
+     AND OLD.absences IS NOT DISTINCT FROM NEW.absences
+     AND OLD.comments IS NOT DISTINCT FROM NEW.comments THEN
+    RETURN NEW;
+  END IF;
+
+  INSERT INTO student_grade_history
+    (tenant_id, grade_id, class_id, student_id, term_id, subject_id, operation,
+     old_score, new_score, old_recovery_score, new_recovery_score,
+     old_absences, new_absences, old_comments, new_comments, changed_by)
+  VALUES
+    (NEW.tenant_id, NEW.id, NEW.class_id, NEW.student_id, NEW.term_id, NEW.subject_id, lower(TG_OP),
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.score END, NEW.score,
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.recovery_score END, NEW.recovery_score,
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.absences END, NEW.absences,
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.comments END, NEW.comments,
+     history_change_author());
+  RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION log_student_attendance_change() RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'UPDATE'
+     AND OLD.present IS NOT DISTINCT FROM NEW.present
+     AND OLD.notes IS NOT DISTINCT FROM NEW.notes THEN
+    RETURN NEW;
+  END IF;
+
+  INSERT INTO student_attendance_history
+    (tenant_id, attendance_id, class_id, student_id, subject_id, attendance_date, operation,
+     old_present, new_present, old_notes, new_notes, changed_by)
+  VALUES
+    (NEW.tenant_id, NEW.id, NEW.class_id, NEW.student_id, NEW.subject_id, NEW.attendance_date, lower(TG_OP),
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.present END, NEW.present,
+     CASE WHEN TG_OP = 'UPDATE' THEN OLD.notes END, NEW.notes,
+     history_change_author());
+  RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_student_grades_history ON student_grades;
+CREATE TRIGGER trg_student_grades_history
+  AFTER INSERT OR UPDATE ON student_grades
+  FOR EACH ROW EXECUTE FUNCTION log_student_grade_change();
+
+DROP TRIGGER IF EXISTS trg_student_attendance_history ON student_attendance;
+CREATE TRIGGER trg_student_attendance_history
+  AFTER INSERT OR UPDATE ON student_attendance
+  FOR EACH ROW EXECUTE FUNCTION log_student_attendance_change();
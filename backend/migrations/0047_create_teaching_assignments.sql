@@ -0,0 +1,24 @@
+-- Atribuição de aulas: professor × turma × disciplina × ano letivo.
+-- Professores só lançam e consultam notas/chamadas das turmas e disciplinas atribuídas.
+CREATE TABLE IF NOT EXISTS teaching_assignments (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  teacher_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  school_year INT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, teacher_user_id, class_id, subject_id, school_year)
+);
+
+CREATE INDEX IF NOT EXISTS idx_teaching_assignments_class
+  ON teaching_assignments (tenant_id, class_id, subject_id);
+
+-- Mantém o acesso atual: o professor titular da disciplina (subjects.teacher_user_id)
+-- fica atribuído a ela em todas as turmas da escola.
+INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year)
+SELECT gen_random_uuid(), s.tenant_id, s.teacher_user_id, c.id, s.id, c.year
+FROM subjects s
+JOIN users u ON u.id = s.teacher_user_id AND u.tenant_id = s.tenant_id AND u.role = 'teacher'
+JOIN classes c ON c.tenant_id = s.tenant_id
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,62 @@
+-- Diário de classe: cada aula (turma, disciplina, data, número da aula no dia) com
+-- conteúdo ministrado e tarefa; a chamada fica presa à aula.
+CREATE TABLE IF NOT EXISTS lessons (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE RESTRICT,
+  lesson_date DATE NOT NULL,
+  lesson_number INT NOT NULL DEFAULT 1 CHECK (lesson_number BETWEEN 1 AND 20),
+  teacher_user_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  content TEXT NULL,
+  homework TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, class_id, subject_id, lesson_date, lesson_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lessons_class_subject_date
+  ON lessons (tenant_id, class_id, subject_id, lesson_date);
+
+ALTER TABLE student_attendance
+  ADD COLUMN IF NOT EXISTS lesson_number INT NOT NULL DEFAULT 1,
+  ADD COLUMN IF NOT EXISTS lesson_id UUID NULL REFERENCES lessons(id) ON DELETE CASCADE;
+
+-- Duas aulas da mesma disciplina no dia: a chamada passa a ser única por aula
+ALTER TABLE student_attendance
+  DROP CONSTRAINT IF EXISTS student_attendance_unique_per_subject;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'student_attendance_unique_per_lesson'
+  ) THEN
+    ALTER TABLE student_attendance
+      ADD CONSTRAINT student_attendance_unique_per_lesson
+      UNIQUE (tenant_id, class_id, student_id, attendance_date, subject_id, lesson_number);
+  END IF;
+END
+$$;
+
+-- Chamadas já lançadas ganham a aula correspondente (aula 1 do dia)
+INSERT INTO lessons (id, tenant_id, class_id, subject_id, lesson_date, lesson_number)
+SELECT gen_random_uuid(), a.tenant_id, a.class_id, a.subject_id, a.attendance_date, a.lesson_number
+FROM student_attendance a
+WHERE a.subject_id IS NOT NULL AND a.lesson_id IS NULL
+GROUP BY a.tenant_id, a.class_id, a.subject_id, a.attendance_date, a.lesson_number
+ON CONFLICT DO NOTHING;
+
+UPDATE student_attendance a
+SET lesson_id = l.id
+FROM lessons l
+WHERE a.lesson_id IS NULL
+  AND l.tenant_id = a.tenant_id
+  AND l.class_id = a.class_id
+  AND l.subject_id = a.subject_id
+  AND l.lesson_date = a.attendance_date
+  AND l.lesson_number = a.lesson_number;
+
+CREATE INDEX IF NOT EXISTS idx_attendance_lesson
+  ON student_attendance (lesson_id);
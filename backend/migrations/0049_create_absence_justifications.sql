@@ -0,0 +1,77 @@
+-- Justificativas de falta (atestados etc.) por aluno e intervalo de datas, com
+-- documento opcional e aprovação da secretaria.
+CREATE TABLE IF NOT EXISTS absence_justifications (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  start_date DATE NOT NULL,
+  end_date DATE NOT NULL,
+  category TEXT NOT NULL CHECK (category IN ('medical', 'legal', 'bereavement', 'religious', 'other')),
+  reason TEXT NULL,
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+  review_notes TEXT NULL,
+  -- sem FK: o registro sobrevive à remoção do usuário
+  submitted_by UUID NULL,
+  reviewed_by UUID NULL,
+  reviewed_at TIMESTAMP NULL,
+  document_path TEXT NULL,
+  document_name TEXT NULL,
+  document_content_type TEXT NULL,
+  document_size INT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK (start_date <= end_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_absence_justifications_student
+  ON absence_justifications (tenant_id, student_id, start_date, end_date);
+
+CREATE INDEX IF NOT EXISTS idx_absence_justifications_status
+  ON absence_justifications (tenant_id, status, created_at);
+
+-- Como a falta justificada entra na frequência:
+--   excluded: a aula sai do cálculo; present: conta como presença; absence: continua falta
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS justified_absence_rule TEXT NOT NULL DEFAULT 'excluded';
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'tenants_justified_absence_rule_check'
+  ) THEN
+    ALTER TABLE tenants
+      ADD CONSTRAINT tenants_justified_absence_rule_check
+      CHECK (justified_absence_rule IN ('excluded', 'present', 'absence'));
+  END IF;
+END
+$$;
+
+-- Chamada com a regra da escola aplicada: `present` já considera a justificativa e
+-- `counted` diz se a aula entra no total. Relatórios de frequência leem daqui.
+CREATE OR REPLACE VIEW student_attendance_effective AS
+SELECT
+  a.id,
+  a.tenant_id,
+  a.class_id,
+  a.student_id,
+  a.subject_id,
+  a.attendance_date,
+  a.lesson_number,
+  a.lesson_id,
+  a.present AS recorded_present,
+  (NOT a.present AND j.id IS NOT NULL) AS justified,
+  (a.present OR (j.id IS NOT NULL AND t.justified_absence_rule = 'present')) AS present,
+  NOT (NOT a.present AND j.id IS NOT NULL AND t.justified_absence_rule = 'excluded') AS counted
+FROM student_attendance a
+JOIN tenants t ON t.id = a.tenant_id
+LEFT JOIN LATERAL (
+  SELECT aj.id
+  FROM absence_justifications aj
+  WHERE aj.tenant_id = a.tenant_id
+    AND aj.student_id = a.student_id
+    AND aj.status = 'approved'
+    AND a.attendance_date BETWEEN aj.start_date AND aj.end_date
+  LIMIT 1
+) j ON TRUE;
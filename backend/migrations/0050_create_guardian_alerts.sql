@@ -0,0 +1,58 @@
+-- Alertas automáticos aos responsáveis (faltas e notas abaixo da média).
+-- Limiares por escola: cada falta (opcional), N dias seguidos de falta (0 desliga)
+-- e nota do período abaixo de `passing_min_grade`.
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS alert_each_absence BOOLEAN NOT NULL DEFAULT FALSE,
+  ADD COLUMN IF NOT EXISTS alert_consecutive_absences INT NOT NULL DEFAULT 3,
+  ADD COLUMN IF NOT EXISTS alert_low_grades BOOLEAN NOT NULL DEFAULT TRUE;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1
+    FROM pg_constraint
+    WHERE conname = 'tenants_alert_consecutive_absences_check'
+  ) THEN
+    ALTER TABLE tenants
+      ADD CONSTRAINT tenants_alert_consecutive_absences_check
+      CHECK (alert_consecutive_absences BETWEEN 0 AND 30);
+  END IF;
+END
+$$;
+
+-- Opt-out por responsável (pessoa), por tipo de alerta
+CREATE TABLE IF NOT EXISTS guardian_alert_preferences (
+  person_id UUID PRIMARY KEY REFERENCES people(id) ON DELETE CASCADE,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  absence_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+  grade_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Fila de envio (outbox): gravada na mesma transação do lançamento e entregue pelo job
+CREATE TABLE IF NOT EXISTS guardian_notifications (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  person_id UUID NOT NULL REFERENCES people(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL CHECK (kind IN ('absence', 'consecutive_absences', 'low_grade')),
+  channel TEXT NOT NULL DEFAULT 'email',
+  recipient TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  body TEXT NOT NULL,
+  -- evita alertar duas vezes o mesmo evento (ex.: chamada salva de novo)
+  dedupe_key TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed')),
+  attempts INT NOT NULL DEFAULT 0,
+  last_error TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  sent_at TIMESTAMP NULL,
+  UNIQUE (tenant_id, person_id, dedupe_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_guardian_notifications_pending
+  ON guardian_notifications (status, created_at)
+  WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_guardian_notifications_student
+  ON guardian_notifications (tenant_id, student_id, created_at DESC);
@@ -0,0 +1,35 @@
+-- Identidade visual dos boletins em PDF (cidade e assinatura já existem em tenants)
+ALTER TABLE tenants
+  ADD COLUMN IF NOT EXISTS report_header_text TEXT NULL,
+  ADD COLUMN IF NOT EXISTS report_brand_color TEXT NULL,
+  ADD COLUMN IF NOT EXISTS report_logo BYTEA NULL;
+
+-- Geração em lote (turma ou escola inteira), processada pelo job de boletins
+CREATE TABLE IF NOT EXISTS report_card_batches (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  requested_by UUID NULL,
+  kind TEXT NOT NULL CHECK (kind IN ('term', 'full')),
+  term_id UUID NULL REFERENCES academic_terms(id) ON DELETE CASCADE,
+  -- NULL: todas as turmas do ano letivo
+  class_id UUID NULL REFERENCES classes(id) ON DELETE CASCADE,
+  school_year INT NOT NULL,
+  format TEXT NOT NULL CHECK (format IN ('pdf', 'zip')),
+  status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'done', 'failed')),
+  total_students INT NOT NULL DEFAULT 0,
+  processed_students INT NOT NULL DEFAULT 0,
+  file_path TEXT NULL,
+  file_size BIGINT NULL,
+  error TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  started_at TIMESTAMP NULL,
+  finished_at TIMESTAMP NULL,
+  CHECK (kind = 'full' OR term_id IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_card_batches_tenant
+  ON report_card_batches (tenant_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_report_card_batches_queued
+  ON report_card_batches (created_at)
+  WHERE status = 'queued';
@@ -0,0 +1,106 @@
+-- Histórico escolar: matrículas do aluno em todas as turmas por onde passou, carga
+-- horária das disciplinas e registros lançados à mão de escolas anteriores.
+
+-- Carga horária anual da disciplina; `subject_grade_workloads` sobrepõe por série.
+ALTER TABLE subjects
+  ADD COLUMN IF NOT EXISTS workload_hours INT NULL CHECK (workload_hours IS NULL OR workload_hours > 0);
+
+CREATE TABLE IF NOT EXISTS subject_grade_workloads (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  grade TEXT NOT NULL,
+  workload_hours INT NOT NULL CHECK (workload_hours > 0),
+  PRIMARY KEY (subject_id, grade)
+);
+
+-- Uma linha por turma em que o aluno esteve matriculado; mantida por trigger para
+-- que nenhuma troca de turma (cadastro, edição, enturmação) fique de fora.
+CREATE TABLE IF NOT EXISTS student_enrollments (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  school_year INT NOT NULL,
+  started_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  ended_at TIMESTAMP NULL,
+  UNIQUE (tenant_id, student_id, class_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_student_enrollments_student
+  ON student_enrollments (tenant_id, student_id, school_year);
+
+CREATE OR REPLACE FUNCTION track_student_enrollment() RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'UPDATE' AND OLD.class_id IS NOT DISTINCT FROM NEW.class_id THEN
+    RETURN NEW;
+  END IF;
+
+  UPDATE student_enrollments
+  SET ended_at = NOW()
+  WHERE student_id = NEW.id
+    AND ended_at IS NULL
+    AND class_id IS DISTINCT FROM NEW.class_id;
+
+  IF NEW.class_id IS NOT NULL THEN
+    INSERT INTO student_enrollments (tenant_id, student_id, class_id, school_year)
+    SELECT NEW.tenant_id, NEW.id, c.id, c.year
+    FROM classes c
+    WHERE c.id = NEW.class_id
+    ON CONFLICT (tenant_id, student_id, class_id) DO UPDATE SET ended_at = NULL;
+  END IF;
+  RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_students_enrollment ON students;
+CREATE TRIGGER trg_students_enrollment
+  AFTER INSERT OR UPDATE OF class_id ON students
+  FOR EACH ROW EXECUTE FUNCTION track_student_enrollment();
+
+-- Matrículas anteriores à tabela: turma atual e turmas com notas ou chamadas.
+INSERT INTO student_enrollments (tenant_id, student_id, class_id, school_year, ended_at)
+SELECT x.tenant_id, x.student_id, x.class_id, c.year,
+       CASE WHEN s.class_id = x.class_id THEN NULL ELSE NOW() END
+FROM (
+  SELECT tenant_id, id AS student_id, class_id FROM students WHERE class_id IS NOT NULL
+  UNION
+  SELECT tenant_id, student_id, class_id FROM student_grades
+  UNION
+  SELECT tenant_id, student_id, class_id FROM student_attendance
+) x
+JOIN classes c ON c.id = x.class_id AND c.tenant_id = x.tenant_id
+JOIN students s ON s.id = x.student_id
+ON CONFLICT DO NOTHING;
+
+-- Anos cursados em outras escolas, transcritos da documentação de transferência.
+CREATE TABLE IF NOT EXISTS transcript_external_records (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  school_year INT NOT NULL,
+  grade TEXT NOT NULL,
+  school_name TEXT NOT NULL,
+  school_city TEXT NULL,
+  attendance_percent NUMERIC(5,2) NULL CHECK (attendance_percent IS NULL OR attendance_percent BETWEEN 0 AND 100),
+  final_status TEXT NOT NULL CHECK (final_status IN ('approved', 'failed_grade', 'failed_attendance')),
+  notes TEXT NULL,
+  created_by UUID NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_transcript_external_records_student
+  ON transcript_external_records (tenant_id, student_id, school_year);
+
+CREATE TABLE IF NOT EXISTS transcript_external_subjects (
+  id UUID PRIMARY KEY,
+  record_id UUID NOT NULL REFERENCES transcript_external_records(id) ON DELETE CASCADE,
+  subject_name TEXT NOT NULL,
+  workload_hours INT NULL CHECK (workload_hours IS NULL OR workload_hours > 0),
+  -- como consta no documento de origem (nota ou conceito)
+  final_grade TEXT NULL,
+  sort_order INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_transcript_external_subjects_record
+  ON transcript_external_subjects (record_id, sort_order);
@@ -0,0 +1,36 @@
+-- Links públicos de boletim para os responsáveis: token opaco aleatório (guardamos só
+-- o SHA-256), revogável, com registro de cada acesso. Substitui os JWTs sem estado;
+-- links emitidos antes desta migração deixam de valer.
+CREATE TABLE IF NOT EXISTS report_share_links (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  token_hash TEXT NOT NULL UNIQUE,
+  -- gradebook (uma disciplina num período) | term (boletim do período) | full (boletim anual)
+  report_type TEXT NOT NULL CHECK (report_type IN ('gradebook', 'term', 'full')),
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  term_id UUID NULL REFERENCES academic_terms(id) ON DELETE CASCADE,
+  subject_id UUID NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  created_by UUID NULL,
+  expires_at TIMESTAMP NOT NULL,
+  revoked BOOLEAN NOT NULL DEFAULT FALSE,
+  revoked_by UUID NULL,
+  revoked_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK (report_type = 'full' OR term_id IS NOT NULL),
+  CHECK (report_type <> 'gradebook' OR subject_id IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_share_links_student
+  ON report_share_links (tenant_id, student_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS report_share_link_accesses (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  link_id UUID NOT NULL REFERENCES report_share_links(id) ON DELETE CASCADE,
+  accessed_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  ip TEXT NULL,
+  user_agent TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_share_link_accesses_link
+  ON report_share_link_accesses (link_id, accessed_at DESC);
@@ -0,0 +1,40 @@
+-- Portal dos responsáveis: uma conta por e-mail em cada escola, cobrindo todas as
+-- pessoas (pai/mãe, responsável, responsável financeiro) cadastradas com esse e-mail.
+CREATE TABLE IF NOT EXISTS guardian_portal_accounts (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  -- sempre em minúsculas
+  email TEXT NOT NULL,
+  -- NULL enquanto o responsável só entra por link mágico
+  password_hash TEXT NULL,
+  is_active BOOLEAN NOT NULL DEFAULT TRUE,
+  last_login_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, email)
+);
+
+-- Links mágicos de acesso: uso único, guardamos só o SHA-256 do token.
+CREATE TABLE IF NOT EXISTS guardian_portal_magic_links (
+  id UUID PRIMARY KEY,
+  account_id UUID NOT NULL REFERENCES guardian_portal_accounts(id) ON DELETE CASCADE,
+  token_hash TEXT NOT NULL UNIQUE,
+  expires_at TIMESTAMP NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_guardian_portal_magic_links_account
+  ON guardian_portal_magic_links (account_id, created_at DESC);
+
+-- O link mágico sai pela mesma fila de e-mails dos alertas. Sem aluno associado, a
+-- mensagem não aparece na listagem da secretaria (que junta com `students`).
+ALTER TABLE guardian_notifications
+  ALTER COLUMN student_id DROP NOT NULL;
+
+ALTER TABLE guardian_notifications
+  DROP CONSTRAINT IF EXISTS guardian_notifications_kind_check;
+
+ALTER TABLE guardian_notifications
+  ADD CONSTRAINT guardian_notifications_kind_check
+  CHECK (kind IN ('absence', 'consecutive_absences', 'low_grade', 'portal_magic_link'));
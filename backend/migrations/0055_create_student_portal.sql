@@ -0,0 +1,16 @@
+-- Portal do aluno (ensino médio/fundamental II): acesso só de leitura aos próprios
+-- boletins, aulas e tarefas. Uma conta por aluno, liberada pela secretaria; o login
+-- é pela matrícula.
+CREATE TABLE IF NOT EXISTS student_portal_accounts (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  person_id UUID NOT NULL UNIQUE REFERENCES people(id) ON DELETE CASCADE,
+  password_hash TEXT NOT NULL,
+  is_active BOOLEAN NOT NULL DEFAULT TRUE,
+  last_login_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_student_portal_accounts_tenant
+  ON student_portal_accounts (tenant_id);
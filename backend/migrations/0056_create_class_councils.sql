@@ -0,0 +1,55 @@
+-- Conselho de classe: reunião por turma e período com os presentes e as decisões por
+-- aluno. A decisão prevalece sobre a situação calculada no boletim anual, e o ajuste
+-- de nota substitui a nota do período daquela disciplina.
+CREATE TABLE IF NOT EXISTS class_councils (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  term_id UUID NOT NULL REFERENCES academic_terms(id) ON DELETE RESTRICT,
+  held_on DATE NOT NULL,
+  notes TEXT NULL,
+  created_by UUID NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (tenant_id, class_id, term_id)
+);
+
+CREATE TABLE IF NOT EXISTS class_council_attendees (
+  id UUID PRIMARY KEY,
+  council_id UUID NOT NULL REFERENCES class_councils(id) ON DELETE CASCADE,
+  -- usuário do sistema, quando houver (professor, coordenação); convidados só pelo nome
+  user_id UUID NULL,
+  name TEXT NOT NULL,
+  role TEXT NULL,
+  sort_order INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_class_council_attendees_council
+  ON class_council_attendees (council_id, sort_order);
+
+-- `subject_id` nulo: decisão sobre a situação geral do aluno.
+CREATE TABLE IF NOT EXISTS class_council_decisions (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  council_id UUID NOT NULL REFERENCES class_councils(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  subject_id UUID NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  decision TEXT NOT NULL CHECK (decision IN ('approve', 'retain', 'recovery')),
+  justification TEXT NOT NULL,
+  -- nota do período após o conselho (só com disciplina)
+  adjusted_score NUMERIC(6,2) NULL,
+  decided_by UUID NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK (adjusted_score IS NULL OR subject_id IS NOT NULL)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_class_council_decisions_subject
+  ON class_council_decisions (council_id, student_id, subject_id)
+  WHERE subject_id IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_class_council_decisions_general
+  ON class_council_decisions (council_id, student_id)
+  WHERE subject_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_class_council_decisions_student
+  ON class_council_decisions (tenant_id, student_id);
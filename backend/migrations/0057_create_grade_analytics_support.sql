@@ -0,0 +1,21 @@
+-- Nota efetiva do período em SQL (mesma regra de grading::promotion::effective_term_score)
+CREATE OR REPLACE FUNCTION student_grade_effective_score(
+  score NUMERIC,
+  recovery_score NUMERIC,
+  recovery_rule TEXT
+) RETURNS NUMERIC
+LANGUAGE sql
+IMMUTABLE
+AS $$
+  SELECT CASE
+    WHEN recovery_score IS NULL THEN score
+    WHEN score IS NULL THEN recovery_score
+    WHEN recovery_rule = 'replace' THEN recovery_score
+    WHEN recovery_rule = 'average' THEN ROUND((score + recovery_score) / 2, 2)
+    ELSE GREATEST(score, recovery_score)
+  END
+$$;
+
+-- Comparação entre turmas da mesma série no mesmo período/disciplina
+CREATE INDEX IF NOT EXISTS idx_student_grades_subject_term
+  ON student_grades (tenant_id, subject_id, term_id);
@@ -0,0 +1,31 @@
+-- Ocorrências disciplinares por aluno (entram no indicador de risco)
+CREATE TABLE IF NOT EXISTS discipline_incidents (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  occurred_on DATE NOT NULL,
+  severity TEXT NOT NULL CHECK (severity IN ('low', 'medium', 'high')),
+  description TEXT NOT NULL,
+  -- sem FK: o registro sobrevive à remoção do usuário
+  created_by UUID NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_discipline_incidents_student
+  ON discipline_incidents (tenant_id, student_id, occurred_on);
+
+-- Snapshot diário do indicador de risco (job noturno), para acompanhar a tendência
+CREATE TABLE IF NOT EXISTS student_risk_snapshots (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  student_id UUID NOT NULL REFERENCES students(id) ON DELETE CASCADE,
+  snapshot_date DATE NOT NULL,
+  class_id UUID NULL REFERENCES classes(id) ON DELETE SET NULL,
+  score INT NOT NULL CHECK (score BETWEEN 0 AND 100),
+  level TEXT NOT NULL CHECK (level IN ('low', 'medium', 'high')),
+  factors JSONB NOT NULL DEFAULT '[]'::jsonb,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (tenant_id, student_id, snapshot_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_student_risk_snapshots_date
+  ON student_risk_snapshots (tenant_id, snapshot_date);
@@ -0,0 +1,25 @@
+-- Grade horária semanal por turma: dia da semana (1 = segunda ... 7 = domingo),
+-- horário, disciplina, professor e sala. Conflitos de professor/sala são checados na API.
+CREATE TABLE IF NOT EXISTS timetable_slots (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  weekday INT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+  start_time TIME NOT NULL,
+  end_time TIME NOT NULL,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  teacher_user_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  room TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK (start_time < end_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_slots_class
+  ON timetable_slots (tenant_id, class_id, weekday, start_time);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_slots_teacher
+  ON timetable_slots (tenant_id, teacher_user_id, weekday);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_slots_room
+  ON timetable_slots (tenant_id, lower(room), weekday);
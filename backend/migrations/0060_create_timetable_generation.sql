@@ -0,0 +1,68 @@
+-- Aulas semanais de cada disciplina na turma: entrada do gerador automático de horários.
+-- double_lessons = quantas dessas aulas devem vir em dupla (dois horários seguidos).
+CREATE TABLE IF NOT EXISTS timetable_requirements (
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  class_id UUID NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+  subject_id UUID NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+  lessons_per_week INT NOT NULL CHECK (lessons_per_week BETWEEN 1 AND 40),
+  double_lessons INT NOT NULL DEFAULT 0 CHECK (double_lessons >= 0),
+  -- NULL: disciplina sem professor atribuído na turma
+  teacher_user_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (class_id, subject_id),
+  CHECK (double_lessons * 2 <= lessons_per_week)
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_requirements_tenant
+  ON timetable_requirements (tenant_id, class_id);
+
+-- Janelas em que o professor não pode dar aula (restrição obrigatória do gerador)
+CREATE TABLE IF NOT EXISTS teacher_unavailability (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  teacher_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  weekday INT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+  start_time TIME NOT NULL,
+  end_time TIME NOT NULL,
+  reason TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK (start_time < end_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_teacher_unavailability_teacher
+  ON teacher_unavailability (tenant_id, teacher_user_id, weekday);
+
+-- Execuções do gerador, processadas pelo job de horários. O resultado substitui a grade
+-- (`timetable_slots`) das turmas envolvidas apenas quando todas as aulas foram encaixadas.
+CREATE TABLE IF NOT EXISTS timetable_generations (
+  id UUID PRIMARY KEY,
+  tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+  requested_by UUID NULL,
+  school_year INT NOT NULL,
+  -- NULL: todas as turmas do ano letivo com aulas configuradas
+  class_ids UUID[] NULL,
+  weekdays INT[] NOT NULL,
+  -- horários do dia: [{"start_time": "07:30:00", "end_time": "08:20:00"}, ...]
+  periods JSONB NOT NULL,
+  status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'done', 'failed')),
+  progress INT NOT NULL DEFAULT 0 CHECK (progress BETWEEN 0 AND 100),
+  total_lessons INT NOT NULL DEFAULT 0,
+  placed_lessons INT NOT NULL DEFAULT 0,
+  teacher_gaps INT NULL,
+  double_lessons_requested INT NULL,
+  double_lessons_met INT NULL,
+  -- aulas que não couberam: [{"class_id", "class_name", "subject_id", "subject_name", "lessons"}]
+  unplaced JSONB NULL,
+  error TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  started_at TIMESTAMP NULL,
+  finished_at TIMESTAMP NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_generations_tenant
+  ON timetable_generations (tenant_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_timetable_generations_queued
+  ON timetable_generations (created_at)
+  WHERE status = 'queued';
@@ -0,0 +1,15 @@
+-- Identidades criadas pelo SSO de uma escola valem só nela: o provedor é configurado pela
+-- escola e não pode autenticar ninguém nas demais (seletor, troca de escola).
+-- Identidades globais (login por senha) continuam com tenant_id nulo e e-mail único.
+ALTER TABLE identities
+  ADD COLUMN IF NOT EXISTS tenant_id UUID NULL;
+
+DROP INDEX IF EXISTS idx_identities_email;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_identities_email
+  ON identities (lower(email))
+  WHERE tenant_id IS NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_identities_tenant_email
+  ON identities (tenant_id, lower(email))
+  WHERE tenant_id IS NOT NULL;
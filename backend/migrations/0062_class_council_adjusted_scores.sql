@@ -0,0 +1,25 @@
+-- Notas do período definidas pelo conselho de classe. O conselho é único por turma e
+-- período, então há no máximo uma por aluno e disciplina em cada período da turma.
+CREATE OR REPLACE VIEW class_council_adjusted_scores AS
+SELECT d.tenant_id, c.class_id, c.term_id, d.student_id, d.subject_id, d.adjusted_score
+FROM class_council_decisions d
+JOIN class_councils c ON c.id = d.council_id
+WHERE d.adjusted_score IS NOT NULL;
+
+-- Nota efetiva de cada lançamento: o ajuste do conselho, quando houver; senão a regra de
+-- recuperação da escola. Análises e risco leem daqui para bater com diário e boletins.
+CREATE OR REPLACE VIEW student_grades_effective AS
+SELECT g.tenant_id, g.class_id, g.student_id, g.subject_id, g.term_id,
+       COALESCE(
+         ca.adjusted_score,
+         student_grade_effective_score(g.score, g.recovery_score, t.recovery_rule)
+       ) AS effective_score,
+       ca.adjusted_score IS NOT NULL AS council_adjusted
+FROM student_grades g
+JOIN tenants t ON t.id = g.tenant_id
+LEFT JOIN class_council_adjusted_scores ca
+  ON ca.tenant_id = g.tenant_id
+ AND ca.class_id = g.class_id
+ AND ca.term_id = g.term_id
+ AND ca.student_id = g.student_id
+ AND ca.subject_id = g.subject_id;
@@ -0,0 +1,11 @@
+-- Entrega com reserva: cada instância marca o lote como `sending` antes do POST, e
+-- as outras pulam essas linhas. `claimed_at` devolve à fila o que ficou preso por queda
+-- do servidor no meio do envio.
+ALTER TABLE guardian_notifications
+  DROP CONSTRAINT IF EXISTS guardian_notifications_status_check;
+ALTER TABLE guardian_notifications
+  ADD CONSTRAINT guardian_notifications_status_check
+  CHECK (status IN ('pending', 'sending', 'sent', 'failed'));
+
+ALTER TABLE guardian_notifications
+  ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMP NULL;
@@ -0,0 +1,26 @@
+-- Fonte única da nota efetiva para diário, boletins e relatórios: inclui também as notas
+-- definidas pelo conselho sem lançamento no diário, e expõe a nota do conselho.
+CREATE OR REPLACE VIEW student_grades_effective AS
+SELECT k.tenant_id, k.class_id, k.student_id, k.subject_id, k.term_id,
+       COALESCE(
+         k.adjusted_score,
+         student_grade_effective_score(k.score, k.recovery_score, t.recovery_rule)
+       ) AS effective_score,
+       k.adjusted_score IS NOT NULL AS council_adjusted,
+       k.adjusted_score AS council_adjusted_score
+FROM (
+  SELECT COALESCE(g.tenant_id, ca.tenant_id) AS tenant_id,
+         COALESCE(g.class_id, ca.class_id) AS class_id,
+         COALESCE(g.student_id, ca.student_id) AS student_id,
+         COALESCE(g.subject_id, ca.subject_id) AS subject_id,
+         COALESCE(g.term_id, ca.term_id) AS term_id,
+         g.score, g.recovery_score, ca.adjusted_score
+  FROM student_grades g
+  FULL JOIN class_council_adjusted_scores ca
+    ON ca.tenant_id = g.tenant_id
+   AND ca.class_id = g.class_id
+   AND ca.term_id = g.term_id
+   AND ca.student_id = g.student_id
+   AND ca.subject_id = g.subject_id
+) k
+JOIN tenants t ON t.id = k.tenant_id;
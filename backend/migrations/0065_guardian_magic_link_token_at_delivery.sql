@@ -0,0 +1,19 @@
+-- O token do link mágico passa a ser gerado na entrega do e-mail: a fila guarda só o
+-- link (`magic_link_id`) e um texto sem o token, e o link fica sem hash até sair.
+ALTER TABLE guardian_portal_magic_links
+  ALTER COLUMN token_hash DROP NOT NULL;
+
+ALTER TABLE guardian_notifications
+  ADD COLUMN IF NOT EXISTS magic_link_id UUID NULL
+    REFERENCES guardian_portal_magic_links(id) ON DELETE SET NULL;
+
+-- Links já enfileirados levam o token no texto: deixam de valer e saem da fila.
+UPDATE guardian_portal_magic_links
+SET expires_at = NOW()
+WHERE used_at IS NULL AND expires_at > NOW();
+
+UPDATE guardian_notifications
+SET body = 'Link de acesso ao portal enviado (removido após a entrega).',
+    status = CASE WHEN status IN ('pending', 'sending') THEN 'failed' ELSE status END,
+    last_error = CASE WHEN status IN ('pending', 'sending') THEN 'Link invalidado; peça um novo' ELSE last_error END
+WHERE kind = 'portal_magic_link';
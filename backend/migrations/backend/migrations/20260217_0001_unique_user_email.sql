@@ -1 +0,0 @@
-CREATE UNIQUE INDEX IF NOT EXISTS users_email_unique ON users (email);
@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::state::AppState;
+
+/// Módulos contratáveis por escola (ver tabela `features`).
+pub const FEATURE_FINANCIAL: &str = "financial";
+pub const FEATURE_PIX: &str = "pix";
+pub const FEATURE_PUBLIC_REPORTS: &str = "public_reports";
+
+/// Lista os códigos dos módulos habilitados para o tenant.
+/// Sem linha em `tenant_features`, vale o `default_enabled` do catálogo.
+pub async fn enabled_features(pool: &PgPool, tenant_id: Uuid) -> Result<Vec<String>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT f.code
+        FROM features f
+        LEFT JOIN tenant_features tf
+          ON tf.feature_code = f.code
+         AND tf.tenant_id = $1
+        WHERE COALESCE(tf.enabled, f.default_enabled)
+        ORDER BY f.code ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows.into_iter().map(|r| r.get("code")).collect())
+}
+
+pub async fn ensure_feature_enabled(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    feature_code: &str,
+) -> Result<(), (StatusCode, String)> {
+    let enabled: Option<bool> = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(tf.enabled, f.default_enabled)
+        FROM features f
+        LEFT JOIN tenant_features tf
+          ON tf.feature_code = f.code
+         AND tf.tenant_id = $1
+        WHERE f.code = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(feature_code)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if enabled != Some(true) {
+        return Err((StatusCode::FORBIDDEN, "Módulo não habilitado para esta escola".into()));
+    }
+    Ok(())
+}
+
+/// Middleware para `route_layer`: bloqueia o router inteiro do módulo financeiro.
+pub async fn require_financial(
+    State(state): State<AppState>,
+    user: AuthUser,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_FINANCIAL).await?;
+    Ok(next.run(req).await)
+}
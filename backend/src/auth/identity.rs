@@ -0,0 +1,224 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::http::StatusCode;
+use sqlx::{PgConnection, PgPool, Row};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+pub const TOTP_ISSUER: &str = "School SaaS";
+
+pub fn hash_password(password: &str) -> Result<String, (StatusCode, String)> {
+    let salt = SaltString::generate(&mut rand::thread_rng());
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro ao gerar hash".into()))
+        .map(|h| h.to_string())
+}
+
+pub fn verify_password(password_hash: &str, password: &str) -> Result<bool, (StatusCode, String)> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Hash inválido".into()))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+/// Gera um segredo TOTP novo (base32), ainda não ativado.
+pub fn generate_totp_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(secret) => secret,
+        Secret::Raw(_) => unreachable!("to_encoded sempre devolve Encoded"),
+    }
+}
+
+pub fn totp_for(secret: &str, email: &str) -> Result<TOTP, (StatusCode, String)> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Segredo 2FA inválido".into()))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        bytes,
+        Some(TOTP_ISSUER.to_string()),
+        email.to_string(),
+    )
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Segredo 2FA inválido".into()))
+}
+
+pub fn verify_totp(secret: &str, email: &str, code: &str) -> Result<bool, (StatusCode, String)> {
+    let totp = totp_for(secret, email)?;
+    totp.check_current(code.trim())
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Relógio do servidor inválido".into()))
+}
+
+/// Confere senha e, se a identidade tiver 2FA ativo, o código TOTP.
+/// Erros sempre como 401 para não revelar qual fator falhou além do necessário.
+///
+/// `row` precisa trazer `identity_id`, `email`, `password_hash`, `totp_enabled` e `totp_secret`
+/// da identidade. Vínculos anteriores às identidades guardam o hash antigo em
+/// `users.password_hash`: ele também é aceito e, no primeiro login completo, vira a senha
+/// da identidade e os hashes antigos são descartados.
+pub async fn check_identity_credentials(
+    pool: &PgPool,
+    row: &sqlx::postgres::PgRow,
+    password: &str,
+    totp_code: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    let identity_id: Uuid = row.get("identity_id");
+    let password_hash: String = row.get("password_hash");
+    let identity_match = verify_password(&password_hash, password)?;
+
+    let legacy_hashes: Vec<String> = sqlx::query_scalar(
+        r#"SELECT password_hash FROM users WHERE identity_id = $1 AND password_hash IS NOT NULL"#,
+    )
+    .bind(identity_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let legacy_match = if identity_match {
+        false
+    } else {
+        let mut matched = false;
+        for hash in &legacy_hashes {
+            if verify_password(hash, password)? {
+                matched = true;
+                break;
+            }
+        }
+        matched
+    };
+    if !identity_match && !legacy_match {
+        return Err((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()));
+    }
+
+    let totp_enabled: bool = row.get("totp_enabled");
+    if totp_enabled {
+        let code = totp_code
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .ok_or((StatusCode::UNAUTHORIZED, "Código 2FA obrigatório".into()))?;
+        let secret: Option<String> = row.get("totp_secret");
+        let secret = secret.ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Segredo 2FA ausente".into()))?;
+        let email: String = row.get("email");
+        if !verify_totp(&secret, &email, code)? {
+            return Err((StatusCode::UNAUTHORIZED, "Código 2FA inválido".into()));
+        }
+    }
+
+    if !legacy_hashes.is_empty() {
+        migrate_legacy_password(pool, identity_id, legacy_match.then_some(password)).await?;
+    }
+
+    Ok(())
+}
+
+/// Descarta os hashes antigos dos vínculos; com `password`, ela passa a ser a senha da identidade.
+async fn migrate_legacy_password(
+    pool: &PgPool,
+    identity_id: Uuid,
+    password: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if let Some(password) = password {
+        sqlx::query(r#"UPDATE identities SET password_hash = $2 WHERE id = $1"#)
+            .bind(identity_id)
+            .bind(hash_password(password)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    sqlx::query(r#"UPDATE users SET password_hash = NULL WHERE identity_id = $1"#)
+        .bind(identity_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))
+}
+
+/// Vincula um novo usuário de escola a uma identidade global.
+/// Se o e-mail já tem identidade, reaproveita; com `require_password_match`
+/// (autocadastro) a senha informada precisa conferir com a existente.
+pub async fn link_or_create_identity(
+    conn: &mut PgConnection,
+    email: &str,
+    password: &str,
+    require_password_match: bool,
+) -> Result<Uuid, (StatusCode, String)> {
+    let existing = sqlx::query(
+        r#"SELECT id, password_hash FROM identities WHERE lower(email) = lower($1) AND tenant_id IS NULL"#,
+    )
+    .bind(email)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if let Some(row) = existing {
+        if require_password_match {
+            let password_hash: String = row.get("password_hash");
+            if !verify_password(&password_hash, password)? {
+                return Err((
+                    StatusCode::CONFLICT,
+                    "E-mail já possui conta; informe a senha atual para vincular a nova escola".into(),
+                ));
+            }
+        }
+        return Ok(row.get("id"));
+    }
+
+    let identity_id = Uuid::new_v4();
+    sqlx::query(r#"INSERT INTO identities (id, email, password_hash) VALUES ($1, $2, $3)"#)
+        .bind(identity_id)
+        .bind(email.trim().to_lowercase())
+        .bind(hash_password(password)?)
+        .execute(&mut *conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(identity_id)
+}
+
+/// Identidade restrita a uma escola, para quem entra pelo SSO dela. Nunca reaproveita a
+/// identidade global do mesmo e-mail: o provedor é da escola e não pode abrir as outras.
+pub async fn find_or_create_tenant_identity(
+    conn: &mut PgConnection,
+    tenant_id: Uuid,
+    email: &str,
+    password: &str,
+) -> Result<Uuid, (StatusCode, String)> {
+    let existing: Option<Uuid> = sqlx::query_scalar(
+        r#"SELECT id FROM identities WHERE tenant_id = $1 AND lower(email) = lower($2)"#,
+    )
+    .bind(tenant_id)
+    .bind(email)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if let Some(identity_id) = existing {
+        return Ok(identity_id);
+    }
+
+    let identity_id = Uuid::new_v4();
+    sqlx::query(r#"INSERT INTO identities (id, tenant_id, email, password_hash) VALUES ($1, $2, $3, $4)"#)
+        .bind(identity_id)
+        .bind(tenant_id)
+        .bind(email.trim().to_lowercase())
+        .bind(hash_password(password)?)
+        .execute(&mut *conn)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(identity_id)
+}
@@ -1,6 +1,6 @@
 use axum::{
     async_trait,
-    extract::{FromRequestParts, State},
+    extract::{FromRef, FromRequestParts, State},
     http::{request::Parts, StatusCode},
 };
 use jsonwebtoken::{decode, DecodingKey, Validation};
@@ -12,7 +12,7 @@ pub struct Claims {
     pub sub: String,      // user_id
     pub tenant_id: Option<String>,
     pub role: String,
-    pub scope: String, // tenant | platform
+    pub scope: String, // tenant | tenant_sso | platform | identity | guardian | student
     pub exp: usize,
 }
 
@@ -21,14 +21,44 @@ pub struct AuthUser {
     pub user_id: Uuid,
     pub tenant_id: Uuid,
     pub role: String,
+    /// Sessão aberta pelo SSO da escola (escopo `tenant_sso`).
+    pub sso: bool,
 }
 
+/// Escopo do token emitido pelo SSO da escola: vale como `tenant` dentro dela, mas não
+/// chega à identidade global (troca de escola, 2FA), que o provedor da escola não autentica.
+pub const SCOPE_TENANT_SSO: &str = "tenant_sso";
+
 #[derive(Clone, Debug)]
 pub struct PlatformUser {
-    pub user_id: Uuid,
     pub role: String,
 }
 
+/// Identidade global autenticada, antes de escolher a escola (seletor pós-login).
+#[derive(Clone, Debug)]
+pub struct IdentityUser {
+    pub identity_id: Uuid,
+}
+
+/// `role` do token do portal dos responsáveis quando a sessão foi aberta por link mágico.
+pub const ROLE_GUARDIAN_MAGIC_LINK: &str = "guardian_magic_link";
+
+/// Conta do portal dos responsáveis (`guardian_portal_accounts`).
+#[derive(Clone, Debug)]
+pub struct GuardianUser {
+    pub account_id: Uuid,
+    pub tenant_id: Uuid,
+    /// Sessão aberta por link mágico, e não por senha.
+    pub magic_link: bool,
+}
+
+/// Aluno no portal do aluno; `person_id` é o `students.person_id`.
+#[derive(Clone, Debug)]
+pub struct StudentUser {
+    pub person_id: Uuid,
+    pub tenant_id: Uuid,
+}
+
 impl AuthUser {
     pub fn require_any_role(&self, allowed: &[&str]) -> Result<(), (StatusCode, String)> {
         if allowed.iter().any(|role| *role == self.role) {
@@ -39,56 +69,70 @@ impl AuthUser {
     }
 }
 
-#[async_trait]
-impl<S> FromRequestParts<S> for AuthUser
+/// Lê o Bearer do cabeçalho, valida o token e confere se ele é de um dos escopos esperados.
+async fn decode_claims<S>(parts: &mut Parts, state: &S, scopes: &[&str]) -> Result<Claims, (StatusCode, String)>
 where
     S: Send + Sync,
-    crate::state::AppState: axum::extract::FromRef<S>,
+    crate::state::AppState: FromRef<S>,
 {
-    type Rejection = (StatusCode, String);
+    let State(app): State<crate::state::AppState> =
+        State::from_request_parts(parts, state).await.map_err(|_| {
+            (StatusCode::INTERNAL_SERVER_ERROR, "State inválido".into())
+        })?;
+
+    let auth = parts
+        .headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .ok_or((StatusCode::UNAUTHORIZED, "Sem Authorization".into()))?;
+
+    let token = auth
+        .strip_prefix("Bearer ")
+        .ok_or((StatusCode::UNAUTHORIZED, "Bearer inválido".into()))?;
+
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(app.jwt_secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido".into()))?;
+
+    if !scopes.contains(&data.claims.scope.as_str()) {
+        return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
+    }
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        // Pegamos AppState via State extractor
-        let State(app): State<crate::state::AppState> =
-            State::from_request_parts(parts, state).await.map_err(|_| {
-                (StatusCode::INTERNAL_SERVER_ERROR, "State inválido".into())
-            })?;
-
-        let auth = parts
-            .headers
-            .get(axum::http::header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or((StatusCode::UNAUTHORIZED, "Sem Authorization".into()))?;
-
-        let token = auth
-            .strip_prefix("Bearer ")
-            .ok_or((StatusCode::UNAUTHORIZED, "Bearer inválido".into()))?;
-
-        let data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(app.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido".into()))?;
-
-        let user_id = Uuid::parse_str(&data.claims.sub)
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "sub inválido".into()))?;
-        let scope = data.claims.scope.as_str();
-        if scope != "tenant" {
-            return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
-        }
+    Ok(data.claims)
+}
+
+impl Claims {
+    fn subject(&self) -> Result<Uuid, (StatusCode, String)> {
+        Uuid::parse_str(&self.sub).map_err(|_| (StatusCode::UNAUTHORIZED, "sub inválido".into()))
+    }
 
-        let tenant_id_str = data
-            .claims
+    fn tenant(&self) -> Result<Uuid, (StatusCode, String)> {
+        let tenant_id = self
             .tenant_id
+            .as_deref()
             .ok_or((StatusCode::UNAUTHORIZED, "tenant ausente".into()))?;
-        let tenant_id = Uuid::parse_str(&tenant_id_str)
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "tenant inválido".into()))?;
+        Uuid::parse_str(tenant_id).map_err(|_| (StatusCode::UNAUTHORIZED, "tenant inválido".into()))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+    crate::state::AppState: FromRef<S>,
+{
+    type Rejection = (StatusCode, String);
 
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = decode_claims(parts, state, &["tenant", SCOPE_TENANT_SSO]).await?;
         Ok(AuthUser {
-            user_id,
-            tenant_id,
-            role: data.claims.role,
+            user_id: claims.subject()?,
+            tenant_id: claims.tenant()?,
+            sso: claims.scope == SCOPE_TENANT_SSO,
+            role: claims.role,
         })
     }
 }
@@ -97,43 +141,63 @@ where
 impl<S> FromRequestParts<S> for PlatformUser
 where
     S: Send + Sync,
-    crate::state::AppState: axum::extract::FromRef<S>,
+    crate::state::AppState: FromRef<S>,
 {
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let State(app): State<crate::state::AppState> =
-            State::from_request_parts(parts, state).await.map_err(|_| {
-                (StatusCode::INTERNAL_SERVER_ERROR, "State inválido".into())
-            })?;
-
-        let auth = parts
-            .headers
-            .get(axum::http::header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or((StatusCode::UNAUTHORIZED, "Sem Authorization".into()))?;
-
-        let token = auth
-            .strip_prefix("Bearer ")
-            .ok_or((StatusCode::UNAUTHORIZED, "Bearer inválido".into()))?;
-
-        let data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(app.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido".into()))?;
-
-        if data.claims.scope != "platform" {
-            return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
-        }
+        let claims = decode_claims(parts, state, &["platform"]).await?;
+        Ok(PlatformUser { role: claims.role })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IdentityUser
+where
+    S: Send + Sync,
+    crate::state::AppState: FromRef<S>,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = decode_claims(parts, state, &["identity"]).await?;
+        Ok(IdentityUser {
+            identity_id: claims.subject()?,
+        })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for GuardianUser
+where
+    S: Send + Sync,
+    crate::state::AppState: FromRef<S>,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = decode_claims(parts, state, &["guardian"]).await?;
+        Ok(GuardianUser {
+            account_id: claims.subject()?,
+            tenant_id: claims.tenant()?,
+            magic_link: claims.role == ROLE_GUARDIAN_MAGIC_LINK,
+        })
+    }
+}
 
-        let user_id = Uuid::parse_str(&data.claims.sub)
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "sub inválido".into()))?;
+#[async_trait]
+impl<S> FromRequestParts<S> for StudentUser
+where
+    S: Send + Sync,
+    crate::state::AppState: FromRef<S>,
+{
+    type Rejection = (StatusCode, String);
 
-        Ok(PlatformUser {
-            user_id,
-            role: data.claims.role,
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = decode_claims(parts, state, &["student"]).await?;
+        Ok(StudentUser {
+            person_id: claims.subject()?,
+            tenant_id: claims.tenant()?,
         })
     }
 }
@@ -1 +1,5 @@
 pub mod jwt;
+pub mod features;
+pub mod teaching;
+pub mod identity;
+pub mod oidc;
@@ -0,0 +1,196 @@
+//! Cliente OpenID Connect mínimo para o SSO por escola:
+//! discovery, URL de autorização com PKCE (S256), troca do código e validação do id_token.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub nonce: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// Só o `email` verificado pelo provedor; `preferred_username` (UPN) não prova posse do e-mail.
+    pub fn login_email(&self) -> Option<String> {
+        self.email
+            .as_deref()
+            .map(|e| e.trim().to_lowercase())
+            .filter(|e| e.contains('@'))
+    }
+}
+
+pub fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Valor aleatório url-safe (state, nonce e code_verifier).
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn same_issuer(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+pub async fn discover(http: &reqwest::Client, issuer: &str) -> Result<ProviderMetadata, String> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let metadata: ProviderMetadata = http
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("discovery falhou: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("discovery inválido: {e}"))?;
+
+    if !same_issuer(&metadata.issuer, issuer) {
+        return Err("issuer do discovery não confere com o configurado".into());
+    }
+    Ok(metadata)
+}
+
+pub fn authorization_url(
+    metadata: &ProviderMetadata,
+    client_id: &str,
+    redirect_uri: &str,
+    state: &str,
+    nonce: &str,
+    code_challenge: &str,
+) -> Result<String, String> {
+    let url = url::Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("scope", "openid email profile"),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", code_challenge),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|e| format!("authorization_endpoint inválido: {e}"))?;
+    Ok(url.into())
+}
+
+pub async fn exchange_code(
+    http: &reqwest::Client,
+    metadata: &ProviderMetadata,
+    client_id: &str,
+    client_secret: &str,
+    redirect_uri: &str,
+    code: &str,
+    code_verifier: &str,
+) -> Result<String, String> {
+    let res: TokenResponse = http
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("troca do código falhou: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("resposta do token inválida: {e}"))?;
+
+    res.id_token.ok_or_else(|| "provedor não devolveu id_token".into())
+}
+
+/// Valida assinatura (JWKS do provedor), issuer, audience, expiração e nonce.
+pub async fn verify_id_token(
+    http: &reqwest::Client,
+    metadata: &ProviderMetadata,
+    client_id: &str,
+    id_token: &str,
+    expected_nonce: &str,
+) -> Result<IdTokenClaims, String> {
+    let header = decode_header(id_token).map_err(|_| "id_token malformado".to_string())?;
+    // só chaves assimétricas publicadas pelo provedor; nunca HS* com segredo compartilhado
+    if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+        return Err("algoritmo do id_token não suportado".into());
+    }
+
+    let jwks: JwkSet = http
+        .get(&metadata.jwks_uri)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("jwks indisponível: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("jwks inválido: {e}"))?;
+
+    let jwk = match header.kid.as_deref() {
+        Some(kid) => jwks.find(kid),
+        None => jwks.keys.first(),
+    }
+    .ok_or("chave do id_token não encontrada no jwks")?;
+
+    let key = DecodingKey::from_jwk(jwk).map_err(|_| "chave jwks não suportada".to_string())?;
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&[metadata.issuer.as_str()]);
+
+    let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+        .map_err(|e| format!("id_token inválido: {e}"))?
+        .claims;
+
+    if claims.nonce.as_deref() != Some(expected_nonce) {
+        return Err("nonce do id_token não confere".into());
+    }
+    if claims.email_verified != Some(true) {
+        return Err("e-mail não verificado no provedor".into());
+    }
+    Ok(claims)
+}
+
+/// Domínio liberado? Lista vazia não libera nenhum.
+pub fn email_domain_allowed(email: &str, allowed_domains: &[String]) -> bool {
+    let domain = match email.rsplit_once('@') {
+        Some((_, d)) => d.to_lowercase(),
+        None => return false,
+    };
+    allowed_domains
+        .iter()
+        .any(|d| d.trim().trim_start_matches('@').eq_ignore_ascii_case(&domain))
+}
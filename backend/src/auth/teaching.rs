@@ -0,0 +1,58 @@
+//! Atribuição de aulas (`teaching_assignments`): professores só acessam as turmas e
+//! disciplinas atribuídas. Owner, admin e secretaria (staff) não são restringidos.
+
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+
+pub const RESTRICTED_ROLE: &str = "teacher";
+
+/// Professor cujo acesso deve ser filtrado pelas atribuições; `None` para os demais papéis.
+pub fn restricted_teacher(user: &AuthUser) -> Option<Uuid> {
+    (user.role == RESTRICTED_ROLE).then_some(user.user_id)
+}
+
+/// Exige que o professor tenha a turma (e, se informada, a disciplina) atribuída.
+pub async fn ensure_teaches<'e, E>(
+    executor: E,
+    user: &AuthUser,
+    class_id: Uuid,
+    subject_id: Option<Uuid>,
+) -> Result<(), (StatusCode, String)>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let Some(teacher_user_id) = restricted_teacher(user) else {
+        return Ok(());
+    };
+
+    let row: Option<i32> = sqlx::query_scalar(
+        r#"
+        SELECT 1
+        FROM teaching_assignments ta
+        JOIN classes c ON c.id = ta.class_id AND c.tenant_id = ta.tenant_id
+        WHERE ta.tenant_id = $1
+          AND ta.teacher_user_id = $2
+          AND ta.class_id = $3
+          AND ($4::uuid IS NULL OR ta.subject_id = $4)
+          AND ta.school_year = c.year
+        LIMIT 1
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .bind(class_id)
+    .bind(subject_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Turma/disciplina não atribuída a este professor".into(),
+        ));
+    }
+    Ok(())
+}
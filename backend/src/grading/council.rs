@@ -0,0 +1,121 @@
+//! Conselho de classe: decisões por aluno (geral ou por disciplina) e ajustes de nota do
+//! período. Só as decisões do conselho do último período do ano letivo prevalecem sobre a
+//! situação anual calculada; os ajustes de nota valem no período do conselho.
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use super::promotion::{STATUS_APPROVED, STATUS_FAILED_GRADE, STATUS_RECOVERY};
+
+pub const DECISION_APPROVE: &str = "approve";
+pub const DECISION_RETAIN: &str = "retain";
+pub const DECISION_RECOVERY: &str = "recovery";
+
+pub fn normalize_decision(decision: &str) -> Result<&'static str, (StatusCode, String)> {
+    match decision.trim().to_lowercase().as_str() {
+        DECISION_APPROVE => Ok(DECISION_APPROVE),
+        DECISION_RETAIN => Ok(DECISION_RETAIN),
+        DECISION_RECOVERY => Ok(DECISION_RECOVERY),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Decisão inválida (use approve, retain ou recovery)".into(),
+        )),
+    }
+}
+
+/// Situação final (`promotion::STATUS_*`) que a decisão impõe.
+pub fn decision_status(decision: &str) -> &'static str {
+    match decision {
+        DECISION_APPROVE => STATUS_APPROVED,
+        DECISION_RECOVERY => STATUS_RECOVERY,
+        _ => STATUS_FAILED_GRADE,
+    }
+}
+
+/// Rótulo curto para a coluna de situação da disciplina.
+pub fn subject_label(decision: &str) -> &'static str {
+    match decision {
+        DECISION_APPROVE => "Aprovado (conselho)",
+        DECISION_RECOVERY => "Em recuperação (conselho)",
+        _ => "Retido (conselho)",
+    }
+}
+
+/// Rótulo da situação geral do aluno.
+pub fn overall_label(decision: &str) -> &'static str {
+    match decision {
+        DECISION_APPROVE => "Aprovado pelo conselho de classe",
+        DECISION_RECOVERY => "Em recuperação por decisão do conselho de classe",
+        _ => "Retido pelo conselho de classe",
+    }
+}
+
+/// Referência à decisão exibida no boletim. A justificativa é registro interno da escola e
+/// fica só nas rotas do conselho.
+#[derive(Debug, Clone, Serialize)]
+pub struct CouncilDecisionRef {
+    pub council_id: Uuid,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub held_on: NaiveDate,
+    pub decision: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CouncilDecision {
+    pub subject_id: Option<Uuid>,
+    /// Conselho do último período do ano letivo: a decisão vale para a situação anual.
+    pub year_end: bool,
+    pub info: CouncilDecisionRef,
+}
+
+/// Decisões do aluno na turma, do período mais antigo para o mais recente: quem aplica
+/// em ordem fica com a última palavra do conselho.
+pub async fn student_decisions(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    student_id: Uuid,
+) -> Result<Vec<CouncilDecision>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT d.subject_id, d.decision,
+               c.id AS council_id, c.term_id, t.name AS term_name, c.held_on,
+               NOT EXISTS (
+                 SELECT 1 FROM academic_terms later
+                 WHERE later.tenant_id = t.tenant_id
+                   AND later.school_year = t.school_year
+                   AND (later.sort_order, later.name) > (t.sort_order, t.name)
+               ) AS year_end
+        FROM class_council_decisions d
+        JOIN class_councils c ON c.id = d.council_id
+        JOIN academic_terms t ON t.id = c.term_id
+        WHERE d.tenant_id = $1 AND c.class_id = $2 AND d.student_id = $3
+        ORDER BY t.school_year ASC, t.sort_order ASC, t.name ASC, c.held_on ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| CouncilDecision {
+            subject_id: r.get("subject_id"),
+            year_end: r.get("year_end"),
+            info: CouncilDecisionRef {
+                council_id: r.get("council_id"),
+                term_id: r.get("term_id"),
+                term_name: r.get("term_name"),
+                held_on: r.get("held_on"),
+                decision: r.get("decision"),
+            },
+        })
+        .collect())
+}
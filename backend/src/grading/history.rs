@@ -0,0 +1,19 @@
+//! Histórico de notas e chamadas. As linhas são gravadas por triggers
+//! (`student_grade_history`, `student_attendance_history`); a API só informa o autor.
+
+use axum::http::StatusCode;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Autor das alterações feitas nesta transação (lido pelos triggers em `app.user_id`).
+pub async fn set_change_author(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query("SELECT set_config('app.user_id', $1, true)")
+        .bind(user_id.to_string())
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(())
+}
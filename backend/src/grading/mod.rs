@@ -0,0 +1,189 @@
+//! Regras de cálculo de notas compartilhadas entre diário, boletins e relatórios.
+
+pub mod council;
+pub mod history;
+pub mod promotion;
+pub mod risk;
+pub mod scales;
+pub mod term_lock;
+
+use axum::http::StatusCode;
+use sqlx::{Postgres, Row, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const FORMULA_WEIGHTED: &str = "weighted";
+pub const FORMULA_ARITHMETIC: &str = "arithmetic";
+
+#[derive(Debug, Clone, Copy)]
+pub struct AssessmentScore {
+    pub score: f64,
+    pub max_score: f64,
+    pub weight: f64,
+}
+
+pub fn normalize_formula(formula: &str) -> Result<&'static str, (StatusCode, String)> {
+    match formula.trim().to_lowercase().as_str() {
+        FORMULA_WEIGHTED => Ok(FORMULA_WEIGHTED),
+        FORMULA_ARITHMETIC => Ok(FORMULA_ARITHMETIC),
+        _ => Err((StatusCode::BAD_REQUEST, "Fórmula inválida (use weighted ou arithmetic)".into())),
+    }
+}
+
+pub fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Média do período a partir das avaliações lançadas. Cada nota é convertida para a
+/// escala do período (`score / max_score * scale_max`); avaliações sem nota ficam de fora.
+pub fn term_average(formula: &str, scores: &[AssessmentScore], scale_max: f64) -> Option<f64> {
+    if scores.is_empty() {
+        return None;
+    }
+
+    let normalized = scores.iter().map(|s| (s.score / s.max_score * scale_max, s.weight));
+    let average = if formula == FORMULA_ARITHMETIC {
+        normalized.map(|(v, _)| v).sum::<f64>() / scores.len() as f64
+    } else {
+        let total_weight: f64 = scores.iter().map(|s| s.weight).sum();
+        if total_weight <= 0.0 {
+            return None;
+        }
+        normalized.map(|(v, w)| v * w).sum::<f64>() / total_weight
+    };
+
+    Some(round2(average))
+}
+
+/// Recalcula `student_grades.score` da turma/período/disciplina a partir das avaliações.
+/// Chamado sempre que uma avaliação ou nota de avaliação muda.
+pub async fn recompute_term_scores(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    term_id: Uuid,
+    subject_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let names = sqlx::query(
+        r#"
+        SELECT tn.grade_formula, t.name AS term_name, s.name AS subject_name
+        FROM tenants tn
+        JOIN academic_terms t ON t.tenant_id = tn.id AND t.id = $2
+        JOIN subjects s ON s.tenant_id = tn.id AND s.id = $3
+        WHERE tn.id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let formula: String = names.get("grade_formula");
+    let term_name: String = names.get("term_name");
+    let subject_name: String = names.get("subject_name");
+
+    // a média do período é expressa na escala da série da turma
+    let scale = scales::scale_for_class(&mut **tx, tenant_id, class_id).await?;
+    if scale.is_conceptual() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Avaliações com nota não se aplicam a séries com escala conceitual".into(),
+        ));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT sc.student_id, sc.score::float8 AS score, a.max_score::float8 AS max_score, a.weight::float8 AS weight
+        FROM assessments a
+        JOIN assessment_scores sc ON sc.assessment_id = a.id AND sc.tenant_id = a.tenant_id
+        WHERE a.tenant_id = $1 AND a.class_id = $2 AND a.term_id = $3 AND a.subject_id = $4
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut by_student: HashMap<Uuid, Vec<AssessmentScore>> = HashMap::new();
+    for r in rows {
+        by_student.entry(r.get("student_id")).or_default().push(AssessmentScore {
+            score: r.get("score"),
+            max_score: r.get("max_score"),
+            weight: r.get("weight"),
+        });
+    }
+
+    // quem não tem mais nenhuma nota de avaliação fica sem nota no período
+    sqlx::query(
+        r#"
+        UPDATE student_grades
+        SET score = NULL
+        WHERE tenant_id = $1 AND class_id = $2 AND term_id = $3 AND subject_id = $4
+          AND NOT (student_id = ANY($5))
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .bind(by_student.keys().copied().collect::<Vec<Uuid>>())
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    for (student_id, scores) in by_student {
+        let average = term_average(&formula, &scores, scale.max_value - scale.min_value)
+            .map(|v| scale.round(scale.min_value + v));
+        sqlx::query(
+            r#"
+            INSERT INTO student_grades
+              (id, tenant_id, class_id, student_id, term, subject, term_id, subject_id, score)
+            VALUES
+              ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (tenant_id, class_id, student_id, term_id, subject_id)
+            DO UPDATE SET score = EXCLUDED.score
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(tenant_id)
+        .bind(class_id)
+        .bind(student_id)
+        .bind(&term_name)
+        .bind(&subject_name)
+        .bind(term_id)
+        .bind(subject_id)
+        .bind(average)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    Ok(())
+}
+
+/// Há avaliações cadastradas? Nesse caso a nota do período não é lançada à mão.
+pub async fn has_assessments(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    term_id: Uuid,
+    subject_id: Uuid,
+) -> Result<bool, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"SELECT 1 FROM assessments
+           WHERE tenant_id = $1 AND class_id = $2 AND term_id = $3 AND subject_id = $4
+           LIMIT 1"#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(row.is_some())
+}
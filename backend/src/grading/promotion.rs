@@ -0,0 +1,119 @@
+//! Resultado final por disciplina: recuperação paralela, prova final e frequência mínima.
+
+use axum::http::StatusCode;
+
+use super::round2;
+
+pub const RECOVERY_MAX: &str = "max";
+pub const RECOVERY_AVERAGE: &str = "average";
+pub const RECOVERY_REPLACE: &str = "replace";
+
+/// Alerta preventivo: frequência a até 5 pontos percentuais do mínimo.
+pub const ATTENDANCE_WARNING_MARGIN: f64 = 5.0;
+
+pub const STATUS_APPROVED: &str = "approved";
+pub const STATUS_RECOVERY: &str = "recovery";
+pub const STATUS_FAILED_GRADE: &str = "failed_grade";
+pub const STATUS_FAILED_ATTENDANCE: &str = "failed_attendance";
+pub const STATUS_NO_GRADES: &str = "no_grades";
+
+pub fn normalize_recovery_rule(rule: &str) -> Result<&'static str, (StatusCode, String)> {
+    match rule.trim().to_lowercase().as_str() {
+        RECOVERY_MAX => Ok(RECOVERY_MAX),
+        RECOVERY_AVERAGE => Ok(RECOVERY_AVERAGE),
+        RECOVERY_REPLACE => Ok(RECOVERY_REPLACE),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Regra de recuperação inválida (use max, average ou replace)".into(),
+        )),
+    }
+}
+
+/// Falta justificada sai do cálculo da frequência (padrão).
+pub const JUSTIFIED_EXCLUDED: &str = "excluded";
+/// Falta justificada conta como presença.
+pub const JUSTIFIED_PRESENT: &str = "present";
+/// Justificativa só fica registrada; a falta continua contando.
+pub const JUSTIFIED_ABSENCE: &str = "absence";
+
+pub fn normalize_justified_absence_rule(rule: &str) -> Result<&'static str, (StatusCode, String)> {
+    match rule.trim().to_lowercase().as_str() {
+        JUSTIFIED_EXCLUDED => Ok(JUSTIFIED_EXCLUDED),
+        JUSTIFIED_PRESENT => Ok(JUSTIFIED_PRESENT),
+        JUSTIFIED_ABSENCE => Ok(JUSTIFIED_ABSENCE),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Regra de faltas justificadas inválida (use excluded, present ou absence)".into(),
+        )),
+    }
+}
+
+/// `None` quando não há chamada registrada.
+pub fn presence_percent(total: i32, present: i32) -> Option<f64> {
+    (total > 0).then(|| round2(present as f64 * 100.0 / total as f64))
+}
+
+/// Faltas que ainda cabem sem ficar abaixo do mínimo, considerando as aulas já dadas.
+pub fn remaining_absences(total: i32, present: i32, min_attendance_percent: f64) -> i32 {
+    let allowed = (total as f64 * (100.0 - min_attendance_percent) / 100.0).floor() as i32;
+    (allowed - (total - present)).max(0)
+}
+
+pub struct FinalStatusInput {
+    pub average: Option<f64>,
+    pub min_passing_grade: f64,
+    pub final_exam_score: Option<f64>,
+    pub final_exam_min_grade: f64,
+    /// `None` quando não há chamada registrada.
+    pub attendance_percent: Option<f64>,
+    pub min_attendance_percent: f64,
+}
+
+/// Abaixo da média vai para a prova final; com a prova final lançada, ela decide.
+/// Frequência abaixo do mínimo retém independentemente da nota.
+pub fn final_status(input: &FinalStatusInput) -> &'static str {
+    if input
+        .attendance_percent
+        .is_some_and(|p| p < input.min_attendance_percent)
+    {
+        return STATUS_FAILED_ATTENDANCE;
+    }
+    let Some(average) = input.average else {
+        return STATUS_NO_GRADES;
+    };
+    if average >= input.min_passing_grade {
+        return STATUS_APPROVED;
+    }
+    match input.final_exam_score {
+        Some(score) if score >= input.final_exam_min_grade => STATUS_APPROVED,
+        Some(_) => STATUS_FAILED_GRADE,
+        None => STATUS_RECOVERY,
+    }
+}
+
+pub fn status_label(status: &str) -> &'static str {
+    match status {
+        STATUS_APPROVED => "Aprovado",
+        STATUS_RECOVERY => "Em recuperação",
+        STATUS_FAILED_GRADE => "Reprovado por nota",
+        STATUS_FAILED_ATTENDANCE => "Retido por falta",
+        _ => "Sem notas",
+    }
+}
+
+/// Situação geral do aluno: a pior situação entre as disciplinas.
+pub fn overall_status<'a>(statuses: impl IntoIterator<Item = &'a str>) -> &'static str {
+    const SEVERITY: [&str; 5] = [
+        STATUS_NO_GRADES,
+        STATUS_APPROVED,
+        STATUS_RECOVERY,
+        STATUS_FAILED_GRADE,
+        STATUS_FAILED_ATTENDANCE,
+    ];
+    statuses
+        .into_iter()
+        .filter_map(|s| SEVERITY.iter().position(|o| *o == s))
+        .max()
+        .map(|i| SEVERITY[i])
+        .unwrap_or(STATUS_NO_GRADES)
+}
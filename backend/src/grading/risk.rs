@@ -0,0 +1,485 @@
+//! Indicador de risco (evasão/reprovação) por aluno: 0 a 100 pontos somando fatores
+//! explicados — queda de notas entre períodos, notas abaixo da média, frequência e sua
+//! tendência, faltas seguidas, parcelas em atraso e ocorrências disciplinares recentes.
+//! Notas e frequência vêm do boletim anual (`records::build_student_full_report`, com
+//! recuperação e ajustes do conselho), sempre na turma atual do aluno. Notas e parcelas
+//! são lidas no estado atual, então o indicador só é calculado para o dia de hoje; datas
+//! anteriores ficam nos snapshots diários.
+
+use axum::http::StatusCode;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::promotion::{presence_percent, ATTENDANCE_WARNING_MARGIN};
+use super::scales::GradingScale;
+use crate::auth::features::{enabled_features, FEATURE_FINANCIAL};
+use crate::routes::records::{build_student_full_report, StudentFullReportResponse};
+
+pub const LEVEL_LOW: &str = "low";
+pub const LEVEL_MEDIUM: &str = "medium";
+pub const LEVEL_HIGH: &str = "high";
+
+pub const FACTOR_GRADE_DECLINE: &str = "grade_decline";
+pub const FACTOR_LOW_GRADES: &str = "low_grades";
+pub const FACTOR_LOW_ATTENDANCE: &str = "low_attendance";
+pub const FACTOR_ATTENDANCE_TREND: &str = "attendance_trend";
+pub const FACTOR_CONSECUTIVE_ABSENCES: &str = "consecutive_absences";
+pub const FACTOR_FINANCIAL_DEBT: &str = "financial_debt";
+pub const FACTOR_DISCIPLINE: &str = "discipline";
+
+/// Janela da tendência de frequência (últimos N dias contra os N anteriores).
+const TREND_WINDOW_DAYS: i64 = 30;
+/// Janela das ocorrências disciplinares.
+const DISCIPLINE_WINDOW_DAYS: i64 = 90;
+
+pub fn level_for(score: i32) -> &'static str {
+    if score >= 50 {
+        LEVEL_HIGH
+    } else if score >= 25 {
+        LEVEL_MEDIUM
+    } else {
+        LEVEL_LOW
+    }
+}
+
+pub fn level_label(level: &str) -> &'static str {
+    match level {
+        LEVEL_HIGH => "Risco alto",
+        LEVEL_MEDIUM => "Risco moderado",
+        _ => "Risco baixo",
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskFactor {
+    pub code: String,
+    pub points: i32,
+    pub max_points: i32,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StudentRisk {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub score: i32,
+    pub level: String,
+    pub level_label: String,
+    /// Só os fatores que pontuaram, do maior para o menor.
+    pub factors: Vec<RiskFactor>,
+}
+
+#[derive(Debug, Default)]
+struct TermMean {
+    term_name: String,
+    mean: f64,
+    below_passing: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct TrendInput {
+    recent_total: i32,
+    recent_present: i32,
+    previous_total: i32,
+    previous_present: i32,
+}
+
+/// Calcula o indicador dos alunos ativos da turma (ou da escola toda), do maior risco
+/// para o menor. `today` é a data de hoje: fecha as janelas de frequência e ocorrências e
+/// define quais parcelas já venceram.
+pub async fn assess_students(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Option<Uuid>,
+    today: NaiveDate,
+) -> Result<Vec<StudentRisk>, (StatusCode, String)> {
+    let settings = sqlx::query(
+        r#"
+        SELECT min_attendance_percent::float8 AS min_attendance_percent, alert_consecutive_absences
+        FROM tenants
+        WHERE id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let min_attendance: f64 = settings.get("min_attendance_percent");
+    // 0 desliga só o alerta aos responsáveis; aqui vale o padrão
+    let consecutive_threshold = match settings.get::<i32, _>("alert_consecutive_absences") {
+        0 => 3,
+        n => n,
+    };
+    let financial_enabled = enabled_features(pool, tenant_id)
+        .await?
+        .iter()
+        .any(|f| f == FEATURE_FINANCIAL);
+
+    let students = sqlx::query(
+        r#"
+        SELECT s.id, s.name, s.registration, s.class_id, c.name AS class_name
+        FROM students s
+        JOIN classes c ON c.id = s.class_id
+        WHERE s.tenant_id = $1
+          AND ($2::uuid IS NULL OR s.class_id = $2)
+          AND COALESCE(s.enrollment_status, 'active') = 'active'
+        ORDER BY s.name ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if students.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // boletim anual de cada aluno: mesmas notas efetivas e frequência do diário e dos boletins
+    let mut reports: HashMap<Uuid, StudentFullReportResponse> = HashMap::new();
+    for r in &students {
+        let student_id: Uuid = r.get("id");
+        let report = build_student_full_report(pool, tenant_id, r.get("class_id"), student_id).await?;
+        reports.insert(student_id, report);
+    }
+
+    // tendência: últimos dias contra os anteriores (o total do ano vem do boletim)
+    let recent_from = today - Duration::days(TREND_WINDOW_DAYS);
+    let previous_from = recent_from - Duration::days(TREND_WINDOW_DAYS);
+    let attendance_rows = sqlx::query(
+        r#"
+        SELECT e.student_id,
+               COUNT(*) FILTER (WHERE e.counted AND e.attendance_date > $4)::int AS recent_total,
+               COUNT(*) FILTER (WHERE e.counted AND e.present AND e.attendance_date > $4)::int AS recent_present,
+               COUNT(*) FILTER (WHERE e.counted AND e.attendance_date > $5 AND e.attendance_date <= $4)::int AS previous_total,
+               COUNT(*) FILTER (WHERE e.counted AND e.present AND e.attendance_date > $5 AND e.attendance_date <= $4)::int AS previous_present
+        FROM student_attendance_effective e
+        JOIN students s ON s.id = e.student_id AND s.class_id = e.class_id
+        WHERE e.tenant_id = $1
+          AND ($2::uuid IS NULL OR e.class_id = $2)
+          AND e.attendance_date <= $3
+        GROUP BY e.student_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(today)
+    .bind(recent_from)
+    .bind(previous_from)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let trends: HashMap<Uuid, TrendInput> = attendance_rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.get("student_id"),
+                TrendInput {
+                    recent_total: r.get("recent_total"),
+                    recent_present: r.get("recent_present"),
+                    previous_total: r.get("previous_total"),
+                    previous_present: r.get("previous_present"),
+                },
+            )
+        })
+        .collect();
+
+    // mesma contagem do alerta de faltas seguidas (guardian_alerts::absence_streak)
+    let streak_rows = sqlx::query(
+        r#"
+        WITH days AS (
+          SELECT e.student_id, e.attendance_date, bool_or(e.present OR e.justified) AS attended
+          FROM student_attendance_effective e
+          JOIN students s ON s.id = e.student_id AND s.class_id = e.class_id
+          WHERE e.tenant_id = $1
+            AND ($2::uuid IS NULL OR e.class_id = $2)
+            AND e.attendance_date <= $3
+          GROUP BY e.student_id, e.attendance_date
+        ),
+        last_attended AS (
+          SELECT student_id, MAX(attendance_date) FILTER (WHERE attended) AS last_date
+          FROM days
+          GROUP BY student_id
+        )
+        SELECT d.student_id, COUNT(*)::int AS streak
+        FROM days d
+        JOIN last_attended l ON l.student_id = d.student_id
+        WHERE d.attendance_date > COALESCE(l.last_date, '-infinity'::date)
+        GROUP BY d.student_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(today)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let streaks: HashMap<Uuid, i32> = streak_rows
+        .into_iter()
+        .map(|r| (r.get("student_id"), r.get("streak")))
+        .collect();
+
+    let mut debts: HashMap<Uuid, (f64, NaiveDate)> = HashMap::new();
+    if financial_enabled {
+        let rows = sqlx::query(
+            r#"
+            SELECT fc.student_id, SUM(i.amount)::float8 AS amount, MIN(i.due_date) AS oldest_due
+            FROM financial_installments i
+            JOIN financial_contracts fc ON fc.id = i.contract_id
+            WHERE i.tenant_id = $1
+              AND i.status IN ('pending', 'overdue')
+              AND i.due_date < $2
+              AND fc.status <> 'cancelled'
+            GROUP BY fc.student_id
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(today)
+        .fetch_all(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        debts = rows
+            .into_iter()
+            .map(|r| (r.get("student_id"), (r.get("amount"), r.get("oldest_due"))))
+            .collect();
+    }
+
+    let incident_rows = sqlx::query(
+        r#"
+        SELECT student_id,
+               COUNT(*)::int AS incidents,
+               SUM(CASE severity WHEN 'high' THEN 6 WHEN 'medium' THEN 4 ELSE 2 END)::int AS weight
+        FROM discipline_incidents
+        WHERE tenant_id = $1
+          AND occurred_on > $2
+          AND occurred_on <= $3
+        GROUP BY student_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(today - Duration::days(DISCIPLINE_WINDOW_DAYS))
+    .bind(today)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let incidents: HashMap<Uuid, (i32, i32)> = incident_rows
+        .into_iter()
+        .map(|r| (r.get("student_id"), (r.get("incidents"), r.get("weight"))))
+        .collect();
+
+    let mut result: Vec<StudentRisk> = students
+        .into_iter()
+        .map(|r| {
+            let student_id: Uuid = r.get("id");
+            let student_class: Uuid = r.get("class_id");
+            let report = &reports[&student_id];
+            let mut factors = Vec::new();
+
+            grade_factors(&report.grading_scale, &term_means(report), &mut factors);
+            attendance_factors(
+                report.attendance_total_days,
+                report.attendance_present_days,
+                trends.get(&student_id),
+                min_attendance,
+                &mut factors,
+            );
+            if let Some(&streak) = streaks.get(&student_id) {
+                let points = if streak >= consecutive_threshold {
+                    15
+                } else if streak >= 2 {
+                    5
+                } else {
+                    0
+                };
+                push_factor(
+                    &mut factors,
+                    FACTOR_CONSECUTIVE_ABSENCES,
+                    points,
+                    15,
+                    format!("{streak} dias letivos seguidos de falta"),
+                );
+            }
+            if let Some(&(amount, oldest_due)) = debts.get(&student_id) {
+                let points = if (today - oldest_due).num_days() > 30 { 10 } else { 5 };
+                push_factor(
+                    &mut factors,
+                    FACTOR_FINANCIAL_DEBT,
+                    points,
+                    10,
+                    format!(
+                        "{} em parcelas vencidas desde {}",
+                        format!("R$ {amount:.2}").replace('.', ","),
+                        oldest_due.format("%d/%m/%Y")
+                    ),
+                );
+            }
+            if let Some(&(count, weight)) = incidents.get(&student_id) {
+                let label = if count == 1 { "ocorrência disciplinar" } else { "ocorrências disciplinares" };
+                push_factor(
+                    &mut factors,
+                    FACTOR_DISCIPLINE,
+                    weight.min(10),
+                    10,
+                    format!("{count} {label} nos últimos {DISCIPLINE_WINDOW_DAYS} dias"),
+                );
+            }
+
+            factors.sort_by_key(|f| Reverse(f.points));
+            let score = factors.iter().map(|f| f.points).sum::<i32>().min(100);
+            let level = level_for(score);
+            StudentRisk {
+                student_id,
+                student_name: r.get("name"),
+                registration: r.get("registration"),
+                class_id: student_class,
+                class_name: r.get("class_name"),
+                score,
+                level: level.to_string(),
+                level_label: level_label(level).to_string(),
+                factors,
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.student_name.cmp(&b.student_name)));
+    Ok(result)
+}
+
+/// Média das notas efetivas de cada período do boletim, do mais recente para o mais
+/// antigo; períodos sem nota ficam de fora.
+fn term_means(report: &StudentFullReportResponse) -> Vec<TermMean> {
+    report
+        .periods
+        .iter()
+        .rev()
+        .filter_map(|period| {
+            let mut term = TermMean {
+                term_name: period.term_name.clone(),
+                ..Default::default()
+            };
+            let mut sum = 0.0;
+            let mut count = 0;
+            for subject in &report.subjects {
+                let score = subject
+                    .period_grades
+                    .iter()
+                    .find(|g| g.term_id == period.term_id)
+                    .and_then(|g| g.effective_score);
+                if let Some(score) = score {
+                    sum += score;
+                    count += 1;
+                    if score < report.grading_scale.passing_value {
+                        term.below_passing.push(subject.subject_name.clone());
+                    }
+                }
+            }
+            (count > 0).then(|| {
+                term.mean = sum / count as f64;
+                term
+            })
+        })
+        .collect()
+}
+
+/// `terms` vem do período mais recente para o mais antigo.
+fn grade_factors(scale: &GradingScale, terms: &[TermMean], factors: &mut Vec<RiskFactor>) {
+    let Some(last) = terms.first() else {
+        return;
+    };
+
+    if let Some(previous) = terms.get(1) {
+        let range = (scale.max_value - scale.min_value).max(f64::EPSILON);
+        // 1 ponto por ponto percentual da escala perdido na média
+        let drop_percent = (previous.mean - last.mean) / range * 100.0;
+        push_factor(
+            factors,
+            FACTOR_GRADE_DECLINE,
+            (drop_percent.round() as i32).clamp(0, 20),
+            20,
+            format!(
+                "Média caiu de {} ({}) para {} ({})",
+                scale.display(Some(previous.mean)).unwrap_or_default(),
+                previous.term_name,
+                scale.display(Some(last.mean)).unwrap_or_default(),
+                last.term_name
+            ),
+        );
+    }
+
+    let below = last.below_passing.len() as i32;
+    let label = if below == 1 { "disciplina" } else { "disciplinas" };
+    push_factor(
+        factors,
+        FACTOR_LOW_GRADES,
+        (below * 5).min(15),
+        15,
+        format!(
+            "{below} {label} abaixo da média em {}: {}",
+            last.term_name,
+            last.below_passing.join(", ")
+        ),
+    );
+}
+
+fn attendance_factors(
+    total: i32,
+    present: i32,
+    trend: Option<&TrendInput>,
+    min_attendance: f64,
+    factors: &mut Vec<RiskFactor>,
+) {
+    if let Some(percent) = presence_percent(total, present) {
+        let points = if percent < min_attendance {
+            20
+        } else if percent < min_attendance + ATTENDANCE_WARNING_MARGIN {
+            10
+        } else {
+            0
+        };
+        push_factor(
+            factors,
+            FACTOR_LOW_ATTENDANCE,
+            points,
+            20,
+            format!("Frequência de {percent:.1}% (mínimo {min_attendance:.1}%)").replace('.', ","),
+        );
+    }
+
+    let Some(a) = trend else {
+        return;
+    };
+    if let (Some(recent), Some(previous)) = (
+        presence_percent(a.recent_total, a.recent_present),
+        presence_percent(a.previous_total, a.previous_present),
+    ) {
+        // 1 ponto a cada 2 pontos percentuais de queda
+        let points = (((previous - recent) / 2.0).round() as i32).clamp(0, 10);
+        push_factor(
+            factors,
+            FACTOR_ATTENDANCE_TREND,
+            points,
+            10,
+            format!("Frequência caiu de {previous:.1}% para {recent:.1}% nos últimos {TREND_WINDOW_DAYS} dias")
+                .replace('.', ","),
+        );
+    }
+}
+
+/// Fatores que não pontuaram ficam de fora da explicação.
+fn push_factor(factors: &mut Vec<RiskFactor>, code: &str, points: i32, max_points: i32, detail: String) {
+    if points > 0 {
+        factors.push(RiskFactor {
+            code: code.to_string(),
+            points,
+            max_points,
+            detail,
+        });
+    }
+}
@@ -0,0 +1,211 @@
+//! Escalas de avaliação: numérica (qualquer faixa e casas decimais) ou conceitual
+//! (lista ordenada de conceitos, do melhor para o pior), atribuídas por série (`classes.grade`).
+//!
+//! Conceitos são gravados em `student_grades.score` pela posição: com N conceitos o melhor
+//! vale N-1 e o pior 0. Assim médias, recuperação e situação final usam a mesma conta.
+
+use axum::http::StatusCode;
+use serde::Serialize;
+use sqlx::Row;
+use uuid::Uuid;
+
+pub const SCALE_NUMERIC: &str = "numeric";
+pub const SCALE_CONCEPTUAL: &str = "conceptual";
+
+/// Faixa usada quando a série não tem escala atribuída.
+pub const DEFAULT_MAX_VALUE: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GradingScale {
+    /// `None` para a escala padrão (0 a 10).
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub scale_type: String,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub decimals: i32,
+    pub passing_value: f64,
+    pub concepts: Vec<String>,
+    pub passing_concept: Option<String>,
+}
+
+impl GradingScale {
+    pub fn default_numeric(passing_min_grade: f64) -> Self {
+        Self {
+            id: None,
+            name: "Padrão (0 a 10)".into(),
+            scale_type: SCALE_NUMERIC.into(),
+            min_value: 0.0,
+            max_value: DEFAULT_MAX_VALUE,
+            decimals: 2,
+            passing_value: passing_min_grade,
+            concepts: Vec::new(),
+            passing_concept: None,
+        }
+    }
+
+    pub fn is_conceptual(&self) -> bool {
+        self.scale_type == SCALE_CONCEPTUAL
+    }
+
+    pub fn round(&self, value: f64) -> f64 {
+        let factor = 10f64.powi(self.decimals);
+        (value * factor).round() / factor
+    }
+
+    /// Nota numérica digitada: dentro da faixa e arredondada à precisão da escala.
+    pub fn validate_score(&self, score: f64) -> Result<f64, (StatusCode, String)> {
+        if self.is_conceptual() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("A escala \"{}\" é conceitual; informe o conceito", self.name),
+            ));
+        }
+        if !score.is_finite() || score < self.min_value || score > self.max_value {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Nota deve estar entre {} e {}",
+                    self.format_number(self.min_value),
+                    self.format_number(self.max_value)
+                ),
+            ));
+        }
+        Ok(self.round(score))
+    }
+
+    pub fn concept_value(&self, label: &str) -> Result<f64, (StatusCode, String)> {
+        let label = label.trim();
+        self.concepts
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(label))
+            .map(|i| (self.concepts.len() - 1 - i) as f64)
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Conceito inválido (use {})", self.concepts.join(", ")),
+                )
+            })
+    }
+
+    /// Valor gravado para um lançamento do diário: nota nas escalas numéricas, conceito nas conceituais.
+    pub fn input_value(
+        &self,
+        score: Option<f64>,
+        concept: Option<&str>,
+    ) -> Result<Option<f64>, (StatusCode, String)> {
+        match (self.is_conceptual(), score, concept.map(str::trim).filter(|c| !c.is_empty())) {
+            (true, None, Some(concept)) => self.concept_value(concept).map(Some),
+            (true, _, _) => Err((StatusCode::BAD_REQUEST, "Conceito é obrigatório".into())),
+            (false, Some(score), None) => self.validate_score(score).map(Some),
+            (false, _, Some(_)) => Err((
+                StatusCode::BAD_REQUEST,
+                "Esta série usa escala numérica; informe a nota".into(),
+            )),
+            (false, None, None) => Ok(None),
+        }
+    }
+
+    /// Média das notas na própria escala; nas conceituais cai no conceito mais próximo.
+    pub fn average(&self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        Some(self.round(values.iter().sum::<f64>() / values.len() as f64))
+    }
+
+    /// Converte um valor da escala 0 a 10 (ex.: média da prova final) para esta escala.
+    pub fn rescale_from_ten(&self, value: f64) -> f64 {
+        self.round(self.min_value + value / DEFAULT_MAX_VALUE * (self.max_value - self.min_value))
+    }
+
+    /// Texto exibido nos boletins: conceito ou número com as casas da escala.
+    pub fn display(&self, value: Option<f64>) -> Option<String> {
+        let value = value?;
+        if self.is_conceptual() {
+            let n = self.concepts.len();
+            let idx = (value.round() as i64).clamp(0, n as i64 - 1) as usize;
+            return self.concepts.get(n - 1 - idx).cloned();
+        }
+        Some(self.format_number(value))
+    }
+
+    /// Valor de configuração expresso nesta escala (ex.: média mínima), já arredondado.
+    pub fn validate_setting(&self, label: &str, value: f64) -> Result<f64, (StatusCode, String)> {
+        if !value.is_finite() || value < self.min_value || value > self.max_value {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "{label} deve estar entre {} e {} na escala \"{}\"",
+                    self.format_number(self.min_value),
+                    self.format_number(self.max_value),
+                    self.name
+                ),
+            ));
+        }
+        Ok(self.round(value))
+    }
+
+    fn format_number(&self, value: f64) -> String {
+        format!("{:.*}", self.decimals.max(0) as usize, value).replace('.', ",")
+    }
+}
+
+/// Escala da série da turma, ou a padrão com a média mínima da escola.
+pub async fn scale_for_class<'e, E>(
+    executor: E,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<GradingScale, (StatusCode, String)>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let row = sqlx::query(
+        r#"
+        SELECT
+          t.passing_min_grade::float8 AS passing_min_grade,
+          gs.id AS scale_id,
+          gs.name,
+          gs.scale_type,
+          gs.min_value::float8 AS min_value,
+          gs.max_value::float8 AS max_value,
+          gs.decimals,
+          gs.passing_value::float8 AS passing_value,
+          gs.concepts,
+          gs.passing_concept
+        FROM classes c
+        JOIN tenants t ON t.id = c.tenant_id
+        LEFT JOIN grading_scale_assignments a
+          ON a.tenant_id = c.tenant_id AND a.class_grade = c.grade
+        LEFT JOIN grading_scales gs ON gs.id = a.scale_id AND gs.tenant_id = a.tenant_id
+        WHERE c.tenant_id = $1 AND c.id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))?;
+
+    Ok(scale_from_row(&row))
+}
+
+/// Linha com as colunas de `grading_scales` (+ `passing_min_grade` do tenant para o padrão).
+pub fn scale_from_row(row: &sqlx::postgres::PgRow) -> GradingScale {
+    let scale_id: Option<Uuid> = row.get("scale_id");
+    match scale_id {
+        None => GradingScale::default_numeric(row.get("passing_min_grade")),
+        Some(id) => GradingScale {
+            id: Some(id),
+            name: row.get("name"),
+            scale_type: row.get("scale_type"),
+            min_value: row.get("min_value"),
+            max_value: row.get("max_value"),
+            decimals: row.get("decimals"),
+            passing_value: row.get("passing_value"),
+            concepts: row.get("concepts"),
+            passing_concept: row.get("passing_concept"),
+        },
+    }
+}
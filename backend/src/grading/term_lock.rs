@@ -0,0 +1,146 @@
+//! Encerramento de período: com o período fechado, notas e chamadas só mudam por
+//! correção justificada de owner/admin, registrada em `term_corrections`.
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+
+pub const CORRECTION_GRADE: &str = "grade";
+pub const CORRECTION_ATTENDANCE: &str = "attendance";
+pub const CORRECTION_RECOVERY: &str = "recovery";
+pub const CORRECTION_ASSESSMENT_SCORE: &str = "assessment_score";
+pub const CORRECTION_LESSON: &str = "lesson";
+pub const CORRECTION_REOPEN: &str = "reopen";
+
+const CORRECTION_ROLES: &[&str] = &["owner", "admin"];
+const MIN_JUSTIFICATION_LEN: usize = 10;
+
+pub fn validate_justification(justification: Option<&str>) -> Result<&str, (StatusCode, String)> {
+    match justification.map(str::trim) {
+        Some(j) if j.chars().count() >= MIN_JUSTIFICATION_LEN => Ok(j),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Justificativa deve ter ao menos {MIN_JUSTIFICATION_LEN} caracteres"),
+        )),
+    }
+}
+
+pub async fn is_term_closed(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    term_id: Uuid,
+) -> Result<bool, (StatusCode, String)> {
+    let closed: Option<bool> = sqlx::query_scalar(
+        "SELECT closed_at IS NOT NULL FROM academic_terms WHERE tenant_id = $1 AND id = $2",
+    )
+    .bind(tenant_id)
+    .bind(term_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(closed.unwrap_or(false))
+}
+
+/// Período encerrado que contém a data (chamada não tem período; vale o intervalo de datas).
+pub async fn closed_term_for_date(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    date: NaiveDate,
+) -> Result<Option<Uuid>, (StatusCode, String)> {
+    sqlx::query_scalar(
+        r#"
+        SELECT id FROM academic_terms
+        WHERE tenant_id = $1 AND closed_at IS NOT NULL
+          AND start_date <= $2 AND end_date >= $2
+        ORDER BY start_date DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(date)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))
+}
+
+/// Estrutura do período (avaliações, pesos) não muda depois do encerramento, nem por correção.
+pub async fn ensure_term_open(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    term_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    if is_term_closed(tx, tenant_id, term_id).await? {
+        return Err((
+            StatusCode::CONFLICT,
+            "Período encerrado; reabra o período para alterar as avaliações".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Libera a escrita num período: aberto passa direto; encerrado exige justificativa
+/// de owner/admin e deixa o registro da correção na mesma transação.
+pub async fn ensure_writable(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    term_id: Option<Uuid>,
+    class_id: Uuid,
+    kind: &str,
+    justification: Option<&str>,
+    payload: serde_json::Value,
+) -> Result<(), (StatusCode, String)> {
+    let Some(term_id) = term_id else {
+        return Ok(());
+    };
+    if !is_term_closed(tx, user.tenant_id, term_id).await? {
+        return Ok(());
+    }
+
+    if justification.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            "Período encerrado; alterações exigem correção justificada de um administrador".into(),
+        ));
+    }
+    if !CORRECTION_ROLES.contains(&user.role.as_str()) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Somente administradores podem corrigir períodos encerrados".into(),
+        ));
+    }
+    let justification = validate_justification(justification)?;
+
+    record_correction(tx, user, term_id, Some(class_id), kind, justification, payload).await
+}
+
+pub async fn record_correction(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    term_id: Uuid,
+    class_id: Option<Uuid>,
+    kind: &str,
+    justification: &str,
+    payload: serde_json::Value,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query(
+        r#"
+        INSERT INTO term_corrections (id, tenant_id, term_id, class_id, user_id, kind, justification, payload)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .bind(class_id)
+    .bind(user.user_id)
+    .bind(kind)
+    .bind(justification)
+    .bind(payload)
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(())
+}
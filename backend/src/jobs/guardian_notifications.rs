@@ -0,0 +1,149 @@
+//! Entrega da fila `guardian_notifications` pelo canal de e-mail: cada mensagem vai em
+//! um POST JSON `{to, subject, body}` para o gateway em `NOTIFICATION_WEBHOOK_URL`.
+//! Sem gateway configurado as mensagens ficam na fila (visíveis em `/guardian-notifications`).
+//! O texto dos links mágicos do portal é montado na entrega: o token não passa pela fila.
+
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::env;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::routes::guardian_portal::issue_magic_link;
+
+/// Intervalo entre rodadas de entrega.
+const RUN_EVERY: Duration = Duration::from_secs(60);
+
+/// Mensagens por rodada.
+const BATCH_SIZE: i64 = 100;
+
+/// Depois disso a mensagem fica como `failed` e sai da fila.
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// Reserva (`sending`) mais antiga que isso é de instância que caiu no meio do envio.
+const CLAIM_TIMEOUT_MINUTES: i32 = 10;
+
+#[derive(Debug, Serialize)]
+struct EmailPayload<'a> {
+    to: &'a str,
+    subject: &'a str,
+    body: &'a str,
+}
+
+pub fn webhook_url() -> Option<String> {
+    env::var("NOTIFICATION_WEBHOOK_URL")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub async fn run(pool: PgPool) {
+    let Some(url) = webhook_url() else {
+        tracing::warn!("NOTIFICATION_WEBHOOK_URL não definido; alertas aos responsáveis ficam na fila");
+        return;
+    };
+    let http = match reqwest::Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Falha ao criar cliente HTTP de notificações: {e}");
+            return;
+        }
+    };
+
+    let mut ticker = tokio::time::interval(RUN_EVERY);
+    loop {
+        ticker.tick().await;
+        match deliver_pending(&pool, &http, &url).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("{count} alertas entregues aos responsáveis"),
+            Err(e) => tracing::error!("Falha ao entregar alertas: {e}"),
+        }
+    }
+}
+
+/// Envia um lote da fila; devolve quantas mensagens foram entregues. O lote é reservado
+/// antes do envio (`FOR UPDATE SKIP LOCKED`), então várias instâncias não mandam o mesmo
+/// e-mail.
+pub async fn deliver_pending(pool: &PgPool, http: &reqwest::Client, url: &str) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        UPDATE guardian_notifications
+        SET status = 'sending', claimed_at = NOW()
+        WHERE id IN (
+          SELECT id FROM guardian_notifications
+          WHERE channel = 'email'
+            AND (status = 'pending'
+                 OR (status = 'sending' AND claimed_at < NOW() - make_interval(mins => $2)))
+          ORDER BY created_at ASC
+          LIMIT $1
+          FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, recipient, subject, body, magic_link_id
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .bind(CLAIM_TIMEOUT_MINUTES)
+    .fetch_all(pool)
+    .await?;
+
+    let mut delivered = 0;
+    for r in rows {
+        let id: Uuid = r.get("id");
+        let recipient: String = r.get("recipient");
+        let subject: String = r.get("subject");
+        let body: String = match r.get::<Option<Uuid>, _>("magic_link_id") {
+            None => r.get("body"),
+            Some(link_id) => match issue_magic_link(pool, link_id).await? {
+                Some(body) => body,
+                None => {
+                    sqlx::query("UPDATE guardian_notifications SET status = 'failed', last_error = $2 WHERE id = $1")
+                        .bind(id)
+                        .bind("Link de acesso já usado ou removido")
+                        .execute(pool)
+                        .await?;
+                    continue;
+                }
+            },
+        };
+
+        let result = http
+            .post(url)
+            .json(&EmailPayload {
+                to: &recipient,
+                subject: &subject,
+                body: &body,
+            })
+            .send()
+            .await
+            .and_then(|res| res.error_for_status());
+
+        match result {
+            Ok(_) => {
+                sqlx::query(
+                    r#"UPDATE guardian_notifications
+                       SET status = 'sent', sent_at = NOW(), attempts = attempts + 1, last_error = NULL
+                       WHERE id = $1"#,
+                )
+                .bind(id)
+                .execute(pool)
+                .await?;
+                delivered += 1;
+            }
+            Err(e) => {
+                sqlx::query(
+                    r#"UPDATE guardian_notifications
+                       SET attempts = attempts + 1,
+                           last_error = $2,
+                           status = CASE WHEN attempts + 1 >= $3 THEN 'failed' ELSE 'pending' END
+                       WHERE id = $1"#,
+                )
+                .bind(id)
+                .bind(e.to_string())
+                .bind(MAX_ATTEMPTS)
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+    Ok(delivered)
+}
@@ -0,0 +1,6 @@
+pub mod usage_metrics;
+pub mod offboarding;
+pub mod guardian_notifications;
+pub mod report_cards;
+pub mod student_risk;
+pub mod timetable_generation;
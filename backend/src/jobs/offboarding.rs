@@ -0,0 +1,509 @@
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{Cursor, Write};
+use std::time::Duration;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+
+/// Intervalo entre verificações de exclusões vencidas.
+const RUN_EVERY: Duration = Duration::from_secs(60 * 60);
+
+/// Tabelas ligadas ao tenant sem coluna `tenant_id` (apagadas em cascata pelo pai).
+const INDIRECT_TABLES: &[(&str, &str)] = &[
+    (
+        "person_roles",
+        "person_id IN (SELECT id FROM people WHERE tenant_id = '{tenant_id}')",
+    ),
+    (
+        "transcript_external_subjects",
+        "record_id IN (SELECT id FROM transcript_external_records WHERE tenant_id = '{tenant_id}')",
+    ),
+    (
+        "report_share_link_accesses",
+        "link_id IN (SELECT id FROM report_share_links WHERE tenant_id = '{tenant_id}')",
+    ),
+    (
+        "guardian_portal_magic_links",
+        "account_id IN (SELECT id FROM guardian_portal_accounts WHERE tenant_id = '{tenant_id}')",
+    ),
+    (
+        "class_council_attendees",
+        "council_id IN (SELECT id FROM class_councils WHERE tenant_id = '{tenant_id}')",
+    ),
+];
+
+/// Tabelas sem dados de nenhuma escola: catálogos globais, controle de migrations e o
+/// próprio registro da saída, que sobrevive à exclusão.
+const GLOBAL_TABLES: &[&str] = &["_sqlx_migrations", "features", "person_types", "tenant_offboardings"];
+
+/// Tabelas da escola que entram na contagem da exclusão mas não no export: estado
+/// transitório de login, sem valor para a escola.
+const NOT_EXPORTED_TABLES: &[&str] = &["sso_login_states"];
+
+/// Credenciais que nunca saem no export.
+const REDACTED_COLUMNS: &[(&str, &str)] = &[
+    ("users", "password_hash"),
+    ("identities", "password_hash"),
+    ("identities", "totp_secret"),
+    ("tenant_sso_configs", "client_secret"),
+    ("guardian_portal_accounts", "password_hash"),
+    ("guardian_portal_magic_links", "token_hash"),
+    ("student_portal_accounts", "password_hash"),
+    ("report_share_links", "token_hash"),
+];
+
+#[derive(Debug, Serialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub table: String,
+    pub format: String,
+    pub rows: i64,
+    pub bytes: usize,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportManifest {
+    pub tenant_id: Uuid,
+    pub tenant_name: String,
+    pub tenant_slug: String,
+    pub generated_at: String,
+    pub files: Vec<ManifestFile>,
+}
+
+pub struct TenantExport {
+    pub file_path: String,
+    pub sha256: String,
+    pub manifest: ExportManifest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeletionReport {
+    pub row_counts: BTreeMap<String, i64>,
+    pub verified: bool,
+}
+
+pub fn export_dir() -> String {
+    let raw = env::var("EXPORT_DIR").unwrap_or_else(|_| "./exports".to_string());
+    raw.trim_end_matches('/').to_string()
+}
+
+/// Loop do job: executa as exclusões definitivas cuja janela de retenção venceu.
+pub async fn run(pool: PgPool) {
+    let mut ticker = tokio::time::interval(RUN_EVERY);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = execute_due_deletions(&pool).await {
+            tracing::error!("Falha ao processar exclusões de escolas: {e}");
+        }
+    }
+}
+
+pub async fn execute_due_deletions(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let due: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM tenant_offboardings
+        WHERE status = 'scheduled'
+          AND delete_after <= NOW()
+        ORDER BY delete_after ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for offboarding_id in due {
+        match hard_delete_tenant(pool, offboarding_id).await {
+            Ok(report) => tracing::info!(
+                "Escola excluída (offboarding {offboarding_id}), verificado = {}",
+                report.verified
+            ),
+            Err(e) => {
+                tracing::error!("Falha ao excluir escola (offboarding {offboarding_id}): {e}");
+                sqlx::query(
+                    r#"UPDATE tenant_offboardings
+                       SET status = 'failed', error_message = $2
+                       WHERE id = $1"#,
+                )
+                .bind(offboarding_id)
+                .bind(e.to_string())
+                .execute(pool)
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lista as tabelas com dados da escola e o filtro de cada uma.
+async fn tenant_tables(pool: &PgPool, tenant_id: Uuid) -> Result<Vec<(String, String)>, sqlx::Error> {
+    let names: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT c.table_name::text
+        FROM information_schema.columns c
+        JOIN information_schema.tables t
+          ON t.table_schema = c.table_schema
+         AND t.table_name = c.table_name
+        WHERE c.table_schema = 'public'
+          AND c.column_name = 'tenant_id'
+          AND t.table_type = 'BASE TABLE'
+          ORDER BY c.table_name ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tables = vec![("tenants".to_string(), format!("id = '{tenant_id}'"))];
+    tables.extend(
+        names
+            .into_iter()
+            .filter(|name| !GLOBAL_TABLES.contains(&name.as_str()))
+            .map(|name| (name, format!("tenant_id = '{tenant_id}'"))),
+    );
+    tables.extend(INDIRECT_TABLES.iter().map(|(name, filter)| {
+        (name.to_string(), filter.replace("{tenant_id}", &tenant_id.to_string()))
+    }));
+    Ok(tables)
+}
+
+/// Colunas exportadas da tabela, sem as de `REDACTED_COLUMNS`.
+async fn export_columns(pool: &PgPool, table: &str) -> Result<String, sqlx::Error> {
+    let columns: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT column_name::text
+        FROM information_schema.columns
+        WHERE table_schema = 'public' AND table_name = $1
+        ORDER BY ordinal_position ASC
+        "#,
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(columns
+        .iter()
+        .filter(|column| !REDACTED_COLUMNS.contains(&(table, column.as_str())))
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
+async fn count_rows(pool: &PgPool, table: &str, filter: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE {filter}", quote_ident(table)))
+        .fetch_one(pool)
+        .await
+}
+
+/// Gera em `dir` o zip com CSV + JSON de cada tabela, `manifest.json` e `SHA256SUMS`.
+pub async fn export_tenant(pool: &PgPool, tenant_id: Uuid, dir: &str) -> Result<TenantExport, String> {
+    let tenant_row = sqlx::query("SELECT name, slug FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Escola não encontrada".to_string())?;
+    let tenant_name: String = tenant_row.get("name");
+    let tenant_slug: String = tenant_row.get("slug");
+
+    let tables = tenant_tables(pool, tenant_id).await.map_err(|e| e.to_string())?;
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut manifest_files = Vec::new();
+
+    for (table, filter) in &tables {
+        if NOT_EXPORTED_TABLES.contains(&table.as_str()) {
+            continue;
+        }
+        let rows = count_rows(pool, table, filter).await.map_err(|e| e.to_string())?;
+        let select = format!(
+            "SELECT {} FROM {} WHERE {filter}",
+            export_columns(pool, table).await.map_err(|e| e.to_string())?,
+            quote_ident(table)
+        );
+
+        let json: String = sqlx::query_scalar(&format!(
+            "SELECT COALESCE(json_agg(t), '[]'::json)::text FROM ({select}) t"
+        ))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        let mut stream = conn
+            .copy_out_raw(&format!("COPY ({select}) TO STDOUT WITH (FORMAT csv, HEADER true)"))
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut csv = Vec::new();
+        while let Some(chunk) = stream.try_next().await.map_err(|e| e.to_string())? {
+            csv.extend_from_slice(&chunk);
+        }
+        drop(stream);
+
+        for (format, bytes) in [("csv", csv), ("json", json.into_bytes())] {
+            let path = format!("{format}/{table}.{format}");
+            manifest_files.push(ManifestFile {
+                path: path.clone(),
+                table: table.clone(),
+                format: format.to_string(),
+                rows,
+                bytes: bytes.len(),
+                sha256: sha256_hex(&bytes),
+            });
+            files.push((path, bytes));
+        }
+    }
+
+    let manifest = ExportManifest {
+        tenant_id,
+        tenant_name,
+        tenant_slug,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        files: manifest_files,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    let mut checksums = String::new();
+    for f in &manifest.files {
+        checksums.push_str(&format!("{}  {}\n", f.sha256, f.path));
+    }
+    checksums.push_str(&format!("{}  manifest.json\n", sha256_hex(&manifest_json)));
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    files.push(("manifest.json".to_string(), manifest_json));
+    files.push(("SHA256SUMS".to_string(), checksums.into_bytes()));
+    for (path, bytes) in &files {
+        zip.start_file(path.as_str(), options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    let archive = zip.finish().map_err(|e| e.to_string())?.into_inner();
+
+    tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+    let file_path = format!(
+        "{dir}/{}-{}.zip",
+        manifest.tenant_slug,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    );
+    tokio::fs::write(&file_path, &archive).await.map_err(|e| e.to_string())?;
+
+    Ok(TenantExport {
+        file_path,
+        sha256: sha256_hex(&archive),
+        manifest,
+    })
+}
+
+/// Exclusão definitiva: conta as linhas por tabela, apaga o tenant (o resto sai via
+/// `ON DELETE CASCADE`) e reconta para confirmar que nada ficou para trás.
+/// O zip exportado também é removido, pois a janela de retenção já venceu.
+pub async fn hard_delete_tenant(pool: &PgPool, offboarding_id: Uuid) -> Result<DeletionReport, sqlx::Error> {
+    let row = sqlx::query(
+        r#"SELECT tenant_id, export_file
+           FROM tenant_offboardings
+           WHERE id = $1 AND status = 'scheduled'"#,
+    )
+    .bind(offboarding_id)
+    .fetch_one(pool)
+    .await?;
+    let tenant_id: Uuid = row.get("tenant_id");
+    let export_file: String = row.get("export_file");
+
+    let tables = tenant_tables(pool, tenant_id).await?;
+    let mut row_counts = BTreeMap::new();
+    for (table, filter) in &tables {
+        row_counts.insert(table.clone(), count_rows(pool, table, filter).await?);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .await?;
+    // identidades que ficaram sem nenhuma escola também saem (e-mail + senha)
+    sqlx::query(
+        r#"DELETE FROM identities i
+           WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.identity_id = i.id)"#,
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let mut verified = true;
+    for (table, filter) in &tables {
+        if count_rows(pool, table, filter).await? != 0 {
+            tracing::error!("Tabela {table} ainda possui dados da escola {tenant_id}");
+            verified = false;
+        }
+    }
+
+    if let Err(e) = tokio::fs::remove_file(&export_file).await {
+        tracing::warn!("Não foi possível remover o export {export_file}: {e}");
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE tenant_offboardings
+        SET status = 'deleted',
+            deleted_at = NOW(),
+            deleted_row_counts = $2,
+            deletion_verified = $3
+        WHERE id = $1
+        "#,
+    )
+    .bind(offboarding_id)
+    .bind(sqlx::types::Json(&row_counts))
+    .bind(verified)
+    .execute(pool)
+    .await?;
+
+    Ok(DeletionReport { row_counts, verified })
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_class, insert_tenant, insert_user, test_pool};
+    use std::io::Read;
+
+    #[tokio::test]
+    async fn export_then_hard_delete_reports_counts_and_verifies() {
+        let pool = test_pool().await;
+        let dir = std::env::temp_dir().join("offboarding-test");
+        let tenant_id = insert_tenant(&pool, "Escola Saída").await;
+        for name in ["Turma A", "Turma B"] {
+            insert_class(&pool, tenant_id, name, "1 ano", 2026).await;
+        }
+
+        let user_id = insert_user(&pool, tenant_id, "Secretaria", "staff").await;
+        sqlx::query("UPDATE users SET password_hash = 'hash-secreto' WHERE id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .expect("falha ao definir senha");
+
+        let export = export_tenant(&pool, tenant_id, dir.to_str().unwrap()).await.expect("falha no export");
+        let classes_csv = export
+            .manifest
+            .files
+            .iter()
+            .find(|f| f.path == "csv/classes.csv")
+            .expect("classes.csv ausente no manifest");
+        assert_eq!(classes_csv.rows, 2);
+
+        let bytes = std::fs::read(&export.file_path).expect("zip não gravado");
+        assert_eq!(sha256_hex(&bytes), export.sha256);
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).expect("zip inválido");
+        let mut csv = String::new();
+        archive
+            .by_name("csv/classes.csv")
+            .expect("csv ausente no zip")
+            .read_to_string(&mut csv)
+            .expect("csv ilegível");
+        assert_eq!(sha256_hex(csv.as_bytes()), classes_csv.sha256);
+        assert!(archive.by_name("SHA256SUMS").is_ok());
+        for path in ["csv/users.csv", "json/users.json"] {
+            let mut users = String::new();
+            archive
+                .by_name(path)
+                .expect("usuários ausentes no zip")
+                .read_to_string(&mut users)
+                .expect("usuários ilegíveis");
+            assert!(users.contains("Secretaria"));
+            assert!(!users.contains("password_hash") && !users.contains("hash-secreto"));
+        }
+        assert!(archive.by_name("csv/sso_login_states.csv").is_err());
+
+        let offboarding_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO tenant_offboardings (
+                 id, tenant_id, tenant_name, tenant_slug, export_file, export_sha256, manifest, delete_after
+               )
+               VALUES ($1, $2, 'Escola Saída', 'escola-saida', $3, $4, '{}'::jsonb, NOW())"#,
+        )
+        .bind(offboarding_id)
+        .bind(tenant_id)
+        .bind(&export.file_path)
+        .bind(&export.sha256)
+        .execute(&pool)
+        .await
+        .expect("falha ao agendar exclusão");
+
+        let report = hard_delete_tenant(&pool, offboarding_id).await.expect("falha na exclusão");
+        assert!(report.verified);
+        assert_eq!(report.row_counts.get("classes"), Some(&2));
+        assert_eq!(report.row_counts.get("tenants"), Some(&1));
+        assert!(!std::path::Path::new(&export.file_path).exists());
+
+        let status: String = sqlx::query_scalar("SELECT status FROM tenant_offboardings WHERE id = $1")
+            .bind(offboarding_id)
+            .fetch_one(&pool)
+            .await
+            .expect("offboarding ausente");
+        assert_eq!(status, "deleted");
+
+        sqlx::query("DELETE FROM tenant_offboardings WHERE id = $1")
+            .bind(offboarding_id)
+            .execute(&pool)
+            .await
+            .expect("falha ao limpar offboarding");
+    }
+
+    #[tokio::test]
+    async fn every_table_is_exported_or_excluded() {
+        let pool = test_pool().await;
+        let all: Vec<String> = sqlx::query_scalar(
+            r#"SELECT table_name::text
+               FROM information_schema.tables
+               WHERE table_schema = 'public' AND table_type = 'BASE TABLE'"#,
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("falha ao listar tabelas");
+        let covered: Vec<String> = tenant_tables(&pool, Uuid::nil())
+            .await
+            .expect("falha ao listar tabelas da escola")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        for table in all {
+            assert!(
+                covered.contains(&table) || GLOBAL_TABLES.contains(&table.as_str()),
+                "tabela {table} sem tenant_id: registre em INDIRECT_TABLES ou GLOBAL_TABLES"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn credential_columns_are_redacted() {
+        let pool = test_pool().await;
+        let columns: Vec<(String, String)> = sqlx::query_as(
+            r#"SELECT table_name::text, column_name::text
+               FROM information_schema.columns
+               WHERE table_schema = 'public'
+                 AND column_name ~ '(password|secret|token)'"#,
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("falha ao listar colunas");
+
+        for (table, column) in columns {
+            assert!(
+                NOT_EXPORTED_TABLES.contains(&table.as_str())
+                    || REDACTED_COLUMNS.contains(&(table.as_str(), column.as_str())),
+                "coluna {table}.{column} iria para o export: registre em REDACTED_COLUMNS"
+            );
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Geração de boletins em lote (`report_card_batches`): uma turma ou todas as turmas do
+//! ano letivo, num único PDF com todos os alunos ou num zip com um PDF por aluno.
+
+use sqlx::{PgPool, Row};
+use std::env;
+use std::io::{Cursor, Write};
+use std::time::Duration;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+
+use crate::reports::report_cards::{load_branding, ReportCardDocument};
+use crate::routes::records::{fetch_student_full_report, fetch_student_term_report};
+
+/// Intervalo entre verificações da fila.
+const RUN_EVERY: Duration = Duration::from_secs(15);
+
+/// Atualiza o progresso a cada N alunos.
+const PROGRESS_EVERY: i32 = 10;
+
+pub fn reports_dir() -> String {
+    let raw = env::var("REPORTS_DIR").unwrap_or_else(|_| "./reports".to_string());
+    raw.trim_end_matches('/').to_string()
+}
+
+pub async fn run(pool: PgPool) {
+    // lote interrompido por reinício do servidor volta para a fila
+    if let Err(e) = sqlx::query("UPDATE report_card_batches SET status = 'queued' WHERE status = 'running'")
+        .execute(&pool)
+        .await
+    {
+        tracing::error!("Falha ao reenfileirar lotes de boletins: {e}");
+    }
+
+    let dir = reports_dir();
+    let mut ticker = tokio::time::interval(RUN_EVERY);
+    loop {
+        ticker.tick().await;
+        loop {
+            match process_next(&pool, &dir).await {
+                Ok(Some(batch_id)) => tracing::info!("Lote de boletins {batch_id} processado"),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Falha ao processar lote de boletins: {e}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+struct Batch {
+    id: Uuid,
+    tenant_id: Uuid,
+    kind: String,
+    term_id: Option<Uuid>,
+    class_id: Option<Uuid>,
+    school_year: i32,
+    format: String,
+}
+
+/// Processa o lote mais antigo da fila, gravando o arquivo em `dir`; `None` quando não
+/// há nada a fazer.
+pub async fn process_next(pool: &PgPool, dir: &str) -> Result<Option<Uuid>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        UPDATE report_card_batches
+        SET status = 'running', started_at = NOW(), processed_students = 0, error = NULL
+        WHERE id = (
+          SELECT id FROM report_card_batches
+          WHERE status = 'queued'
+          ORDER BY created_at ASC
+          LIMIT 1
+          FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, tenant_id, kind, term_id, class_id, school_year, format
+        "#,
+    )
+    .fetch_optional(pool)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let batch = Batch {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        kind: row.get("kind"),
+        term_id: row.get("term_id"),
+        class_id: row.get("class_id"),
+        school_year: row.get("school_year"),
+        format: row.get("format"),
+    };
+
+    match generate(pool, &batch, dir).await {
+        Ok((file_path, file_size)) => {
+            sqlx::query(
+                r#"UPDATE report_card_batches
+                   SET status = 'done', file_path = $2, file_size = $3, finished_at = NOW()
+                   WHERE id = $1"#,
+            )
+            .bind(batch.id)
+            .bind(file_path)
+            .bind(file_size as i64)
+            .execute(pool)
+            .await?;
+        }
+        Err(e) => {
+            sqlx::query(
+                r#"UPDATE report_card_batches
+                   SET status = 'failed', error = $2, finished_at = NOW()
+                   WHERE id = $1"#,
+            )
+            .bind(batch.id)
+            .bind(e)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(Some(batch.id))
+}
+
+async fn generate(pool: &PgPool, batch: &Batch, dir: &str) -> Result<(String, usize), String> {
+    let students = sqlx::query(
+        r#"
+        SELECT s.id AS student_id, s.name, s.registration, c.id AS class_id, c.name AS class_name
+        FROM students s
+        JOIN classes c ON c.id = s.class_id AND c.tenant_id = s.tenant_id
+        WHERE s.tenant_id = $1
+          AND ($2::uuid IS NULL OR c.id = $2)
+          AND c.year = $3
+        ORDER BY c.name ASC, s.name ASC
+        "#,
+    )
+    .bind(batch.tenant_id)
+    .bind(batch.class_id)
+    .bind(batch.school_year)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if students.is_empty() {
+        return Err("Nenhum aluno encontrado para o lote".into());
+    }
+    set_progress(pool, batch.id, Some(students.len() as i32), 0).await?;
+
+    let branding = load_branding(pool, batch.tenant_id).await.map_err(|(_, msg)| msg)?;
+    let mut merged = ReportCardDocument::new(branding.clone());
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for (i, s) in students.iter().enumerate() {
+        let student_id: Uuid = s.get("student_id");
+        let class_id: Uuid = s.get("class_id");
+        let mut single = ReportCardDocument::new(branding.clone());
+        let target = if batch.format == "zip" { &mut single } else { &mut merged };
+
+        if batch.kind == "term" {
+            let term_id = batch.term_id.ok_or("Lote de período sem term_id")?;
+            let report = fetch_student_term_report(pool, batch.tenant_id, class_id, student_id, term_id)
+                .await
+                .map_err(|(_, msg)| msg)?;
+            target.add_term_report(&report);
+        } else {
+            let report = fetch_student_full_report(pool, batch.tenant_id, class_id, student_id)
+                .await
+                .map_err(|(_, msg)| msg)?;
+            target.add_full_report(&report);
+        }
+
+        if batch.format == "zip" {
+            let class_name: String = s.get("class_name");
+            let name: String = s.get("name");
+            let registration: String = s.get("registration");
+            let path = format!(
+                "{}/{}-{}.pdf",
+                file_slug(&class_name),
+                file_slug(&name),
+                file_slug(&registration)
+            );
+            zip.start_file(path, options).map_err(|e| e.to_string())?;
+            zip.write_all(&single.to_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        let done = i as i32 + 1;
+        if done % PROGRESS_EVERY == 0 {
+            set_progress(pool, batch.id, None, done).await?;
+        }
+    }
+    set_progress(pool, batch.id, None, students.len() as i32).await?;
+
+    let bytes = if batch.format == "zip" {
+        zip.finish().map_err(|e| e.to_string())?.into_inner()
+    } else {
+        merged.to_bytes()
+    };
+
+    let dir = format!("{dir}/{}", batch.tenant_id);
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+    let file_path = format!("{dir}/boletins-{}.{}", batch.id, batch.format);
+    tokio::fs::write(&file_path, &bytes).await.map_err(|e| e.to_string())?;
+    Ok((file_path, bytes.len()))
+}
+
+async fn set_progress(pool: &PgPool, batch_id: Uuid, total: Option<i32>, processed: i32) -> Result<(), String> {
+    sqlx::query(
+        r#"UPDATE report_card_batches
+           SET total_students = COALESCE($2, total_students), processed_students = $3
+           WHERE id = $1"#,
+    )
+    .bind(batch_id)
+    .bind(total)
+    .bind(processed)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Nome de arquivo seguro dentro do zip.
+fn file_slug(input: &str) -> String {
+    let slug: String = input
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|p| !p.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() {
+        "sem-nome".into()
+    } else {
+        slug
+    }
+}
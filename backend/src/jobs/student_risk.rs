@@ -0,0 +1,138 @@
+//! Snapshot diário do indicador de risco de todos os alunos ativos, para acompanhar a
+//! tendência (`/students/:student_id/risk-history`).
+
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::grading::risk::assess_students;
+
+/// Hora local do snapshot noturno.
+const RUN_AT_HOUR: u32 = 2;
+
+/// Loop do job: dorme até a próxima execução às `RUN_AT_HOUR` horas e registra o snapshot
+/// do dia. O snapshot é sobrescrito, então reexecuções são seguras.
+pub async fn run(pool: PgPool) {
+    loop {
+        tokio::time::sleep(until_next_run(chrono::Local::now().naive_local())).await;
+        let today = chrono::Local::now().date_naive();
+        match snapshot_once(&pool, today).await {
+            Ok(Some(count)) => tracing::info!("Indicador de risco registrado para {count} alunos"),
+            Ok(None) => {}
+            Err(e) => tracing::error!("Falha ao registrar indicador de risco: {e}"),
+        }
+    }
+}
+
+fn until_next_run(now: NaiveDateTime) -> Duration {
+    let run_at = now
+        .date()
+        .and_hms_opt(RUN_AT_HOUR, 0, 0)
+        .expect("hora do snapshot válida");
+    let next = if now < run_at { run_at } else { run_at + chrono::Duration::days(1) };
+    (next - now).to_std().unwrap_or_default()
+}
+
+/// Com várias instâncias do servidor, só a que pegar o lock registra o snapshot do dia;
+/// `None` quando outra instância está rodando ou já registrou.
+async fn snapshot_once(pool: &PgPool, date: NaiveDate) -> Result<Option<u64>, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext('student_risk_snapshot'))")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !locked {
+        return Ok(None);
+    }
+
+    let result = async {
+        let done: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM student_risk_snapshots WHERE snapshot_date = $1)")
+                .bind(date)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        if done {
+            return Ok(None);
+        }
+        snapshot_all_tenants(pool, date).await.map(Some)
+    }
+    .await;
+
+    sqlx::query("SELECT pg_advisory_unlock(hashtext('student_risk_snapshot'))")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    result
+}
+
+pub async fn snapshot_all_tenants(pool: &PgPool, date: NaiveDate) -> Result<u64, String> {
+    let tenant_ids: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM tenants ORDER BY created_at ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut total = 0;
+    for tenant_id in tenant_ids {
+        // uma escola com problema não impede as demais
+        match snapshot_tenant(pool, tenant_id, date).await {
+            Ok(count) => total += count,
+            Err(e) => tracing::error!("Falha no indicador de risco da escola {tenant_id}: {e}"),
+        }
+    }
+    Ok(total)
+}
+
+pub async fn snapshot_tenant(pool: &PgPool, tenant_id: Uuid, date: NaiveDate) -> Result<u64, String> {
+    let students = assess_students(pool, tenant_id, None, date)
+        .await
+        .map_err(|(_, msg)| msg)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for s in &students {
+        let factors = serde_json::to_value(&s.factors).map_err(|e| e.to_string())?;
+        sqlx::query(
+            r#"
+            INSERT INTO student_risk_snapshots (tenant_id, student_id, snapshot_date, class_id, score, level, factors)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (tenant_id, student_id, snapshot_date)
+            DO UPDATE SET
+              class_id = EXCLUDED.class_id,
+              score = EXCLUDED.score,
+              level = EXCLUDED.level,
+              factors = EXCLUDED.factors,
+              created_at = NOW()
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(s.student_id)
+        .bind(date)
+        .bind(s.class_id)
+        .bind(s.score)
+        .bind(&s.level)
+        .bind(factors)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(students.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn next_run_is_the_coming_night() {
+        assert_eq!(until_next_run(at("2026-05-31", "01:30:00")), Duration::from_secs(30 * 60));
+        assert_eq!(until_next_run(at("2026-05-31", "02:00:00")), Duration::from_secs(24 * 60 * 60));
+        assert_eq!(until_next_run(at("2026-05-31", "23:00:00")), Duration::from_secs(3 * 60 * 60));
+    }
+}
@@ -0,0 +1,480 @@
+//! Gerador automático de horários (`timetable_generations`): monta a grade das turmas a
+//! partir das aulas semanais configuradas e grava em `timetable_slots` quando todas as
+//! aulas couberem. Cada disciplina da turma continua na sala em que já estava na grade
+//! (a mais usada, quando havia mais de uma), e a sala entra como restrição do gerador.
+
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::scheduling::solver::{solve, Problem, Requirement, Solution};
+use crate::scheduling::Period;
+
+/// Intervalo entre verificações da fila.
+const RUN_EVERY: Duration = Duration::from_secs(15);
+
+/// Intervalo entre atualizações do progresso durante a geração.
+const PROGRESS_EVERY: Duration = Duration::from_secs(1);
+
+pub async fn run(pool: PgPool) {
+    // geração interrompida por reinício do servidor volta para a fila
+    if let Err(e) = sqlx::query("UPDATE timetable_generations SET status = 'queued' WHERE status = 'running'")
+        .execute(&pool)
+        .await
+    {
+        tracing::error!("Falha ao reenfileirar gerações de horário: {e}");
+    }
+
+    let mut ticker = tokio::time::interval(RUN_EVERY);
+    loop {
+        ticker.tick().await;
+        loop {
+            match process_next(&pool).await {
+                Ok(Some(id)) => tracing::info!("Geração de horário {id} processada"),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Falha ao processar geração de horário: {e}");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+struct Generation {
+    id: Uuid,
+    tenant_id: Uuid,
+    school_year: i32,
+    class_ids: Option<Vec<Uuid>>,
+    weekdays: Vec<i32>,
+    periods: Vec<Period>,
+}
+
+struct RequirementRow {
+    class_id: Uuid,
+    class_name: String,
+    subject_id: Uuid,
+    subject_name: String,
+    teacher_user_id: Option<Uuid>,
+    room: Option<String>,
+}
+
+#[derive(Serialize)]
+struct UnplacedLessons {
+    class_id: Uuid,
+    class_name: String,
+    subject_id: Uuid,
+    subject_name: String,
+    lessons: usize,
+}
+
+/// Processa a geração mais antiga da fila; `None` quando não há nada a fazer.
+pub async fn process_next(pool: &PgPool) -> Result<Option<Uuid>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        UPDATE timetable_generations
+        SET status = 'running', started_at = NOW(), progress = 0, placed_lessons = 0, error = NULL
+        WHERE id = (
+          SELECT id FROM timetable_generations
+          WHERE status = 'queued'
+          ORDER BY created_at ASC
+          LIMIT 1
+          FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, tenant_id, school_year, class_ids, weekdays, periods
+        "#,
+    )
+    .fetch_optional(pool)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let generation = Generation {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        school_year: row.get("school_year"),
+        class_ids: row.get("class_ids"),
+        weekdays: row.get("weekdays"),
+        periods: row.get::<sqlx::types::Json<Vec<Period>>, _>("periods").0,
+    };
+
+    let outcome = generate(pool, &generation).await;
+    let (status, error) = match &outcome {
+        Ok(_) => ("done", None),
+        Err(e) => ("failed", Some(e.clone())),
+    };
+    sqlx::query(
+        r#"UPDATE timetable_generations
+           SET status = $2, error = $3, finished_at = NOW(),
+               progress = CASE WHEN $2 = 'done' THEN 100 ELSE progress END
+           WHERE id = $1"#,
+    )
+    .bind(generation.id)
+    .bind(status)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(Some(generation.id))
+}
+
+async fn generate(pool: &PgPool, generation: &Generation) -> Result<(), String> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.class_id, c.name AS class_name, r.subject_id, s.name AS subject_name,
+               r.lessons_per_week, r.double_lessons, r.teacher_user_id
+        FROM timetable_requirements r
+        JOIN classes c ON c.id = r.class_id AND c.tenant_id = r.tenant_id
+        JOIN subjects s ON s.id = r.subject_id
+        WHERE r.tenant_id = $1
+          AND c.year = $2
+          AND ($3::uuid[] IS NULL OR r.class_id = ANY($3))
+        ORDER BY c.name ASC, s.name ASC
+        "#,
+    )
+    .bind(generation.tenant_id)
+    .bind(generation.school_year)
+    .bind(&generation.class_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if rows.is_empty() {
+        return Err("Nenhuma turma com aulas semanais configuradas".into());
+    }
+
+    // sala atual de cada disciplina da turma; a mais usada vem primeiro
+    let room_rows = sqlx::query(
+        r#"
+        SELECT ts.class_id, ts.subject_id, MIN(ts.room) AS room
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        WHERE ts.tenant_id = $1
+          AND c.year = $2
+          AND ($3::uuid[] IS NULL OR ts.class_id = ANY($3))
+          AND ts.room IS NOT NULL
+        GROUP BY ts.class_id, ts.subject_id, lower(ts.room)
+        ORDER BY ts.class_id, ts.subject_id, COUNT(*) DESC, lower(ts.room) ASC
+        "#,
+    )
+    .bind(generation.tenant_id)
+    .bind(generation.school_year)
+    .bind(&generation.class_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut current_rooms: HashMap<(Uuid, Uuid), String> = HashMap::new();
+    for r in room_rows {
+        current_rooms
+            .entry((r.get("class_id"), r.get("subject_id")))
+            .or_insert_with(|| r.get("room"));
+    }
+
+    let mut class_ids: Vec<Uuid> = Vec::new();
+    let mut teacher_ids: Vec<Uuid> = Vec::new();
+    // salas pelo nome em minúsculas, como na checagem de conflitos da grade
+    let mut room_keys: Vec<String> = Vec::new();
+    let mut requirements = Vec::new();
+    let mut details = Vec::new();
+    for r in &rows {
+        let class_id: Uuid = r.get("class_id");
+        let subject_id: Uuid = r.get("subject_id");
+        let teacher_user_id: Option<Uuid> = r.get("teacher_user_id");
+        let room = current_rooms.get(&(class_id, subject_id)).cloned();
+        let class = index_of(&mut class_ids, class_id);
+        let teacher = teacher_user_id.map(|t| index_of(&mut teacher_ids, t));
+        requirements.push(Requirement {
+            class,
+            teacher,
+            room: room.as_ref().map(|name| index_of(&mut room_keys, name.to_lowercase())),
+            lessons: r.get::<i32, _>("lessons_per_week") as usize,
+            doubles: r.get::<i32, _>("double_lessons") as usize,
+        });
+        details.push(RequirementRow {
+            class_id,
+            class_name: r.get("class_name"),
+            subject_id,
+            subject_name: r.get("subject_name"),
+            teacher_user_id,
+            room,
+        });
+    }
+
+    let days = generation.weekdays.len();
+    let periods = generation.periods.len();
+    let slot_of = |weekday: i32, start: chrono::NaiveTime, end: chrono::NaiveTime| -> Vec<usize> {
+        let Some(day) = generation.weekdays.iter().position(|w| *w == weekday) else {
+            return Vec::new();
+        };
+        generation
+            .periods
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.start_time < end && start < p.end_time)
+            .map(|(p, _)| day * periods + p)
+            .collect()
+    };
+
+    let mut teacher_unavailable = vec![vec![false; days * periods]; teacher_ids.len()];
+    let windows = sqlx::query(
+        r#"SELECT teacher_user_id, weekday, start_time, end_time
+           FROM teacher_unavailability
+           WHERE tenant_id = $1 AND teacher_user_id = ANY($2)"#,
+    )
+    .bind(generation.tenant_id)
+    .bind(&teacher_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for w in windows {
+        let teacher_user_id: Uuid = w.get("teacher_user_id");
+        let Some(t) = teacher_ids.iter().position(|id| *id == teacher_user_id) else { continue };
+        for s in slot_of(w.get("weekday"), w.get("start_time"), w.get("end_time")) {
+            teacher_unavailable[t][s] = true;
+        }
+    }
+
+    // aulas já marcadas em turmas que ficam de fora desta geração
+    let mut teacher_busy = vec![vec![false; days * periods]; teacher_ids.len()];
+    let lessons = sqlx::query(
+        r#"
+        SELECT ts.teacher_user_id, ts.weekday, ts.start_time, ts.end_time
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        WHERE ts.tenant_id = $1
+          AND c.year = $2
+          AND ts.teacher_user_id = ANY($3)
+          AND NOT (ts.class_id = ANY($4))
+        "#,
+    )
+    .bind(generation.tenant_id)
+    .bind(generation.school_year)
+    .bind(&teacher_ids)
+    .bind(&class_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for l in lessons {
+        let teacher_user_id: Uuid = l.get("teacher_user_id");
+        let Some(t) = teacher_ids.iter().position(|id| *id == teacher_user_id) else { continue };
+        for s in slot_of(l.get("weekday"), l.get("start_time"), l.get("end_time")) {
+            teacher_busy[t][s] = true;
+        }
+    }
+
+    // salas ocupadas por turmas que ficam de fora desta geração
+    let mut room_busy = vec![vec![false; days * periods]; room_keys.len()];
+    let room_lessons = sqlx::query(
+        r#"
+        SELECT lower(ts.room) AS room, ts.weekday, ts.start_time, ts.end_time
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        WHERE ts.tenant_id = $1
+          AND c.year = $2
+          AND lower(ts.room) = ANY($3)
+          AND NOT (ts.class_id = ANY($4))
+        "#,
+    )
+    .bind(generation.tenant_id)
+    .bind(generation.school_year)
+    .bind(&room_keys)
+    .bind(&class_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for l in room_lessons {
+        let room: String = l.get("room");
+        let Some(r) = room_keys.iter().position(|key| *key == room) else { continue };
+        for s in slot_of(l.get("weekday"), l.get("start_time"), l.get("end_time")) {
+            room_busy[r][s] = true;
+        }
+    }
+
+    let problem = Problem {
+        days,
+        periods,
+        joined: generation
+            .periods
+            .windows(2)
+            .map(|w| w[0].end_time == w[1].start_time)
+            .chain(std::iter::once(false))
+            .collect(),
+        classes: class_ids.len(),
+        teacher_unavailable,
+        teacher_busy,
+        room_busy,
+        requirements,
+    };
+    let total: usize = problem.requirements.iter().map(|r| r.lessons).sum();
+    sqlx::query("UPDATE timetable_generations SET total_lessons = $2 WHERE id = $1")
+        .bind(generation.id)
+        .bind(total as i32)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let solution = run_solver(pool, generation.id, problem).await?;
+
+    let unplaced: Vec<UnplacedLessons> = solution
+        .unplaced
+        .iter()
+        .map(|(r, lessons)| UnplacedLessons {
+            class_id: details[*r].class_id,
+            class_name: details[*r].class_name.clone(),
+            subject_id: details[*r].subject_id,
+            subject_name: details[*r].subject_name.clone(),
+            lessons: *lessons,
+        })
+        .collect();
+    let placed: usize = solution.placements.iter().map(|p| p.length).sum();
+    sqlx::query(
+        r#"UPDATE timetable_generations
+           SET placed_lessons = $2, teacher_gaps = $3, double_lessons_requested = $4,
+               double_lessons_met = $5, unplaced = $6
+           WHERE id = $1"#,
+    )
+    .bind(generation.id)
+    .bind(placed as i32)
+    .bind(solution.teacher_gaps as i32)
+    .bind(solution.doubles_requested as i32)
+    .bind(solution.doubles_met as i32)
+    .bind(sqlx::types::Json(&unplaced))
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !unplaced.is_empty() {
+        let missing: usize = unplaced.iter().map(|u| u.lessons).sum();
+        return Err(format!(
+            "{missing} aula(s) não couberam na grade; revise as aulas semanais, horários ou indisponibilidades"
+        ));
+    }
+
+    save(pool, generation, &class_ids, &details, &solution).await
+}
+
+/// Roda o gerador fora do runtime, gravando o progresso enquanto ele trabalha.
+async fn run_solver(pool: &PgPool, generation_id: Uuid, problem: Problem) -> Result<Solution, String> {
+    let placed = Arc::new(AtomicUsize::new(0));
+    let percent = Arc::new(AtomicUsize::new(0));
+    let (placed_w, percent_w) = (placed.clone(), percent.clone());
+    let seed = generation_id.as_u64_pair().0;
+
+    let mut handle = tokio::task::spawn_blocking(move || {
+        solve(&problem, seed, &|p, pct| {
+            placed_w.store(p, Ordering::Relaxed);
+            percent_w.store(pct, Ordering::Relaxed);
+        })
+    });
+
+    let mut ticker = tokio::time::interval(PROGRESS_EVERY);
+    loop {
+        tokio::select! {
+            result = &mut handle => return result.map_err(|e| e.to_string()),
+            _ = ticker.tick() => {
+                sqlx::query("UPDATE timetable_generations SET placed_lessons = $2, progress = $3 WHERE id = $1")
+                    .bind(generation_id)
+                    .bind(placed.load(Ordering::Relaxed) as i32)
+                    .bind(percent.load(Ordering::Relaxed).min(99) as i32)
+                    .execute(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+}
+
+/// Substitui a grade das turmas geradas, mantendo a sala de cada disciplina.
+async fn save(
+    pool: &PgPool,
+    generation: &Generation,
+    class_ids: &[Uuid],
+    details: &[RequirementRow],
+    solution: &Solution,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('timetable:' || $1::text))")
+        .bind(generation.tenant_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM timetable_slots WHERE tenant_id = $1 AND class_id = ANY($2)")
+        .bind(generation.tenant_id)
+        .bind(class_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for placement in &solution.placements {
+        let r = &details[placement.requirement];
+        // a dupla vira dois horários, um por aula da chamada
+        for k in 0..placement.length {
+            let period = generation.periods[placement.period + k];
+            sqlx::query(
+                r#"
+                INSERT INTO timetable_slots
+                  (id, tenant_id, class_id, weekday, start_time, end_time, subject_id, teacher_user_id, room)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(generation.tenant_id)
+            .bind(r.class_id)
+            .bind(generation.weekdays[placement.day])
+            .bind(period.start_time)
+            .bind(period.end_time)
+            .bind(r.subject_id)
+            .bind(r.teacher_user_id)
+            .bind(&r.room)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // outra turma pode ter ganho aula do mesmo professor ou na mesma sala enquanto a grade
+    // era gerada
+    let clash: Option<i32> = sqlx::query_scalar(
+        r#"
+        SELECT 1
+        FROM timetable_slots a
+        JOIN timetable_slots b
+          ON b.tenant_id = a.tenant_id
+         AND (b.teacher_user_id = a.teacher_user_id OR lower(b.room) = lower(a.room))
+         AND b.weekday = a.weekday
+         AND b.start_time < a.end_time
+         AND a.start_time < b.end_time
+        JOIN classes c ON c.id = b.class_id
+        WHERE a.tenant_id = $1
+          AND a.class_id = ANY($2)
+          AND NOT (b.class_id = ANY($2))
+          AND c.year = $3
+        LIMIT 1
+        "#,
+    )
+    .bind(generation.tenant_id)
+    .bind(class_ids)
+    .bind(generation.school_year)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if clash.is_some() {
+        return Err("A grade de outra turma mudou durante a geração; gere novamente".into());
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn index_of<T: PartialEq>(ids: &mut Vec<T>, id: T) -> usize {
+    match ids.iter().position(|x| *x == id) {
+        Some(i) => i,
+        None => {
+            ids.push(id);
+            ids.len() - 1
+        }
+    }
+}
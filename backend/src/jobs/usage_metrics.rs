@@ -0,0 +1,113 @@
+use sqlx::PgPool;
+use std::time::Duration;
+
+/// Intervalo entre execuções do snapshot diário de uso por escola.
+const RUN_EVERY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Loop do job: roda na subida do servidor e depois a cada 24h.
+/// O snapshot do dia é sobrescrito, então reexecuções são seguras.
+pub async fn run(pool: PgPool) {
+    let mut ticker = tokio::time::interval(RUN_EVERY);
+    loop {
+        ticker.tick().await;
+        match snapshot_all_tenants(&pool).await {
+            Ok(count) => tracing::info!("Snapshot de uso gerado para {count} escolas"),
+            Err(e) => tracing::error!("Falha ao gerar snapshot de uso: {e}"),
+        }
+    }
+}
+
+/// Calcula as métricas de uso de todas as escolas para a data atual.
+pub async fn snapshot_all_tenants(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO tenant_usage_snapshots (
+          tenant_id, snapshot_date, active_students, classes_total, users_total, logins_30d,
+          attendance_records_30d, grade_entries_30d, contracts_total, receivables_amount_30d
+        )
+        SELECT
+          t.id,
+          CURRENT_DATE,
+          (SELECT COUNT(*) FROM students s
+            WHERE s.tenant_id = t.id
+              AND COALESCE(s.enrollment_status, 'active') = 'active')::int,
+          (SELECT COUNT(*) FROM classes c WHERE c.tenant_id = t.id)::int,
+          (SELECT COUNT(*) FROM users u WHERE u.tenant_id = t.id)::int,
+          (SELECT COUNT(*) FROM login_events l
+            WHERE l.tenant_id = t.id
+              AND l.created_at >= NOW() - INTERVAL '30 days')::int,
+          (SELECT COUNT(*) FROM student_attendance a
+            WHERE a.tenant_id = t.id
+              AND a.created_at >= NOW() - INTERVAL '30 days')::int,
+          (SELECT COUNT(*) FROM student_grades g
+            WHERE g.tenant_id = t.id
+              AND g.created_at >= NOW() - INTERVAL '30 days')::int,
+          (SELECT COUNT(*) FROM financial_contracts fc WHERE fc.tenant_id = t.id)::int,
+          (SELECT COALESCE(SUM(fr.amount), 0) FROM financial_receivables fr
+            WHERE fr.tenant_id = t.id
+              AND fr.status <> 'cancelled'
+              AND fr.due_date >= CURRENT_DATE - 30
+              AND fr.due_date <= CURRENT_DATE)
+        FROM tenants t
+        ON CONFLICT (tenant_id, snapshot_date)
+        DO UPDATE SET
+          active_students = EXCLUDED.active_students,
+          classes_total = EXCLUDED.classes_total,
+          users_total = EXCLUDED.users_total,
+          logins_30d = EXCLUDED.logins_30d,
+          attendance_records_30d = EXCLUDED.attendance_records_30d,
+          grade_entries_30d = EXCLUDED.grade_entries_30d,
+          contracts_total = EXCLUDED.contracts_total,
+          receivables_amount_30d = EXCLUDED.receivables_amount_30d,
+          created_at = NOW()
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_class, insert_tenant, insert_user, test_pool};
+    use sqlx::Row;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn snapshot_counts_only_last_30_days_of_logins() {
+        let pool = test_pool().await;
+        let tenant_id = insert_tenant(&pool, "Escola Métricas").await;
+        let user_id = insert_user(&pool, tenant_id, "Secretaria", "staff").await;
+        insert_class(&pool, tenant_id, "5A", "5º ano", 2026).await;
+        for days_ago in [1, 10, 45] {
+            sqlx::query(
+                "INSERT INTO login_events (id, tenant_id, user_id, created_at) VALUES ($1, $2, $3, NOW() - make_interval(days => $4))",
+            )
+            .bind(Uuid::new_v4())
+            .bind(tenant_id)
+            .bind(user_id)
+            .bind(days_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        snapshot_all_tenants(&pool).await.unwrap();
+        // reexecução no mesmo dia sobrescreve o snapshot
+        snapshot_all_tenants(&pool).await.unwrap();
+
+        let rows = sqlx::query(
+            "SELECT classes_total, users_total, logins_30d FROM tenant_usage_snapshots WHERE tenant_id = $1",
+        )
+        .bind(tenant_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<i32, _>("classes_total"), 1);
+        assert_eq!(rows[0].get::<i32, _>("users_total"), 1);
+        assert_eq!(rows[0].get::<i32, _>("logins_30d"), 2);
+    }
+}
@@ -3,10 +3,17 @@ mod db;
 mod routes;
 mod models;
 mod auth;
+mod jobs;
+mod grading;
+mod reports;
+mod scheduling;
 mod state;
+#[cfg(test)]
+mod test_support;
 
 use axum::http::Method;
 use axum::Router;
+use std::net::SocketAddr;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::EnvFilter;
 
@@ -21,6 +28,13 @@ async fn main() {
     let pool = db::make_pool(&cfg.database_url).await;
     db::run_migrations(&pool).await;
 
+    tokio::spawn(jobs::usage_metrics::run(pool.clone()));
+    tokio::spawn(jobs::offboarding::run(pool.clone()));
+    tokio::spawn(jobs::guardian_notifications::run(pool.clone()));
+    tokio::spawn(jobs::report_cards::run(pool.clone()));
+    tokio::spawn(jobs::student_risk::run(pool.clone()));
+    tokio::spawn(jobs::timetable_generation::run(pool.clone()));
+
     // ✅ CORS (dev). Em VPS/produção, depois vamos restringir ao domínio do frontend.
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -31,20 +45,42 @@ async fn main() {
     let app = Router::new()
         .merge(routes::health::routes())
         .merge(routes::auth::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::identity::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::sso::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::platform_auth::routes(
             cfg.jwt_secret.clone(),
             cfg.platform_admin_email.clone(),
             cfg.platform_admin_password.clone(),
         ))
         .merge(routes::admin::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::offboarding::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::dashboard::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::teachers::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::session::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::school_settings::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::share_links::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::subjects::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::teaching_assignments::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::transcripts::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::terms::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::records::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::lessons::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::assessments::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::recovery::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::attendance_alerts::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::grading_scales::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::record_history::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::absence_justifications::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::guardian_alerts::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::guardian_portal::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::report_cards::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::students::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::student_portal::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::class_councils::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::grade_analytics::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::student_risk::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::timetable::routes(pool.clone(), cfg.jwt_secret.clone()))
+        .merge(routes::timetable_generation::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::classes::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::guardians::routes(pool.clone(), cfg.jwt_secret.clone()))
         .merge(routes::people::routes(pool.clone(), cfg.jwt_secret.clone()))
@@ -57,5 +93,8 @@ async fn main() {
 
     tracing::info!("API rodando em http://{}", cfg.bind_addr);
 
-    axum::serve(listener, app).await.expect("Erro no server");
+    // endereço do cliente para o registro de acessos aos links públicos
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .expect("Erro no server");
 }
@@ -0,0 +1,4 @@
+//! Documentos gerados pelo servidor (PDF).
+
+pub mod pdf;
+pub mod report_cards;
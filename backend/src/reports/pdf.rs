@@ -0,0 +1,351 @@
+//! Gerador de PDF mínimo (PDF 1.4) para documentos tabulares: fontes padrão Helvetica
+//! (sem embutir arquivo de fonte, texto em WinAnsiEncoding), linhas, retângulos e
+//! imagens JPEG. Coordenadas em pontos, com `y` medido a partir do topo da página.
+
+use std::fmt::Write as _;
+
+/// A4 retrato, em pontos.
+pub const PAGE_WIDTH: f32 = 595.28;
+pub const PAGE_HEIGHT: f32 = 841.89;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub f32, pub f32, pub f32);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Rgb = Rgb(1.0, 1.0, 1.0);
+    pub const GRAY: Rgb = Rgb(0.45, 0.45, 0.45);
+    pub const LIGHT_GRAY: Rgb = Rgb(0.93, 0.93, 0.93);
+
+    /// `#RRGGBB` (o `#` é opcional).
+    pub fn from_hex(hex: &str) -> Option<Rgb> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|v| v as f32 / 255.0);
+        Some(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+/// JPEG embutido como está (filtro DCTDecode); só lemos as dimensões do cabeçalho.
+#[derive(Debug, Clone)]
+pub struct JpegImage {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub components: u8,
+}
+
+impl JpegImage {
+    pub fn parse(data: Vec<u8>) -> Option<JpegImage> {
+        if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+            return None;
+        }
+        let mut i = 2;
+        while i + 9 < data.len() {
+            if data[i] != 0xFF {
+                return None;
+            }
+            let marker = data[i + 1];
+            let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+            // SOF0..SOF15, exceto DHT (C4), JPG (C8) e DAC (CC)
+            if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                let height = u16::from_be_bytes([data[i + 5], data[i + 6]]) as u32;
+                let width = u16::from_be_bytes([data[i + 7], data[i + 8]]) as u32;
+                let components = data[i + 9];
+                if width == 0 || height == 0 || ![1, 3, 4].contains(&components) {
+                    return None;
+                }
+                return Some(JpegImage {
+                    data,
+                    width,
+                    height,
+                    components,
+                });
+            }
+            i += 2 + len;
+        }
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Page {
+    ops: String,
+}
+
+impl Page {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&mut self, x: f32, y: f32, size: f32, font: Font, color: Rgb, text: &str) {
+        let _ = writeln!(
+            self.ops,
+            "BT {} {} {} rg /{} {} Tf {} {} Td ({}) Tj ET",
+            num(color.0),
+            num(color.1),
+            num(color.2),
+            font.resource(),
+            num(size),
+            num(x),
+            num(PAGE_HEIGHT - y),
+            escape(text)
+        );
+    }
+
+    pub fn text_centered(&mut self, center: f32, y: f32, size: f32, font: Font, color: Rgb, text: &str) {
+        self.text(center - text_width(text, size, font) / 2.0, y, size, font, color, text);
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32, color: Rgb) {
+        let _ = writeln!(
+            self.ops,
+            "{} {} {} RG {} w {} {} m {} {} l S",
+            num(color.0),
+            num(color.1),
+            num(color.2),
+            num(width),
+            num(x1),
+            num(PAGE_HEIGHT - y1),
+            num(x2),
+            num(PAGE_HEIGHT - y2)
+        );
+    }
+
+    /// Retângulo preenchido; `y` é a borda superior.
+    pub fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32, color: Rgb) {
+        let _ = writeln!(
+            self.ops,
+            "{} {} {} rg {} {} {} {} re f",
+            num(color.0),
+            num(color.1),
+            num(color.2),
+            num(x),
+            num(PAGE_HEIGHT - y - h),
+            num(w),
+            num(h)
+        );
+    }
+
+    /// Imagem registrada com `PdfDocument::add_image`; `y` é a borda superior.
+    pub fn image(&mut self, image: usize, x: f32, y: f32, w: f32, h: f32) {
+        let _ = writeln!(
+            self.ops,
+            "q {} 0 0 {} {} {} cm /Im{} Do Q",
+            num(w),
+            num(h),
+            num(x),
+            num(PAGE_HEIGHT - y - h),
+            image
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PdfDocument {
+    pages: Vec<Page>,
+    images: Vec<JpegImage>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_image(&mut self, image: JpegImage) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    pub fn add_page(&mut self, page: Page) {
+        self.pages.push(page);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // 1 catálogo, 2 páginas, 3-4 fontes, depois imagens e pares página/conteúdo
+        let first_image = 5;
+        let first_page = first_image + self.images.len();
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", first_page + i * 2))
+            .collect();
+        objects.push(
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), self.pages.len()).into_bytes(),
+        );
+        for base in ["Helvetica", "Helvetica-Bold"] {
+            objects.push(
+                format!("<< /Type /Font /Subtype /Type1 /BaseFont /{base} /Encoding /WinAnsiEncoding >>")
+                    .into_bytes(),
+            );
+        }
+        for image in &self.images {
+            let color_space = match image.components {
+                1 => "/DeviceGray",
+                4 => "/DeviceCMYK",
+                _ => "/DeviceRGB",
+            };
+            let mut obj = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {color_space} /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                image.width,
+                image.height,
+                image.data.len()
+            )
+            .into_bytes();
+            obj.extend_from_slice(&image.data);
+            obj.extend_from_slice(b"\nendstream");
+            objects.push(obj);
+        }
+        let xobjects: String = (0..self.images.len())
+            .map(|i| format!("/Im{i} {} 0 R ", first_image + i))
+            .collect();
+        for (i, page) in self.pages.iter().enumerate() {
+            let content_id = first_page + i * 2 + 1;
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> /XObject << {xobjects}>> >> /Contents {content_id} 0 R >>",
+                    num(PAGE_WIDTH),
+                    num(PAGE_HEIGHT)
+                )
+                .into_bytes(),
+            );
+            let mut obj = format!("<< /Length {} >>\nstream\n", page.ops.len()).into_bytes();
+            obj.extend_from_slice(page.ops.as_bytes());
+            obj.extend_from_slice(b"endstream");
+            objects.push(obj);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, obj) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(obj);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.extend_from_slice(trailer.as_bytes());
+        out
+    }
+}
+
+/// Largura aproximada do texto (métricas da Helvetica; acentuadas como a letra base).
+pub fn text_width(text: &str, size: f32, font: Font) -> f32 {
+    let units: u32 = text.chars().map(char_width).sum();
+    let bold = if font == Font::Bold { 1.05 } else { 1.0 };
+    units as f32 * size * bold / 1000.0
+}
+
+/// Corta o texto com reticências para caber em `max_width`.
+pub fn fit(text: &str, max_width: f32, size: f32, font: Font) -> String {
+    if text_width(text, size, font) <= max_width {
+        return text.to_string();
+    }
+    let mut out: String = text.to_string();
+    while !out.is_empty() && text_width(&format!("{out}..."), size, font) > max_width {
+        out.pop();
+    }
+    format!("{}...", out.trim_end())
+}
+
+fn char_width(c: char) -> u32 {
+    const ASCII: [u16; 95] = [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // ' '..'/'
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0'..'9'
+        278, 278, 584, 584, 584, 556, 1015, // ':'..'@'
+        667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A'..'M'
+        722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N'..'Z'
+        278, 278, 278, 469, 556, 333, // '['..'`'
+        556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a'..'m'
+        556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n'..'z'
+        334, 260, 334, 584, // '{'..'~'
+    ];
+    let base = match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => 'A',
+        'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'Ç' => 'C',
+        'º' | 'ª' => 'o',
+        other => other,
+    };
+    match base as u32 {
+        32..=126 => ASCII[(base as u32 - 32) as usize] as u32,
+        _ => 556,
+    }
+}
+
+/// String literal PDF em WinAnsi: Latin-1 direto, algumas pontuações mapeadas, o resto vira `?`.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        let code: u32 = match c {
+            '€' => 0x80,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if (c as u32) < 0x20 => 0x20,
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u32,
+            _ => '?' as u32,
+        };
+        match code {
+            0x28 | 0x29 | 0x5C => {
+                out.push('\\');
+                out.push(char::from_u32(code).unwrap_or('?'));
+            }
+            0x20..=0x7E => out.push(char::from_u32(code).unwrap_or('?')),
+            _ => {
+                let _ = write!(out, "\\{code:03o}");
+            }
+        }
+    }
+    out
+}
+
+fn num(v: f32) -> String {
+    let s = format!("{v:.2}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s.is_empty() || s == "-" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,429 @@
+//! Boletim em PDF (do período e anual) com a identidade da escola: logotipo, cor,
+//! texto de cabeçalho, cidade e responsável pela assinatura.
+
+use axum::http::StatusCode;
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::pdf::{fit, Font, JpegImage, Page, PdfDocument, Rgb, PAGE_HEIGHT, PAGE_WIDTH};
+use crate::routes::records::{StudentFullReportResponse, StudentTermReportResponse};
+use crate::routes::transcripts::TranscriptResponse;
+
+const MARGIN: f32 = 40.0;
+const ROW_HEIGHT: f32 = 18.0;
+/// Espaço reservado no pé da página (assinatura e data).
+const FOOTER_HEIGHT: f32 = 110.0;
+const DEFAULT_BRAND_COLOR: Rgb = Rgb(0.12, 0.29, 0.53);
+
+#[derive(Debug, Clone)]
+pub struct Branding {
+    pub school_name: String,
+    pub header_text: Option<String>,
+    pub city: Option<String>,
+    pub signature_name: Option<String>,
+    pub color: Rgb,
+    pub logo: Option<JpegImage>,
+}
+
+/// Identidade visual da escola (`tenants`), usada em todos os boletins.
+pub async fn load_branding<'e, E>(executor: E, tenant_id: Uuid) -> Result<Branding, (StatusCode, String)>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let row = sqlx::query(
+        r#"
+        SELECT name, report_header_text, school_city, school_signature_name,
+               report_brand_color, report_logo
+        FROM tenants
+        WHERE id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
+
+    Ok(Branding {
+        school_name: row.get("name"),
+        header_text: row.get("report_header_text"),
+        city: row.get("school_city"),
+        signature_name: row.get("school_signature_name"),
+        color: row
+            .get::<Option<String>, _>("report_brand_color")
+            .as_deref()
+            .and_then(Rgb::from_hex)
+            .unwrap_or(DEFAULT_BRAND_COLOR),
+        logo: row
+            .get::<Option<Vec<u8>>, _>("report_logo")
+            .and_then(JpegImage::parse),
+    })
+}
+
+/// Documento com o logotipo já registrado; boletins de vários alunos vão no mesmo PDF.
+pub struct ReportCardDocument {
+    doc: PdfDocument,
+    branding: Branding,
+    logo: Option<usize>,
+}
+
+impl ReportCardDocument {
+    pub fn new(branding: Branding) -> Self {
+        let mut doc = PdfDocument::new();
+        let logo = branding.logo.clone().map(|img| doc.add_image(img));
+        Self { doc, branding, logo }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.doc.to_bytes()
+    }
+
+    pub fn add_term_report(&mut self, report: &StudentTermReportResponse) {
+        let title = format!("Boletim Escolar - {}", report.term_name);
+        let class_line = class_line(&report.class_name, &report.class_grade, &report.class_period, report.class_year);
+        let mut writer = self.start(&title, &report.student_name, &report.registration, &class_line);
+
+        let columns = [
+            Column::left("Disciplina", 190.0),
+            Column::center("Nota", 60.0),
+            Column::center("Recuperação", 75.0),
+            Column::center("Faltas", 50.0),
+            Column::left("Observações", 140.0),
+        ];
+        writer.table_header(self, &columns);
+        for s in &report.subjects {
+            let recovery = s.recovery_score.map(format_decimal).unwrap_or_default();
+            writer.table_row(
+                self,
+                &columns,
+                &[
+                    s.subject_name.clone(),
+                    s.display_score.clone().unwrap_or_else(|| "-".into()),
+                    recovery,
+                    s.absences_gradebook.to_string(),
+                    s.comments.clone().unwrap_or_default(),
+                ],
+            );
+        }
+        if report.subjects.is_empty() {
+            writer.note(self, "Nenhuma disciplina com lançamento neste período.");
+        }
+
+        writer.gap(10.0);
+        writer.note(
+            self,
+            &attendance_line(
+                report.attendance_percent,
+                report.attendance_present_days,
+                report.attendance_total_days,
+                report.attendance_justified_absences,
+            ),
+        );
+        writer.finish(self);
+    }
+
+    pub fn add_full_report(&mut self, report: &StudentFullReportResponse) {
+        let title = format!("Boletim Anual - {}", report.class_year);
+        let class_line = class_line(&report.class_name, &report.class_grade, &report.class_period, report.class_year);
+        let mut writer = self.start(&title, &report.student_name, &report.registration, &class_line);
+
+        // períodos dividem o espaço que sobra entre as colunas fixas
+        let fixed = 150.0 + 45.0 + 45.0 + 45.0 + 95.0;
+        let period_width = if report.periods.is_empty() {
+            0.0
+        } else {
+            ((PAGE_WIDTH - 2.0 * MARGIN - fixed) / report.periods.len() as f32).min(70.0)
+        };
+        let mut columns = vec![Column::left("Disciplina", 150.0)];
+        columns.extend(report.periods.iter().map(|p| Column::center_owned(p.term_name.clone(), period_width)));
+        columns.push(Column::center("Média", 45.0));
+        columns.push(Column::center("P. final", 45.0));
+        columns.push(Column::center("Freq. %", 45.0));
+        columns.push(Column::left("Situação", 95.0));
+
+        writer.table_header(self, &columns);
+        for s in &report.subjects {
+            let mut cells = vec![s.subject_name.clone()];
+            cells.extend(report.periods.iter().map(|p| {
+                s.period_grades
+                    .iter()
+                    .find(|g| g.term_id == p.term_id)
+                    .and_then(|g| g.effective_display.clone())
+                    .unwrap_or_else(|| "-".into())
+            }));
+            cells.push(s.average_display.clone().unwrap_or_else(|| "-".into()));
+            cells.push(s.final_exam_score.map(format_decimal).unwrap_or_default());
+            cells.push(s.attendance_percent.map(format_decimal).unwrap_or_else(|| "-".into()));
+            cells.push(s.status.clone());
+            writer.table_row(self, &columns, &cells);
+        }
+        if report.subjects.is_empty() {
+            writer.note(self, "Nenhuma disciplina com lançamento no ano.");
+        }
+
+        writer.gap(10.0);
+        writer.note(
+            self,
+            &attendance_line(
+                report.attendance_percent,
+                report.attendance_present_days,
+                report.attendance_total_days,
+                report.attendance_justified_absences,
+            ),
+        );
+        writer.note_bold(self, &format!("Situação final: {}", report.final_status_label));
+        if let Some(d) = &report.council_decision {
+            let text = format!(
+                "Decisão do conselho de classe de {} ({}).",
+                d.held_on.format("%d/%m/%Y"),
+                d.term_name
+            );
+            writer.note(self, &fit(&text, PAGE_WIDTH - 2.0 * MARGIN, 10.0, Font::Regular));
+        }
+        if report.subjects.iter().any(|s| s.council_decision.is_some()) {
+            writer.note(self, "Situações marcadas com (conselho) foram definidas pelo conselho de classe.");
+        }
+        writer.finish(self);
+    }
+
+    /// Histórico escolar: uma tabela por ano cursado, desta escola ou de escolas anteriores.
+    pub fn add_transcript(&mut self, transcript: &TranscriptResponse) {
+        let mut personal = Vec::new();
+        if let Some(birth_date) = transcript.birth_date {
+            personal.push(format!("Nascimento: {}", birth_date.format("%d/%m/%Y")));
+        }
+        if let Some(place) = transcript.place_of_birth.as_deref() {
+            personal.push(format!("Naturalidade: {place}"));
+        }
+        if let Some(nationality) = transcript.nationality.as_deref() {
+            personal.push(format!("Nacionalidade: {nationality}"));
+        }
+        let mut writer = self.start(
+            "Histórico Escolar",
+            &transcript.student_name,
+            &transcript.registration,
+            &personal.join("    "),
+        );
+
+        let columns = [
+            Column::left("Disciplina", 220.0),
+            Column::center("C.H.", 60.0),
+            Column::center("Média", 60.0),
+            Column::center("Freq. %", 60.0),
+            Column::left("Situação", 115.0),
+        ];
+        for year in &transcript.years {
+            let school = match year.school_city.as_deref() {
+                Some(city) => format!("{} ({city})", year.school_name),
+                None => year.school_name.clone(),
+            };
+            writer.note_bold(self, &format!("{} - {} - {school}", year.school_year, year.grade));
+            writer.table_header(self, &columns);
+            for s in &year.subjects {
+                writer.table_row(
+                    self,
+                    &columns,
+                    &[
+                        s.subject_name.clone(),
+                        s.workload_hours.map(|h| h.to_string()).unwrap_or_else(|| "-".into()),
+                        s.final_grade.clone().unwrap_or_else(|| "-".into()),
+                        s.attendance_percent.map(format_decimal).unwrap_or_else(|| "-".into()),
+                        s.status.clone().unwrap_or_default(),
+                    ],
+                );
+            }
+            if year.subjects.is_empty() {
+                writer.note(self, "Nenhuma disciplina registrada no ano.");
+            }
+            let attendance = year
+                .attendance_percent
+                .map(|p| format!("{}%", format_decimal(p)))
+                .unwrap_or_else(|| "-".into());
+            writer.note(
+                self,
+                &format!(
+                    "Frequência: {attendance}    Carga horária: {} h    Resultado: {}",
+                    year.total_workload_hours, year.final_status_label
+                ),
+            );
+            if let Some(notes) = year.notes.as_deref() {
+                writer.note(self, &fit(notes, PAGE_WIDTH - 2.0 * MARGIN, 10.0, Font::Regular));
+            }
+            writer.gap(10.0);
+        }
+        if transcript.years.is_empty() {
+            writer.note(self, "Nenhum ano letivo registrado.");
+        }
+        writer.note_bold(self, &format!("Carga horária total: {} h", transcript.total_workload_hours));
+        writer.finish(self);
+    }
+
+    fn start(&mut self, title: &str, student_name: &str, registration: &str, class_line: &str) -> Writer {
+        let mut writer = Writer {
+            page: Page::new(),
+            y: 0.0,
+            title: title.to_string(),
+            student_line: format!("Aluno(a): {student_name}    Matrícula: {registration}"),
+            class_line: class_line.to_string(),
+        };
+        writer.header(self);
+        writer
+    }
+}
+
+struct Column {
+    title: String,
+    width: f32,
+    centered: bool,
+}
+
+impl Column {
+    fn left(title: &str, width: f32) -> Self {
+        Self { title: title.into(), width, centered: false }
+    }
+
+    fn center(title: &str, width: f32) -> Self {
+        Self { title: title.into(), width, centered: true }
+    }
+
+    fn center_owned(title: String, width: f32) -> Self {
+        Self { title, width, centered: true }
+    }
+}
+
+/// Página em construção de um aluno; quebra a tabela em novas páginas quando precisa.
+struct Writer {
+    page: Page,
+    y: f32,
+    title: String,
+    student_line: String,
+    class_line: String,
+}
+
+impl Writer {
+    fn header(&mut self, doc: &ReportCardDocument) {
+        let b = &doc.branding;
+        self.page.fill_rect(0.0, 0.0, PAGE_WIDTH, 8.0, b.color);
+
+        let mut text_x = MARGIN;
+        if let (Some(index), Some(logo)) = (doc.logo, b.logo.as_ref()) {
+            let h = 48.0;
+            let w = (h * logo.width as f32 / logo.height as f32).min(120.0);
+            self.page.image(index, MARGIN, 22.0, w, h);
+            text_x += w + 12.0;
+        }
+        let text_width = PAGE_WIDTH - MARGIN - text_x;
+        self.page.text(text_x, 40.0, 16.0, Font::Bold, b.color, &fit(&b.school_name, text_width, 16.0, Font::Bold));
+        if let Some(header_text) = b.header_text.as_deref() {
+            self.page.text(text_x, 56.0, 9.0, Font::Regular, Rgb::GRAY, &fit(header_text, text_width, 9.0, Font::Regular));
+        }
+        if let Some(city) = b.city.as_deref() {
+            self.page.text(text_x, 68.0, 9.0, Font::Regular, Rgb::GRAY, &fit(city, text_width, 9.0, Font::Regular));
+        }
+        self.page.line(MARGIN, 80.0, PAGE_WIDTH - MARGIN, 80.0, 1.0, b.color);
+
+        self.page.text(MARGIN, 102.0, 14.0, Font::Bold, Rgb::BLACK, &self.title);
+        self.page.text(MARGIN, 120.0, 10.0, Font::Regular, Rgb::BLACK, &self.student_line);
+        self.page.text(MARGIN, 134.0, 10.0, Font::Regular, Rgb::BLACK, &self.class_line);
+        self.y = 152.0;
+    }
+
+    fn ensure_space(&mut self, doc: &mut ReportCardDocument, height: f32, columns: Option<&[Column]>) {
+        if self.y + height <= PAGE_HEIGHT - FOOTER_HEIGHT {
+            return;
+        }
+        let full = std::mem::take(&mut self.page);
+        doc.doc.add_page(full);
+        self.header(doc);
+        if let Some(columns) = columns {
+            self.table_header(doc, columns);
+        }
+    }
+
+    fn table_header(&mut self, doc: &mut ReportCardDocument, columns: &[Column]) {
+        self.ensure_space(doc, ROW_HEIGHT * 2.0, None);
+        let width: f32 = columns.iter().map(|c| c.width).sum();
+        self.page.fill_rect(MARGIN, self.y, width, ROW_HEIGHT, doc.branding.color);
+        let titles: Vec<String> = columns.iter().map(|c| c.title.clone()).collect();
+        self.cells(columns, &titles, Font::Bold, Rgb::WHITE);
+        self.y += ROW_HEIGHT;
+    }
+
+    fn table_row(&mut self, doc: &mut ReportCardDocument, columns: &[Column], cells: &[String]) {
+        self.ensure_space(doc, ROW_HEIGHT, Some(columns));
+        let width: f32 = columns.iter().map(|c| c.width).sum();
+        self.cells(columns, cells, Font::Regular, Rgb::BLACK);
+        self.page.line(MARGIN, self.y + ROW_HEIGHT, MARGIN + width, self.y + ROW_HEIGHT, 0.5, Rgb::LIGHT_GRAY);
+        self.y += ROW_HEIGHT;
+    }
+
+    fn cells(&mut self, columns: &[Column], cells: &[String], font: Font, color: Rgb) {
+        let size = 9.0;
+        let baseline = self.y + ROW_HEIGHT - 6.0;
+        let mut x = MARGIN;
+        for (column, cell) in columns.iter().zip(cells) {
+            let text = fit(cell, column.width - 6.0, size, font);
+            if column.centered {
+                self.page.text_centered(x + column.width / 2.0, baseline, size, font, color, &text);
+            } else {
+                self.page.text(x + 3.0, baseline, size, font, color, &text);
+            }
+            x += column.width;
+        }
+    }
+
+    fn note(&mut self, doc: &mut ReportCardDocument, text: &str) {
+        self.ensure_space(doc, 16.0, None);
+        self.page.text(MARGIN, self.y + 12.0, 10.0, Font::Regular, Rgb::BLACK, text);
+        self.y += 16.0;
+    }
+
+    fn note_bold(&mut self, doc: &mut ReportCardDocument, text: &str) {
+        self.ensure_space(doc, 16.0, None);
+        self.page.text(MARGIN, self.y + 12.0, 11.0, Font::Bold, Rgb::BLACK, text);
+        self.y += 16.0;
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    /// Pé com local/data e linha de assinatura; encerra a página do aluno.
+    fn finish(mut self, doc: &mut ReportCardDocument) {
+        let b = &doc.branding;
+        let top = PAGE_HEIGHT - FOOTER_HEIGHT + 20.0;
+        let today = chrono::Local::now().format("%d/%m/%Y").to_string();
+        let place = match b.city.as_deref() {
+            Some(city) => format!("{city}, {today}"),
+            None => today,
+        };
+        self.page.text(MARGIN, top + 10.0, 10.0, Font::Regular, Rgb::BLACK, &place);
+
+        let center = PAGE_WIDTH - MARGIN - 110.0;
+        self.page.line(center - 110.0, top + 45.0, center + 110.0, top + 45.0, 0.8, Rgb::BLACK);
+        let signer = b.signature_name.clone().unwrap_or_else(|| "Direção / Secretaria".into());
+        self.page.text_centered(center, top + 58.0, 9.0, Font::Regular, Rgb::BLACK, &signer);
+        self.page.text(MARGIN, PAGE_HEIGHT - 20.0, 7.0, Font::Regular, Rgb::GRAY, &b.school_name);
+
+        doc.doc.add_page(self.page);
+    }
+}
+
+fn class_line(name: &str, grade: &str, period: &str, year: i32) -> String {
+    format!("Turma: {name} - {grade} - {period}    Ano letivo: {year}")
+}
+
+fn attendance_line(percent: f64, present: i32, total: i32, justified: i32) -> String {
+    let mut line = format!(
+        "Frequência: {}% ({present} de {total} aulas)",
+        format_decimal(percent)
+    );
+    if justified > 0 {
+        line.push_str(&format!(" - faltas justificadas: {justified}"));
+    }
+    line
+}
+
+fn format_decimal(value: f64) -> String {
+    format!("{value:.1}").replace('.', ",")
+}
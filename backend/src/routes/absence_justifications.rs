@@ -0,0 +1,696 @@
+//! Justificativas de falta: atestados e afins cobrindo um intervalo de datas do aluno,
+//! com documento comprobatório opcional. Só justificativas aprovadas pela secretaria
+//! entram no cálculo da frequência (view `student_attendance_effective`).
+
+use std::env;
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    Extension,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::state::AppState;
+
+/// Tamanho máximo do documento enviado (5 MB).
+pub const MAX_DOCUMENT_BYTES: usize = 5 * 1024 * 1024;
+
+const CATEGORIES: [&str; 5] = ["medical", "legal", "bereavement", "religious", "other"];
+const DOCUMENT_TYPES: [&str; 3] = ["application/pdf", "image/jpeg", "image/png"];
+
+#[derive(Debug, Deserialize)]
+pub struct JustificationsQuery {
+    pub student_id: Option<Uuid>,
+    pub class_id: Option<Uuid>,
+    /// pending | approved | rejected
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateJustificationRequest {
+    pub student_id: Uuid,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// medical | legal | bereavement | religious | other
+    pub category: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewJustificationRequest {
+    /// approved | rejected
+    pub status: String,
+    pub review_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentQuery {
+    pub filename: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JustificationResponse {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub class_id: Option<Uuid>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub category: String,
+    pub reason: Option<String>,
+    pub status: String,
+    pub review_notes: Option<String>,
+    pub submitted_by: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_by_name: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub has_document: bool,
+    pub document_name: Option<String>,
+    pub document_content_type: Option<String>,
+    pub document_size: Option<i32>,
+    /// Aulas com falta registrada no intervalo (justificadas se aprovada).
+    pub absences_covered: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+/// Pasta onde os documentos são gravados.
+#[derive(Clone)]
+struct DocumentsDir(String);
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    routes_with_documents_dir(pool, jwt_secret, documents_dir())
+}
+
+pub fn routes_with_documents_dir(pool: PgPool, jwt_secret: String, documents_dir: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/absence-justifications",
+            get(list_justifications).post(create_justification),
+        )
+        .route(
+            "/absence-justifications/:justification_id",
+            get(get_justification).delete(delete_justification),
+        )
+        .route(
+            "/absence-justifications/:justification_id/review",
+            post(review_justification),
+        )
+        .route(
+            "/absence-justifications/:justification_id/document",
+            get(download_document)
+                .put(upload_document)
+                .layer(DefaultBodyLimit::max(MAX_DOCUMENT_BYTES)),
+        )
+        .layer(Extension(DocumentsDir(documents_dir)))
+        .with_state(state)
+}
+
+pub fn documents_dir() -> String {
+    let raw = env::var("DOCUMENTS_DIR").unwrap_or_else(|_| "./documents".to_string());
+    raw.trim_end_matches('/').to_string()
+}
+
+const SELECT_JUSTIFICATION: &str = r#"
+    SELECT j.id, j.student_id, s.name AS student_name, s.class_id, j.start_date, j.end_date,
+           j.category, j.reason, j.status, j.review_notes, j.submitted_by, j.reviewed_by,
+           COALESCE(u.full_name, u.email) AS reviewed_by_name, j.reviewed_at,
+           j.document_path, j.document_name, j.document_content_type, j.document_size,
+           (SELECT COUNT(*) FROM student_attendance a
+             WHERE a.tenant_id = j.tenant_id AND a.student_id = j.student_id AND NOT a.present
+               AND a.attendance_date BETWEEN j.start_date AND j.end_date) AS absences_covered,
+           j.created_at
+    FROM absence_justifications j
+    JOIN students s ON s.id = j.student_id AND s.tenant_id = j.tenant_id
+    LEFT JOIN users u ON u.id = j.reviewed_by AND u.tenant_id = j.tenant_id
+"#;
+
+fn map_justification(r: &sqlx::postgres::PgRow) -> JustificationResponse {
+    JustificationResponse {
+        id: r.get("id"),
+        student_id: r.get("student_id"),
+        student_name: r.get("student_name"),
+        class_id: r.get("class_id"),
+        start_date: r.get("start_date"),
+        end_date: r.get("end_date"),
+        category: r.get("category"),
+        reason: r.get("reason"),
+        status: r.get("status"),
+        review_notes: r.get("review_notes"),
+        submitted_by: r.get("submitted_by"),
+        reviewed_by: r.get("reviewed_by"),
+        reviewed_by_name: r.get("reviewed_by_name"),
+        reviewed_at: r.get("reviewed_at"),
+        has_document: r.get::<Option<String>, _>("document_path").is_some(),
+        document_name: r.get("document_name"),
+        document_content_type: r.get("document_content_type"),
+        document_size: r.get("document_size"),
+        absences_covered: r.get("absences_covered"),
+        created_at: r.get("created_at"),
+    }
+}
+
+async fn list_justifications(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<JustificationsQuery>,
+) -> Result<Json<Vec<JustificationResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    let status = query.status.as_deref().map(normalize_status).transpose()?;
+
+    // professor só vê alunos das turmas atribuídas a ele
+    let sql = format!(
+        r#"{SELECT_JUSTIFICATION}
+        WHERE j.tenant_id = $1
+          AND ($2::uuid IS NULL OR j.student_id = $2)
+          AND ($3::uuid IS NULL OR s.class_id = $3)
+          AND ($4::text IS NULL OR j.status = $4)
+          AND ($5::uuid IS NULL OR EXISTS (
+                SELECT 1
+                FROM teaching_assignments ta
+                JOIN classes c ON c.id = ta.class_id AND c.tenant_id = ta.tenant_id
+                WHERE ta.tenant_id = j.tenant_id AND ta.teacher_user_id = $5
+                  AND ta.class_id = s.class_id AND ta.school_year = c.year))
+        ORDER BY j.start_date DESC, j.created_at DESC"#
+    );
+    let rows = sqlx::query(&sql)
+        .bind(user.tenant_id)
+        .bind(query.student_id)
+        .bind(query.class_id)
+        .bind(status)
+        .bind(restricted_teacher(&user))
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(map_justification).collect()))
+}
+
+async fn get_justification(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(justification_id): Path<Uuid>,
+) -> Result<Json<JustificationResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    let justification = load_justification(&state.pool, &user, justification_id).await?;
+    Ok(Json(justification))
+}
+
+async fn create_justification(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<CreateJustificationRequest>,
+) -> Result<Json<JustificationResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    if req.start_date > req.end_date {
+        return Err((StatusCode::BAD_REQUEST, "Data inicial após a data final".into()));
+    }
+    let category = normalize_category(&req.category)?;
+    let reason = normalize_optional_text(req.reason);
+
+    let student: Option<i32> =
+        sqlx::query_scalar("SELECT 1 FROM students WHERE tenant_id = $1 AND id = $2")
+            .bind(user.tenant_id)
+            .bind(req.student_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if student.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Aluno inválido para este tenant".into()));
+    }
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO absence_justifications
+          (id, tenant_id, student_id, start_date, end_date, category, reason, submitted_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(req.student_id)
+    .bind(req.start_date)
+    .bind(req.end_date)
+    .bind(category)
+    .bind(reason)
+    .bind(user.user_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let justification = load_justification(&state.pool, &user, id).await?;
+    Ok(Json(justification))
+}
+
+async fn review_justification(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(justification_id): Path<Uuid>,
+    Json(req): Json<ReviewJustificationRequest>,
+) -> Result<Json<JustificationResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let status = normalize_status(&req.status)?;
+    if status == "pending" {
+        return Err((StatusCode::BAD_REQUEST, "Status da análise deve ser approved ou rejected".into()));
+    }
+    let review_notes = normalize_optional_text(req.review_notes);
+    if status == "rejected" && review_notes.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Informe o motivo da recusa".into()));
+    }
+
+    let res = sqlx::query(
+        r#"
+        UPDATE absence_justifications
+        SET status = $3, review_notes = $4, reviewed_by = $5, reviewed_at = NOW(), updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(justification_id)
+    .bind(status)
+    .bind(review_notes)
+    .bind(user.user_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Justificativa não encontrada".into()));
+    }
+
+    let justification = load_justification(&state.pool, &user, justification_id).await?;
+    Ok(Json(justification))
+}
+
+async fn delete_justification(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(justification_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let document_path: Option<Option<String>> = sqlx::query_scalar(
+        r#"DELETE FROM absence_justifications WHERE tenant_id = $1 AND id = $2 RETURNING document_path"#,
+    )
+    .bind(user.tenant_id)
+    .bind(justification_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let Some(document_path) = document_path else {
+        return Err((StatusCode::NOT_FOUND, "Justificativa não encontrada".into()));
+    };
+    if let Some(path) = document_path {
+        // o registro já saiu; arquivo órfão não impede a exclusão
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            tracing::warn!("Falha ao remover documento {path}: {e}");
+        }
+    }
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Envia (ou substitui) o documento: corpo cru do arquivo com o `Content-Type` dele.
+async fn upload_document(
+    State(state): State<AppState>,
+    Extension(DocumentsDir(documents_dir)): Extension<DocumentsDir>,
+    user: AuthUser,
+    Path(justification_id): Path<Uuid>,
+    Query(query): Query<DocumentQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<JustificationResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or_default().trim().to_lowercase())
+        .unwrap_or_default();
+    if !DOCUMENT_TYPES.contains(&content_type.as_str()) {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Documento deve ser PDF, JPEG ou PNG".into(),
+        ));
+    }
+    if body.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Documento vazio".into()));
+    }
+
+    let current = load_justification(&state.pool, &user, justification_id).await?;
+    let filename = sanitize_filename(query.filename.as_deref())
+        .unwrap_or_else(|| format!("justificativa-{}.{}", current.start_date, extension_for(&content_type)));
+
+    let dir = format!("{documents_dir}/{}/absence-justifications", user.tenant_id);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Falha ao gravar documento".into()))?;
+    let path = format!("{dir}/{justification_id}");
+    tokio::fs::write(&path, &body)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Falha ao gravar documento".into()))?;
+
+    sqlx::query(
+        r#"
+        UPDATE absence_justifications
+        SET document_path = $3, document_name = $4, document_content_type = $5,
+            document_size = $6, updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(justification_id)
+    .bind(&path)
+    .bind(filename)
+    .bind(&content_type)
+    .bind(body.len() as i32)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let justification = load_justification(&state.pool, &user, justification_id).await?;
+    Ok(Json(justification))
+}
+
+async fn download_document(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(justification_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    // valida tenant e turma do professor
+    load_justification(&state.pool, &user, justification_id).await?;
+
+    let row = sqlx::query(
+        r#"SELECT document_path, document_name, document_content_type
+           FROM absence_justifications
+           WHERE tenant_id = $1 AND id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(justification_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let Some(path) = row.get::<Option<String>, _>("document_path") else {
+        return Err((StatusCode::NOT_FOUND, "Justificativa sem documento".into()));
+    };
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|_| (StatusCode::NOT_FOUND, "Arquivo do documento não encontrado".into()))?;
+    let name: String = row
+        .get::<Option<String>, _>("document_name")
+        .unwrap_or_else(|| "documento".to_string());
+    let content_type: String = row
+        .get::<Option<String>, _>("document_content_type")
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{name}\"")),
+        ],
+        bytes,
+    ))
+}
+
+async fn load_justification(
+    pool: &PgPool,
+    user: &AuthUser,
+    justification_id: Uuid,
+) -> Result<JustificationResponse, (StatusCode, String)> {
+    let sql = format!("{SELECT_JUSTIFICATION} WHERE j.tenant_id = $1 AND j.id = $2");
+    let row = sqlx::query(&sql)
+        .bind(user.tenant_id)
+        .bind(justification_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Justificativa não encontrada".into()))?;
+    let justification = map_justification(&row);
+
+    if restricted_teacher(user).is_some() {
+        let Some(class_id) = justification.class_id else {
+            return Err((StatusCode::FORBIDDEN, "Aluno sem turma atribuída a este professor".into()));
+        };
+        ensure_teaches(pool, user, class_id, None).await?;
+    }
+    Ok(justification)
+}
+
+fn normalize_category(category: &str) -> Result<&'static str, (StatusCode, String)> {
+    let category = category.trim().to_lowercase();
+    CATEGORIES
+        .iter()
+        .find(|c| **c == category)
+        .copied()
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "Categoria inválida (use medical, legal, bereavement, religious ou other)".into(),
+        ))
+}
+
+fn normalize_status(status: &str) -> Result<&'static str, (StatusCode, String)> {
+    match status.trim().to_lowercase().as_str() {
+        "pending" => Ok("pending"),
+        "approved" => Ok("approved"),
+        "rejected" => Ok("rejected"),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Status inválido (use pending, approved ou rejected)".into(),
+        )),
+    }
+}
+
+/// Nome exibido no download: sem diretórios, aspas ou caracteres de controle.
+fn sanitize_filename(input: Option<&str>) -> Option<String> {
+    let name = input?.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(120)
+        .collect();
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+        None
+    } else {
+        Some(cleaned)
+    }
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        _ => "pdf",
+    }
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call, call_bytes, call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 6A com uma aluna que faltou 2 de 4 aulas de Ciências (03 e 04/03).
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Atestado").await;
+            let class_id = insert_class(&pool, tenant_id, "6A", "6 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Ciências").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Lívia", "L1").await;
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let documents_dir = std::env::temp_dir().join("justifications-test");
+            let app = routes_with_documents_dir(pool.clone(), SECRET.into(), documents_dir.to_string_lossy().into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::school_settings::routes(pool.clone(), SECRET.into()));
+
+            // 4 aulas: presente, falta, falta, presente
+            for (date, present) in [("2026-03-02", true), ("2026-03-03", false), ("2026-03-04", false), ("2026-03-05", true)] {
+                let (status, _) = call_json(&app, "PUT", &format!("/classes/{class_id}/attendance"), &owner, Some(json!({
+                    "date": date, "subject_id": subject_id,
+                    "records": [{"student_id": student_id, "present": present}]
+                }))).await;
+                assert_eq!(status, StatusCode::OK);
+            }
+
+            Fixture { pool, app, owner, tenant_id, class_id, term_id, subject_id, student_id }
+        }
+
+        /// Atestado pendente cobrindo as duas faltas; devolve o caminho da justificativa.
+        async fn justify(&self) -> String {
+            let (status, created) = call_json(&self.app, "POST", "/absence-justifications", &self.owner, Some(json!({
+                "student_id": self.student_id, "start_date": "2026-03-03", "end_date": "2026-03-04",
+                "category": "medical", "reason": "Atestado de 2 dias"
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+            format!("/absence-justifications/{}", created["id"].as_str().unwrap())
+        }
+
+        async fn review(&self, path: &str, token: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", &format!("{path}/review"), token, Some(json!({"status": "approved"}))).await
+        }
+
+        async fn student_report(&self) -> Value {
+            let path = format!(
+                "/classes/{}/gradebook-report?term_id={}&subject_id={}",
+                self.class_id, self.term_id, self.subject_id
+            );
+            let (status, report) = call_json(&self.app, "GET", &path, &self.owner, None).await;
+            assert_eq!(status, StatusCode::OK);
+            report["students"][0].clone()
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn pending_justification_covers_absences_without_changing_attendance() {
+        let f = Fixture::new().await;
+        let (status, created) = call_json(&f.app, "POST", "/absence-justifications", &f.owner, Some(json!({
+            "student_id": f.student_id, "start_date": "2026-03-03", "end_date": "2026-03-04",
+            "category": "medical", "reason": "Atestado de 2 dias"
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(created["status"], "pending");
+        assert_eq!(created["absences_covered"], 2);
+
+        assert_eq!(f.student_report().await["attendance_percent"], 50.0);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn document_must_be_pdf_or_image_and_is_served_back() {
+        let f = Fixture::new().await;
+        let path = f.justify().await;
+
+        let (status, _) = call_bytes(&f.app, "PUT", &format!("{path}/document?filename=atestado.txt"), &f.owner, "text/plain", b"x".to_vec()).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let pdf = b"%PDF-1.4 atestado".to_vec();
+        let (status, _) =
+            call_bytes(&f.app, "PUT", &format!("{path}/document?filename=../atestado.pdf"), &f.owner, "application/pdf", pdf.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, bytes) = call(&f.app, "GET", &format!("{path}/document"), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(bytes, pdf);
+        let (_, reviewed) = f.review(&path, &f.owner).await;
+        assert_eq!(reviewed["document_name"], "atestado.pdf");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_cannot_review_justifications() {
+        let f = Fixture::new().await;
+        let path = f.justify().await;
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+
+        let (status, _) = f.review(&path, &teacher).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn approved_justification_leaves_attendance_calculation_by_default() {
+        let f = Fixture::new().await;
+        let path = f.justify().await;
+        let (status, reviewed) = f.review(&path, &f.owner).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(reviewed["status"], "approved");
+
+        let student = f.student_report().await;
+        assert_eq!(student["attendance_total_days"], 2);
+        assert_eq!(student["attendance_percent"], 100.0);
+        assert_eq!(student["attendance_justified_absences"], 2);
+
+        let listed_path = format!("/absence-justifications?student_id={}&status=approved", f.student_id);
+        let (_, listed) = call_json(&f.app, "GET", &listed_path, &f.owner, None).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn absence_rule_keeps_justified_absences_as_absences() {
+        let f = Fixture::new().await;
+        let path = f.justify().await;
+        f.review(&path, &f.owner).await;
+
+        let (status, _) = call_json(&f.app, "PUT", "/school/settings", &f.owner, Some(json!({
+            "passing_min_grade": 6.0, "justified_absence_rule": "absence"
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let student = f.student_report().await;
+        assert_eq!(student["attendance_percent"], 50.0);
+        assert_eq!(student["attendance_justified_absences"], 2);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn deleted_justification_no_longer_listed() {
+        let f = Fixture::new().await;
+        let path = f.justify().await;
+
+        let (status, _) = call_json(&f.app, "DELETE", &path, &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, listed) = call_json(&f.app, "GET", &format!("/absence-justifications?student_id={}", f.student_id), &f.owner, None).await;
+        assert!(listed.as_array().unwrap().is_empty());
+
+        f.cleanup().await;
+    }
+}
@@ -10,6 +10,7 @@ use sqlx::{PgPool, Row};
 use validator::Validate;
 
 use crate::auth::jwt::PlatformUser;
+use crate::grading::scales::GradingScale;
 use crate::state::AppState;
 
 #[derive(Debug, Clone, Copy, Default, Deserialize)]
@@ -51,6 +52,56 @@ pub struct UpdatePassingGradeRequest {
     pub passing_min_grade: f64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct TenantFeatureResponse {
+    pub code: String,
+    pub label: String,
+    pub enabled: bool,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTenantFeatureRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageSeriesQuery {
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TenantUsageSnapshotResponse {
+    pub snapshot_date: NaiveDate,
+    pub active_students: i32,
+    pub classes_total: i32,
+    pub users_total: i32,
+    pub logins_30d: i32,
+    pub attendance_records_30d: i32,
+    pub grade_entries_30d: i32,
+    pub contracts_total: i32,
+    pub receivables_amount_30d: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChurnRiskClientResponse {
+    pub tenant_id: uuid::Uuid,
+    pub name: String,
+    pub slug: String,
+    pub snapshot_date: NaiveDate,
+    pub baseline_date: Option<NaiveDate>,
+    pub activity_30d: i32,
+    pub baseline_activity_30d: i32,
+    pub logins_30d: i32,
+    pub activity_change_percent: Option<f64>,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageSnapshotRunResponse {
+    pub tenants_processed: u64,
+}
+
 pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
     let state = AppState { pool, jwt_secret };
 
@@ -61,6 +112,14 @@ pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
             "/admin/clients/:tenant_id/passing-grade",
             put(update_passing_grade),
         )
+        .route("/admin/clients/:tenant_id/features", get(list_tenant_features))
+        .route(
+            "/admin/clients/:tenant_id/features/:feature_code",
+            put(update_tenant_feature),
+        )
+        .route("/admin/clients/:tenant_id/usage", get(get_tenant_usage_series))
+        .route("/admin/usage/churn-risk", get(list_churn_risk_clients))
+        .route("/admin/usage/snapshot", post(run_usage_snapshot))
         .with_state(state)
 }
 
@@ -169,9 +228,8 @@ async fn update_passing_grade(
         return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
     }
 
-    if !(0.0..=10.0).contains(&req.passing_min_grade) {
-        return Err((StatusCode::BAD_REQUEST, "Média mínima deve estar entre 0 e 10".into()));
-    }
+    let passing_min_grade = GradingScale::default_numeric(req.passing_min_grade)
+        .validate_setting("Média mínima", req.passing_min_grade)?;
 
     let row = sqlx::query(
         r#"
@@ -186,7 +244,7 @@ async fn update_passing_grade(
         "#,
     )
     .bind(tenant_id)
-    .bind(req.passing_min_grade)
+    .bind(passing_min_grade)
     .fetch_optional(&state.pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
@@ -203,3 +261,322 @@ async fn update_passing_grade(
         passing_min_grade: row.get("passing_min_grade"),
     }))
 }
+
+async fn list_tenant_features(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(tenant_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<TenantFeatureResponse>>, (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    ensure_tenant_exists(&state.pool, tenant_id).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          f.code,
+          f.label,
+          COALESCE(tf.enabled, f.default_enabled) AS enabled,
+          (tf.enabled IS NULL) AS is_default
+        FROM features f
+        LEFT JOIN tenant_features tf
+          ON tf.feature_code = f.code
+         AND tf.tenant_id = $1
+        ORDER BY f.code ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let out = rows
+        .into_iter()
+        .map(|r| TenantFeatureResponse {
+            code: r.get("code"),
+            label: r.get("label"),
+            enabled: r.get("enabled"),
+            is_default: r.get("is_default"),
+        })
+        .collect();
+
+    Ok(Json(out))
+}
+
+async fn update_tenant_feature(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path((tenant_id, feature_code)): Path<(uuid::Uuid, String)>,
+    Json(req): Json<UpdateTenantFeatureRequest>,
+) -> Result<Json<TenantFeatureResponse>, (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    ensure_tenant_exists(&state.pool, tenant_id).await?;
+
+    let feature_code = feature_code.trim().to_lowercase();
+    let label: Option<String> = sqlx::query_scalar("SELECT label FROM features WHERE code = $1")
+        .bind(&feature_code)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let label = label.ok_or((StatusCode::NOT_FOUND, "Módulo não encontrado".into()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tenant_features (tenant_id, feature_code, enabled, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (tenant_id, feature_code)
+        DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&feature_code)
+    .bind(req.enabled)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(TenantFeatureResponse {
+        code: feature_code,
+        label,
+        enabled: req.enabled,
+        is_default: false,
+    }))
+}
+
+async fn get_tenant_usage_series(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(tenant_id): Path<uuid::Uuid>,
+    Query(query): Query<UsageSeriesQuery>,
+) -> Result<Json<Vec<TenantUsageSnapshotResponse>>, (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    ensure_tenant_exists(&state.pool, tenant_id).await?;
+    let days = query.days.unwrap_or(90).clamp(1, 730);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          snapshot_date,
+          active_students,
+          classes_total,
+          users_total,
+          logins_30d,
+          attendance_records_30d,
+          grade_entries_30d,
+          contracts_total,
+          receivables_amount_30d::float8 AS receivables_amount_30d
+        FROM tenant_usage_snapshots
+        WHERE tenant_id = $1
+          AND snapshot_date > CURRENT_DATE - $2
+        ORDER BY snapshot_date ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(days)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let out = rows
+        .into_iter()
+        .map(|r| TenantUsageSnapshotResponse {
+            snapshot_date: r.get("snapshot_date"),
+            active_students: r.get("active_students"),
+            classes_total: r.get("classes_total"),
+            users_total: r.get("users_total"),
+            logins_30d: r.get("logins_30d"),
+            attendance_records_30d: r.get("attendance_records_30d"),
+            grade_entries_30d: r.get("grade_entries_30d"),
+            contracts_total: r.get("contracts_total"),
+            receivables_amount_30d: r.get("receivables_amount_30d"),
+        })
+        .collect();
+
+    Ok(Json(out))
+}
+
+/// Escolas com atividade em queda: compara o último snapshot de cada escola
+/// com o snapshot de ~30 dias antes (logins + presenças + notas lançadas).
+async fn list_churn_risk_clients(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+) -> Result<Json<Vec<ChurnRiskClientResponse>>, (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        WITH latest AS (
+          SELECT DISTINCT ON (tenant_id)
+            tenant_id, snapshot_date, logins_30d,
+            (logins_30d + attendance_records_30d + grade_entries_30d) AS activity
+          FROM tenant_usage_snapshots
+          ORDER BY tenant_id, snapshot_date DESC
+        )
+        SELECT
+          t.id AS tenant_id,
+          t.name,
+          t.slug,
+          l.snapshot_date,
+          l.logins_30d,
+          l.activity AS activity_30d,
+          b.snapshot_date AS baseline_date,
+          COALESCE(b.activity, 0) AS baseline_activity_30d
+        FROM latest l
+        JOIN tenants t ON t.id = l.tenant_id
+        LEFT JOIN LATERAL (
+          SELECT
+            s.snapshot_date,
+            (s.logins_30d + s.attendance_records_30d + s.grade_entries_30d) AS activity
+          FROM tenant_usage_snapshots s
+          WHERE s.tenant_id = l.tenant_id
+            AND s.snapshot_date <= l.snapshot_date - 30
+          ORDER BY s.snapshot_date DESC
+          LIMIT 1
+        ) b ON TRUE
+        ORDER BY t.name ASC
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut out = Vec::new();
+    for r in rows {
+        let activity: i32 = r.get("activity_30d");
+        let baseline: i32 = r.get("baseline_activity_30d");
+        let baseline_date: Option<NaiveDate> = r.get("baseline_date");
+        let logins_30d: i32 = r.get("logins_30d");
+
+        let activity_change_percent = if baseline_date.is_some() && baseline > 0 {
+            Some(((activity - baseline) as f64 * 100.0 / baseline as f64 * 100.0).round() / 100.0)
+        } else {
+            None
+        };
+
+        let mut reasons = Vec::new();
+        if activity_change_percent.is_some_and(|p| p <= -50.0) {
+            reasons.push("Atividade caiu 50% ou mais em 30 dias".to_string());
+        }
+        if logins_30d == 0 {
+            reasons.push("Nenhum login nos últimos 30 dias".to_string());
+        }
+        if reasons.is_empty() {
+            continue;
+        }
+
+        out.push(ChurnRiskClientResponse {
+            tenant_id: r.get("tenant_id"),
+            name: r.get("name"),
+            slug: r.get("slug"),
+            snapshot_date: r.get("snapshot_date"),
+            baseline_date,
+            activity_30d: activity,
+            baseline_activity_30d: baseline,
+            logins_30d,
+            activity_change_percent,
+            reasons,
+        });
+    }
+
+    Ok(Json(out))
+}
+
+async fn run_usage_snapshot(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+) -> Result<Json<UsageSnapshotRunResponse>, (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+
+    let tenants_processed = crate::jobs::usage_metrics::snapshot_all_tenants(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(UsageSnapshotRunResponse { tenants_processed }))
+}
+
+async fn ensure_tenant_exists(pool: &PgPool, tenant_id: uuid::Uuid) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query("SELECT 1 FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Escola não encontrada".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{call_json, insert_tenant, make_token, platform_token, test_pool, SECRET};
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    async fn insert_snapshot(pool: &PgPool, tenant_id: Uuid, days_ago: i32, logins: i32, attendance: i32) {
+        sqlx::query(
+            r#"INSERT INTO tenant_usage_snapshots (tenant_id, snapshot_date, logins_30d, attendance_records_30d)
+               VALUES ($1, CURRENT_DATE - $2, $3, $4)"#,
+        )
+        .bind(tenant_id)
+        .bind(days_ago)
+        .bind(logins)
+        .bind(attendance)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn find(list: &Value, tenant_id: Uuid) -> Option<&Value> {
+        list.as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["tenant_id"] == tenant_id.to_string())
+    }
+
+    #[tokio::test]
+    async fn churn_risk_flags_activity_drop_and_no_logins() {
+        let pool = test_pool().await;
+        let falling = insert_tenant(&pool, "Escola em queda").await;
+        let idle = insert_tenant(&pool, "Escola parada").await;
+        let steady = insert_tenant(&pool, "Escola estável").await;
+        insert_snapshot(&pool, falling, 31, 40, 160).await;
+        insert_snapshot(&pool, falling, 0, 10, 70).await;
+        insert_snapshot(&pool, idle, 0, 0, 30).await;
+        insert_snapshot(&pool, steady, 31, 20, 100).await;
+        insert_snapshot(&pool, steady, 0, 20, 90).await;
+        let app = routes(pool.clone(), SECRET.into());
+
+        let (status, list) = call_json(&app, "GET", "/admin/usage/churn-risk", &platform_token(), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let falling = find(&list, falling).expect("queda de 60% deve aparecer");
+        assert_eq!(falling["activity_change_percent"], -60.0);
+        assert_eq!(falling["reasons"].as_array().unwrap().len(), 1);
+        let idle = find(&list, idle).expect("escola sem login deve aparecer");
+        assert!(idle["activity_change_percent"].is_null());
+        assert_eq!(idle["reasons"][0], "Nenhum login nos últimos 30 dias");
+        assert!(find(&list, steady).is_none());
+    }
+
+    #[tokio::test]
+    async fn churn_risk_requires_platform_admin() {
+        let pool = test_pool().await;
+        let tenant_id = insert_tenant(&pool, "Escola curiosa").await;
+        let app = routes(pool.clone(), SECRET.into());
+
+        let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+        let (status, _) = call_json(&app, "GET", "/admin/usage/churn-risk", &owner, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
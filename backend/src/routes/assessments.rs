@@ -0,0 +1,687 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::ensure_teaches;
+use crate::grading::history::set_change_author;
+use crate::grading::recompute_term_scores;
+use crate::grading::term_lock::{ensure_term_open, ensure_writable, CORRECTION_ASSESSMENT_SCORE};
+use crate::routes::guardian_alerts::alert_low_grades;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct AssessmentListQuery {
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAssessmentRequest {
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+    pub name: String,
+    pub assessment_date: Option<NaiveDate>,
+    pub weight: Option<f64>,
+    pub max_score: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAssessmentRequest {
+    pub name: String,
+    pub assessment_date: Option<NaiveDate>,
+    pub weight: f64,
+    pub max_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssessmentResponse {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+    pub name: String,
+    pub assessment_date: Option<NaiveDate>,
+    pub weight: f64,
+    pub max_score: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AssessmentScoreInput {
+    pub student_id: Uuid,
+    /// `null` remove a nota lançada.
+    pub score: Option<f64>,
+    pub comments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertAssessmentScoresRequest {
+    pub records: Vec<AssessmentScoreInput>,
+    /// Obrigatória (e só para owner/admin) quando o período está encerrado.
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/classes/:class_id/assessments",
+            get(list_assessments).post(create_assessment),
+        )
+        .route(
+            "/classes/:class_id/assessments/:assessment_id",
+            put(update_assessment).delete(delete_assessment),
+        )
+        .route(
+            "/classes/:class_id/assessments/:assessment_id/scores",
+            put(upsert_assessment_scores),
+        )
+        .with_state(state)
+}
+
+async fn list_assessments(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<AssessmentListQuery>,
+) -> Result<Json<Vec<AssessmentResponse>>, (StatusCode, String)> {
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE tenant_id = $1 AND class_id = $2 AND term_id = $3 AND subject_id = $4
+            ORDER BY assessment_date ASC NULLS LAST, created_at ASC",
+        ASSESSMENT_SELECT
+    ))
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.term_id)
+    .bind(query.subject_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.into_iter().map(map_assessment_row).collect()))
+}
+
+async fn create_assessment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<CreateAssessmentRequest>,
+) -> Result<Json<AssessmentResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, req.term_id).await?;
+    ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, req.subject_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+
+    let name = validate_name(&req.name)?;
+    let weight = req.weight.unwrap_or(1.0);
+    let max_score = req.max_score.unwrap_or(10.0);
+    validate_weight_and_max(weight, max_score)?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    // período encerrado não ganha avaliações novas
+    ensure_term_open(&mut tx, user.tenant_id, req.term_id).await?;
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO assessments
+          (id, tenant_id, class_id, subject_id, term_id, name, assessment_date, weight, max_score)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id, class_id, term_id, subject_id, name, assessment_date,
+                  weight::float8 AS weight, max_score::float8 AS max_score
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(req.subject_id)
+    .bind(req.term_id)
+    .bind(name)
+    .bind(req.assessment_date)
+    .bind(weight)
+    .bind(max_score)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    // a primeira avaliação passa a definir a nota do período
+    recompute_term_scores(&mut tx, user.tenant_id, class_id, req.term_id, req.subject_id).await?;
+    alert_low_grades(&mut tx, user.tenant_id, class_id, req.term_id, req.subject_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(map_assessment_row(row)))
+}
+
+async fn update_assessment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, assessment_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateAssessmentRequest>,
+) -> Result<Json<AssessmentResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+
+    let name = validate_name(&req.name)?;
+    validate_weight_and_max(req.weight, req.max_score)?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    let max_given: Option<f64> = sqlx::query_scalar(
+        r#"SELECT MAX(score)::float8 FROM assessment_scores WHERE tenant_id = $1 AND assessment_id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(assessment_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if max_given.is_some_and(|m| m > req.max_score) {
+        return Err((StatusCode::BAD_REQUEST, "Já existem notas acima da nova nota máxima".into()));
+    }
+
+    let row = sqlx::query(
+        r#"
+        UPDATE assessments
+        SET name = $4, assessment_date = $5, weight = $6, max_score = $7
+        WHERE tenant_id = $1 AND class_id = $2 AND id = $3
+        RETURNING id, class_id, term_id, subject_id, name, assessment_date,
+                  weight::float8 AS weight, max_score::float8 AS max_score
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(assessment_id)
+    .bind(name)
+    .bind(req.assessment_date)
+    .bind(req.weight)
+    .bind(req.max_score)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Avaliação não encontrada".into()))?;
+
+    let assessment = map_assessment_row(row);
+    ensure_teaches(&mut *tx, &user, class_id, Some(assessment.subject_id)).await?;
+    ensure_term_open(&mut tx, user.tenant_id, assessment.term_id).await?;
+    recompute_term_scores(
+        &mut tx,
+        user.tenant_id,
+        class_id,
+        assessment.term_id,
+        assessment.subject_id,
+    )
+    .await?;
+    alert_low_grades(&mut tx, user.tenant_id, class_id, assessment.term_id, assessment.subject_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(assessment))
+}
+
+async fn delete_assessment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, assessment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    let row = sqlx::query(
+        r#"
+        DELETE FROM assessments
+        WHERE tenant_id = $1 AND class_id = $2 AND id = $3
+        RETURNING term_id, subject_id
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(assessment_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Avaliação não encontrada".into()))?;
+    ensure_teaches(&mut *tx, &user, class_id, Some(row.get("subject_id"))).await?;
+    ensure_term_open(&mut tx, user.tenant_id, row.get("term_id")).await?;
+
+    recompute_term_scores(
+        &mut tx,
+        user.tenant_id,
+        class_id,
+        row.get("term_id"),
+        row.get("subject_id"),
+    )
+    .await?;
+    alert_low_grades(&mut tx, user.tenant_id, class_id, row.get("term_id"), row.get("subject_id")).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn upsert_assessment_scores(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, assessment_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpsertAssessmentScoresRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    let assessment = sqlx::query(
+        r#"
+        SELECT term_id, subject_id, max_score::float8 AS max_score
+        FROM assessments
+        WHERE tenant_id = $1 AND class_id = $2 AND id = $3
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(assessment_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Avaliação não encontrada".into()))?;
+    let max_score: f64 = assessment.get("max_score");
+    ensure_teaches(&mut *tx, &user, class_id, Some(assessment.get("subject_id"))).await?;
+    ensure_writable(
+        &mut tx,
+        &user,
+        Some(assessment.get("term_id")),
+        class_id,
+        CORRECTION_ASSESSMENT_SCORE,
+        req.correction_justification.as_deref(),
+        serde_json::json!({ "assessment_id": assessment_id, "records": req.records }),
+    )
+    .await?;
+
+    for r in &req.records {
+        ensure_student_belongs_to_class(&mut tx, user.tenant_id, class_id, r.student_id).await?;
+
+        match r.score {
+            Some(score) => {
+                if !(0.0..=max_score).contains(&score) {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        format!("Nota deve estar entre 0 e {max_score}"),
+                    ));
+                }
+                sqlx::query(
+                    r#"
+                    INSERT INTO assessment_scores (id, tenant_id, assessment_id, student_id, score, comments)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (assessment_id, student_id)
+                    DO UPDATE SET score = EXCLUDED.score, comments = EXCLUDED.comments, updated_at = NOW()
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(user.tenant_id)
+                .bind(assessment_id)
+                .bind(r.student_id)
+                .bind(score)
+                .bind(normalize_optional_text(r.comments.clone()))
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+            }
+            None => {
+                sqlx::query(
+                    r#"DELETE FROM assessment_scores
+                       WHERE tenant_id = $1 AND assessment_id = $2 AND student_id = $3"#,
+                )
+                .bind(user.tenant_id)
+                .bind(assessment_id)
+                .bind(r.student_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+            }
+        }
+    }
+
+    recompute_term_scores(
+        &mut tx,
+        user.tenant_id,
+        class_id,
+        assessment.get("term_id"),
+        assessment.get("subject_id"),
+    )
+    .await?;
+    alert_low_grades(
+        &mut tx,
+        user.tenant_id,
+        class_id,
+        assessment.get("term_id"),
+        assessment.get("subject_id"),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+const ASSESSMENT_SELECT: &str = r#"
+    SELECT id, class_id, term_id, subject_id, name, assessment_date,
+           weight::float8 AS weight, max_score::float8 AS max_score
+    FROM assessments
+"#;
+
+fn map_assessment_row(r: sqlx::postgres::PgRow) -> AssessmentResponse {
+    AssessmentResponse {
+        id: r.get("id"),
+        class_id: r.get("class_id"),
+        term_id: r.get("term_id"),
+        subject_id: r.get("subject_id"),
+        name: r.get("name"),
+        assessment_date: r.get("assessment_date"),
+        weight: r.get("weight"),
+        max_score: r.get("max_score"),
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, (StatusCode, String)> {
+    let name = name.trim().to_string();
+    if name.len() < 2 {
+        return Err((StatusCode::BAD_REQUEST, "Nome da avaliação inválido".into()));
+    }
+    Ok(name)
+}
+
+fn validate_weight_and_max(weight: f64, max_score: f64) -> Result<(), (StatusCode, String)> {
+    if !(weight > 0.0 && weight <= 100.0) {
+        return Err((StatusCode::BAD_REQUEST, "Peso deve ser maior que 0 e até 100".into()));
+    }
+    if !(max_score > 0.0 && max_score <= 1000.0) {
+        return Err((StatusCode::BAD_REQUEST, "Nota máxima inválida".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM classes WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Turma não encontrada".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_term_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    term_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM academic_terms WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(term_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Período letivo inválido".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_subject_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(subject_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_student_belongs_to_class(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    student_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"SELECT 1 FROM students WHERE tenant_id = $1 AND id = $2 AND class_id = $3"#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .bind(class_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Aluno não pertence a esta turma".into()));
+    }
+    Ok(())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 6A com a aluna Ana, Matemática e o 1º bimestre. A prova (peso 3, nota máxima
+    /// 10) recebe 8 e o trabalho (peso 1, nota máxima 20) recebe 10, ou 5 na escala de 0 a 10.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Avaliações").await;
+            let class_id = insert_class(&pool, tenant_id, "6A", "6 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Matemática").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Ana", "A1").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::school_settings::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, token, tenant_id, class_id, term_id, subject_id, student_id }
+        }
+
+        fn assessments_path(&self) -> String {
+            format!("/classes/{}/assessments", self.class_id)
+        }
+
+        async fn create_assessment(&self, name: &str, weight: f64, max_score: f64) -> Value {
+            let (status, assessment) = call_json(&self.app, "POST", &self.assessments_path(), &self.token, Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id, "name": name, "weight": weight, "max_score": max_score
+            }))).await;
+            assert_eq!(status, StatusCode::OK, "{assessment}");
+            assessment
+        }
+
+        async fn set_score(&self, assessment: &Value, score: f64) {
+            let (status, _) = call_json(
+                &self.app,
+                "PUT",
+                &format!("{}/{}/scores", self.assessments_path(), assessment["id"].as_str().unwrap()),
+                &self.token,
+                Some(json!({"records": [{"student_id": self.student_id, "score": score}]})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        /// Prova e trabalho com as notas da Ana.
+        async fn grade_assessments(&self) {
+            let prova = self.create_assessment("Prova", 3.0, 10.0).await;
+            let trabalho = self.create_assessment("Trabalho", 1.0, 20.0).await;
+            self.set_score(&prova, 8.0).await;
+            self.set_score(&trabalho, 10.0).await;
+        }
+
+        async fn gradebook(&self) -> Value {
+            let path = format!(
+                "/classes/{}/gradebook?term_id={}&subject_id={}",
+                self.class_id, self.term_id, self.subject_id
+            );
+            let (status, gradebook) = call_json(&self.app, "GET", &path, &self.token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            gradebook
+        }
+
+        async fn set_arithmetic_formula(&self) {
+            let (status, _) = call_json(&self.app, "PUT", "/school/settings", &self.token, Some(json!({
+                "passing_min_grade": 6, "grade_formula": "arithmetic"
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn term_score_is_weighted_average_of_assessments() {
+        let f = Fixture::new().await;
+        f.grade_assessments().await;
+
+        // (8 * 3 + 5 * 1) / 4 = 7.25
+        let gradebook = f.gradebook().await;
+        assert_eq!(gradebook["derived_from_assessments"], true);
+        assert_eq!(gradebook["assessments"].as_array().unwrap().len(), 2);
+        assert_eq!(gradebook["items"][0]["score"], 7.25);
+        assert_eq!(gradebook["items"][0]["assessment_scores"], json!([8.0, 10.0]));
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn term_score_cannot_be_typed_when_assessments_exist() {
+        let f = Fixture::new().await;
+        f.grade_assessments().await;
+
+        let (status, _) = call_json(&f.app, "PUT", &format!("/classes/{}/gradebook", f.class_id), &f.token, Some(json!({
+            "term_id": f.term_id, "subject_id": f.subject_id,
+            "records": [{"student_id": f.student_id, "score": 10}]
+        }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(f.gradebook().await["items"][0]["score"], 7.25);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn arithmetic_formula_recomputes_term_score() {
+        let f = Fixture::new().await;
+        f.grade_assessments().await;
+
+        // média aritmética: (8 + 5) / 2 = 6.5
+        f.set_arithmetic_formula().await;
+        assert_eq!(f.gradebook().await["items"][0]["score"], 6.5);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn formula_change_leaves_closed_terms_alone() {
+        let f = Fixture::new().await;
+        f.grade_assessments().await;
+        sqlx::query("UPDATE academic_terms SET closed_at = NOW() WHERE id = $1")
+            .bind(f.term_id)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+
+        f.set_arithmetic_formula().await;
+        assert_eq!(f.gradebook().await["items"][0]["score"], 7.25);
+        let corrections: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM term_corrections WHERE term_id = $1")
+            .bind(f.term_id)
+            .fetch_one(&f.pool)
+            .await
+            .unwrap();
+        assert_eq!(corrections, 0);
+
+        f.cleanup().await;
+    }
+}
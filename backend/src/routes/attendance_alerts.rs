@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::grading::promotion::{presence_percent, remaining_absences, ATTENDANCE_WARNING_MARGIN};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct EarlyWarningQuery {
+    pub class_id: Option<Uuid>,
+    /// Pontos percentuais acima do mínimo que já disparam o alerta.
+    pub margin: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EarlyWarningItem {
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    /// `None` para a frequência geral.
+    pub subject_id: Option<Uuid>,
+    pub subject_name: Option<String>,
+    pub total_lessons: i32,
+    pub absences: i32,
+    pub attendance_percent: f64,
+    pub remaining_absences: i32,
+    pub below_minimum: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EarlyWarningResponse {
+    pub min_attendance_percent: f64,
+    pub warning_threshold: f64,
+    pub items: Vec<EarlyWarningItem>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/attendance/early-warning", get(get_early_warning))
+        .with_state(state)
+}
+
+/// Alunos abaixo ou perto da frequência mínima, no geral e por disciplina.
+async fn get_early_warning(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<EarlyWarningQuery>,
+) -> Result<Json<EarlyWarningResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let margin = query.margin.unwrap_or(ATTENDANCE_WARNING_MARGIN);
+    if !(0.0..=100.0).contains(&margin) {
+        return Err((StatusCode::BAD_REQUEST, "Margem deve estar entre 0 e 100".into()));
+    }
+
+    let min_attendance_percent: f64 = sqlx::query_scalar(
+        "SELECT min_attendance_percent::float8 FROM tenants WHERE id = $1",
+    )
+    .bind(user.tenant_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
+    let warning_threshold = (min_attendance_percent + margin).min(100.0);
+
+    let student_rows = sqlx::query(
+        r#"
+        SELECT s.id, s.name, s.registration, c.id AS class_id, c.name AS class_name
+        FROM students s
+        JOIN classes c ON c.id = s.class_id AND c.tenant_id = s.tenant_id
+        WHERE s.tenant_id = $1
+          AND ($2::uuid IS NULL OR s.class_id = $2)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(query.class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let students: HashMap<Uuid, (Uuid, String, String, String)> = student_rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.get("id"),
+                (
+                    r.get("class_id"),
+                    r.get("class_name"),
+                    r.get("name"),
+                    r.get("registration"),
+                ),
+            )
+        })
+        .collect();
+
+    // uma linha por aluno (geral, subject_id nulo) e uma por aluno+disciplina
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          a.student_id,
+          a.subject_id,
+          sbj.name AS subject_name,
+          GROUPING(a.subject_id) = 1 AS is_overall,
+          COUNT(*) FILTER (WHERE a.counted)::int AS total_lessons,
+          COUNT(*) FILTER (WHERE a.counted AND a.present)::int AS present_lessons
+        FROM student_attendance_effective a
+        LEFT JOIN subjects sbj ON sbj.id = a.subject_id AND sbj.tenant_id = a.tenant_id
+        WHERE a.tenant_id = $1
+          AND ($2::uuid IS NULL OR a.class_id = $2)
+        GROUP BY GROUPING SETS ((a.student_id), (a.student_id, a.subject_id, sbj.name))
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(query.class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut items = Vec::new();
+    for r in rows {
+        let is_overall: bool = r.get("is_overall");
+        let subject_id: Option<Uuid> = r.get("subject_id");
+        // chamada diária (sem disciplina) já entra na frequência geral
+        if !is_overall && subject_id.is_none() {
+            continue;
+        }
+        let student_id: Uuid = r.get("student_id");
+        let Some((class_id, class_name, student_name, registration)) = students.get(&student_id)
+        else {
+            continue;
+        };
+
+        let total_lessons: i32 = r.get("total_lessons");
+        let present_lessons: i32 = r.get::<Option<i32>, _>("present_lessons").unwrap_or(0);
+        let Some(attendance_percent) = presence_percent(total_lessons, present_lessons) else {
+            continue;
+        };
+        if attendance_percent >= warning_threshold {
+            continue;
+        }
+
+        items.push(EarlyWarningItem {
+            class_id: *class_id,
+            class_name: class_name.clone(),
+            student_id,
+            student_name: student_name.clone(),
+            registration: registration.clone(),
+            subject_id: if is_overall { None } else { subject_id },
+            subject_name: if is_overall { None } else { r.get("subject_name") },
+            total_lessons,
+            absences: total_lessons - present_lessons,
+            attendance_percent,
+            remaining_absences: remaining_absences(
+                total_lessons,
+                present_lessons,
+                min_attendance_percent,
+            ),
+            below_minimum: attendance_percent < min_attendance_percent,
+        });
+    }
+
+    items.sort_by(|a, b| {
+        a.attendance_percent
+            .total_cmp(&b.attendance_percent)
+            .then_with(|| a.student_name.cmp(&b.student_name))
+    });
+
+    Ok(Json(EarlyWarningResponse {
+        min_attendance_percent,
+        warning_threshold,
+        items,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 8A com a aluna Carla, nota 9 em Matemática e Português no 1º bimestre. Em
+    /// quatro dias de aula ela vai a todas as de Matemática e a só duas de Português: 50% na
+    /// disciplina e 75% no geral, exatamente o mínimo padrão.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Frequência").await;
+            let class_id = insert_class(&pool, tenant_id, "8A", "8 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let math_id = insert_subject(&pool, tenant_id, "Matemática").await;
+            let portuguese_id = insert_subject(&pool, tenant_id, "Português").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Carla", "C1").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()));
+
+            for day in 1..=4 {
+                for (subject_id, present) in [(math_id, true), (portuguese_id, day <= 2)] {
+                    let (status, _) = call_json(&app, "PUT", &format!("/classes/{class_id}/attendance"), &token, Some(json!({
+                        "date": format!("2026-03-0{day}"), "subject_id": subject_id,
+                        "records": [{"student_id": student_id, "present": present}]
+                    }))).await;
+                    assert_eq!(status, StatusCode::OK);
+                }
+            }
+            for subject_id in [math_id, portuguese_id] {
+                let (status, _) = call_json(&app, "PUT", &format!("/classes/{class_id}/gradebook"), &token, Some(json!({
+                    "term_id": term_id, "subject_id": subject_id,
+                    "records": [{"student_id": student_id, "score": 9}]
+                }))).await;
+                assert_eq!(status, StatusCode::OK);
+            }
+
+            Fixture { pool, app, token, tenant_id, class_id, student_id }
+        }
+
+        async fn early_warning(&self) -> Vec<Value> {
+            let path = format!("/attendance/early-warning?class_id={}", self.class_id);
+            let (status, warning) = call_json(&self.app, "GET", &path, &self.token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            warning["items"].as_array().unwrap().clone()
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn subject_attendance_below_minimum_retains_student() {
+        let f = Fixture::new().await;
+
+        let path = format!("/classes/{}/students/{}/full-report", f.class_id, f.student_id);
+        let (status, report) = call_json(&f.app, "GET", &path, &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["min_attendance_percent"], 75.0);
+        assert_eq!(report["subjects"][0]["final_status"], "approved");
+        assert_eq!(report["subjects"][1]["attendance_percent"], 50.0);
+        assert_eq!(report["subjects"][1]["final_status"], "failed_attendance");
+        assert_eq!(report["final_status_label"], "Retido por falta");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn early_warning_flags_the_subject_below_minimum() {
+        let f = Fixture::new().await;
+
+        let items = f.early_warning().await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["subject_name"], "Português");
+        assert_eq!(items[0]["below_minimum"], true);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn early_warning_overall_row_at_minimum_has_no_absences_left() {
+        let f = Fixture::new().await;
+
+        let items = f.early_warning().await;
+        let overall = items.iter().find(|i| i["subject_id"].is_null()).expect("linha geral");
+        assert_eq!(overall["attendance_percent"], 75.0);
+        assert_eq!(overall["below_minimum"], false);
+        assert_eq!(overall["remaining_absences"], 0);
+
+        f.cleanup().await;
+    }
+}
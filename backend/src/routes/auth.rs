@@ -10,14 +10,11 @@ use sqlx::PgPool;
 use uuid::Uuid;
 use validator::Validate;
 
-use argon2::{
-    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
-
 use jsonwebtoken::{encode, EncodingKey, Header};
 use sqlx::Row;
 
+use crate::auth::identity::{check_identity_credentials, link_or_create_identity};
+
 #[derive(Clone)]
 pub struct AuthState {
     pub pool: PgPool,
@@ -46,6 +43,9 @@ pub struct LoginRequest {
 
     #[validate(length(min = 1))]
     pub password: String,
+
+    /// Obrigatório quando a identidade tem 2FA ativo.
+    pub totp_code: Option<String>,
 }
 
 
@@ -85,14 +85,6 @@ async fn register(
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
-    // Gera salt e hash Argon2
-    let salt = SaltString::generate(&mut rand::thread_rng());
-    let argon2 = Argon2::default();
-    let password_hash = argon2
-        .hash_password(req.password.as_bytes(), &salt)
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro ao gerar hash".into()))?
-        .to_string();
-
     // Transação: cria tenant + cria owner
     let mut tx = state.pool.begin().await.map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
     let slug = req.school_code.trim().to_lowercase();
@@ -142,15 +134,18 @@ async fn register(
     .await
     .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro criando papel base: {e}")))?;
 
+    // Quem já usa o sistema em outra escola reaproveita a mesma identidade (e senha).
+    let identity_id = link_or_create_identity(&mut tx, &owner_email, &req.password, true).await?;
+
     sqlx::query(
-        r#"INSERT INTO users (id, tenant_id, person_id, email, password_hash, role)
+        r#"INSERT INTO users (id, tenant_id, person_id, identity_id, email, role)
            VALUES ($1, $2, $3, $4, $5, $6)"#,
     )
     .bind(user_id)
     .bind(tenant_id)
     .bind(user_id)
+    .bind(identity_id)
     .bind(owner_email)
-    .bind(password_hash)
     .bind("owner")
     .execute(&mut *tx)
     .await
@@ -182,11 +177,13 @@ async fn login(
 
     let row = sqlx::query(
         r#"
-        SELECT u.id, u.tenant_id, u.password_hash, u.role
+        SELECT u.id, u.tenant_id, u.role
+             , i.id AS identity_id, i.email, i.password_hash, i.totp_enabled, i.totp_secret
              , t.name as school_name, t.slug as school_code
         FROM users u
+        JOIN identities i ON i.id = u.identity_id
         JOIN tenants t ON t.id = u.tenant_id
-        WHERE t.slug = $1 AND u.email = $2
+        WHERE t.slug = $1 AND lower(i.email) = $2
         "#,
     )
     .bind(&school_code)
@@ -198,24 +195,19 @@ async fn login(
 
 
     let row = row.ok_or((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
+    check_identity_credentials(&state.pool, &row, &req.password, req.totp_code.as_deref()).await?;
+
     let user_id: Uuid = row.get("id");
-    let tenant_id: Uuid = row.get("tenant_id"); 
-    let password_hash_db: String = row.get("password_hash");
+    let tenant_id: Uuid = row.get("tenant_id");
     let role: String = row.get("role");
     let school_name: String = row.get("school_name");
     let school_code: String = row.get("school_code");
 
-    // Verifica senha Argon2
-    let parsed_hash = PasswordHash::new(&password_hash_db)
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Hash inválido".into()))?;
-
-    Argon2::default()
-        .verify_password(req.password.as_bytes(), &parsed_hash)
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
-
     let token = make_jwt(&state.jwt_secret, user_id, tenant_id, &role)
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
 
+    record_login(&state.pool, tenant_id, user_id).await;
+
     Ok(Json(AuthResponse {
         tenant_id,
         user_id,
@@ -225,7 +217,32 @@ async fn login(
     }))
 }
 
-fn make_jwt(jwt_secret: &str, user_id: Uuid, tenant_id: Uuid, role: &str) -> Result<String, ()> {
+/// Alimenta as métricas de uso da plataforma (logins nos últimos 30 dias).
+/// Falha aqui só perde a métrica; o login segue normalmente.
+pub(crate) async fn record_login(pool: &PgPool, tenant_id: Uuid, user_id: Uuid) {
+    if let Err(e) = sqlx::query("INSERT INTO login_events (id, tenant_id, user_id) VALUES ($1, $2, $3)")
+        .bind(Uuid::new_v4())
+        .bind(tenant_id)
+        .bind(user_id)
+        .execute(pool)
+        .await
+    {
+        tracing::warn!("Falha ao registrar login do usuário {user_id}: {e}");
+    }
+}
+
+pub(crate) fn make_jwt(jwt_secret: &str, user_id: Uuid, tenant_id: Uuid, role: &str) -> Result<String, ()> {
+    make_scoped_jwt(jwt_secret, user_id, tenant_id, role, "tenant")
+}
+
+/// Token da escola com escopo explícito (`tenant` ou `tenant_sso`).
+pub(crate) fn make_scoped_jwt(
+    jwt_secret: &str,
+    user_id: Uuid,
+    tenant_id: Uuid,
+    role: &str,
+    scope: &str,
+) -> Result<String, ()> {
     // 7 dias em segundos
     let exp = (chrono::Utc::now() + chrono::Duration::days(7)).timestamp() as usize;
 
@@ -233,7 +250,7 @@ fn make_jwt(jwt_secret: &str, user_id: Uuid, tenant_id: Uuid, role: &str) -> Res
         sub: user_id.to_string(),
         tenant_id: Some(tenant_id.to_string()),
         role: role.to_string(),
-        scope: "tenant".to_string(),
+        scope: scope.to_string(),
         exp,
     };
 
@@ -0,0 +1,932 @@
+//! Conselho de classe: uma reunião por turma e período, com os presentes e as decisões
+//! por aluno (aprovar, reter ou encaminhar para recuperação), justificativa e ajuste de
+//! nota. O boletim anual aplica as decisões em `grading::council`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::grading::council::normalize_decision;
+use crate::grading::scales::scale_for_class;
+use crate::grading::term_lock::validate_justification;
+use crate::state::AppState;
+
+const MANAGE_ROLES: [&str; 3] = ["owner", "admin", "staff"];
+
+#[derive(Debug, Deserialize)]
+pub struct AttendeeInput {
+    pub user_id: Option<Uuid>,
+    /// Padrão: nome do usuário informado.
+    pub name: Option<String>,
+    /// Ex.: "Coordenação pedagógica", "Professora de Matemática".
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCouncilRequest {
+    pub term_id: Uuid,
+    pub held_on: NaiveDate,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<AttendeeInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCouncilRequest {
+    pub held_on: NaiveDate,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<AttendeeInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecisionInput {
+    /// Sem disciplina: decisão sobre a situação geral do aluno.
+    pub subject_id: Option<Uuid>,
+    /// approve | retain | recovery
+    pub decision: String,
+    pub justification: String,
+    /// Nova nota do período na disciplina (escalas numéricas).
+    pub adjusted_score: Option<f64>,
+    /// Novo conceito do período na disciplina (escalas conceituais).
+    pub adjusted_concept: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveDecisionsRequest {
+    pub decisions: Vec<DecisionInput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AttendeeResponse {
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecisionResponse {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub subject_id: Option<Uuid>,
+    pub subject_name: Option<String>,
+    pub decision: String,
+    pub justification: String,
+    pub adjusted_score: Option<f64>,
+    pub adjusted_display: Option<String>,
+    pub decided_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CouncilSummaryResponse {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub held_on: NaiveDate,
+    pub notes: Option<String>,
+    pub attendees_count: i64,
+    pub students_decided: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CouncilResponse {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub held_on: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub attendees: Vec<AttendeeResponse>,
+    pub decisions: Vec<DecisionResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/classes/:class_id/councils",
+            get(list_councils).post(create_council),
+        )
+        .route(
+            "/councils/:council_id",
+            get(get_council).put(update_council).delete(delete_council),
+        )
+        .route(
+            "/councils/:council_id/students/:student_id/decisions",
+            put(save_student_decisions),
+        )
+        .with_state(state)
+}
+
+async fn list_councils(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+) -> Result<Json<Vec<CouncilSummaryResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    if restricted_teacher(&user).is_some() {
+        ensure_teaches(&state.pool, &user, class_id, None).await?;
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.id, c.class_id, c.term_id, t.name AS term_name, c.held_on, c.notes,
+               (SELECT COUNT(*) FROM class_council_attendees a WHERE a.council_id = c.id) AS attendees_count,
+               (SELECT COUNT(DISTINCT d.student_id) FROM class_council_decisions d WHERE d.council_id = c.id) AS students_decided
+        FROM class_councils c
+        JOIN academic_terms t ON t.id = c.term_id
+        WHERE c.tenant_id = $1 AND c.class_id = $2
+        ORDER BY t.school_year ASC, t.sort_order ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| CouncilSummaryResponse {
+                id: r.get("id"),
+                class_id: r.get("class_id"),
+                term_id: r.get("term_id"),
+                term_name: r.get("term_name"),
+                held_on: r.get("held_on"),
+                notes: r.get("notes"),
+                attendees_count: r.get("attendees_count"),
+                students_decided: r.get("students_decided"),
+            })
+            .collect(),
+    ))
+}
+
+async fn create_council(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<CreateCouncilRequest>,
+) -> Result<Json<CouncilResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let class_year = ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+
+    let term_year: Option<i32> = sqlx::query_scalar(
+        "SELECT school_year FROM academic_terms WHERE tenant_id = $1 AND id = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(req.term_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    match term_year {
+        None => return Err((StatusCode::BAD_REQUEST, "Período inválido para este tenant".into())),
+        Some(year) if year != class_year => {
+            return Err((StatusCode::BAD_REQUEST, "Período não pertence ao ano letivo da turma".into()));
+        }
+        Some(_) => {}
+    }
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let council_id = Uuid::new_v4();
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO class_councils (id, tenant_id, class_id, term_id, held_on, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (tenant_id, class_id, term_id) DO NOTHING
+        "#,
+    )
+    .bind(council_id)
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(req.term_id)
+    .bind(req.held_on)
+    .bind(normalize_optional_text(req.notes))
+    .bind(user.user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if inserted.rows_affected() == 0 {
+        return Err((StatusCode::CONFLICT, "Já existe conselho de classe desta turma neste período".into()));
+    }
+
+    replace_attendees(&mut tx, user.tenant_id, council_id, req.attendees).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let data = load_council(&state.pool, user.tenant_id, council_id).await?;
+    Ok(Json(data))
+}
+
+async fn get_council(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(council_id): Path<Uuid>,
+) -> Result<Json<CouncilResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    let data = load_council(&state.pool, user.tenant_id, council_id).await?;
+    if restricted_teacher(&user).is_some() {
+        ensure_teaches(&state.pool, &user, data.class_id, None).await?;
+    }
+    Ok(Json(data))
+}
+
+async fn update_council(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(council_id): Path<Uuid>,
+    Json(req): Json<UpdateCouncilRequest>,
+) -> Result<Json<CouncilResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE class_councils
+        SET held_on = $3, notes = $4, updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(council_id)
+    .bind(req.held_on)
+    .bind(normalize_optional_text(req.notes))
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if updated.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Conselho de classe não encontrado".into()));
+    }
+
+    replace_attendees(&mut tx, user.tenant_id, council_id, req.attendees).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let data = load_council(&state.pool, user.tenant_id, council_id).await?;
+    Ok(Json(data))
+}
+
+async fn delete_council(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(council_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let deleted = sqlx::query("DELETE FROM class_councils WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(council_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if deleted.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Conselho de classe não encontrado".into()));
+    }
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Substitui todas as decisões do aluno neste conselho; lista vazia limpa.
+async fn save_student_decisions(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((council_id, student_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<SaveDecisionsRequest>,
+) -> Result<Json<Vec<DecisionResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+
+    let class_id: Uuid = sqlx::query_scalar("SELECT class_id FROM class_councils WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(council_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Conselho de classe não encontrado".into()))?;
+
+    // vale também para quem já saiu da turma (conselho do último período)
+    let enrolled = sqlx::query(
+        "SELECT 1 FROM student_enrollments WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3",
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if enrolled.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Aluno não pertence a esta turma".into()));
+    }
+
+    let scale = scale_for_class(&state.pool, user.tenant_id, class_id).await?;
+    let mut seen = HashSet::new();
+    let mut rows = Vec::with_capacity(req.decisions.len());
+    for input in req.decisions {
+        if !seen.insert(input.subject_id) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Decisão repetida para a mesma disciplina (ou para a situação geral)".into(),
+            ));
+        }
+        let decision = normalize_decision(&input.decision)?;
+        let justification = validate_justification(Some(&input.justification))?.to_string();
+        let adjusted_score = scale.input_value(input.adjusted_score, input.adjusted_concept.as_deref())?;
+        match input.subject_id {
+            Some(subject_id) => ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, subject_id).await?,
+            None if adjusted_score.is_some() => {
+                return Err((StatusCode::BAD_REQUEST, "Ajuste de nota exige a disciplina".into()));
+            }
+            None => {}
+        }
+        rows.push((input.subject_id, decision, justification, adjusted_score));
+    }
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    sqlx::query("DELETE FROM class_council_decisions WHERE council_id = $1 AND student_id = $2")
+        .bind(council_id)
+        .bind(student_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    for (subject_id, decision, justification, adjusted_score) in rows {
+        sqlx::query(
+            r#"
+            INSERT INTO class_council_decisions
+              (id, tenant_id, council_id, student_id, subject_id, decision, justification, adjusted_score, decided_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user.tenant_id)
+        .bind(council_id)
+        .bind(student_id)
+        .bind(subject_id)
+        .bind(decision)
+        .bind(justification)
+        .bind(adjusted_score)
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let decisions = load_decisions(&state.pool, user.tenant_id, council_id, class_id, Some(student_id)).await?;
+    Ok(Json(decisions))
+}
+
+async fn replace_attendees(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    council_id: Uuid,
+    attendees: Vec<AttendeeInput>,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query("DELETE FROM class_council_attendees WHERE council_id = $1")
+        .bind(council_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    for (i, attendee) in attendees.into_iter().enumerate() {
+        let user_name = match attendee.user_id {
+            Some(user_id) => Some(
+                sqlx::query_scalar::<_, String>(
+                    "SELECT COALESCE(full_name, email) FROM users WHERE tenant_id = $1 AND id = $2",
+                )
+                .bind(tenant_id)
+                .bind(user_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+                .ok_or((StatusCode::BAD_REQUEST, "Usuário inválido para este tenant".into()))?,
+            ),
+            None => None,
+        };
+        let name = normalize_optional_text(attendee.name)
+            .or(user_name)
+            .ok_or((StatusCode::BAD_REQUEST, "Informe o nome de cada participante".into()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO class_council_attendees (id, council_id, user_id, name, role, sort_order)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(council_id)
+        .bind(attendee.user_id)
+        .bind(name)
+        .bind(normalize_optional_text(attendee.role))
+        .bind(i as i32)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+    Ok(())
+}
+
+async fn load_council(pool: &PgPool, tenant_id: Uuid, council_id: Uuid) -> Result<CouncilResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT c.id, c.class_id, cl.name AS class_name, c.term_id, t.name AS term_name,
+               c.held_on, c.notes, c.created_by, c.created_at
+        FROM class_councils c
+        JOIN classes cl ON cl.id = c.class_id
+        JOIN academic_terms t ON t.id = c.term_id
+        WHERE c.tenant_id = $1 AND c.id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(council_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Conselho de classe não encontrado".into()))?;
+    let class_id: Uuid = row.get("class_id");
+
+    let attendees = sqlx::query(
+        r#"
+        SELECT user_id, name, role
+        FROM class_council_attendees
+        WHERE council_id = $1
+        ORDER BY sort_order ASC
+        "#,
+    )
+    .bind(council_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .into_iter()
+    .map(|r| AttendeeResponse {
+        user_id: r.get("user_id"),
+        name: r.get("name"),
+        role: r.get("role"),
+    })
+    .collect();
+
+    let decisions = load_decisions(pool, tenant_id, council_id, class_id, None).await?;
+
+    Ok(CouncilResponse {
+        id: row.get("id"),
+        class_id,
+        class_name: row.get("class_name"),
+        term_id: row.get("term_id"),
+        term_name: row.get("term_name"),
+        held_on: row.get("held_on"),
+        notes: row.get("notes"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+        attendees,
+        decisions,
+    })
+}
+
+async fn load_decisions(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    council_id: Uuid,
+    class_id: Uuid,
+    student_id: Option<Uuid>,
+) -> Result<Vec<DecisionResponse>, (StatusCode, String)> {
+    let scale = scale_for_class(pool, tenant_id, class_id).await?;
+    let rows = sqlx::query(
+        r#"
+        SELECT d.id, d.student_id, s.name AS student_name, d.subject_id, sub.name AS subject_name,
+               d.decision, d.justification, d.adjusted_score::float8 AS adjusted_score,
+               d.decided_by, d.created_at
+        FROM class_council_decisions d
+        JOIN students s ON s.id = d.student_id
+        LEFT JOIN subjects sub ON sub.id = d.subject_id
+        WHERE d.tenant_id = $1 AND d.council_id = $2
+          AND ($3::uuid IS NULL OR d.student_id = $3)
+        ORDER BY s.name ASC, sub.name ASC NULLS FIRST
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(council_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let adjusted_score: Option<f64> = r.get("adjusted_score");
+            DecisionResponse {
+                id: r.get("id"),
+                student_id: r.get("student_id"),
+                student_name: r.get("student_name"),
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                decision: r.get("decision"),
+                justification: r.get("justification"),
+                adjusted_score,
+                adjusted_display: scale.display(adjusted_score),
+                decided_by: r.get("decided_by"),
+                created_at: r.get("created_at"),
+            }
+        })
+        .collect())
+}
+
+/// Devolve o ano letivo da turma.
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<i32, (StatusCode, String)> {
+    sqlx::query_scalar("SELECT year FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))
+}
+
+async fn ensure_subject_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query("SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(subject_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()));
+    }
+    Ok(())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Davi na 9A com nota 4,5 em Química no 1º e no 4º (último) bimestre de 2026.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        first_term_id: Uuid,
+        last_term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Conselho").await;
+            let class_id = insert_class(&pool, tenant_id, "9A", "9º ano", 2026).await;
+            let first_term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let last_term_id = insert_term(&pool, tenant_id, "4º Bimestre", 2026, 4).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Química").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Davi", "C-1").await;
+            for (term_id, term_name) in [(first_term_id, "1º Bimestre"), (last_term_id, "4º Bimestre")] {
+                sqlx::query(
+                    "INSERT INTO student_grades (id, tenant_id, class_id, student_id, term, subject, term_id, subject_id, score)
+                     VALUES ($1, $2, $3, $4, $5, 'Química', $6, $7, 4.5)",
+                )
+                .bind(Uuid::new_v4())
+                .bind(tenant_id)
+                .bind(class_id)
+                .bind(student_id)
+                .bind(term_name)
+                .bind(term_id)
+                .bind(subject_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::grade_analytics::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, owner, tenant_id, class_id, first_term_id, last_term_id, subject_id, student_id }
+        }
+
+        fn councils_path(&self) -> String {
+            format!("/classes/{}/councils", self.class_id)
+        }
+
+        async fn create_council(&self, term_id: Uuid) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", &self.councils_path(), &self.owner, Some(json!({
+                "term_id": term_id,
+                "held_on": "2026-12-10",
+                "notes": "Conselho",
+                "attendees": [{"name": "Coordenação", "role": "Coordenadora pedagógica"}],
+            })))
+            .await
+        }
+
+        /// Conselho do período com as decisões do Davi; devolve o id do conselho.
+        async fn decide(&self, term_id: Uuid, decisions: Value) -> String {
+            let (status, council) = self.create_council(term_id).await;
+            assert_eq!(status, StatusCode::OK);
+            let council_id = council["id"].as_str().unwrap().to_string();
+            let (status, _) = call_json(&self.app, "PUT", &self.decisions_path(&council_id), &self.owner, Some(json!({
+                "decisions": decisions
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            council_id
+        }
+
+        fn decisions_path(&self, council_id: &str) -> String {
+            format!("/councils/{council_id}/students/{}/decisions", self.student_id)
+        }
+
+        /// Aprovação na disciplina com nota 6 e aprovação geral.
+        fn approve_all(&self) -> Value {
+            json!([
+                {"subject_id": self.subject_id, "decision": "approve", "justification": "Participação e evolução no semestre", "adjusted_score": 6.0},
+                {"decision": "approve", "justification": "Aprovado após análise do conselho"},
+            ])
+        }
+
+        async fn get(&self, path: &str) -> Value {
+            let (status, body) = call_json(&self.app, "GET", path, &self.owner, None).await;
+            assert_eq!(status, StatusCode::OK);
+            body
+        }
+
+        /// Linha do Davi no relatório do diário de Química.
+        async fn gradebook_report(&self, term_id: Uuid) -> Value {
+            let report = self
+                .get(&format!(
+                    "/classes/{}/gradebook-report?term_id={}&subject_id={}",
+                    self.class_id, term_id, self.subject_id
+                ))
+                .await;
+            report["students"][0].clone()
+        }
+
+        /// A mesma linha, aberta pelo link público.
+        async fn public_gradebook_report(&self, term_id: Uuid) -> Value {
+            let (status, shared) = call_json(
+                &self.app,
+                "POST",
+                &format!("/classes/{}/gradebook-report/share", self.class_id),
+                &self.owner,
+                Some(json!({"student_id": self.student_id, "term_id": term_id, "subject_id": self.subject_id})),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, report) = call_json(
+                &self.app,
+                "GET",
+                &format!("/public/gradebook-report?token={}", shared["token"].as_str().unwrap()),
+                "",
+                None,
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            report["students"][0].clone()
+        }
+
+        /// Química no boletim do período do Davi.
+        async fn term_report(&self, term_id: Uuid) -> Value {
+            let report = self
+                .get(&format!("/classes/{}/students/{}/term-report?term_id={}", self.class_id, self.student_id, term_id))
+                .await;
+            report["subjects"][0].clone()
+        }
+
+        async fn full_report(&self) -> Value {
+            self.get(&format!("/classes/{}/students/{}/full-report", self.class_id, self.student_id)).await
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn one_council_per_class_and_term() {
+        let f = Fixture::new().await;
+        let (status, council) = f.create_council(f.last_term_id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(council["attendees"][0]["name"], "Coordenação");
+        let (status, _) = f.create_council(f.last_term_id).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn decisions_need_justification_and_adjust_only_subjects() {
+        let f = Fixture::new().await;
+        let (_, council) = f.create_council(f.last_term_id).await;
+        let path = f.decisions_path(council["id"].as_str().unwrap());
+
+        let short = json!({"decisions": [{"subject_id": f.subject_id, "decision": "approve", "justification": "ok"}]});
+        let (status, _) = call_json(&f.app, "PUT", &path, &f.owner, Some(short)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let general_adjust = json!({"decisions": [{"decision": "approve", "justification": "Evolução consistente no ano", "adjusted_score": 6.0}]});
+        let (status, _) = call_json(&f.app, "PUT", &path, &f.owner, Some(general_adjust)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teachers_cannot_record_decisions() {
+        let f = Fixture::new().await;
+        let (_, council) = f.create_council(f.last_term_id).await;
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+
+        let (status, _) = call_json(&f.app, "PUT", &f.decisions_path(council["id"].as_str().unwrap()), &teacher, Some(json!({
+            "decisions": f.approve_all()
+        })))
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn year_end_council_overrides_annual_status_without_justification() {
+        let f = Fixture::new().await;
+        assert_eq!(f.full_report().await["subjects"][0]["approved"], false);
+
+        f.decide(f.last_term_id, f.approve_all()).await;
+
+        let report = f.full_report().await;
+        let subject = &report["subjects"][0];
+        assert_eq!(subject["approved"], true);
+        assert!(subject["status"].as_str().unwrap().contains("conselho"));
+        assert_eq!(subject["council_decision"]["decision"], "approve");
+        assert_eq!(report["council_decision"]["decision"], "approve");
+        assert_eq!(report["council_decision"]["held_on"], "2026-12-10");
+        // justificativa é registro interno: não vai para boletins, links e portais
+        assert!(!report.to_string().contains("Aprovado após análise"));
+        assert!(!report.to_string().contains("Participação e evolução"));
+
+        let list = f.get(&f.councils_path()).await;
+        assert_eq!(list[0]["students_decided"], 1);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn earlier_term_council_only_adjusts_its_term() {
+        let f = Fixture::new().await;
+        f.decide(f.first_term_id, json!([
+            {"subject_id": f.subject_id, "decision": "retain", "justification": "Faltou às avaliações do bimestre", "adjusted_score": 6.0},
+            {"decision": "retain", "justification": "Acompanhamento no próximo bimestre"},
+        ]))
+        .await;
+
+        let report = f.full_report().await;
+        assert!(report["council_decision"].is_null());
+        let subject = &report["subjects"][0];
+        assert!(subject["council_decision"].is_null());
+        assert!(!subject["status"].as_str().unwrap().contains("conselho"));
+        let first = subject["period_grades"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|g| g["term_id"] == f.first_term_id.to_string())
+            .unwrap();
+        assert_eq!(first["council_adjusted"], true);
+        assert_eq!(first["effective_score"], 6.0);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn adjusted_score_reaches_gradebook_reports_and_analytics() {
+        let f = Fixture::new().await;
+        f.decide(f.last_term_id, f.approve_all()).await;
+
+        let gradebook = f
+            .get(&format!("/classes/{}/gradebook?term_id={}&subject_id={}", f.class_id, f.last_term_id, f.subject_id))
+            .await;
+        assert_eq!(gradebook["items"][0]["score"], 4.5);
+        assert_eq!(gradebook["items"][0]["council_adjusted_score"], 6.0);
+        assert_eq!(gradebook["items"][0]["effective_score"], 6.0);
+
+        let report = f.gradebook_report(f.last_term_id).await;
+        assert_eq!(report["score"], 4.5);
+        assert_eq!(report["effective_score"], 6.0);
+        assert_eq!(report["display_score"], "6,00");
+        assert_eq!(report["council_adjusted"], true);
+        assert_eq!(f.public_gradebook_report(f.last_term_id).await, report);
+
+        let term_report = f.term_report(f.last_term_id).await;
+        assert_eq!(term_report["council_adjusted_score"], 6.0);
+        assert_eq!(term_report["effective_score"], 6.0);
+        assert_eq!(term_report["display_score"], "6,00");
+
+        let analytics = f
+            .get(&format!("/classes/{}/grade-analytics?term_id={}&subject_id={}", f.class_id, f.last_term_id, f.subject_id))
+            .await;
+        assert_eq!(analytics["stats"]["mean"], 6.0);
+        assert_eq!(analytics["stats"]["passed_count"], 1);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn recovery_score_is_the_same_in_every_report() {
+        let f = Fixture::new().await;
+        sqlx::query("UPDATE student_grades SET recovery_score = 7 WHERE student_id = $1 AND term_id = $2")
+            .bind(f.student_id)
+            .bind(f.first_term_id)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+
+        let gradebook = f
+            .get(&format!("/classes/{}/gradebook?term_id={}&subject_id={}", f.class_id, f.first_term_id, f.subject_id))
+            .await;
+        assert_eq!(gradebook["items"][0]["effective_score"], 7.0);
+        let report = f.gradebook_report(f.first_term_id).await;
+        assert_eq!(report["effective_score"], 7.0);
+        assert_eq!(report["council_adjusted"], false);
+        assert_eq!(f.public_gradebook_report(f.first_term_id).await, report);
+        assert_eq!(f.term_report(f.first_term_id).await["effective_score"], 7.0);
+        let full = f.full_report().await;
+        let first = full["subjects"][0]["period_grades"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|g| g["term_id"] == f.first_term_id.to_string())
+            .unwrap()
+            .clone();
+        assert_eq!(first["effective_score"], 7.0);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn deleting_council_restores_calculated_status() {
+        let f = Fixture::new().await;
+        let council_id = f.decide(f.last_term_id, f.approve_all()).await;
+
+        let (status, _) = call_json(&f.app, "DELETE", &format!("/councils/{council_id}"), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let report = f.full_report().await;
+        assert_eq!(report["subjects"][0]["approved"], false);
+        assert!(report["council_decision"].is_null());
+        f.cleanup().await;
+    }
+}
@@ -11,6 +11,7 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::restricted_teacher;
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize, Validate)]
@@ -85,13 +86,20 @@ async fn list_classes(
     State(state): State<AppState>,
     user: AuthUser,
 ) -> Result<Json<Vec<ClassResponse>>, (StatusCode, String)> {
+    // professor vê só as turmas em que tem aula atribuída
     let rows = sqlx::query(
-        r#"SELECT id, tenant_id, name, grade, year, period
-           FROM classes
-           WHERE tenant_id = $1
-           ORDER BY year DESC, grade ASC, name ASC"#,
+        r#"SELECT c.id, c.tenant_id, c.name, c.grade, c.year, c.period
+           FROM classes c
+           WHERE c.tenant_id = $1
+             AND ($2::uuid IS NULL OR EXISTS (
+               SELECT 1 FROM teaching_assignments ta
+               WHERE ta.tenant_id = c.tenant_id AND ta.class_id = c.id
+                 AND ta.teacher_user_id = $2 AND ta.school_year = c.year
+             ))
+           ORDER BY c.year DESC, c.grade ASC, c.name ASC"#,
     )
     .bind(user.tenant_id)
+    .bind(restricted_teacher(&user))
     .fetch_all(&state.pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
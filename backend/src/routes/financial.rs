@@ -1,6 +1,7 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
+    middleware,
     routing::{get, post, put},
     Json, Router,
 };
@@ -11,6 +12,7 @@ use sqlx::Row;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::auth::features::{ensure_feature_enabled, require_financial, FEATURE_PIX};
 use crate::auth::jwt::AuthUser;
 use crate::state::AppState;
 
@@ -305,6 +307,7 @@ pub fn routes(pool: sqlx::PgPool, jwt_secret: String) -> Router {
             "/financial/contracts/:contract_id/installments/:installment_id/pay",
             put(mark_installment_paid),
         )
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_financial))
         .with_state(state)
 }
 
@@ -868,7 +871,18 @@ async fn get_financial_guardian_statement(
 ) -> Result<Json<FinancialGuardianStatementResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "staff"])?;
     ensure_person_belongs_to_tenant(&state.pool, user.tenant_id, person_id).await?;
+    let statement = build_financial_guardian_statement(&state.pool, user.tenant_id, person_id, &query).await?;
+    Ok(Json(statement))
+}
 
+/// Extrato do pagador (recebíveis em `payer_person_id`), usado pela secretaria e pelo
+/// portal dos responsáveis.
+pub(crate) async fn build_financial_guardian_statement(
+    pool: &sqlx::PgPool,
+    tenant_id: Uuid,
+    person_id: Uuid,
+    query: &FinancialGuardianStatementQuery,
+) -> Result<FinancialGuardianStatementResponse, (StatusCode, String)> {
     let person_row = sqlx::query(
         r#"
         SELECT full_name, email, phone, document, street, address_number, neighborhood, city_name, state_uf
@@ -876,9 +890,9 @@ async fn get_financial_guardian_statement(
         WHERE tenant_id = $1 AND id = $2
         "#,
     )
-    .bind(user.tenant_id)
+    .bind(tenant_id)
     .bind(person_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
     .ok_or((StatusCode::NOT_FOUND, "Responsável financeiro não encontrado".into()))?;
@@ -891,8 +905,8 @@ async fn get_financial_guardian_statement(
         WHERE id = $1
         "#,
     )
-    .bind(user.tenant_id)
-    .fetch_optional(&state.pool)
+    .bind(tenant_id)
+    .fetch_optional(pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
     .ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
@@ -922,11 +936,11 @@ async fn get_financial_guardian_statement(
         ORDER BY fr.due_date DESC, fr.created_at DESC
         "#,
     )
-    .bind(user.tenant_id)
+    .bind(tenant_id)
     .bind(person_id)
     .bind(query.date_from)
     .bind(query.date_to)
-    .fetch_all(&state.pool)
+    .fetch_all(pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
@@ -941,9 +955,9 @@ async fn get_financial_guardian_statement(
           AND status = 'pending'
         "#,
     )
-    .bind(user.tenant_id)
+    .bind(tenant_id)
     .bind(person_id)
-    .fetch_one(&state.pool)
+    .fetch_one(pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
     let mut items = Vec::with_capacity(rows.len());
@@ -967,7 +981,7 @@ async fn get_financial_guardian_statement(
         });
     }
 
-    Ok(Json(FinancialGuardianStatementResponse {
+    Ok(FinancialGuardianStatementResponse {
         person_id,
         person_name,
         person_email: person_row.get("email"),
@@ -986,7 +1000,7 @@ async fn get_financial_guardian_statement(
         total_open: round2(total_open),
         pending_balance_total: round2(pending_balance_total),
         items,
-    }))
+    })
 }
 
 async fn mark_receivable_received(
@@ -1315,6 +1329,9 @@ async fn create_contract(
     if billing_mode == "school_booklet_pix" && school_pix_key.is_none() {
         return Err((StatusCode::BAD_REQUEST, "Informe a chave PIX para o modo Carnê + PIX".into()));
     }
+    if billing_mode == "school_booklet_pix" {
+        ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_PIX).await?;
+    }
     ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, req.student_id).await?;
     if let Some(payer) = req.payer_person_id {
         ensure_financial_person_belongs_to_tenant(&state.pool, user.tenant_id, payer).await?;
@@ -1448,6 +1465,9 @@ async fn generate_boletos(
     let school_payment_instructions: Option<String> = contract_row.get("school_payment_instructions");
     let contract_description: String = contract_row.get("contract_description");
     let student_name: String = contract_row.get("student_name");
+    if billing_mode == "school_booklet_pix" {
+        ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_PIX).await?;
+    }
 
     let rows = sqlx::query(
         r#"
@@ -1802,7 +1822,7 @@ async fn mark_installment_paid(
     get_contract(State(state), user, Path(contract_id)).await
 }
 
-async fn load_contract_response(
+pub(crate) async fn load_contract_response(
     pool: &sqlx::PgPool,
     tenant_id: Uuid,
     contract_id: Uuid,
@@ -2252,6 +2272,7 @@ async fn load_person_name(
     Ok(name)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn insert_account_movement(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     tenant_id: Uuid,
@@ -2358,3 +2379,37 @@ fn days_in_month(year: i32, month: u32) -> u32 {
 fn round2(value: f64) -> f64 {
     (value * 100.0).round() / 100.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{call, insert_tenant, make_token, test_pool, SECRET};
+
+    #[tokio::test]
+    async fn http_financial_routes_blocked_when_module_disabled() {
+        let pool = test_pool().await;
+        let tenant_id = insert_tenant(&pool, "Escola Financeiro").await;
+        let app = routes(pool.clone(), SECRET.into());
+        let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+
+        assert_eq!(call(&app, "GET", "/financial/accounts", &token, None).await.0, StatusCode::OK);
+
+        sqlx::query(
+            r#"INSERT INTO tenant_features (tenant_id, feature_code, enabled)
+               VALUES ($1, 'financial', FALSE)"#,
+        )
+        .bind(tenant_id)
+        .execute(&pool)
+        .await
+        .expect("falha ao desabilitar módulo");
+
+        assert_eq!(call(&app, "GET", "/financial/accounts", &token, None).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(call(&app, "GET", "/financial/contracts", &token, None).await.0, StatusCode::FORBIDDEN);
+
+        sqlx::query("DELETE FROM tenants WHERE id = $1")
+            .bind(tenant_id)
+            .execute(&pool)
+            .await
+            .expect("falha ao limpar tenant de teste");
+    }
+}
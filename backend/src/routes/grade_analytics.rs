@@ -0,0 +1,683 @@
+//! Estatísticas de notas por turma, disciplina e período: média, mediana, desvio padrão,
+//! histograma e aprovação contra a nota mínima da escala, comparadas com os períodos
+//! anteriores da turma e com as outras turmas da mesma série. Tudo calculado no banco,
+//! sobre a nota efetiva (`student_grades_effective`: recuperação paralela e ajustes do
+//! conselho de classe).
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::ensure_teaches;
+use crate::grading::scales::{scale_for_class, GradingScale};
+use crate::state::AppState;
+
+const DEFAULT_BUCKETS: i32 = 5;
+const MAX_BUCKETS: i32 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct GradeAnalyticsQuery {
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+    /// Faixas do histograma (escalas numéricas); conceituais usam uma faixa por conceito.
+    pub buckets: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct GradeStats {
+    pub graded_count: i64,
+    pub mean: Option<f64>,
+    pub mean_display: Option<String>,
+    pub median: Option<f64>,
+    pub median_display: Option<String>,
+    pub std_dev: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub passed_count: i64,
+    pub failed_count: i64,
+    /// Percentual de aprovados entre os que têm nota.
+    pub pass_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistogramBucket {
+    pub from: f64,
+    pub to: f64,
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TermComparison {
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub stats: GradeStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClassComparison {
+    pub class_id: Uuid,
+    pub class_name: String,
+    /// Nota mínima da escala da própria turma, usada na aprovação de `stats`.
+    pub passing_value: f64,
+    pub stats: GradeStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradeAnalyticsResponse {
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub class_grade: String,
+    pub class_year: i32,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub grading_scale: GradingScale,
+    pub passing_value: f64,
+    pub stats: GradeStats,
+    /// Alunos atuais da turma sem nota no período.
+    pub students_without_grade: i64,
+    pub histogram: Vec<HistogramBucket>,
+    pub previous_terms: Vec<TermComparison>,
+    pub other_classes: Vec<ClassComparison>,
+    pub generated_at: String,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/classes/:class_id/grade-analytics", get(get_grade_analytics))
+        .with_state(state)
+}
+
+async fn get_grade_analytics(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<GradeAnalyticsQuery>,
+) -> Result<Json<GradeAnalyticsResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let class_row = sqlx::query("SELECT name, grade, year FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(class_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))?;
+    let class_name: String = class_row.get("name");
+    let class_grade: String = class_row.get("grade");
+    let class_year: i32 = class_row.get("year");
+
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+
+    let term_row = sqlx::query(
+        "SELECT name, school_year, sort_order FROM academic_terms WHERE tenant_id = $1 AND id = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(query.term_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::BAD_REQUEST, "Período inválido para este tenant".into()))?;
+    let term_name: String = term_row.get("name");
+    let school_year: i32 = term_row.get("school_year");
+    let sort_order: i32 = term_row.get("sort_order");
+
+    let subject_name: String = sqlx::query_scalar("SELECT name FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(query.subject_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()))?;
+
+    let grading_scale = scale_for_class(&state.pool, user.tenant_id, class_id).await?;
+    let passing_value = grading_scale.passing_value;
+
+    // períodos anteriores do mesmo ano letivo
+    let previous_term_rows = sqlx::query(
+        r#"
+        SELECT id, name
+        FROM academic_terms
+        WHERE tenant_id = $1 AND school_year = $2 AND sort_order < $3
+        ORDER BY sort_order ASC, name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(school_year)
+    .bind(sort_order)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let previous_term_ids: Vec<Uuid> = previous_term_rows.iter().map(|r| r.get("id")).collect();
+
+    // mesma série no mesmo ano
+    let peer_rows = sqlx::query(
+        r#"
+        SELECT id, name
+        FROM classes
+        WHERE tenant_id = $1 AND grade = $2 AND year = $3 AND id <> $4
+        ORDER BY name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&class_grade)
+    .bind(class_year)
+    .bind(class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut current = group_stats(
+        &state.pool,
+        user.tenant_id,
+        query.subject_id,
+        &[class_id],
+        &[query.term_id],
+        &grading_scale,
+    )
+    .await?;
+    let stats = current.pop().map(|(_, _, s)| s).unwrap_or_default();
+
+    let mut by_term = group_stats(
+        &state.pool,
+        user.tenant_id,
+        query.subject_id,
+        &[class_id],
+        &previous_term_ids,
+        &grading_scale,
+    )
+    .await?;
+    let previous_terms = previous_term_rows
+        .iter()
+        .filter_map(|r| {
+            let term_id: Uuid = r.get("id");
+            let idx = by_term.iter().position(|(_, t, _)| *t == term_id)?;
+            Some(TermComparison {
+                term_id,
+                term_name: r.get("name"),
+                stats: by_term.swap_remove(idx).2,
+            })
+        })
+        .collect();
+
+    // cada turma é comparada na sua própria escala
+    let mut other_classes = Vec::with_capacity(peer_rows.len());
+    for r in &peer_rows {
+        let peer_id: Uuid = r.get("id");
+        let peer_scale = scale_for_class(&state.pool, user.tenant_id, peer_id).await?;
+        let peer_stats = group_stats(
+            &state.pool,
+            user.tenant_id,
+            query.subject_id,
+            &[peer_id],
+            &[query.term_id],
+            &peer_scale,
+        )
+        .await?;
+        if let Some((_, _, stats)) = peer_stats.into_iter().next() {
+            other_classes.push(ClassComparison {
+                class_id: peer_id,
+                class_name: r.get("name"),
+                passing_value: peer_scale.passing_value,
+                stats,
+            });
+        }
+    }
+
+    let histogram = histogram(
+        &state.pool,
+        user.tenant_id,
+        class_id,
+        query.term_id,
+        query.subject_id,
+        &grading_scale,
+        query.buckets,
+    )
+    .await?;
+
+    let students_without_grade: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM students s
+        WHERE s.tenant_id = $1
+          AND s.class_id = $2
+          AND NOT EXISTS (
+            SELECT 1
+            FROM student_grades g
+            WHERE g.tenant_id = s.tenant_id
+              AND g.class_id = s.class_id
+              AND g.student_id = s.id
+              AND g.term_id = $3
+              AND g.subject_id = $4
+              AND COALESCE(g.score, g.recovery_score) IS NOT NULL
+          )
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.term_id)
+    .bind(query.subject_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(GradeAnalyticsResponse {
+        class_id,
+        class_name,
+        class_grade,
+        class_year,
+        term_id: query.term_id,
+        term_name,
+        subject_id: query.subject_id,
+        subject_name,
+        grading_scale,
+        passing_value,
+        stats,
+        students_without_grade,
+        histogram,
+        previous_terms,
+        other_classes,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+/// Estatísticas agrupadas por (turma, período); grupos sem nota não aparecem.
+async fn group_stats(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject_id: Uuid,
+    class_ids: &[Uuid],
+    term_ids: &[Uuid],
+    scale: &GradingScale,
+) -> Result<Vec<(Uuid, Uuid, GradeStats)>, (StatusCode, String)> {
+    if class_ids.is_empty() || term_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query(
+        r#"
+        WITH scores AS (
+          SELECT g.class_id, g.term_id, g.effective_score AS score
+          FROM student_grades_effective g
+          WHERE g.tenant_id = $1
+            AND g.subject_id = $2
+            AND g.class_id = ANY($3)
+            AND g.term_id = ANY($4)
+        )
+        SELECT
+          class_id,
+          term_id,
+          COUNT(*) AS graded_count,
+          ROUND(AVG(score), 2)::float8 AS mean,
+          ROUND((percentile_cont(0.5) WITHIN GROUP (ORDER BY score))::numeric, 2)::float8 AS median,
+          ROUND(stddev_pop(score), 2)::float8 AS std_dev,
+          MIN(score)::float8 AS min,
+          MAX(score)::float8 AS max,
+          COUNT(*) FILTER (WHERE score >= $5::float8) AS passed_count
+        FROM scores
+        WHERE score IS NOT NULL
+        GROUP BY class_id, term_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(subject_id)
+    .bind(class_ids)
+    .bind(term_ids)
+    .bind(scale.passing_value)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let graded_count: i64 = r.get("graded_count");
+            let passed_count: i64 = r.get("passed_count");
+            let mean: Option<f64> = r.get("mean");
+            let median: Option<f64> = r.get("median");
+            let stats = GradeStats {
+                graded_count,
+                mean,
+                mean_display: scale.display(mean),
+                median,
+                median_display: scale.display(median),
+                std_dev: r.get("std_dev"),
+                min: r.get("min"),
+                max: r.get("max"),
+                passed_count,
+                failed_count: graded_count - passed_count,
+                pass_rate: (graded_count > 0)
+                    .then(|| (passed_count as f64 * 10000.0 / graded_count as f64).round() / 100.0),
+            };
+            (r.get("class_id"), r.get("term_id"), stats)
+        })
+        .collect())
+}
+
+/// Faixas iguais entre o mínimo e o máximo da escala (o máximo entra na última faixa);
+/// na escala conceitual, uma faixa por conceito, do pior para o melhor.
+async fn histogram(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    term_id: Uuid,
+    subject_id: Uuid,
+    scale: &GradingScale,
+    buckets: Option<i32>,
+) -> Result<Vec<HistogramBucket>, (StatusCode, String)> {
+    let (count, low, high) = if scale.is_conceptual() {
+        let n = scale.concepts.len().max(1) as i32;
+        (n, -0.5, n as f64 - 0.5)
+    } else {
+        let n = buckets.unwrap_or(DEFAULT_BUCKETS);
+        if !(2..=MAX_BUCKETS).contains(&n) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Número de faixas deve estar entre 2 e {MAX_BUCKETS}"),
+            ));
+        }
+        (n, scale.min_value, scale.max_value)
+    };
+
+    let rows = sqlx::query(
+        r#"
+        WITH scores AS (
+          SELECT g.effective_score::float8 AS score
+          FROM student_grades_effective g
+          WHERE g.tenant_id = $1
+            AND g.class_id = $2
+            AND g.term_id = $3
+            AND g.subject_id = $4
+        )
+        SELECT b.bucket, COUNT(s.score) AS count
+        FROM generate_series(1, $5) AS b(bucket)
+        LEFT JOIN scores s
+          ON s.score IS NOT NULL
+         AND LEAST(GREATEST(width_bucket(s.score, $6, $7, $5), 1), $5) = b.bucket
+        GROUP BY b.bucket
+        ORDER BY b.bucket ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .bind(count)
+    .bind(low)
+    .bind(high)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let width = (high - low) / count as f64;
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let bucket: i32 = r.get("bucket");
+            let count: i64 = r.get("count");
+            if scale.is_conceptual() {
+                let value = (bucket - 1) as f64;
+                HistogramBucket {
+                    from: value,
+                    to: value,
+                    label: scale.display(Some(value)).unwrap_or_default(),
+                    count,
+                }
+            } else {
+                let from = scale.round(low + width * (bucket - 1) as f64);
+                let to = scale.round(low + width * bucket as f64);
+                HistogramBucket {
+                    from,
+                    to,
+                    label: format!(
+                        "{} a {}",
+                        scale.display(Some(from)).unwrap_or_default(),
+                        scale.display(Some(to)).unwrap_or_default()
+                    ),
+                    count,
+                }
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grading::promotion::{RECOVERY_AVERAGE, RECOVERY_MAX, RECOVERY_REPLACE};
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::Value;
+
+    /// Turmas 9A e 9B (9º ano/2026), Matemática e dois bimestres. A 9A tem notas 4 (5 na
+    /// recuperação), 6, 7 e 9 no 2º bimestre, um aluno com nota só no 1º (8) e um sem nota;
+    /// a 9B tem um aluno com 3 no 2º bimestre.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        peer_class_id: Uuid,
+        first_term_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Estatística").await;
+            let class_id = insert_class(&pool, tenant_id, "9A", "9º ano", 2026).await;
+            let peer_class_id = insert_class(&pool, tenant_id, "9B", "9º ano", 2026).await;
+            let first_term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let term_id = insert_term(&pool, tenant_id, "2º Bimestre", 2026, 2).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Matemática").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+            let f = Fixture { pool, app, token, tenant_id, class_id, peer_class_id, first_term_id, term_id, subject_id };
+
+            let grades = [
+                (class_id, Some((term_id, 4.0, Some(5.0)))),
+                (class_id, Some((term_id, 6.0, None))),
+                (class_id, Some((term_id, 7.0, None))),
+                (class_id, Some((term_id, 9.0, None))),
+                (class_id, Some((first_term_id, 8.0, None))),
+                (class_id, None),
+                (peer_class_id, Some((term_id, 3.0, None))),
+            ];
+            for (i, (class, grade)) in grades.into_iter().enumerate() {
+                let name = format!("Aluno {i}");
+                let student_id = insert_student(&f.pool, f.tenant_id, class, &name, &name).await;
+                if let Some((term, score, recovery)) = grade {
+                    f.insert_grade(class, student_id, term, score, recovery).await;
+                }
+            }
+            f
+        }
+
+        async fn insert_grade(&self, class_id: Uuid, student_id: Uuid, term_id: Uuid, score: f64, recovery: Option<f64>) {
+            sqlx::query(
+                "INSERT INTO student_grades (id, tenant_id, class_id, student_id, term, subject, term_id, subject_id, score, recovery_score)
+                 VALUES ($1, $2, $3, $4, 'Bimestre', 'Matemática', $5, $6, $7, $8)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(self.tenant_id)
+            .bind(class_id)
+            .bind(student_id)
+            .bind(term_id)
+            .bind(self.subject_id)
+            .bind(score)
+            .bind(recovery)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        fn path(&self) -> String {
+            format!(
+                "/classes/{}/grade-analytics?term_id={}&subject_id={}",
+                self.class_id, self.term_id, self.subject_id
+            )
+        }
+
+        async fn analytics(&self, token: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "GET", &self.path(), token, None).await
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn grade_analytics_computes_class_statistics_on_effective_scores() {
+        let f = Fixture::new().await;
+
+        let (status, data) = f.analytics(&f.token).await;
+        assert_eq!(status, StatusCode::OK);
+        let stats = &data["stats"];
+        assert_eq!(stats["graded_count"], 4);
+        assert_eq!(stats["mean"], 6.75);
+        assert_eq!(stats["median"], 6.5);
+        assert_eq!(stats["std_dev"], 1.48);
+        assert_eq!(stats["min"], 5.0);
+        assert_eq!(stats["passed_count"], 3);
+        assert_eq!(stats["failed_count"], 1);
+        assert_eq!(stats["pass_rate"], 75.0);
+        assert_eq!(data["students_without_grade"], 2);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn grade_analytics_builds_histogram_and_validates_buckets() {
+        let f = Fixture::new().await;
+
+        let (_, data) = f.analytics(&f.token).await;
+        let counts: Vec<i64> = data["histogram"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["count"].as_i64().unwrap())
+            .collect();
+        assert_eq!(counts, vec![0, 0, 1, 2, 1]);
+        assert_eq!(data["histogram"][4]["label"], "8,00 a 10,00");
+
+        let (status, _) = call_json(&f.app, "GET", &format!("{}&buckets=1", f.path()), &f.token, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn grade_analytics_compares_previous_terms_and_peer_classes() {
+        let f = Fixture::new().await;
+
+        let (_, data) = f.analytics(&f.token).await;
+        assert_eq!(data["previous_terms"][0]["term_id"], f.first_term_id.to_string());
+        assert_eq!(data["previous_terms"][0]["term_name"], "1º Bimestre");
+        assert_eq!(data["previous_terms"][0]["stats"]["mean"], 8.0);
+        assert_eq!(data["other_classes"][0]["class_id"], f.peer_class_id.to_string());
+        assert_eq!(data["other_classes"][0]["class_name"], "9B");
+        assert_eq!(data["other_classes"][0]["stats"]["pass_rate"], 0.0);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn grade_analytics_judges_peer_classes_against_their_scale() {
+        let f = Fixture::new().await;
+        let scale_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO grading_scales (id, tenant_id, name, scale_type, min_value, max_value, decimals, passing_value)
+             VALUES ($1, $2, 'Zero a dez, média 3', 'numeric', 0, 10, 1, 3)",
+        )
+        .bind(scale_id)
+        .bind(f.tenant_id)
+        .execute(&f.pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO grading_scale_assignments (tenant_id, class_grade, scale_id) VALUES ($1, '9º ano', $2)")
+            .bind(f.tenant_id)
+            .bind(scale_id)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+
+        let (status, data) = f.analytics(&f.token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(data["passing_value"], 3.0);
+        assert_eq!(data["stats"]["pass_rate"], 100.0);
+        assert_eq!(data["other_classes"][0]["passing_value"], 3.0);
+        assert_eq!(data["other_classes"][0]["stats"]["passed_count"], 1);
+        assert_eq!(data["other_classes"][0]["stats"]["mean_display"], "3,0");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn grade_analytics_is_limited_to_school_roles_and_assigned_teachers() {
+        let f = Fixture::new().await;
+
+        let parent = make_token(f.tenant_id, Uuid::new_v4(), "parent");
+        let (status, _) = f.analytics(&parent).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+        let (status, _) = f.analytics(&teacher).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let staff = make_token(f.tenant_id, Uuid::new_v4(), "staff");
+        let (status, _) = f.analytics(&staff).await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn sql_effective_score_applies_recovery_rule() {
+        let pool = test_pool().await;
+        let cases = [
+            (RECOVERY_MAX, None, None, None),
+            (RECOVERY_MAX, Some(4.0), None, Some(4.0)),
+            (RECOVERY_MAX, None, Some(6.0), Some(6.0)),
+            (RECOVERY_MAX, Some(4.0), Some(7.0), Some(7.0)),
+            (RECOVERY_MAX, Some(8.0), Some(5.0), Some(8.0)),
+            (RECOVERY_AVERAGE, Some(4.0), Some(7.0), Some(5.5)),
+            (RECOVERY_AVERAGE, Some(4.25), Some(7.5), Some(5.88)),
+            (RECOVERY_AVERAGE, None, Some(6.0), Some(6.0)),
+            (RECOVERY_REPLACE, Some(8.0), Some(5.0), Some(5.0)),
+            (RECOVERY_REPLACE, Some(4.0), None, Some(4.0)),
+        ];
+        for (rule, score, recovery, expected) in cases {
+            let sql: Option<f64> = sqlx::query_scalar(
+                "SELECT student_grade_effective_score($1::float8::numeric, $2::float8::numeric, $3)::float8",
+            )
+            .bind(score)
+            .bind(recovery)
+            .bind(rule)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(sql, expected, "{rule} {score:?} {recovery:?}");
+        }
+    }
+}
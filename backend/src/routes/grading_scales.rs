@@ -0,0 +1,628 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::grading::scales::{scale_from_row, GradingScale, SCALE_CONCEPTUAL, SCALE_NUMERIC};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertGradingScaleRequest {
+    pub name: String,
+    /// `numeric` ou `conceptual`.
+    pub scale_type: String,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub decimals: Option<i32>,
+    pub passing_value: Option<f64>,
+    /// Do melhor para o pior, ex.: `["A", "B", "C", "D"]`.
+    pub concepts: Option<Vec<String>>,
+    pub passing_concept: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradingScaleResponse {
+    #[serde(flatten)]
+    pub scale: GradingScale,
+    pub assigned_grades: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignScaleRequest {
+    pub class_grade: String,
+    /// `null` volta a série para a escala padrão.
+    pub scale_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScaleAssignmentItem {
+    pub class_grade: String,
+    pub scale_id: Option<Uuid>,
+    pub scale_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/grading-scales", get(list_scales).post(create_scale))
+        .route(
+            "/grading-scales/:scale_id",
+            put(update_scale).delete(delete_scale),
+        )
+        .route(
+            "/grading-scale-assignments",
+            get(list_assignments).put(assign_scale),
+        )
+        .with_state(state)
+}
+
+const SCALE_COLUMNS: &str = r#"
+    gs.id AS scale_id, gs.name, gs.scale_type,
+    gs.min_value::float8 AS min_value, gs.max_value::float8 AS max_value, gs.decimals,
+    gs.passing_value::float8 AS passing_value, gs.concepts, gs.passing_concept,
+    COALESCE(
+      (SELECT array_agg(a.class_grade ORDER BY a.class_grade)
+       FROM grading_scale_assignments a
+       WHERE a.scale_id = gs.id),
+      '{}'
+    ) AS assigned_grades
+"#;
+
+fn map_scale_row(row: sqlx::postgres::PgRow) -> GradingScaleResponse {
+    GradingScaleResponse {
+        scale: scale_from_row(&row),
+        assigned_grades: row.get("assigned_grades"),
+    }
+}
+
+async fn list_scales(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<Vec<GradingScaleResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let rows = sqlx::query(&format!(
+        "SELECT {SCALE_COLUMNS} FROM grading_scales gs WHERE gs.tenant_id = $1 ORDER BY gs.name ASC"
+    ))
+    .bind(user.tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.into_iter().map(map_scale_row).collect()))
+}
+
+async fn create_scale(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<UpsertGradingScaleRequest>,
+) -> Result<Json<GradingScaleResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+    let scale = build_scale(req)?;
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO grading_scales
+          (id, tenant_id, name, scale_type, min_value, max_value, decimals, passing_value, concepts, passing_concept)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(&scale.name)
+    .bind(&scale.scale_type)
+    .bind(scale.min_value)
+    .bind(scale.max_value)
+    .bind(scale.decimals)
+    .bind(scale.passing_value)
+    .bind(&scale.concepts)
+    .bind(&scale.passing_concept)
+    .execute(&state.pool)
+    .await
+    .map_err(map_unique_name_error)?;
+
+    fetch_scale(&state.pool, user.tenant_id, id).await.map(Json)
+}
+
+async fn update_scale(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(scale_id): Path<Uuid>,
+    Json(req): Json<UpsertGradingScaleRequest>,
+) -> Result<Json<GradingScaleResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+    let scale = build_scale(req)?;
+    let current = fetch_scale(&state.pool, user.tenant_id, scale_id).await?.scale;
+
+    // notas já gravadas dependem da faixa e da ordem dos conceitos; só nome e aprovação podem mudar
+    let shape_changed = current.scale_type != scale.scale_type
+        || current.min_value != scale.min_value
+        || current.max_value != scale.max_value
+        || current.decimals != scale.decimals
+        || current.concepts != scale.concepts;
+    if shape_changed && scale_has_grades(&state.pool, user.tenant_id, scale_id).await? {
+        return Err((
+            StatusCode::CONFLICT,
+            "Já há notas lançadas com esta escala; só o nome e a nota de aprovação podem mudar".into(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE grading_scales
+        SET name = $3, scale_type = $4, min_value = $5, max_value = $6, decimals = $7,
+            passing_value = $8, concepts = $9, passing_concept = $10, updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(scale_id)
+    .bind(&scale.name)
+    .bind(&scale.scale_type)
+    .bind(scale.min_value)
+    .bind(scale.max_value)
+    .bind(scale.decimals)
+    .bind(scale.passing_value)
+    .bind(&scale.concepts)
+    .bind(&scale.passing_concept)
+    .execute(&state.pool)
+    .await
+    .map_err(map_unique_name_error)?;
+
+    fetch_scale(&state.pool, user.tenant_id, scale_id).await.map(Json)
+}
+
+async fn delete_scale(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(scale_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+    let current = fetch_scale(&state.pool, user.tenant_id, scale_id).await?;
+    if !current.assigned_grades.is_empty() {
+        return Err((
+            StatusCode::CONFLICT,
+            "Escala atribuída a séries; remova as atribuições antes de excluir".into(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM grading_scales WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(scale_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Séries das turmas da escola com a escala de cada uma (`null` = padrão 0 a 10).
+async fn list_assignments(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<Vec<ScaleAssignmentItem>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT g.class_grade, gs.id AS scale_id, gs.name AS scale_name
+        FROM (
+          SELECT grade AS class_grade FROM classes WHERE tenant_id = $1
+          UNION
+          SELECT class_grade FROM grading_scale_assignments WHERE tenant_id = $1
+        ) g
+        LEFT JOIN grading_scale_assignments a
+          ON a.tenant_id = $1 AND a.class_grade = g.class_grade
+        LEFT JOIN grading_scales gs ON gs.id = a.scale_id
+        ORDER BY g.class_grade ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| ScaleAssignmentItem {
+                class_grade: r.get("class_grade"),
+                scale_id: r.get("scale_id"),
+                scale_name: r.get("scale_name"),
+            })
+            .collect(),
+    ))
+}
+
+async fn assign_scale(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<AssignScaleRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let class_grade = req.class_grade.trim();
+    if class_grade.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Série é obrigatória".into()));
+    }
+    if let Some(scale_id) = req.scale_id {
+        fetch_scale(&state.pool, user.tenant_id, scale_id).await?;
+    }
+
+    let current: Option<Uuid> = sqlx::query_scalar(
+        "SELECT scale_id FROM grading_scale_assignments WHERE tenant_id = $1 AND class_grade = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(class_grade)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if current == req.scale_id {
+        return Ok(Json(OkResponse { ok: true }));
+    }
+
+    if grade_has_grades(&state.pool, user.tenant_id, class_grade).await? {
+        return Err((
+            StatusCode::CONFLICT,
+            "Já há notas lançadas nesta série; a escala não pode ser trocada".into(),
+        ));
+    }
+
+    match req.scale_id {
+        Some(scale_id) => {
+            sqlx::query(
+                r#"
+                INSERT INTO grading_scale_assignments (tenant_id, class_grade, scale_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (tenant_id, class_grade) DO UPDATE SET scale_id = EXCLUDED.scale_id
+                "#,
+            )
+            .bind(user.tenant_id)
+            .bind(class_grade)
+            .bind(scale_id)
+            .execute(&state.pool)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        }
+        None => {
+            sqlx::query("DELETE FROM grading_scale_assignments WHERE tenant_id = $1 AND class_grade = $2")
+                .bind(user.tenant_id)
+                .bind(class_grade)
+                .execute(&state.pool)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        }
+    }
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+fn build_scale(req: UpsertGradingScaleRequest) -> Result<GradingScale, (StatusCode, String)> {
+    let name = req.name.trim().to_string();
+    if name.len() < 2 {
+        return Err((StatusCode::BAD_REQUEST, "Nome da escala é obrigatório".into()));
+    }
+
+    match req.scale_type.trim().to_lowercase().as_str() {
+        SCALE_NUMERIC => {
+            let min_value = req.min_value.unwrap_or(0.0);
+            let max_value = req
+                .max_value
+                .ok_or((StatusCode::BAD_REQUEST, "Nota máxima é obrigatória".into()))?;
+            let decimals = req.decimals.unwrap_or(1);
+            if !(0.0..max_value).contains(&min_value) || max_value > 999.0 {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Faixa inválida: mínimo >= 0, máximo maior que o mínimo e até 999".into(),
+                ));
+            }
+            if !(0..=2).contains(&decimals) {
+                return Err((StatusCode::BAD_REQUEST, "Casas decimais devem ser 0, 1 ou 2".into()));
+            }
+            let passing_value = req
+                .passing_value
+                .ok_or((StatusCode::BAD_REQUEST, "Nota de aprovação é obrigatória".into()))?;
+            if !(min_value..=max_value).contains(&passing_value) {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Nota de aprovação deve estar dentro da faixa".into(),
+                ));
+            }
+            Ok(GradingScale {
+                id: None,
+                name,
+                scale_type: SCALE_NUMERIC.into(),
+                min_value,
+                max_value,
+                decimals,
+                passing_value,
+                concepts: Vec::new(),
+                passing_concept: None,
+            })
+        }
+        SCALE_CONCEPTUAL => {
+            let concepts: Vec<String> = req
+                .concepts
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect();
+            if concepts.len() < 2 {
+                return Err((StatusCode::BAD_REQUEST, "Informe ao menos dois conceitos".into()));
+            }
+            for (i, c) in concepts.iter().enumerate() {
+                if concepts[..i].iter().any(|o| o.eq_ignore_ascii_case(c)) {
+                    return Err((StatusCode::BAD_REQUEST, format!("Conceito repetido: {c}")));
+                }
+            }
+            let mut scale = GradingScale {
+                id: None,
+                name,
+                scale_type: SCALE_CONCEPTUAL.into(),
+                min_value: 0.0,
+                max_value: (concepts.len() - 1) as f64,
+                decimals: 0,
+                passing_value: 0.0,
+                concepts,
+                passing_concept: None,
+            };
+            let passing_concept = req
+                .passing_concept
+                .ok_or((StatusCode::BAD_REQUEST, "Conceito mínimo de aprovação é obrigatório".into()))?;
+            scale.passing_value = scale.concept_value(&passing_concept)?;
+            scale.passing_concept = scale.display(Some(scale.passing_value));
+            Ok(scale)
+        }
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            "Tipo de escala inválido (use numeric ou conceptual)".into(),
+        )),
+    }
+}
+
+async fn fetch_scale(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    scale_id: Uuid,
+) -> Result<GradingScaleResponse, (StatusCode, String)> {
+    let row = sqlx::query(&format!(
+        "SELECT {SCALE_COLUMNS} FROM grading_scales gs WHERE gs.tenant_id = $1 AND gs.id = $2"
+    ))
+    .bind(tenant_id)
+    .bind(scale_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    row.map(map_scale_row)
+        .ok_or((StatusCode::NOT_FOUND, "Escala não encontrada".into()))
+}
+
+async fn grade_has_grades(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_grade: &str,
+) -> Result<bool, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT 1
+        FROM classes c
+        WHERE c.tenant_id = $1 AND c.grade = $2
+          AND (
+            EXISTS (SELECT 1 FROM student_grades g
+                    WHERE g.tenant_id = c.tenant_id AND g.class_id = c.id
+                      AND (g.score IS NOT NULL OR g.recovery_score IS NOT NULL))
+            OR EXISTS (SELECT 1 FROM student_final_exams f
+                       WHERE f.tenant_id = c.tenant_id AND f.class_id = c.id)
+          )
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_grade)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(row.is_some())
+}
+
+async fn scale_has_grades(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    scale_id: Uuid,
+) -> Result<bool, (StatusCode, String)> {
+    let grades: Vec<String> = sqlx::query_scalar(
+        "SELECT class_grade FROM grading_scale_assignments WHERE tenant_id = $1 AND scale_id = $2",
+    )
+    .bind(tenant_id)
+    .bind(scale_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    for grade in grades {
+        if grade_has_grades(pool, tenant_id, &grade).await? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn map_unique_name_error(e: sqlx::Error) -> (StatusCode, String) {
+    match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            (StatusCode::CONFLICT, "Já existe uma escala com este nome".into())
+        }
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Série "Infantil 4" com escala conceitual A-D, dois semestres e um aluno.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        terms: [Uuid; 2],
+        subject_id: Uuid,
+        student_id: Uuid,
+        scale_id: String,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Conceitos").await;
+            let class_id = insert_class(&pool, tenant_id, "Infantil A", "Infantil 4", 2026).await;
+            let terms = [
+                insert_term(&pool, tenant_id, "1º Semestre", 2026, 1).await,
+                insert_term(&pool, tenant_id, "2º Semestre", 2026, 2).await,
+            ];
+            let subject_id = insert_subject(&pool, tenant_id, "Linguagem").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Davi", "D1").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::school_settings::routes(pool.clone(), SECRET.into()));
+
+            let (status, scale) = call_json(&app, "POST", "/grading-scales", &token, Some(json!({
+                "name": "Conceitos A-D", "scale_type": "conceptual",
+                "concepts": ["A", "B", "C", "D"], "passing_concept": "c"
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(scale["passing_concept"], "C");
+            assert_eq!(scale["max_value"], 3.0);
+            let scale_id = scale["id"].as_str().unwrap().to_string();
+
+            let (status, _) = call_json(&app, "PUT", "/grading-scale-assignments", &token, Some(json!({
+                "class_grade": "Infantil 4", "scale_id": scale_id
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+
+            Fixture { pool, app, token, tenant_id, class_id, terms, subject_id, student_id, scale_id }
+        }
+
+        async fn put_grade(&self, term_id: Uuid, record: Value) -> StatusCode {
+            let mut record = record;
+            record["student_id"] = json!(self.student_id);
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/gradebook", self.class_id), &self.token, Some(json!({
+                "term_id": term_id, "subject_id": self.subject_id, "records": [record]
+            }))).await;
+            status
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn conceptual_gradebook_rejects_numbers_and_unknown_concepts() {
+        let f = Fixture::new().await;
+
+        assert_eq!(f.put_grade(f.terms[0], json!({"score": 8})).await, StatusCode::BAD_REQUEST);
+        assert_eq!(f.put_grade(f.terms[0], json!({"concept": "E"})).await, StatusCode::BAD_REQUEST);
+        assert_eq!(f.put_grade(f.terms[0], json!({"concept": "a"})).await, StatusCode::OK);
+
+        let (_, gradebook) = call_json(
+            &f.app,
+            "GET",
+            &format!("/classes/{}/gradebook?term_id={}&subject_id={}", f.class_id, f.terms[0], f.subject_id),
+            &f.token,
+            None,
+        )
+        .await;
+        assert_eq!(gradebook["grading_scale"]["scale_type"], "conceptual");
+        assert_eq!(gradebook["items"][0]["display_score"], "A");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn conceptual_report_averages_to_nearest_concept() {
+        let f = Fixture::new().await;
+        assert_eq!(f.put_grade(f.terms[0], json!({"concept": "A"})).await, StatusCode::OK);
+        assert_eq!(f.put_grade(f.terms[1], json!({"concept": "C"})).await, StatusCode::OK);
+
+        // A (3) e C (1): média 2 = B, acima do conceito mínimo C
+        let (_, report) = call_json(
+            &f.app,
+            "GET",
+            &format!("/classes/{}/students/{}/full-report", f.class_id, f.student_id),
+            &f.token,
+            None,
+        )
+        .await;
+        assert_eq!(report["subjects"][0]["average_display"], "B");
+        assert_eq!(report["subjects"][0]["final_status"], "approved");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn scale_with_grades_cannot_change_shape_or_be_unassigned() {
+        let f = Fixture::new().await;
+        assert_eq!(f.put_grade(f.terms[0], json!({"concept": "B"})).await, StatusCode::OK);
+
+        let (status, _) = call_json(&f.app, "PUT", "/grading-scale-assignments", &f.token, Some(json!({
+            "class_grade": "Infantil 4", "scale_id": null
+        }))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call_json(&f.app, "PUT", &format!("/grading-scales/{}", f.scale_id), &f.token, Some(json!({
+            "name": "Conceitos A-D", "scale_type": "conceptual",
+            "concepts": ["A", "B", "C"], "passing_concept": "C"
+        }))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call_json(&f.app, "DELETE", &format!("/grading-scales/{}", f.scale_id), &f.token, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn school_settings_are_validated_on_the_default_scale() {
+        let f = Fixture::new().await;
+        let settings = |passing: f64, final_exam: f64| {
+            Some(json!({"passing_min_grade": passing, "final_exam_min_grade": final_exam}))
+        };
+
+        let (status, _) = call_json(&f.app, "PUT", "/school/settings", &f.token, settings(11.0, 5.0)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, body) = call_json(&f.app, "PUT", "/school/settings", &f.token, settings(6.0, 12.0)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.as_str().unwrap().contains("Média da prova final"));
+
+        let (status, saved) = call_json(&f.app, "PUT", "/school/settings", &f.token, settings(6.257, 5.0)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(saved["passing_min_grade"], 6.26);
+
+        f.cleanup().await;
+    }
+}
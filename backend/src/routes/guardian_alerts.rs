@@ -0,0 +1,717 @@
+//! Alertas automáticos aos responsáveis (`parent_students` e `student_guardians`):
+//! faltas (cada uma e dias seguidos) e nota do período abaixo da média da escala.
+//! Os lançamentos gravam na fila `guardian_notifications` dentro da própria transação;
+//! a entrega fica com o job `jobs::guardian_notifications`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::grading::scales::scale_for_class;
+use crate::grading::term_lock::is_term_closed;
+use crate::routes::lessons::LessonKey;
+use crate::state::AppState;
+
+pub const KIND_ABSENCE: &str = "absence";
+pub const KIND_CONSECUTIVE_ABSENCES: &str = "consecutive_absences";
+pub const KIND_LOW_GRADE: &str = "low_grade";
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    pub student_id: Option<Uuid>,
+    pub person_id: Option<Uuid>,
+    /// pending | sending | sent | failed
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationResponse {
+    pub id: Uuid,
+    pub person_id: Uuid,
+    pub person_name: String,
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub kind: String,
+    pub channel: String,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlertPreferences {
+    pub absence_alerts: bool,
+    pub grade_alerts: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/guardian-notifications", get(list_notifications))
+        .route(
+            "/people/:person_id/alert-preferences",
+            get(get_preferences).put(update_preferences),
+        )
+        .with_state(state)
+}
+
+async fn list_notifications(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<NotificationsQuery>,
+) -> Result<Json<Vec<NotificationResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT n.id, n.person_id, p.full_name AS person_name, n.student_id, s.name AS student_name,
+               n.kind, n.channel, n.recipient, n.subject, n.body, n.status, n.attempts,
+               n.last_error, n.created_at, n.sent_at
+        FROM guardian_notifications n
+        JOIN people p ON p.id = n.person_id
+        JOIN students s ON s.id = n.student_id
+        WHERE n.tenant_id = $1
+          AND ($2::uuid IS NULL OR n.student_id = $2)
+          AND ($3::uuid IS NULL OR n.person_id = $3)
+          AND ($4::text IS NULL OR n.status = $4)
+        ORDER BY n.created_at DESC
+        LIMIT 500
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(query.student_id)
+    .bind(query.person_id)
+    .bind(query.status.as_deref().map(str::trim))
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| NotificationResponse {
+                id: r.get("id"),
+                person_id: r.get("person_id"),
+                person_name: r.get("person_name"),
+                student_id: r.get("student_id"),
+                student_name: r.get("student_name"),
+                kind: r.get("kind"),
+                channel: r.get("channel"),
+                recipient: r.get("recipient"),
+                subject: r.get("subject"),
+                body: r.get("body"),
+                status: r.get("status"),
+                attempts: r.get("attempts"),
+                last_error: r.get("last_error"),
+                created_at: r.get("created_at"),
+                sent_at: r.get("sent_at"),
+            })
+            .collect(),
+    ))
+}
+
+async fn get_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(person_id): Path<Uuid>,
+) -> Result<Json<AlertPreferences>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    ensure_person_belongs_to_tenant(&state.pool, user.tenant_id, person_id).await?;
+
+    let row = sqlx::query(
+        r#"SELECT absence_alerts, grade_alerts
+           FROM guardian_alert_preferences
+           WHERE tenant_id = $1 AND person_id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(person_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    // sem registro: recebe tudo
+    Ok(Json(match row {
+        Some(r) => AlertPreferences {
+            absence_alerts: r.get("absence_alerts"),
+            grade_alerts: r.get("grade_alerts"),
+        },
+        None => AlertPreferences {
+            absence_alerts: true,
+            grade_alerts: true,
+        },
+    }))
+}
+
+async fn update_preferences(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(person_id): Path<Uuid>,
+    Json(req): Json<AlertPreferences>,
+) -> Result<Json<AlertPreferences>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    ensure_person_belongs_to_tenant(&state.pool, user.tenant_id, person_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO guardian_alert_preferences (person_id, tenant_id, absence_alerts, grade_alerts)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (person_id)
+        DO UPDATE SET absence_alerts = EXCLUDED.absence_alerts,
+                      grade_alerts = EXCLUDED.grade_alerts,
+                      updated_at = NOW()
+        "#,
+    )
+    .bind(person_id)
+    .bind(user.tenant_id)
+    .bind(req.absence_alerts)
+    .bind(req.grade_alerts)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(req))
+}
+
+/// Alertas de falta depois de gravar a chamada de uma aula. Faltas cobertas por
+/// justificativa aprovada não geram alerta nem contam na sequência.
+pub async fn alert_absences(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    lesson: &LessonKey,
+    absent_student_ids: &[Uuid],
+) -> Result<(), (StatusCode, String)> {
+    if absent_student_ids.is_empty() {
+        return Ok(());
+    }
+
+    let settings = sqlx::query(
+        r#"SELECT alert_each_absence, alert_consecutive_absences FROM tenants WHERE id = $1"#,
+    )
+    .bind(tenant_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let each_absence: bool = settings.get("alert_each_absence");
+    let consecutive_threshold: i32 = settings.get("alert_consecutive_absences");
+    if !each_absence && consecutive_threshold == 0 {
+        return Ok(());
+    }
+
+    let subject_name: String = sqlx::query_scalar("SELECT name FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(lesson.subject_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let date = lesson.lesson_date.format("%d/%m/%Y");
+
+    for &student_id in absent_student_ids {
+        let row = sqlx::query(
+            r#"
+            SELECT s.name,
+                   EXISTS (
+                     SELECT 1 FROM absence_justifications j
+                     WHERE j.tenant_id = s.tenant_id AND j.student_id = s.id AND j.status = 'approved'
+                       AND $3 BETWEEN j.start_date AND j.end_date
+                   ) AS justified
+            FROM students s
+            WHERE s.tenant_id = $1 AND s.id = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(student_id)
+        .bind(lesson.lesson_date)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        if row.get::<bool, _>("justified") {
+            continue;
+        }
+        let student_name: String = row.get("name");
+
+        if each_absence {
+            enqueue(
+                tx,
+                tenant_id,
+                student_id,
+                KIND_ABSENCE,
+                &format!("absence:{}", lesson.lesson_date),
+                &format!("Falta registrada: {student_name}"),
+                &format!("{student_name} faltou à aula de {subject_name} em {date}."),
+            )
+            .await?;
+        }
+
+        if consecutive_threshold > 0 {
+            let (streak, streak_start) =
+                absence_streak(tx, tenant_id, class_id, student_id, lesson.lesson_date).await?;
+            if let (true, Some(streak_start)) = (streak >= consecutive_threshold, streak_start) {
+                enqueue(
+                    tx,
+                    tenant_id,
+                    student_id,
+                    KIND_CONSECUTIVE_ABSENCES,
+                    &format!("consecutive:{streak_start}"),
+                    &format!("Faltas seguidas: {student_name}"),
+                    &format!(
+                        "{student_name} está há {streak} dias letivos seguidos sem comparecer às aulas (desde {}).",
+                        streak_start.format("%d/%m/%Y")
+                    ),
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Dias seguidos (até `until`) em que o aluno teve chamada na turma e faltou a todas as aulas.
+async fn absence_streak(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    student_id: Uuid,
+    until: NaiveDate,
+) -> Result<(i32, Option<NaiveDate>), (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        WITH days AS (
+          SELECT attendance_date, bool_or(present OR justified) AS attended
+          FROM student_attendance_effective
+          WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3 AND attendance_date <= $4
+          GROUP BY attendance_date
+        )
+        SELECT COUNT(*)::int AS streak, MIN(attendance_date) AS streak_start
+        FROM days
+        WHERE attendance_date > COALESCE(
+          (SELECT MAX(attendance_date) FROM days WHERE attended), '-infinity'::date
+        )
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .bind(until)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok((row.get("streak"), row.get("streak_start")))
+}
+
+/// Alertas de nota do período abaixo da média da escala da turma (`passing_min_grade` na escala
+/// padrão). Lê as notas já gravadas em `student_grades`, então serve tanto ao lançamento direto
+/// quanto à média recalculada pelas avaliações; chame depois de qualquer escrita de `score`.
+/// Período encerrado não gera alerta: correções depois do fechamento não viram aviso às famílias.
+pub async fn alert_low_grades(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    term_id: Uuid,
+    subject_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let enabled: bool = sqlx::query_scalar("SELECT alert_low_grades FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if !enabled || is_term_closed(tx, tenant_id, term_id).await? {
+        return Ok(());
+    }
+
+    let scale = scale_for_class(&mut **tx, tenant_id, class_id).await?;
+    let rows = sqlx::query(
+        r#"
+        SELECT g.student_id, g.score::float8 AS score, s.name AS student_name,
+               t.name AS term_name, sub.name AS subject_name
+        FROM student_grades g
+        JOIN students s ON s.id = g.student_id AND s.tenant_id = g.tenant_id
+        JOIN academic_terms t ON t.id = g.term_id AND t.tenant_id = g.tenant_id
+        JOIN subjects sub ON sub.id = g.subject_id AND sub.tenant_id = g.tenant_id
+        WHERE g.tenant_id = $1 AND g.class_id = $2 AND g.term_id = $3 AND g.subject_id = $4
+          AND g.score IS NOT NULL
+          AND g.score < $5
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(term_id)
+    .bind(subject_id)
+    .bind(scale.passing_value)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let passing = scale.display(Some(scale.passing_value)).unwrap_or_default();
+    for r in rows {
+        let student_name: String = r.get("student_name");
+        let term_name: String = r.get("term_name");
+        let subject_name: String = r.get("subject_name");
+        let display = scale.display(Some(r.get("score"))).unwrap_or_default();
+
+        enqueue(
+            tx,
+            tenant_id,
+            r.get("student_id"),
+            KIND_LOW_GRADE,
+            &format!("low_grade:{term_id}:{subject_id}"),
+            &format!("Nota abaixo da média: {student_name}"),
+            &format!(
+                "{student_name} ficou com {display} em {subject_name} no período {term_name} (mínimo para aprovação: {passing})."
+            ),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Enfileira a mensagem para cada responsável ativo, com e-mail e sem opt-out do tipo.
+/// `dedupe_key` é por aluno; o mesmo evento não gera segundo alerta.
+async fn enqueue(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    student_id: Uuid,
+    kind: &str,
+    dedupe_key: &str,
+    subject: &str,
+    body: &str,
+) -> Result<(), (StatusCode, String)> {
+    sqlx::query(
+        r#"
+        INSERT INTO guardian_notifications
+          (id, tenant_id, person_id, student_id, kind, channel, recipient, subject, body, dedupe_key)
+        SELECT gen_random_uuid(), $1, p.id, $2, $3, 'email', p.email, $4, $5, $2::text || ':' || $6
+        FROM people p
+        LEFT JOIN guardian_alert_preferences pref ON pref.person_id = p.id
+        WHERE p.tenant_id = $1
+          AND p.is_active
+          AND NULLIF(TRIM(p.email), '') IS NOT NULL
+          AND (
+            p.id IN (SELECT ps.parent_person_id FROM parent_students ps
+                     WHERE ps.tenant_id = $1 AND ps.student_id = $2)
+            OR p.id IN (SELECT COALESCE(g.person_id, g.id)
+                        FROM student_guardians sg
+                        JOIN guardians g ON g.id = sg.guardian_id AND g.tenant_id = sg.tenant_id
+                        WHERE sg.tenant_id = $1 AND sg.student_id = $2 AND g.is_active)
+          )
+          AND CASE WHEN $3 = 'low_grade' THEN COALESCE(pref.grade_alerts, TRUE)
+                   ELSE COALESCE(pref.absence_alerts, TRUE) END
+        ON CONFLICT (tenant_id, person_id, dedupe_key) DO NOTHING
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .bind(kind)
+    .bind(subject)
+    .bind(body)
+    .bind(dedupe_key)
+    .execute(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(())
+}
+
+async fn ensure_person_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    person_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query("SELECT 1 FROM people WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(person_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Pessoa não encontrada".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::guardian_notifications::deliver_pending;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, spawn_gateway, test_pool, NOTIFICATION_DELIVERY, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 7A com o aluno Caio, História e o 1º bimestre. A mãe está em `parent_students` e o
+    /// avô em `student_guardians`; a escola alerta cada falta e sequências de duas.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+        parent_id: Uuid,
+        guardian_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Alertas").await;
+            let parent_id = Uuid::new_v4();
+            let guardian_id = Uuid::new_v4();
+            sqlx::query("UPDATE tenants SET alert_each_absence = TRUE, alert_consecutive_absences = 2 WHERE id = $1")
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let class_id = insert_class(&pool, tenant_id, "7A", "7 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "História").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Caio", "C1").await;
+            for (person_id, person_type, name) in [(parent_id, "parent", "Mãe do Caio"), (guardian_id, "guardian", "Avô do Caio")] {
+                sqlx::query("INSERT INTO people (id, tenant_id, person_type, full_name, email) VALUES ($1, $2, $3, $4, $5)")
+                    .bind(person_id)
+                    .bind(tenant_id)
+                    .bind(person_type)
+                    .bind(name)
+                    .bind(format!("{person_id}@example.com"))
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+            sqlx::query("INSERT INTO parent_students (parent_person_id, student_id, tenant_id) VALUES ($1, $2, $3)")
+                .bind(parent_id)
+                .bind(student_id)
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO guardians (id, tenant_id, full_name, person_id) VALUES ($1, $2, 'Avô do Caio', $1)")
+                .bind(guardian_id)
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO student_guardians (guardian_id, student_id, tenant_id) VALUES ($1, $2, $3)")
+                .bind(guardian_id)
+                .bind(student_id)
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::assessments::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, token, tenant_id, class_id, term_id, subject_id, student_id, parent_id, guardian_id }
+        }
+
+        async fn mark_absent(&self, date: &str) {
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/attendance", self.class_id), &self.token, Some(json!({
+                "date": date, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "present": false}]
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn save_grade(&self, score: f64) {
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/gradebook", self.class_id), &self.token, Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "score": score}]
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn alerts_for(&self, person_id: Uuid) -> Vec<Value> {
+            let (status, alerts) = call_json(&self.app, "GET", &format!("/guardian-notifications?person_id={person_id}"), &self.token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            alerts.as_array().unwrap().clone()
+        }
+
+        async fn alert_kinds_for(&self, person_id: Uuid) -> Vec<String> {
+            let mut kinds: Vec<String> = self
+                .alerts_for(person_id)
+                .await
+                .iter()
+                .map(|n| n["kind"].as_str().unwrap().to_string())
+                .collect();
+            kinds.sort();
+            kinds
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn absences_alert_each_day_and_streak_once() {
+        let f = Fixture::new().await;
+        // a segunda falta é salva de novo e não repete o alerta
+        for date in ["2026-03-09", "2026-03-10", "2026-03-10"] {
+            f.mark_absent(date).await;
+        }
+
+        assert_eq!(f.alert_kinds_for(f.parent_id).await, vec!["absence", "absence", "consecutive_absences"]);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn guardian_opt_out_skips_absence_alerts() {
+        let f = Fixture::new().await;
+        let (status, _) = call_json(&f.app, "PUT", &format!("/people/{}/alert-preferences", f.guardian_id), &f.token, Some(json!({
+            "absence_alerts": false, "grade_alerts": true
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.mark_absent("2026-03-09").await;
+        f.mark_absent("2026-03-10").await;
+
+        assert!(f.alerts_for(f.guardian_id).await.is_empty());
+        assert_eq!(f.alert_kinds_for(f.parent_id).await.len(), 3);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn corrections_in_closed_terms_raise_no_alert() {
+        let f = Fixture::new().await;
+        sqlx::query("UPDATE academic_terms SET closed_at = NOW() WHERE id = $1")
+            .bind(f.term_id)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+
+        let (status, _) = call_json(&f.app, "PUT", &format!("/classes/{}/gradebook", f.class_id), &f.token, Some(json!({
+            "term_id": f.term_id, "subject_id": f.subject_id,
+            "records": [{"student_id": f.student_id, "score": 4.5}],
+            "correction_justification": "Nota lançada errada no fechamento"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(f.alerts_for(f.parent_id).await.is_empty());
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn low_gradebook_score_alerts_every_guardian() {
+        let f = Fixture::new().await;
+        f.save_grade(4.5).await;
+        // regravar a mesma nota não duplica o alerta
+        f.save_grade(4.5).await;
+
+        for person_id in [f.parent_id, f.guardian_id] {
+            let alerts = f.alerts_for(person_id).await;
+            assert_eq!(alerts.len(), 1);
+            assert_eq!(alerts[0]["kind"], "low_grade");
+            assert!(alerts[0]["body"].as_str().unwrap().contains("4,50"));
+        }
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn passing_gradebook_score_raises_no_alert() {
+        let f = Fixture::new().await;
+        f.save_grade(7.0).await;
+
+        assert!(f.alerts_for(f.parent_id).await.is_empty());
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn low_assessment_average_alerts_guardians() {
+        let f = Fixture::new().await;
+        let (status, assessment) = call_json(&f.app, "POST", &format!("/classes/{}/assessments", f.class_id), &f.token, Some(json!({
+            "term_id": f.term_id, "subject_id": f.subject_id, "name": "Prova 1"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let assessment_id = assessment["id"].as_str().unwrap();
+
+        let (status, _) = call_json(
+            &f.app,
+            "PUT",
+            &format!("/classes/{}/assessments/{assessment_id}/scores", f.class_id),
+            &f.token,
+            Some(json!({"records": [{"student_id": f.student_id, "score": 3.0}]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let alerts = f.alerts_for(f.guardian_id).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0]["kind"], "low_grade");
+        assert!(alerts[0]["body"].as_str().unwrap().contains("3,00"));
+        f.cleanup().await;
+    }
+
+    /// Gateway de e-mail fake: devolve a URL e os payloads recebidos.
+    #[tokio::test]
+    async fn pending_alerts_are_delivered_through_gateway() {
+        let _queue = NOTIFICATION_DELIVERY.lock().await;
+        let f = Fixture::new().await;
+        f.mark_absent("2026-03-09").await;
+        f.save_grade(4.5).await;
+        let (url, received) = spawn_gateway().await;
+
+        deliver_pending(&f.pool, &reqwest::Client::new(), &url).await.unwrap();
+
+        let parent_email = format!("{}@example.com", f.parent_id);
+        assert_eq!(
+            received.lock().unwrap().iter().filter(|p| p["to"] == parent_email.as_str()).count(),
+            2
+        );
+        let (_, sent) = call_json(&f.app, "GET", &format!("/guardian-notifications?student_id={}&status=sent", f.student_id), &f.token, None).await;
+        assert_eq!(sent.as_array().unwrap().len(), 4);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn concurrent_deliveries_send_each_alert_once() {
+        let _queue = NOTIFICATION_DELIVERY.lock().await;
+        let f = Fixture::new().await;
+        f.mark_absent("2026-03-09").await;
+        f.save_grade(4.5).await;
+        let (url, received) = spawn_gateway().await;
+
+        // duas instâncias do servidor na mesma rodada
+        let http = reqwest::Client::new();
+        let (first, second) = tokio::join!(deliver_pending(&f.pool, &http, &url), deliver_pending(&f.pool, &http, &url));
+        first.unwrap();
+        second.unwrap();
+
+        let parent_email = format!("{}@example.com", f.parent_id);
+        assert_eq!(
+            received.lock().unwrap().iter().filter(|p| p["to"] == parent_email.as_str()).count(),
+            2
+        );
+        let attempts: Vec<i32> = sqlx::query_scalar("SELECT attempts FROM guardian_notifications WHERE student_id = $1")
+            .bind(f.student_id)
+            .fetch_all(&f.pool)
+            .await
+            .unwrap();
+        assert_eq!(attempts, vec![1; 4]);
+        f.cleanup().await;
+    }
+}
@@ -0,0 +1,1363 @@
+//! Portal dos responsáveis: conta por e-mail na escola (`guardian_portal_accounts`) com
+//! senha ou link mágico, e JWT de escopo `guardian`. A conta cobre todas as pessoas
+//! com esse e-mail e papel de pai/mãe, responsável ou responsável financeiro, e dá
+//! acesso aos alunos vinculados a elas em `parent_students` e
+//! `financial_guardian_students`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::env;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::features::{ensure_feature_enabled, FEATURE_FINANCIAL};
+use crate::auth::identity::{hash_password, verify_password};
+use crate::auth::jwt::{AuthUser, Claims, GuardianUser, ROLE_GUARDIAN_MAGIC_LINK};
+use crate::auth::oidc::random_token;
+use crate::grading::promotion::presence_percent;
+use crate::routes::financial::{
+    build_financial_guardian_statement, load_contract_response, ContractResponse,
+    FinancialGuardianStatementQuery, FinancialGuardianStatementResponse,
+};
+use crate::routes::records::{
+    fetch_student_full_report, fetch_student_term_report, StudentFullReportResponse,
+    StudentTermReportQuery, StudentTermReportResponse,
+};
+use crate::state::AppState;
+
+pub const KIND_PORTAL_MAGIC_LINK: &str = "portal_magic_link";
+
+/// Texto do link mágico na fila: o token só existe no e-mail, gerado na entrega
+/// (`issue_magic_link`).
+pub const MAGIC_LINK_QUEUED_BODY: &str = "Link de acesso ao portal dos responsáveis (gerado no envio).";
+
+/// Papéis (`person_type` ou `person_roles`) que dão acesso ao portal.
+const GUARDIAN_ROLES: [&str; 3] = ["parent", "guardian", "financial_guardian"];
+
+const MAGIC_LINK_MINUTES: i64 = 30;
+
+/// Sessão aberta por link mágico troca a senha sem pedir a atual: dura pouco.
+const MAGIC_LINK_SESSION_MINUTES: i64 = 30;
+
+/// Limite de links mágicos por conta dentro de `MAGIC_LINK_WINDOW_MINUTES`.
+const MAGIC_LINK_MAX_PER_WINDOW: i64 = 3;
+const MAGIC_LINK_WINDOW_MINUTES: i64 = 15;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct PortalLoginRequest {
+    #[validate(length(min = 3))]
+    pub school_code: String,
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 1))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct MagicLinkRequest {
+    #[validate(length(min = 3))]
+    pub school_code: String,
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyMagicLinkRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalAuthResponse {
+    pub tenant_id: Uuid,
+    pub account_id: Uuid,
+    pub token: String,
+    pub school_name: String,
+    pub school_code: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangePortalPasswordRequest {
+    /// Obrigatória, exceto em sessão aberta por link mágico.
+    pub current_password: Option<String>,
+    #[validate(length(min = 8))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalPerson {
+    pub person_id: Uuid,
+    pub full_name: String,
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalChild {
+    pub student_id: Uuid,
+    pub name: String,
+    pub registration: String,
+    pub class_id: Option<Uuid>,
+    pub class_name: Option<String>,
+    /// Vínculo em `parent_students`.
+    pub parent_link: bool,
+    /// Vínculo em `financial_guardian_students`.
+    pub financial_link: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalMeResponse {
+    pub account_id: Uuid,
+    pub email: String,
+    pub has_password: bool,
+    pub school_name: String,
+    pub school_code: String,
+    pub people: Vec<PortalPerson>,
+    pub children: Vec<PortalChild>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalAttendanceQuery {
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalAbsence {
+    pub date: NaiveDate,
+    pub lesson_number: i32,
+    pub subject_id: Option<Uuid>,
+    pub subject_name: Option<String>,
+    pub justified: bool,
+    /// Se entra no cálculo da frequência (regra de falta justificada da escola).
+    pub counted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalAttendanceResponse {
+    pub student_id: Uuid,
+    pub class_id: Uuid,
+    pub total_lessons: i32,
+    pub present_lessons: i32,
+    pub absences: i32,
+    pub justified_absences: i32,
+    pub attendance_percent: Option<f64>,
+    pub items: Vec<PortalAbsence>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalAccessRequest {
+    pub is_active: bool,
+    /// Senha inicial opcional; sem ela o responsável entra por link mágico.
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalAccessResponse {
+    pub person_id: Uuid,
+    pub email: String,
+    pub account_id: Option<Uuid>,
+    pub is_active: bool,
+    pub has_password: bool,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+/// Conta do portal com as pessoas que ela representa.
+struct PortalAccount {
+    email: String,
+    has_password: bool,
+    person_ids: Vec<Uuid>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/portal/auth/login", post(login))
+        .route("/portal/auth/magic-link", post(request_magic_link))
+        .route("/portal/auth/magic-link/verify", post(verify_magic_link))
+        .route("/portal/me", get(get_me))
+        .route("/portal/me/password", put(change_password))
+        .route("/portal/children/:student_id/term-report", get(get_child_term_report))
+        .route("/portal/children/:student_id/full-report", get(get_child_full_report))
+        .route("/portal/children/:student_id/attendance", get(get_child_attendance))
+        .route("/portal/financial/contracts", get(list_financial_contracts))
+        .route("/portal/financial/statements", get(list_financial_statements))
+        .route(
+            "/people/:person_id/portal-access",
+            get(get_portal_access).put(update_portal_access),
+        )
+        .with_state(state)
+}
+
+async fn login(
+    State(state): State<AppState>,
+    Json(req): Json<PortalLoginRequest>,
+) -> Result<Json<PortalAuthResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let school_code = req.school_code.trim().to_lowercase();
+    let email = req.email.trim().to_lowercase();
+
+    let row = sqlx::query(
+        r#"
+        SELECT a.id, a.tenant_id, a.password_hash, t.name AS school_name, t.slug AS school_code
+        FROM guardian_portal_accounts a
+        JOIN tenants t ON t.id = a.tenant_id
+        WHERE t.slug = $1 AND a.email = $2 AND a.is_active
+        "#,
+    )
+    .bind(&school_code)
+    .bind(&email)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
+
+    let password_hash: Option<String> = row.get("password_hash");
+    let password_hash = password_hash.ok_or((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
+    if !verify_password(&password_hash, &req.password)? {
+        return Err((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()));
+    }
+
+    let response = start_session(
+        &state,
+        row.get("id"),
+        row.get("tenant_id"),
+        &email,
+        row.get("school_name"),
+        row.get("school_code"),
+        false,
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+/// Sempre responde `ok`, para não revelar quais e-mails têm cadastro na escola.
+async fn request_magic_link(
+    State(state): State<AppState>,
+    Json(req): Json<MagicLinkRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let school_code = req.school_code.trim().to_lowercase();
+    let email = req.email.trim().to_lowercase();
+
+    let tenant = sqlx::query("SELECT id, name FROM tenants WHERE slug = $1")
+        .bind(&school_code)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let Some(tenant) = tenant else {
+        return Ok(Json(OkResponse { ok: true }));
+    };
+    let tenant_id: Uuid = tenant.get("id");
+    let school_name: String = tenant.get("name");
+
+    let person_ids = guardian_people_by_email(&state.pool, tenant_id, &email).await?;
+    let Some(person_id) = person_ids.first().copied() else {
+        return Ok(Json(OkResponse { ok: true }));
+    };
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    // Primeiro acesso cria a conta; conta desativada pela escola não recebe link.
+    let account = sqlx::query(
+        r#"
+        INSERT INTO guardian_portal_accounts (id, tenant_id, email)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (tenant_id, email) DO UPDATE SET updated_at = guardian_portal_accounts.updated_at
+        RETURNING id, is_active
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(tenant_id)
+    .bind(&email)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let account_id: Uuid = account.get("id");
+    let is_active: bool = account.get("is_active");
+    if !is_active {
+        return Ok(Json(OkResponse { ok: true }));
+    }
+
+    // Pedidos demais em pouco tempo são ignorados em silêncio, como os e-mails sem cadastro.
+    let recent_links: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM guardian_portal_magic_links
+        WHERE account_id = $1 AND created_at > NOW() - make_interval(mins => $2)
+        "#,
+    )
+    .bind(account_id)
+    .bind(MAGIC_LINK_WINDOW_MINUTES as i32)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if recent_links >= MAGIC_LINK_MAX_PER_WINDOW {
+        return Ok(Json(OkResponse { ok: true }));
+    }
+
+    // Sem token por enquanto: ele é gerado na entrega do e-mail.
+    let link_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO guardian_portal_magic_links (id, account_id, expires_at)
+        VALUES ($1, $2, NOW() + make_interval(mins => $3))
+        "#,
+    )
+    .bind(link_id)
+    .bind(account_id)
+    .bind(MAGIC_LINK_MINUTES as i32)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO guardian_notifications
+          (id, tenant_id, person_id, student_id, kind, channel, recipient, subject, body, dedupe_key, magic_link_id)
+        VALUES ($1, $2, $3, NULL, $4, 'email', $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(tenant_id)
+    .bind(person_id)
+    .bind(KIND_PORTAL_MAGIC_LINK)
+    .bind(&email)
+    .bind(format!("Acesso ao portal dos responsáveis - {school_name}"))
+    .bind(MAGIC_LINK_QUEUED_BODY)
+    .bind(format!("{KIND_PORTAL_MAGIC_LINK}:{link_id}"))
+    .bind(link_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Gera o token do link na entrega e devolve o texto do e-mail. Cada tentativa troca o
+/// token e renova o prazo; `None` quando o link já foi usado ou não existe mais.
+pub async fn issue_magic_link(pool: &PgPool, link_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    let token = random_token();
+    let issued = sqlx::query(
+        r#"
+        UPDATE guardian_portal_magic_links
+        SET token_hash = $2, expires_at = NOW() + make_interval(mins => $3)
+        WHERE id = $1 AND used_at IS NULL
+        "#,
+    )
+    .bind(link_id)
+    .bind(token_hash(&token))
+    .bind(MAGIC_LINK_MINUTES as i32)
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0;
+    Ok(issued.then(|| {
+        format!(
+            "Use o link abaixo para entrar no portal dos responsáveis. Ele vale por {MAGIC_LINK_MINUTES} minutos e só pode ser usado uma vez.\n{}?token={token}",
+            portal_base_url()
+        )
+    }))
+}
+
+async fn verify_magic_link(
+    State(state): State<AppState>,
+    Json(req): Json<VerifyMagicLinkRequest>,
+) -> Result<Json<PortalAuthResponse>, (StatusCode, String)> {
+    let token = req.token.trim();
+    if token.is_empty() {
+        return Err((StatusCode::UNAUTHORIZED, "Link inválido ou expirado".into()));
+    }
+
+    // Marca o uso na mesma instrução: o link não serve duas vezes nem em paralelo.
+    let row = sqlx::query(
+        r#"
+        WITH used AS (
+          UPDATE guardian_portal_magic_links
+          SET used_at = NOW()
+          WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+          RETURNING account_id
+        )
+        SELECT a.id, a.tenant_id, a.email, a.is_active, t.name AS school_name, t.slug AS school_code
+        FROM used
+        JOIN guardian_portal_accounts a ON a.id = used.account_id
+        JOIN tenants t ON t.id = a.tenant_id
+        "#,
+    )
+    .bind(token_hash(token))
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Link inválido ou expirado".into()))?;
+
+    let is_active: bool = row.get("is_active");
+    if !is_active {
+        return Err((StatusCode::UNAUTHORIZED, "Link inválido ou expirado".into()));
+    }
+
+    let email: String = row.get("email");
+    let response = start_session(
+        &state,
+        row.get("id"),
+        row.get("tenant_id"),
+        &email,
+        row.get("school_name"),
+        row.get("school_code"),
+        true,
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+async fn get_me(
+    State(state): State<AppState>,
+    user: GuardianUser,
+) -> Result<Json<PortalMeResponse>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+
+    let school = sqlx::query("SELECT name, slug FROM tenants WHERE id = $1")
+        .bind(user.tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let people_rows = sqlx::query(
+        r#"
+        SELECT p.id, p.full_name,
+               ARRAY(
+                 SELECT DISTINCT r FROM (
+                   SELECT p.person_type AS r
+                   UNION ALL
+                   SELECT pr.role_code FROM person_roles pr WHERE pr.person_id = p.id
+                 ) x
+                 WHERE r = ANY($3)
+                 ORDER BY r
+               ) AS roles
+        FROM people p
+        WHERE p.tenant_id = $1 AND p.id = ANY($2)
+        ORDER BY p.full_name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&account.person_ids)
+    .bind(GUARDIAN_ROLES.as_slice())
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let child_rows = sqlx::query(
+        r#"
+        SELECT * FROM (
+          SELECT s.id, s.name, s.registration, s.class_id, c.name AS class_name,
+                 EXISTS (
+                   SELECT 1 FROM parent_students ps
+                   WHERE ps.tenant_id = s.tenant_id AND ps.student_id = s.id
+                     AND ps.parent_person_id = ANY($2)
+                 ) AS parent_link,
+                 EXISTS (
+                   SELECT 1 FROM financial_guardian_students fs
+                   WHERE fs.tenant_id = s.tenant_id AND fs.student_id = s.id
+                     AND fs.financial_person_id = ANY($2)
+                 ) AS financial_link
+          FROM students s
+          LEFT JOIN classes c ON c.id = s.class_id AND c.tenant_id = s.tenant_id
+          WHERE s.tenant_id = $1
+        ) x
+        WHERE x.parent_link OR x.financial_link
+        ORDER BY x.name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&account.person_ids)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(PortalMeResponse {
+        account_id: user.account_id,
+        email: account.email,
+        has_password: account.has_password,
+        school_name: school.get("name"),
+        school_code: school.get("slug"),
+        people: people_rows
+            .into_iter()
+            .map(|r| PortalPerson {
+                person_id: r.get("id"),
+                full_name: r.get("full_name"),
+                roles: r.get("roles"),
+            })
+            .collect(),
+        children: child_rows
+            .into_iter()
+            .map(|r| PortalChild {
+                student_id: r.get("id"),
+                name: r.get("name"),
+                registration: r.get("registration"),
+                class_id: r.get("class_id"),
+                class_name: r.get("class_name"),
+                parent_link: r.get("parent_link"),
+                financial_link: r.get("financial_link"),
+            })
+            .collect(),
+    }))
+}
+
+/// Pede a senha atual, exceto em sessão aberta por link mágico: quem entrou por ele já
+/// provou ser dono do e-mail, e é assim que o responsável recupera uma senha esquecida.
+async fn change_password(
+    State(state): State<AppState>,
+    user: GuardianUser,
+    Json(req): Json<ChangePortalPasswordRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    load_portal_account(&state.pool, &user).await?;
+
+    if !user.magic_link {
+        let current_hash: Option<String> = sqlx::query_scalar(
+            "SELECT password_hash FROM guardian_portal_accounts WHERE tenant_id = $1 AND id = $2",
+        )
+        .bind(user.tenant_id)
+        .bind(user.account_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        let current_password = req.current_password.as_deref().unwrap_or_default();
+        let matches = match current_hash {
+            Some(hash) => verify_password(&hash, current_password)?,
+            None => false,
+        };
+        if !matches {
+            return Err((StatusCode::UNAUTHORIZED, "Senha atual incorreta".into()));
+        }
+    }
+
+    let password_hash = hash_password(&req.new_password)?;
+    sqlx::query(
+        r#"UPDATE guardian_portal_accounts
+           SET password_hash = $3, updated_at = NOW()
+           WHERE tenant_id = $1 AND id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(user.account_id)
+    .bind(password_hash)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn get_child_term_report(
+    State(state): State<AppState>,
+    user: GuardianUser,
+    Path(student_id): Path<Uuid>,
+    Query(query): Query<StudentTermReportQuery>,
+) -> Result<Json<StudentTermReportResponse>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+    let class_id = child_current_class(&state.pool, user.tenant_id, &account, student_id).await?;
+    let data = fetch_student_term_report(&state.pool, user.tenant_id, class_id, student_id, query.term_id).await?;
+    Ok(Json(data))
+}
+
+async fn get_child_full_report(
+    State(state): State<AppState>,
+    user: GuardianUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<Json<StudentFullReportResponse>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+    let class_id = child_current_class(&state.pool, user.tenant_id, &account, student_id).await?;
+    let data = fetch_student_full_report(&state.pool, user.tenant_id, class_id, student_id).await?;
+    Ok(Json(data))
+}
+
+async fn get_child_attendance(
+    State(state): State<AppState>,
+    user: GuardianUser,
+    Path(student_id): Path<Uuid>,
+    Query(query): Query<PortalAttendanceQuery>,
+) -> Result<Json<PortalAttendanceResponse>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+    let class_id = child_current_class(&state.pool, user.tenant_id, &account, student_id).await?;
+
+    let totals = sqlx::query(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE counted)::int AS total,
+               COUNT(*) FILTER (WHERE counted AND present)::int AS present,
+               COUNT(*) FILTER (WHERE NOT recorded_present)::int AS absences,
+               COUNT(*) FILTER (WHERE justified)::int AS justified
+        FROM student_attendance_effective
+        WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3
+          AND ($4::date IS NULL OR attendance_date >= $4)
+          AND ($5::date IS NULL OR attendance_date <= $5)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .bind(query.date_from)
+    .bind(query.date_to)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT a.attendance_date, a.lesson_number, a.subject_id, sub.name AS subject_name,
+               a.justified, a.counted
+        FROM student_attendance_effective a
+        LEFT JOIN subjects sub ON sub.id = a.subject_id
+        WHERE a.tenant_id = $1 AND a.class_id = $2 AND a.student_id = $3
+          AND NOT a.recorded_present
+          AND ($4::date IS NULL OR a.attendance_date >= $4)
+          AND ($5::date IS NULL OR a.attendance_date <= $5)
+        ORDER BY a.attendance_date DESC, a.lesson_number ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .bind(query.date_from)
+    .bind(query.date_to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let total: i32 = totals.get("total");
+    let present: i32 = totals.get("present");
+    Ok(Json(PortalAttendanceResponse {
+        student_id,
+        class_id,
+        total_lessons: total,
+        present_lessons: present,
+        absences: totals.get("absences"),
+        justified_absences: totals.get("justified"),
+        attendance_percent: presence_percent(total, present),
+        items: rows
+            .into_iter()
+            .map(|r| PortalAbsence {
+                date: r.get("attendance_date"),
+                lesson_number: r.get("lesson_number"),
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                justified: r.get("justified"),
+                counted: r.get("counted"),
+            })
+            .collect(),
+    }))
+}
+
+/// Contratos dos alunos vinculados e os que têm uma das pessoas da conta como pagador
+/// ou destinatário, com parcelas, boletos e PIX copia e cola.
+async fn list_financial_contracts(
+    State(state): State<AppState>,
+    user: GuardianUser,
+) -> Result<Json<Vec<ContractResponse>>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_FINANCIAL).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.id
+        FROM financial_contracts c
+        WHERE c.tenant_id = $1
+          AND (
+            c.payer_person_id = ANY($2)
+            OR EXISTS (
+              SELECT 1 FROM financial_contract_recipients fcr
+              WHERE fcr.tenant_id = c.tenant_id AND fcr.contract_id = c.id
+                AND fcr.person_id = ANY($2)
+            )
+            OR EXISTS (
+              SELECT 1 FROM parent_students ps
+              WHERE ps.tenant_id = c.tenant_id AND ps.student_id = c.student_id
+                AND ps.parent_person_id = ANY($2)
+            )
+            OR EXISTS (
+              SELECT 1 FROM financial_guardian_students fs
+              WHERE fs.tenant_id = c.tenant_id AND fs.student_id = c.student_id
+                AND fs.financial_person_id = ANY($2)
+            )
+          )
+        ORDER BY c.created_at DESC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&account.person_ids)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: Uuid = row.get("id");
+        out.push(load_contract_response(&state.pool, user.tenant_id, id).await?);
+    }
+    Ok(Json(out))
+}
+
+/// Um extrato por pessoa da conta que seja responsável financeiro ou pagador de algum
+/// recebível.
+async fn list_financial_statements(
+    State(state): State<AppState>,
+    user: GuardianUser,
+    Query(query): Query<FinancialGuardianStatementQuery>,
+) -> Result<Json<Vec<FinancialGuardianStatementResponse>>, (StatusCode, String)> {
+    let account = load_portal_account(&state.pool, &user).await?;
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_FINANCIAL).await?;
+
+    let payer_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT p.id
+        FROM people p
+        WHERE p.tenant_id = $1 AND p.id = ANY($2)
+          AND (
+            p.person_type = 'financial_guardian'
+            OR EXISTS (
+              SELECT 1 FROM person_roles pr
+              WHERE pr.person_id = p.id AND pr.role_code = 'financial_guardian'
+            )
+            OR EXISTS (
+              SELECT 1 FROM financial_receivables fr
+              WHERE fr.tenant_id = p.tenant_id AND fr.payer_person_id = p.id
+            )
+          )
+        ORDER BY p.full_name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&account.person_ids)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut out = Vec::with_capacity(payer_ids.len());
+    for person_id in payer_ids {
+        out.push(build_financial_guardian_statement(&state.pool, user.tenant_id, person_id, &query).await?);
+    }
+    Ok(Json(out))
+}
+
+async fn get_portal_access(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(person_id): Path<Uuid>,
+) -> Result<Json<PortalAccessResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    let email = guardian_person_email(&state.pool, user.tenant_id, person_id).await?;
+    let data = load_portal_access(&state.pool, user.tenant_id, person_id, email).await?;
+    Ok(Json(data))
+}
+
+async fn update_portal_access(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(person_id): Path<Uuid>,
+    Json(req): Json<PortalAccessRequest>,
+) -> Result<Json<PortalAccessResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    let email = guardian_person_email(&state.pool, user.tenant_id, person_id).await?;
+
+    let password_hash = match normalize_optional_text(req.password) {
+        Some(password) if password.chars().count() < 8 => {
+            return Err((StatusCode::BAD_REQUEST, "Senha deve ter ao menos 8 caracteres".into()));
+        }
+        Some(password) => Some(hash_password(&password)?),
+        None => None,
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO guardian_portal_accounts (id, tenant_id, email, password_hash, is_active)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (tenant_id, email) DO UPDATE
+        SET is_active = EXCLUDED.is_active,
+            password_hash = COALESCE(EXCLUDED.password_hash, guardian_portal_accounts.password_hash),
+            updated_at = NOW()
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(&email)
+    .bind(password_hash)
+    .bind(req.is_active)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let data = load_portal_access(&state.pool, user.tenant_id, person_id, email).await?;
+    Ok(Json(data))
+}
+
+async fn start_session(
+    state: &AppState,
+    account_id: Uuid,
+    tenant_id: Uuid,
+    email: &str,
+    school_name: String,
+    school_code: String,
+    magic_link: bool,
+) -> Result<PortalAuthResponse, (StatusCode, String)> {
+    // O e-mail pode ter deixado de estar em qualquer cadastro de responsável.
+    if guardian_people_by_email(&state.pool, tenant_id, email).await?.is_empty() {
+        return Err((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()));
+    }
+
+    sqlx::query("UPDATE guardian_portal_accounts SET last_login_at = NOW() WHERE id = $1")
+        .bind(account_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let token = make_guardian_jwt(&state.jwt_secret, account_id, tenant_id, magic_link)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
+
+    Ok(PortalAuthResponse {
+        tenant_id,
+        account_id,
+        token,
+        school_name,
+        school_code,
+    })
+}
+
+/// Confere a cada requisição se a conta segue ativa: desativar o acesso derruba
+/// também os tokens já emitidos.
+async fn load_portal_account(pool: &PgPool, user: &GuardianUser) -> Result<PortalAccount, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT email, password_hash IS NOT NULL AS has_password
+        FROM guardian_portal_accounts
+        WHERE tenant_id = $1 AND id = $2 AND is_active
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(user.account_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Acesso ao portal desativado".into()))?;
+
+    let email: String = row.get("email");
+    let person_ids = guardian_people_by_email(pool, user.tenant_id, &email).await?;
+    Ok(PortalAccount {
+        email,
+        has_password: row.get("has_password"),
+        person_ids,
+    })
+}
+
+async fn guardian_people_by_email(pool: &PgPool, tenant_id: Uuid, email: &str) -> Result<Vec<Uuid>, (StatusCode, String)> {
+    sqlx::query_scalar(
+        r#"
+        SELECT p.id
+        FROM people p
+        WHERE p.tenant_id = $1 AND lower(p.email) = $2 AND p.is_active
+          AND (
+            p.person_type = ANY($3)
+            OR EXISTS (
+              SELECT 1 FROM person_roles pr
+              WHERE pr.person_id = p.id AND pr.role_code = ANY($3)
+            )
+          )
+        ORDER BY p.created_at ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(email)
+    .bind(GUARDIAN_ROLES.as_slice())
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))
+}
+
+/// Turma atual de um aluno vinculado à conta. Aluno de outra família dá 404, como se
+/// não existisse.
+async fn child_current_class(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    account: &PortalAccount,
+    student_id: Uuid,
+) -> Result<Uuid, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT s.class_id
+        FROM students s
+        WHERE s.tenant_id = $1 AND s.id = $2
+          AND (
+            EXISTS (
+              SELECT 1 FROM parent_students ps
+              WHERE ps.tenant_id = s.tenant_id AND ps.student_id = s.id
+                AND ps.parent_person_id = ANY($3)
+            )
+            OR EXISTS (
+              SELECT 1 FROM financial_guardian_students fs
+              WHERE fs.tenant_id = s.tenant_id AND fs.student_id = s.id
+                AND fs.financial_person_id = ANY($3)
+            )
+          )
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .bind(&account.person_ids)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))?;
+
+    let class_id: Option<Uuid> = row.get("class_id");
+    class_id.ok_or((StatusCode::NOT_FOUND, "Aluno sem turma atual".into()))
+}
+
+async fn guardian_person_email(pool: &PgPool, tenant_id: Uuid, person_id: Uuid) -> Result<String, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT lower(p.email) AS email,
+               (p.person_type = ANY($3)
+                OR EXISTS (
+                  SELECT 1 FROM person_roles pr
+                  WHERE pr.person_id = p.id AND pr.role_code = ANY($3)
+                )) AS is_guardian
+        FROM people p
+        WHERE p.tenant_id = $1 AND p.id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(person_id)
+    .bind(GUARDIAN_ROLES.as_slice())
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Pessoa não encontrada".into()))?;
+
+    let is_guardian: bool = row.get("is_guardian");
+    if !is_guardian {
+        return Err((StatusCode::BAD_REQUEST, "Pessoa não é responsável por nenhum aluno".into()));
+    }
+    let email: Option<String> = row.get("email");
+    normalize_optional_text(email).ok_or((StatusCode::BAD_REQUEST, "Pessoa sem e-mail cadastrado".into()))
+}
+
+async fn load_portal_access(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    person_id: Uuid,
+    email: String,
+) -> Result<PortalAccessResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, is_active, password_hash IS NOT NULL AS has_password, last_login_at
+        FROM guardian_portal_accounts
+        WHERE tenant_id = $1 AND email = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&email)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(match row {
+        Some(r) => PortalAccessResponse {
+            person_id,
+            email,
+            account_id: Some(r.get("id")),
+            is_active: r.get("is_active"),
+            has_password: r.get("has_password"),
+            last_login_at: r.get("last_login_at"),
+        },
+        // Sem conta ainda: o primeiro link mágico cria uma ativa.
+        None => PortalAccessResponse {
+            person_id,
+            email,
+            account_id: None,
+            is_active: true,
+            has_password: false,
+            last_login_at: None,
+        },
+    })
+}
+
+fn make_guardian_jwt(jwt_secret: &str, account_id: Uuid, tenant_id: Uuid, magic_link: bool) -> Result<String, ()> {
+    // 7 dias com senha; sessão de link mágico só o bastante para trocar a senha
+    let ttl = if magic_link {
+        chrono::Duration::minutes(MAGIC_LINK_SESSION_MINUTES)
+    } else {
+        chrono::Duration::days(7)
+    };
+    let exp = (chrono::Utc::now() + ttl).timestamp() as usize;
+
+    let role = if magic_link { ROLE_GUARDIAN_MAGIC_LINK } else { "guardian" };
+    let claims = Claims {
+        sub: account_id.to_string(),
+        tenant_id: Some(tenant_id.to_string()),
+        role: role.to_string(),
+        scope: "guardian".to_string(),
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_bytes()),
+    )
+    .map_err(|_| ())
+}
+
+fn portal_base_url() -> String {
+    let raw = env::var("PORTAL_BASE_URL")
+        .unwrap_or_else(|_| "http://localhost:5173/portal/entrar".to_string());
+    raw.trim_end_matches('/').to_string()
+}
+
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::guardian_notifications::deliver_pending;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_tenant, insert_term, make_token, spawn_gateway, test_pool, NOTIFICATION_DELIVERY, SECRET,
+    };
+    use jsonwebtoken::{decode, DecodingKey, Validation};
+    use serde_json::{json, Value};
+
+    const PASSWORD: &str = "senha-forte-1";
+
+    /// Lia e Rui na 5A, com o 1º bimestre; a mãe da Lia (e-mail com maiúsculas) só está
+    /// vinculada à Lia.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        school_code: String,
+        term_id: Uuid,
+        child_id: Uuid,
+        other_id: Uuid,
+        guardian_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Portal").await;
+            let school_code = format!("teste-{tenant_id}");
+            let guardian_id = Uuid::new_v4();
+            let class_id = insert_class(&pool, tenant_id, "5A", "5 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let child_id = insert_student(&pool, tenant_id, class_id, "Lia", "Lia").await;
+            let other_id = insert_student(&pool, tenant_id, class_id, "Rui", "Rui").await;
+            sqlx::query("INSERT INTO people (id, tenant_id, person_type, full_name, email) VALUES ($1, $2, 'parent', 'Mãe da Lia', 'Mae.Lia@Exemplo.com')")
+                .bind(guardian_id)
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO parent_students (parent_person_id, student_id, tenant_id) VALUES ($1, $2, $3)")
+                .bind(guardian_id)
+                .bind(child_id)
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+
+            Fixture { pool, app, owner, tenant_id, school_code, term_id, child_id, other_id, guardian_id }
+        }
+
+        fn access_path(&self) -> String {
+            format!("/people/{}/portal-access", self.guardian_id)
+        }
+
+        async fn set_access(&self, body: Value) -> Value {
+            let (status, access) = call_json(&self.app, "PUT", &self.access_path(), &self.owner, Some(body)).await;
+            assert_eq!(status, StatusCode::OK);
+            access
+        }
+
+        async fn login(&self, password: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", "/portal/auth/login", "", Some(json!({
+                "school_code": self.school_code, "email": "MAE.LIA@exemplo.com", "password": password
+            })))
+            .await
+        }
+
+        /// Acesso com senha e sessão aberta por ela.
+        async fn password_session(&self) -> String {
+            self.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+            let (status, session) = self.login(PASSWORD).await;
+            assert_eq!(status, StatusCode::OK);
+            session["token"].as_str().unwrap().to_string()
+        }
+
+        async fn request_magic_link(&self, email: &str) {
+            let (status, _) = call_json(&self.app, "POST", "/portal/auth/magic-link", "", Some(json!({
+                "school_code": self.school_code, "email": email
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn magic_link_bodies(&self) -> Vec<String> {
+            sqlx::query_scalar("SELECT body FROM guardian_notifications WHERE tenant_id = $1 AND kind = $2 ORDER BY created_at")
+                .bind(self.tenant_id)
+                .bind(KIND_PORTAL_MAGIC_LINK)
+                .fetch_all(&self.pool)
+                .await
+                .unwrap()
+        }
+
+        /// Pede o link e entrega a fila; devolve o token que chegou por e-mail para esta escola.
+        async fn send_magic_link(&self, email: &str) -> Option<String> {
+            let _queue = NOTIFICATION_DELIVERY.lock().await;
+            self.request_magic_link(email).await;
+            let (url, received) = spawn_gateway().await;
+            deliver_pending(&self.pool, &reqwest::Client::new(), &url).await.unwrap();
+
+            let bodies: Vec<String> = received
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|p| p["body"].as_str().map(str::to_string))
+                .collect();
+            for body in bodies {
+                let Some((_, token)) = body.rsplit_once("token=") else { continue };
+                let ours: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM guardian_portal_magic_links l
+                     JOIN guardian_portal_accounts a ON a.id = l.account_id
+                     WHERE a.tenant_id = $1 AND l.token_hash = $2)",
+                )
+                .bind(self.tenant_id)
+                .bind(token_hash(token))
+                .fetch_one(&self.pool)
+                .await
+                .unwrap();
+                if ours {
+                    return Some(token.to_string());
+                }
+            }
+            None
+        }
+
+        async fn verify_magic_link(&self, token: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", "/portal/auth/magic-link/verify", "", Some(json!({"token": token}))).await
+        }
+
+        async fn magic_link_session(&self) -> String {
+            let token = self.send_magic_link("mae.lia@exemplo.com").await.unwrap();
+            let (status, session) = self.verify_magic_link(&token).await;
+            assert_eq!(status, StatusCode::OK);
+            session["token"].as_str().unwrap().to_string()
+        }
+
+        async fn change_password(&self, token: &str, body: Value) -> StatusCode {
+            call_json(&self.app, "PUT", "/portal/me/password", token, Some(body)).await.0
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn staff_grants_access_and_login_checks_password() {
+        let f = Fixture::new().await;
+        let access = f.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+        assert_eq!(access["email"], "mae.lia@exemplo.com");
+        assert_eq!(access["has_password"], true);
+
+        let (status, _) = f.login("senha-errada").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, session) = f.login(PASSWORD).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(session["token"].is_string());
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn guardian_sees_only_linked_children() {
+        let f = Fixture::new().await;
+        let guardian = f.password_session().await;
+
+        let (status, me) = call_json(&f.app, "GET", "/portal/me", &guardian, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(me["people"][0]["roles"], json!(["parent"]));
+        assert_eq!(me["children"].as_array().unwrap().len(), 1);
+        assert_eq!(me["children"][0]["name"], "Lia");
+        assert_eq!(me["children"][0]["parent_link"], true);
+
+        let report_path = |student_id: Uuid| format!("/portal/children/{student_id}/term-report?term_id={}", f.term_id);
+        let (status, report) = call_json(&f.app, "GET", &report_path(f.child_id), &guardian, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["student_name"], "Lia");
+        let (status, _) = call_json(&f.app, "GET", &report_path(f.other_id), &guardian, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, attendance) = call_json(&f.app, "GET", &format!("/portal/children/{}/attendance", f.child_id), &guardian, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(attendance["total_lessons"], 0);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn tokens_of_other_scopes_are_rejected() {
+        let f = Fixture::new().await;
+        let guardian = f.password_session().await;
+
+        let (status, _) = call_json(&f.app, "GET", "/portal/me", &f.owner, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call_json(&f.app, "GET", &f.access_path(), &guardian, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn magic_link_is_sent_only_to_known_guardians_and_works_once() {
+        let f = Fixture::new().await;
+        assert!(f.send_magic_link("ninguem@exemplo.com").await.is_none());
+        assert!(f.magic_link_bodies().await.is_empty());
+
+        let token = f.send_magic_link("mae.lia@exemplo.com").await.unwrap();
+        let (status, session) = f.verify_magic_link(&token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(session["token"].is_string());
+        let (status, _) = f.verify_magic_link(&token).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn magic_link_requests_are_rate_limited() {
+        let f = Fixture::new().await;
+        for _ in 0..MAGIC_LINK_MAX_PER_WINDOW + 2 {
+            f.request_magic_link("mae.lia@exemplo.com").await;
+        }
+
+        assert_eq!(f.magic_link_bodies().await.len() as i64, MAGIC_LINK_MAX_PER_WINDOW);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn magic_link_token_is_created_only_at_delivery() {
+        let f = Fixture::new().await;
+        {
+            let _queue = NOTIFICATION_DELIVERY.lock().await;
+            f.request_magic_link("mae.lia@exemplo.com").await;
+            let hashes: Vec<Option<String>> = sqlx::query_scalar(
+                "SELECT l.token_hash FROM guardian_portal_magic_links l
+                 JOIN guardian_portal_accounts a ON a.id = l.account_id WHERE a.tenant_id = $1",
+            )
+            .bind(f.tenant_id)
+            .fetch_all(&f.pool)
+            .await
+            .unwrap();
+            assert_eq!(hashes, vec![None]);
+        }
+        assert_eq!(f.magic_link_bodies().await, vec![MAGIC_LINK_QUEUED_BODY.to_string()]);
+
+        let token = f.send_magic_link("mae.lia@exemplo.com").await.unwrap();
+        assert!(f.magic_link_bodies().await.iter().all(|b| b == MAGIC_LINK_QUEUED_BODY));
+        assert_eq!(f.verify_magic_link(&token).await.0, StatusCode::OK);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn magic_link_session_is_short_lived() {
+        let f = Fixture::new().await;
+        let exp = |token: &str| {
+            decode::<Claims>(token, &DecodingKey::from_secret(SECRET.as_bytes()), &Validation::default())
+                .unwrap()
+                .claims
+                .exp as i64
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(exp(&f.password_session().await) > now + 24 * 3600);
+        assert!(exp(&f.magic_link_session().await) <= now + MAGIC_LINK_SESSION_MINUTES * 60 + 5);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn password_session_must_confirm_current_password() {
+        let f = Fixture::new().await;
+        let guardian = f.password_session().await;
+
+        assert_eq!(
+            f.change_password(&guardian, json!({"new_password": "outra-senha-2"})).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            f.change_password(&guardian, json!({"current_password": "senha-errada", "new_password": "outra-senha-2"})).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            f.change_password(&guardian, json!({"current_password": PASSWORD, "new_password": "outra-senha-2"})).await,
+            StatusCode::OK
+        );
+        assert_eq!(f.login("outra-senha-2").await.0, StatusCode::OK);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn magic_link_session_sets_password_without_current_one() {
+        let f = Fixture::new().await;
+        f.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+        let guardian = f.magic_link_session().await;
+
+        assert_eq!(
+            f.change_password(&guardian, json!({"new_password": "senha-nova-3"})).await,
+            StatusCode::OK
+        );
+        assert_eq!(f.login(PASSWORD).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(f.login("senha-nova-3").await.0, StatusCode::OK);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn deactivating_access_revokes_issued_tokens() {
+        let f = Fixture::new().await;
+        let guardian = f.password_session().await;
+
+        f.set_access(json!({"is_active": false})).await;
+        let (status, _) = call_json(&f.app, "GET", "/portal/me", &guardian, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        f.cleanup().await;
+    }
+}
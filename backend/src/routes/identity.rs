@@ -0,0 +1,543 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::identity::{check_identity_credentials, generate_totp_secret, totp_for, verify_totp};
+use crate::auth::jwt::{AuthUser, Claims, IdentityUser};
+use crate::routes::auth::{make_jwt, record_login, AuthResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct IdentityLoginRequest {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 1))]
+    pub password: String,
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MembershipResponse {
+    pub tenant_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub school_name: String,
+    pub school_code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdentityLoginResponse {
+    pub identity_id: Uuid,
+    pub identity_token: String,
+    pub memberships: Vec<MembershipResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectTenantRequest {
+    pub tenant_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetupResponse {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub totp_enabled: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/auth/identity/login", post(identity_login))
+        .route("/auth/identity/memberships", get(identity_memberships))
+        .route("/auth/select-tenant", post(select_tenant))
+        .route("/auth/memberships", get(my_memberships))
+        .route("/auth/switch-tenant", post(switch_tenant))
+        .route("/auth/2fa/setup", post(setup_two_factor))
+        .route("/auth/2fa/enable", post(enable_two_factor))
+        .route("/auth/2fa/disable", post(disable_two_factor))
+        .with_state(state)
+}
+
+/// Login sem código da escola: valida a identidade e devolve as escolas
+/// vinculadas para o seletor. O token devolvido só serve para escolher a escola.
+async fn identity_login(
+    State(state): State<AppState>,
+    Json(req): Json<IdentityLoginRequest>,
+) -> Result<Json<IdentityLoginResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT id AS identity_id, email, password_hash, totp_enabled, totp_secret
+        FROM identities
+        WHERE lower(email) = lower($1) AND tenant_id IS NULL
+        "#,
+    )
+    .bind(req.email.trim())
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
+
+    check_identity_credentials(&state.pool, &row, &req.password, req.totp_code.as_deref()).await?;
+
+    let identity_id: Uuid = row.get("identity_id");
+    let memberships = load_memberships(&state.pool, identity_id).await?;
+    if memberships.is_empty() {
+        return Err((StatusCode::FORBIDDEN, "Nenhuma escola vinculada a este usuário".into()));
+    }
+
+    let identity_token = make_identity_jwt(&state.jwt_secret, identity_id)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
+
+    Ok(Json(IdentityLoginResponse {
+        identity_id,
+        identity_token,
+        memberships,
+    }))
+}
+
+async fn identity_memberships(
+    State(state): State<AppState>,
+    identity: IdentityUser,
+) -> Result<Json<Vec<MembershipResponse>>, (StatusCode, String)> {
+    load_memberships(&state.pool, identity.identity_id).await.map(Json)
+}
+
+async fn select_tenant(
+    State(state): State<AppState>,
+    identity: IdentityUser,
+    Json(req): Json<SelectTenantRequest>,
+) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+    issue_tenant_token(&state, identity.identity_id, req.tenant_id).await.map(Json)
+}
+
+async fn my_memberships(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<Vec<MembershipResponse>>, (StatusCode, String)> {
+    let identity_id = identity_of(&state.pool, &user).await?;
+    load_memberships(&state.pool, identity_id).await.map(Json)
+}
+
+/// Troca de escola sem novo login: reemite o JWT para outro vínculo da mesma identidade.
+async fn switch_tenant(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<SelectTenantRequest>,
+) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+    let identity_id = identity_of(&state.pool, &user).await?;
+    issue_tenant_token(&state, identity_id, req.tenant_id).await.map(Json)
+}
+
+/// Gera (ou regenera) o segredo TOTP. Só passa a valer após `/auth/2fa/enable`.
+async fn setup_two_factor(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<TwoFactorSetupResponse>, (StatusCode, String)> {
+    let identity_id = identity_of(&state.pool, &user).await?;
+    let secret = generate_totp_secret();
+
+    let row = sqlx::query(
+        r#"
+        UPDATE identities
+        SET totp_secret = $2
+        WHERE id = $1 AND totp_enabled = FALSE
+        RETURNING email
+        "#,
+    )
+    .bind(identity_id)
+    .bind(&secret)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::CONFLICT, "2FA já está ativo".into()))?;
+
+    let email: String = row.get("email");
+    let otpauth_url = totp_for(&secret, &email)?.get_url();
+
+    Ok(Json(TwoFactorSetupResponse { secret, otpauth_url }))
+}
+
+async fn enable_two_factor(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> Result<Json<TwoFactorStatusResponse>, (StatusCode, String)> {
+    let identity_id = identity_of(&state.pool, &user).await?;
+    let (email, secret, _) = load_two_factor(&state.pool, identity_id).await?;
+    let secret = secret.ok_or((StatusCode::BAD_REQUEST, "Gere o segredo 2FA antes de ativar".into()))?;
+
+    if !verify_totp(&secret, &email, &req.code)? {
+        return Err((StatusCode::BAD_REQUEST, "Código 2FA inválido".into()));
+    }
+
+    set_two_factor_enabled(&state.pool, identity_id, true).await
+}
+
+async fn disable_two_factor(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> Result<Json<TwoFactorStatusResponse>, (StatusCode, String)> {
+    let identity_id = identity_of(&state.pool, &user).await?;
+    let (email, secret, enabled) = load_two_factor(&state.pool, identity_id).await?;
+    if !enabled {
+        return Err((StatusCode::BAD_REQUEST, "2FA não está ativo".into()));
+    }
+    let secret = secret.ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Segredo 2FA ausente".into()))?;
+
+    if !verify_totp(&secret, &email, &req.code)? {
+        return Err((StatusCode::BAD_REQUEST, "Código 2FA inválido".into()));
+    }
+
+    set_two_factor_enabled(&state.pool, identity_id, false).await
+}
+
+async fn issue_tenant_token(
+    state: &AppState,
+    identity_id: Uuid,
+    tenant_id: Uuid,
+) -> Result<AuthResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT u.id, u.role, t.name AS school_name, t.slug AS school_code
+        FROM users u
+        JOIN tenants t ON t.id = u.tenant_id
+        WHERE u.identity_id = $1 AND u.tenant_id = $2
+        "#,
+    )
+    .bind(identity_id)
+    .bind(tenant_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::FORBIDDEN, "Usuário não vinculado a esta escola".into()))?;
+
+    let user_id: Uuid = row.get("id");
+    let role: String = row.get("role");
+    let token = make_jwt(&state.jwt_secret, user_id, tenant_id, &role)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
+
+    record_login(&state.pool, tenant_id, user_id).await;
+
+    Ok(AuthResponse {
+        tenant_id,
+        user_id,
+        token,
+        school_name: row.get("school_name"),
+        school_code: row.get("school_code"),
+    })
+}
+
+async fn load_memberships(pool: &PgPool, identity_id: Uuid) -> Result<Vec<MembershipResponse>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT u.tenant_id, u.id AS user_id, u.role, t.name AS school_name, t.slug AS school_code
+        FROM users u
+        JOIN tenants t ON t.id = u.tenant_id
+        WHERE u.identity_id = $1
+        ORDER BY t.name ASC
+        "#,
+    )
+    .bind(identity_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| MembershipResponse {
+            tenant_id: r.get("tenant_id"),
+            user_id: r.get("user_id"),
+            role: r.get("role"),
+            school_name: r.get("school_name"),
+            school_code: r.get("school_code"),
+        })
+        .collect())
+}
+
+/// Identidade do usuário logado. Sessão aberta pelo SSO da escola não chega a ela: o
+/// provedor autentica só para aquela escola.
+async fn identity_of(pool: &PgPool, user: &AuthUser) -> Result<Uuid, (StatusCode, String)> {
+    if user.sso {
+        return Err((StatusCode::FORBIDDEN, "Sessão aberta pelo SSO vale só para esta escola".into()));
+    }
+    sqlx::query_scalar(r#"SELECT identity_id FROM users WHERE id = $1 AND tenant_id = $2"#)
+        .bind(user.user_id)
+        .bind(user.tenant_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::UNAUTHORIZED, "Usuário não encontrado".into()))
+}
+
+async fn load_two_factor(
+    pool: &PgPool,
+    identity_id: Uuid,
+) -> Result<(String, Option<String>, bool), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT email, totp_secret, totp_enabled FROM identities WHERE id = $1"#)
+        .bind(identity_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok((row.get("email"), row.get("totp_secret"), row.get("totp_enabled")))
+}
+
+async fn set_two_factor_enabled(
+    pool: &PgPool,
+    identity_id: Uuid,
+    enabled: bool,
+) -> Result<Json<TwoFactorStatusResponse>, (StatusCode, String)> {
+    sqlx::query(
+        r#"
+        UPDATE identities
+        SET totp_enabled = $2,
+            totp_secret = CASE WHEN $2 THEN totp_secret ELSE NULL END
+        WHERE id = $1
+        "#,
+    )
+    .bind(identity_id)
+    .bind(enabled)
+    .execute(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(TwoFactorStatusResponse { totp_enabled: enabled }))
+}
+
+fn make_identity_jwt(jwt_secret: &str, identity_id: Uuid) -> Result<String, ()> {
+    // curto: só cobre o intervalo entre o login e a escolha da escola
+    let exp = (chrono::Utc::now() + chrono::Duration::minutes(15)).timestamp() as usize;
+
+    let claims = Claims {
+        sub: identity_id.to_string(),
+        tenant_id: None,
+        role: "identity".to_string(),
+        scope: "identity".to_string(),
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_bytes()),
+    )
+    .map_err(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity::hash_password;
+    use crate::test_support::{call_json, insert_tenant, insert_user, make_token, test_pool, SECRET};
+    use serde_json::{json, Value};
+
+    fn app(pool: &PgPool) -> Router {
+        crate::routes::auth::routes(pool.clone(), SECRET.to_string())
+            .merge(routes(pool.clone(), SECRET.to_string()))
+            .merge(crate::routes::teachers::routes(pool.clone(), SECRET.to_string()))
+    }
+
+    async fn register(app: &Router, school_code: &str, email: &str, password: &str) -> (StatusCode, Value) {
+        call_json(
+            app,
+            "POST",
+            "/auth/register",
+            "",
+            Some(json!({"school_name": "Escola", "school_code": school_code, "email": email, "password": password})),
+        )
+        .await
+    }
+
+    async fn cleanup(pool: &PgPool, tenants: &[Uuid], email: &str) {
+        for tenant_id in tenants {
+            sqlx::query("DELETE FROM tenants WHERE id = $1").bind(tenant_id).execute(pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM identities WHERE lower(email) = lower($1)")
+            .bind(email)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    fn tenant_of(body: &Value) -> Uuid {
+        Uuid::parse_str(body["tenant_id"].as_str().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn register_in_second_school_requires_existing_password() {
+        let pool = test_pool().await;
+        let app = app(&pool);
+        let suffix = Uuid::new_v4().simple().to_string();
+        let email = format!("dona-{suffix}@example.com");
+
+        let (status, first) = register(&app, &format!("escola-a-{}", &suffix[..8]), &email, "senha-forte-1").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let slug_b = format!("escola-b-{}", &suffix[..8]);
+        let (status, _) = register(&app, &slug_b, &email, "outra-senha-2").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, second) = register(&app, &slug_b, &email, "senha-forte-1").await;
+        assert_eq!(status, StatusCode::OK);
+
+        cleanup(&pool, &[tenant_of(&first), tenant_of(&second)], &email).await;
+    }
+
+    #[tokio::test]
+    async fn identity_login_lists_schools_and_switches_tenant() {
+        let pool = test_pool().await;
+        let app = app(&pool);
+        let suffix = Uuid::new_v4().simple().to_string();
+        let email = format!("dona-{suffix}@example.com");
+        let slug_a = format!("escola-a-{}", &suffix[..8]);
+        let (_, first) = register(&app, &slug_a, &email, "senha-forte-1").await;
+        let (_, second) = register(&app, &format!("escola-b-{}", &suffix[..8]), &email, "senha-forte-1").await;
+
+        let (status, login) = call_json(
+            &app,
+            "POST",
+            "/auth/identity/login",
+            "",
+            Some(json!({"email": email, "password": "senha-forte-1"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(login["memberships"].as_array().unwrap().len(), 2);
+
+        // o token de identidade não acessa rotas da escola, só o seletor
+        let identity_token = login["identity_token"].as_str().unwrap();
+        let (status, _) = call_json(&app, "GET", "/teachers", identity_token, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, selected) = call_json(
+            &app,
+            "POST",
+            "/auth/select-tenant",
+            identity_token,
+            Some(json!({"tenant_id": first["tenant_id"]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(selected["school_code"], slug_a.as_str());
+
+        let (status, switched) = call_json(
+            &app,
+            "POST",
+            "/auth/switch-tenant",
+            selected["token"].as_str().unwrap(),
+            Some(json!({"tenant_id": second["tenant_id"]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(switched["tenant_id"], second["tenant_id"]);
+
+        cleanup(&pool, &[tenant_of(&first), tenant_of(&second)], &email).await;
+    }
+
+    #[tokio::test]
+    async fn switch_tenant_refuses_school_without_membership() {
+        let pool = test_pool().await;
+        let app = app(&pool);
+        let suffix = Uuid::new_v4().simple().to_string();
+        let email = format!("dona-{suffix}@example.com");
+        let (_, first) = register(&app, &format!("escola-a-{}", &suffix[..8]), &email, "senha-forte-1").await;
+        let other_tenant = insert_tenant(&pool, "Escola alheia").await;
+
+        let (status, _) = call_json(
+            &app,
+            "POST",
+            "/auth/switch-tenant",
+            first["token"].as_str().unwrap(),
+            Some(json!({"tenant_id": other_tenant})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        cleanup(&pool, &[tenant_of(&first), other_tenant], &email).await;
+    }
+
+    #[tokio::test]
+    async fn creating_teacher_with_existing_email_requires_its_password() {
+        let pool = test_pool().await;
+        let app = app(&pool);
+        let suffix = Uuid::new_v4().simple().to_string();
+        let email = format!("prof-{suffix}@example.com");
+        let (_, first) = register(&app, &format!("escola-a-{}", &suffix[..8]), &email, "senha-forte-1").await;
+
+        let tenant_b = insert_tenant(&pool, "Escola B").await;
+        let admin_b = insert_user(&pool, tenant_b, "Admin B", "admin").await;
+        let token = make_token(tenant_b, admin_b, "admin");
+        let teacher = |password: &str| {
+            json!({"full_name": "Prof. Ana", "email": email, "password": password, "role": "teacher"})
+        };
+
+        // a escola B não pode assumir a conta de quem já tem identidade
+        let (status, _) = call_json(&app, "POST", "/teachers", &token, Some(teacher("senha-da-escola-b"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = call_json(&app, "POST", "/teachers", &token, Some(teacher("senha-forte-1"))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        cleanup(&pool, &[tenant_of(&first), tenant_b], &email).await;
+    }
+
+    #[tokio::test]
+    async fn legacy_link_password_is_accepted_once_and_moved_to_identity() {
+        let pool = test_pool().await;
+        let app = app(&pool);
+        let suffix = Uuid::new_v4().simple().to_string();
+        let email = format!("dona-{suffix}@example.com");
+        let slug = format!("escola-a-{}", &suffix[..8]);
+        let (_, first) = register(&app, &slug, &email, "senha-forte-1").await;
+
+        // vínculo anterior às identidades: o hash antigo continua em users.password_hash
+        sqlx::query("UPDATE users SET password_hash = $2 WHERE tenant_id = $1")
+            .bind(tenant_of(&first))
+            .bind(hash_password("senha-antiga-9").unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let login = |password: &'static str| {
+            json!({"school_code": slug, "email": email, "password": password})
+        };
+        let (status, _) = call_json(&app, "POST", "/auth/login", "", Some(login("senha-antiga-9"))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let legacy: Option<String> = sqlx::query_scalar("SELECT password_hash FROM users WHERE tenant_id = $1")
+            .bind(tenant_of(&first))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(legacy.is_none());
+
+        // a senha usada virou a da identidade
+        let (status, _) = call_json(&app, "POST", "/auth/login", "", Some(login("senha-antiga-9"))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call_json(&app, "POST", "/auth/login", "", Some(login("senha-forte-1"))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        cleanup(&pool, &[tenant_of(&first)], &email).await;
+    }
+}
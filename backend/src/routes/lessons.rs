@@ -0,0 +1,1172 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::grading::history::set_change_author;
+use crate::grading::promotion::presence_percent;
+use crate::grading::term_lock::{
+    closed_term_for_date, ensure_writable, CORRECTION_ATTENDANCE, CORRECTION_LESSON,
+};
+use crate::routes::guardian_alerts::alert_absences;
+use crate::routes::records::AttendanceRecordInput;
+use crate::state::AppState;
+
+pub const MAX_LESSONS_PER_DAY: i32 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct LessonsQuery {
+    pub subject_id: Option<Uuid>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateLessonRequest {
+    pub subject_id: Uuid,
+    pub lesson_date: NaiveDate,
+    /// Aula do dia (1ª, 2ª...). Padrão: 1.
+    pub lesson_number: Option<i32>,
+    /// Padrão: o próprio professor que registra a aula.
+    pub teacher_user_id: Option<Uuid>,
+    pub content: Option<String>,
+    pub homework: Option<String>,
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateLessonRequest {
+    pub teacher_user_id: Option<Uuid>,
+    pub content: Option<String>,
+    pub homework: Option<String>,
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteLessonQuery {
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LessonResponse {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub lesson_date: NaiveDate,
+    pub lesson_number: i32,
+    pub teacher_user_id: Option<Uuid>,
+    pub teacher_name: Option<String>,
+    pub content: Option<String>,
+    pub homework: Option<String>,
+    pub attendance_taken: bool,
+    pub absences: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LessonAttendanceItem {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    /// `None` enquanto a chamada não foi feita.
+    pub present: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LessonAttendanceResponse {
+    pub lesson: LessonResponse,
+    pub items: Vec<LessonAttendanceItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertLessonAttendanceRequest {
+    pub records: Vec<AttendanceRecordInput>,
+    /// Obrigatória (e só para owner/admin) quando a aula cai num período encerrado.
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiaryQuery {
+    pub subject_id: Uuid,
+    pub term_id: Uuid,
+    /// `json` (padrão) ou `csv`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiaryLesson {
+    pub lesson_id: Option<Uuid>,
+    pub lesson_date: NaiveDate,
+    pub lesson_number: i32,
+    pub teacher_name: Option<String>,
+    pub content: Option<String>,
+    pub homework: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiaryStudent {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    /// Uma posição por aula de `lessons`: presente, falta ou sem registro.
+    pub marks: Vec<Option<bool>>,
+    /// Faltas que contam na frequência, já aplicada a regra de faltas justificadas.
+    pub absences: i32,
+    pub justified_absences: i32,
+    pub attendance_percent: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiaryResponse {
+    pub school_name: String,
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub class_grade: String,
+    pub class_year: i32,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub teachers: Vec<String>,
+    pub total_lessons: i32,
+    pub lessons: Vec<DiaryLesson>,
+    pub students: Vec<DiaryStudent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/classes/:class_id/lessons", get(list_lessons).post(create_lesson))
+        .route(
+            "/classes/:class_id/lessons/:lesson_id",
+            get(get_lesson).put(update_lesson).delete(delete_lesson),
+        )
+        .route(
+            "/classes/:class_id/lessons/:lesson_id/attendance",
+            get(get_lesson_attendance).put(upsert_lesson_attendance),
+        )
+        .route("/classes/:class_id/diary", get(get_class_diary))
+        .with_state(state)
+}
+
+const LESSON_SELECT: &str = r#"
+    SELECT l.id, l.class_id, l.subject_id, s.name AS subject_name, l.lesson_date, l.lesson_number,
+           l.teacher_user_id, COALESCE(u.full_name, u.email) AS teacher_name, l.content, l.homework,
+           (SELECT COUNT(*) FROM student_attendance a WHERE a.lesson_id = l.id) AS attendance_count,
+           (SELECT COUNT(*) FROM student_attendance a WHERE a.lesson_id = l.id AND NOT a.present) AS absences
+    FROM lessons l
+    JOIN subjects s ON s.id = l.subject_id AND s.tenant_id = l.tenant_id
+    LEFT JOIN users u ON u.id = l.teacher_user_id AND u.tenant_id = l.tenant_id
+"#;
+
+async fn list_lessons(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<LessonsQuery>,
+) -> Result<Json<Vec<LessonResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, query.subject_id).await?;
+
+    // professor sem disciplina no filtro vê só as aulas das disciplinas dele
+    let rows = sqlx::query(&format!(
+        r#"{LESSON_SELECT}
+        WHERE l.tenant_id = $1 AND l.class_id = $2
+          AND ($3::uuid IS NULL OR l.subject_id = $3)
+          AND ($4::date IS NULL OR l.lesson_date >= $4)
+          AND ($5::date IS NULL OR l.lesson_date <= $5)
+          AND ($6::uuid IS NULL OR EXISTS (
+            SELECT 1 FROM teaching_assignments ta
+            WHERE ta.tenant_id = l.tenant_id AND ta.class_id = l.class_id
+              AND ta.subject_id = l.subject_id AND ta.teacher_user_id = $6
+          ))
+        ORDER BY l.lesson_date DESC, l.lesson_number ASC, s.name ASC"#
+    ))
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.subject_id)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(restricted_teacher(&user))
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(map_lesson_row).collect()))
+}
+
+async fn get_lesson(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, lesson_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<LessonResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    let lesson = fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(lesson.subject_id)).await?;
+    Ok(Json(lesson))
+}
+
+async fn create_lesson(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<CreateLessonRequest>,
+) -> Result<Json<LessonResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, req.subject_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+    let lesson_number = validate_lesson_number(req.lesson_number)?;
+    let teacher_user_id = resolve_teacher(&state.pool, &user, req.teacher_user_id).await?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let closed_term = closed_term_for_date(&mut tx, user.tenant_id, req.lesson_date).await?;
+    ensure_writable(
+        &mut tx,
+        &user,
+        closed_term,
+        class_id,
+        CORRECTION_LESSON,
+        req.correction_justification.as_deref(),
+        serde_json::json!({ "action": "create", "lesson": &req }),
+    )
+    .await?;
+
+    let id = Uuid::new_v4();
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO lessons
+          (id, tenant_id, class_id, subject_id, lesson_date, lesson_number, teacher_user_id, content, homework)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (tenant_id, class_id, subject_id, lesson_date, lesson_number) DO NOTHING
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(req.subject_id)
+    .bind(req.lesson_date)
+    .bind(lesson_number)
+    .bind(teacher_user_id)
+    .bind(normalize_optional_text(req.content.clone()))
+    .bind(normalize_optional_text(req.homework.clone()))
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if inserted.rows_affected() == 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Já existe a {lesson_number}ª aula desta disciplina nesta data"),
+        ));
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    fetch_lesson(&state.pool, user.tenant_id, class_id, id).await.map(Json)
+}
+
+async fn update_lesson(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, lesson_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateLessonRequest>,
+) -> Result<Json<LessonResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    let lesson = fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(lesson.subject_id)).await?;
+    let teacher_user_id = match req.teacher_user_id {
+        Some(_) => resolve_teacher(&state.pool, &user, req.teacher_user_id).await?,
+        None => lesson.teacher_user_id,
+    };
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let closed_term = closed_term_for_date(&mut tx, user.tenant_id, lesson.lesson_date).await?;
+    ensure_writable(
+        &mut tx,
+        &user,
+        closed_term,
+        class_id,
+        CORRECTION_LESSON,
+        req.correction_justification.as_deref(),
+        serde_json::json!({ "action": "update", "lesson_id": lesson_id, "lesson": &req }),
+    )
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE lessons
+        SET teacher_user_id = $3, content = $4, homework = $5, updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(lesson_id)
+    .bind(teacher_user_id)
+    .bind(normalize_optional_text(req.content.clone()))
+    .bind(normalize_optional_text(req.homework.clone()))
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await.map(Json)
+}
+
+/// Remove a aula e a chamada dela.
+async fn delete_lesson(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, lesson_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<DeleteLessonQuery>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    let lesson = fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(lesson.subject_id)).await?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let closed_term = closed_term_for_date(&mut tx, user.tenant_id, lesson.lesson_date).await?;
+    ensure_writable(
+        &mut tx,
+        &user,
+        closed_term,
+        class_id,
+        CORRECTION_LESSON,
+        query.correction_justification.as_deref(),
+        serde_json::json!({ "action": "delete", "lesson": &lesson }),
+    )
+    .await?;
+
+    sqlx::query("DELETE FROM lessons WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(lesson_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn get_lesson_attendance(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, lesson_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<LessonAttendanceResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    let lesson = fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(lesson.subject_id)).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT s.id AS student_id, s.name AS student_name, s.registration, a.present, a.notes
+        FROM students s
+        LEFT JOIN student_attendance a
+          ON a.tenant_id = s.tenant_id AND a.student_id = s.id AND a.lesson_id = $3
+        WHERE s.tenant_id = $1 AND s.class_id = $2
+        ORDER BY s.name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(lesson_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let items = rows
+        .into_iter()
+        .map(|r| LessonAttendanceItem {
+            student_id: r.get("student_id"),
+            student_name: r.get("student_name"),
+            registration: r.get("registration"),
+            present: r.get("present"),
+            notes: r.get("notes"),
+        })
+        .collect();
+
+    Ok(Json(LessonAttendanceResponse { lesson, items }))
+}
+
+async fn upsert_lesson_attendance(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, lesson_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpsertLessonAttendanceRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    let lesson = fetch_lesson(&state.pool, user.tenant_id, class_id, lesson_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(lesson.subject_id)).await?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    save_lesson_attendance(
+        &mut tx,
+        &user,
+        class_id,
+        &LessonKey {
+            lesson_id,
+            subject_id: lesson.subject_id,
+            lesson_date: lesson.lesson_date,
+            lesson_number: lesson.lesson_number,
+        },
+        &req.records,
+        req.correction_justification.as_deref(),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Diário oficial da turma na disciplina e período: aulas (conteúdo e tarefa) e a
+/// grade de presenças de cada aluno, a partir de `student_attendance_effective`.
+async fn get_class_diary(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<DiaryQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+    let format = query.format.as_deref().unwrap_or("json").trim().to_lowercase();
+    if format != "json" && format != "csv" {
+        return Err((StatusCode::BAD_REQUEST, "Formato inválido (use json ou csv)".into()));
+    }
+
+    let header_row = sqlx::query(
+        r#"
+        SELECT t.name AS school_name, c.name AS class_name, c.grade, c.year,
+               s.name AS subject_name, p.name AS term_name, p.start_date, p.end_date
+        FROM classes c
+        JOIN tenants t ON t.id = c.tenant_id
+        JOIN subjects s ON s.tenant_id = c.tenant_id AND s.id = $3
+        JOIN academic_terms p ON p.tenant_id = c.tenant_id AND p.id = $4
+        WHERE c.tenant_id = $1 AND c.id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.subject_id)
+    .bind(query.term_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Disciplina ou período não encontrado".into()))?;
+
+    let (Some(start_date), Some(end_date)) = (
+        header_row.get::<Option<NaiveDate>, _>("start_date"),
+        header_row.get::<Option<NaiveDate>, _>("end_date"),
+    ) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Defina as datas do período para emitir o diário".into(),
+        ));
+    };
+
+    let lesson_rows = sqlx::query(
+        r#"
+        SELECT k.lesson_date, k.lesson_number, l.id AS lesson_id, l.content, l.homework,
+               COALESCE(u.full_name, u.email) AS teacher_name
+        FROM (
+          SELECT attendance_date AS lesson_date, lesson_number
+          FROM student_attendance
+          WHERE tenant_id = $1 AND class_id = $2 AND subject_id = $3
+            AND attendance_date BETWEEN $4 AND $5
+          UNION
+          SELECT lesson_date, lesson_number
+          FROM lessons
+          WHERE tenant_id = $1 AND class_id = $2 AND subject_id = $3
+            AND lesson_date BETWEEN $4 AND $5
+        ) k
+        LEFT JOIN lessons l
+          ON l.tenant_id = $1 AND l.class_id = $2 AND l.subject_id = $3
+         AND l.lesson_date = k.lesson_date AND l.lesson_number = k.lesson_number
+        LEFT JOIN users u ON u.id = l.teacher_user_id AND u.tenant_id = l.tenant_id
+        ORDER BY k.lesson_date ASC, k.lesson_number ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.subject_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let lessons: Vec<DiaryLesson> = lesson_rows
+        .into_iter()
+        .map(|r| DiaryLesson {
+            lesson_id: r.get("lesson_id"),
+            lesson_date: r.get("lesson_date"),
+            lesson_number: r.get("lesson_number"),
+            teacher_name: r.get("teacher_name"),
+            content: r.get("content"),
+            homework: r.get("homework"),
+        })
+        .collect();
+    let column_of: HashMap<(NaiveDate, i32), usize> = lessons
+        .iter()
+        .enumerate()
+        .map(|(i, l)| ((l.lesson_date, l.lesson_number), i))
+        .collect();
+
+    // alunos atuais da turma e quem teve chamada nela no período (transferidos)
+    let mark_rows = sqlx::query(
+        r#"
+        SELECT st.id AS student_id, st.name AS student_name, st.registration,
+               a.attendance_date, a.lesson_number, a.recorded_present, a.present,
+               a.justified, a.counted
+        FROM students st
+        LEFT JOIN student_attendance_effective a
+          ON a.tenant_id = st.tenant_id AND a.student_id = st.id
+         AND a.class_id = $2 AND a.subject_id = $3
+         AND a.attendance_date BETWEEN $4 AND $5
+        WHERE st.tenant_id = $1
+          AND (st.class_id = $2 OR a.id IS NOT NULL)
+        ORDER BY st.name ASC, st.id
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.subject_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut students: Vec<DiaryStudent> = Vec::new();
+    // (total, presentes) que entram no cálculo, por aluno
+    let mut counted: Vec<(i32, i32)> = Vec::new();
+    for r in mark_rows {
+        let student_id: Uuid = r.get("student_id");
+        if students.last().map(|s| s.student_id) != Some(student_id) {
+            students.push(DiaryStudent {
+                student_id,
+                student_name: r.get("student_name"),
+                registration: r.get("registration"),
+                marks: vec![None; lessons.len()],
+                absences: 0,
+                justified_absences: 0,
+                attendance_percent: None,
+            });
+            counted.push((0, 0));
+        }
+        let (Some(date), Some(number)) = (
+            r.get::<Option<NaiveDate>, _>("attendance_date"),
+            r.get::<Option<i32>, _>("lesson_number"),
+        ) else {
+            continue;
+        };
+        let (Some(&col), Some(student), Some(totals)) =
+            (column_of.get(&(date, number)), students.last_mut(), counted.last_mut())
+        else {
+            continue;
+        };
+        student.marks[col] = r.get("recorded_present");
+        if r.get::<bool, _>("justified") {
+            student.justified_absences += 1;
+        }
+        if r.get::<bool, _>("counted") {
+            totals.0 += 1;
+            if r.get::<bool, _>("present") {
+                totals.1 += 1;
+            }
+        }
+    }
+    for (student, (total, present)) in students.iter_mut().zip(counted) {
+        student.absences = total - present;
+        student.attendance_percent = presence_percent(total, present);
+    }
+
+    let mut teachers: Vec<String> = lessons.iter().filter_map(|l| l.teacher_name.clone()).collect();
+    teachers.sort();
+    teachers.dedup();
+
+    let diary = DiaryResponse {
+        school_name: header_row.get("school_name"),
+        class_id,
+        class_name: header_row.get("class_name"),
+        class_grade: header_row.get("grade"),
+        class_year: header_row.get("year"),
+        subject_id: query.subject_id,
+        subject_name: header_row.get("subject_name"),
+        term_id: query.term_id,
+        term_name: header_row.get("term_name"),
+        start_date,
+        end_date,
+        teachers,
+        total_lessons: lessons.len() as i32,
+        lessons,
+        students,
+    };
+
+    if format == "csv" {
+        let filename = format!(
+            "diario-{}-{}-{}.csv",
+            slugify(&diary.class_name),
+            slugify(&diary.subject_name),
+            slugify(&diary.term_name)
+        );
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{filename}\"")),
+            ],
+            diary_csv(&diary),
+        )
+            .into_response());
+    }
+    Ok(Json(diary).into_response())
+}
+
+pub struct LessonKey {
+    pub lesson_id: Uuid,
+    pub subject_id: Uuid,
+    pub lesson_date: NaiveDate,
+    pub lesson_number: i32,
+}
+
+/// Aula da turma/disciplina/data/número, criada na hora se a chamada chegar antes do registro da aula.
+pub async fn ensure_lesson(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    class_id: Uuid,
+    subject_id: Uuid,
+    lesson_date: NaiveDate,
+    lesson_number: i32,
+) -> Result<LessonKey, (StatusCode, String)> {
+    let lesson_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO lessons (id, tenant_id, class_id, subject_id, lesson_date, lesson_number, teacher_user_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (tenant_id, class_id, subject_id, lesson_date, lesson_number)
+        DO UPDATE SET teacher_user_id = COALESCE(lessons.teacher_user_id, EXCLUDED.teacher_user_id)
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(subject_id)
+    .bind(lesson_date)
+    .bind(lesson_number)
+    .bind(restricted_teacher(user))
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(LessonKey {
+        lesson_id,
+        subject_id,
+        lesson_date,
+        lesson_number,
+    })
+}
+
+/// Grava a chamada de uma aula (respeitando o encerramento do período).
+pub async fn save_lesson_attendance(
+    tx: &mut Transaction<'_, Postgres>,
+    user: &AuthUser,
+    class_id: Uuid,
+    lesson: &LessonKey,
+    records: &[AttendanceRecordInput],
+    correction_justification: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    let closed_term = closed_term_for_date(tx, user.tenant_id, lesson.lesson_date).await?;
+    ensure_writable(
+        tx,
+        user,
+        closed_term,
+        class_id,
+        CORRECTION_ATTENDANCE,
+        correction_justification,
+        serde_json::json!({
+            "date": lesson.lesson_date,
+            "lesson_number": lesson.lesson_number,
+            "subject_id": lesson.subject_id,
+            "records": records
+        }),
+    )
+    .await?;
+
+    for r in records {
+        ensure_student_belongs_to_class(tx, user.tenant_id, class_id, r.student_id).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO student_attendance
+              (id, tenant_id, class_id, student_id, attendance_date, subject_id, lesson_number, lesson_id, present, notes)
+            VALUES
+              ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (tenant_id, class_id, student_id, attendance_date, subject_id, lesson_number)
+            DO UPDATE SET present = EXCLUDED.present, notes = EXCLUDED.notes, lesson_id = EXCLUDED.lesson_id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user.tenant_id)
+        .bind(class_id)
+        .bind(r.student_id)
+        .bind(lesson.lesson_date)
+        .bind(lesson.subject_id)
+        .bind(lesson.lesson_number)
+        .bind(lesson.lesson_id)
+        .bind(r.present)
+        .bind(normalize_optional_text(r.notes.clone()))
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    let absent: Vec<Uuid> = records.iter().filter(|r| !r.present).map(|r| r.student_id).collect();
+    alert_absences(tx, user.tenant_id, class_id, lesson, &absent).await?;
+    Ok(())
+}
+
+pub fn validate_lesson_number(lesson_number: Option<i32>) -> Result<i32, (StatusCode, String)> {
+    let n = lesson_number.unwrap_or(1);
+    if !(1..=MAX_LESSONS_PER_DAY).contains(&n) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Número da aula deve estar entre 1 e {MAX_LESSONS_PER_DAY}"),
+        ));
+    }
+    Ok(n)
+}
+
+/// Professor responsável: o próprio usuário quando é professor; owner/admin podem indicar outro.
+async fn resolve_teacher(
+    pool: &PgPool,
+    user: &AuthUser,
+    teacher_user_id: Option<Uuid>,
+) -> Result<Option<Uuid>, (StatusCode, String)> {
+    if let Some(own_id) = restricted_teacher(user) {
+        if teacher_user_id.is_some_and(|id| id != own_id) {
+            return Err((StatusCode::FORBIDDEN, "Professor só registra as próprias aulas".into()));
+        }
+        return Ok(Some(own_id));
+    }
+    let Some(teacher_user_id) = teacher_user_id else {
+        return Ok(None);
+    };
+
+    let row: Option<i32> = sqlx::query_scalar(
+        r#"SELECT 1 FROM users
+           WHERE tenant_id = $1 AND id = $2 AND role IN ('owner', 'admin', 'teacher')"#,
+    )
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Professor inválido para este tenant".into()));
+    }
+    Ok(Some(teacher_user_id))
+}
+
+async fn fetch_lesson(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    lesson_id: Uuid,
+) -> Result<LessonResponse, (StatusCode, String)> {
+    let row = sqlx::query(&format!(
+        "{LESSON_SELECT} WHERE l.tenant_id = $1 AND l.class_id = $2 AND l.id = $3"
+    ))
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(lesson_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Aula não encontrada".into()))?;
+
+    Ok(map_lesson_row(&row))
+}
+
+fn map_lesson_row(r: &sqlx::postgres::PgRow) -> LessonResponse {
+    let attendance_count: i64 = r.get("attendance_count");
+    LessonResponse {
+        id: r.get("id"),
+        class_id: r.get("class_id"),
+        subject_id: r.get("subject_id"),
+        subject_name: r.get("subject_name"),
+        lesson_date: r.get("lesson_date"),
+        lesson_number: r.get("lesson_number"),
+        teacher_user_id: r.get("teacher_user_id"),
+        teacher_name: r.get("teacher_name"),
+        content: r.get("content"),
+        homework: r.get("homework"),
+        attendance_taken: attendance_count > 0,
+        absences: r.get("absences"),
+    }
+}
+
+/// CSV (separado por `;`, como o Excel em pt-BR espera): cabeçalho, grade de presenças e conteúdos.
+fn diary_csv(diary: &DiaryResponse) -> String {
+    let mut out = String::new();
+    let line = |fields: Vec<String>| -> String {
+        let mut l = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(";");
+        l.push('\n');
+        l
+    };
+
+    out.push_str(&line(vec!["Escola".into(), diary.school_name.clone()]));
+    out.push_str(&line(vec![
+        "Turma".into(),
+        format!("{} - {} ({})", diary.class_name, diary.class_grade, diary.class_year),
+    ]));
+    out.push_str(&line(vec!["Disciplina".into(), diary.subject_name.clone()]));
+    out.push_str(&line(vec![
+        "Período".into(),
+        format!(
+            "{} ({} a {})",
+            diary.term_name,
+            diary.start_date.format("%d/%m/%Y"),
+            diary.end_date.format("%d/%m/%Y")
+        ),
+    ]));
+    out.push_str(&line(vec!["Professor(es)".into(), diary.teachers.join(", ")]));
+    out.push('\n');
+
+    let mut header = vec!["Aluno".to_string(), "Matrícula".to_string()];
+    header.extend(
+        diary
+            .lessons
+            .iter()
+            .map(|l| format!("{} ({}ª)", l.lesson_date.format("%d/%m"), l.lesson_number)),
+    );
+    header.push("Faltas".into());
+    header.push("Faltas justificadas".into());
+    header.push("Frequência (%)".into());
+    out.push_str(&line(header));
+    for s in &diary.students {
+        let mut fields = vec![s.student_name.clone(), s.registration.clone()];
+        fields.extend(s.marks.iter().map(|m| match m {
+            Some(true) => "P".to_string(),
+            Some(false) => "F".to_string(),
+            None => String::new(),
+        }));
+        fields.push(s.absences.to_string());
+        fields.push(s.justified_absences.to_string());
+        fields.push(
+            s.attendance_percent
+                .map(|p| format!("{p:.1}").replace('.', ","))
+                .unwrap_or_default(),
+        );
+        out.push_str(&line(fields));
+    }
+    out.push('\n');
+
+    out.push_str(&line(vec![
+        "Data".into(),
+        "Aula".into(),
+        "Professor".into(),
+        "Conteúdo ministrado".into(),
+        "Tarefa".into(),
+    ]));
+    for l in &diary.lessons {
+        out.push_str(&line(vec![
+            l.lesson_date.format("%d/%m/%Y").to_string(),
+            l.lesson_number.to_string(),
+            l.teacher_name.clone().unwrap_or_default(),
+            l.content.clone().unwrap_or_default(),
+            l.homework.clone().unwrap_or_default(),
+        ]));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn slugify(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM classes WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Turma não encontrada".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_subject_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(subject_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()));
+    }
+    Ok(())
+}
+
+async fn ensure_student_belongs_to_class(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    student_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"SELECT 1
+           FROM students
+           WHERE tenant_id = $1 AND id = $2 AND class_id = $3"#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .bind(class_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Aluno não pertence a esta turma".into()));
+    }
+    Ok(())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call, call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 5A com um aluno, Geografia e o 1º bimestre (fev-abr/2026).
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Diário").await;
+            let class_id = insert_class(&pool, tenant_id, "5A", "5 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            sqlx::query("UPDATE academic_terms SET start_date = '2026-02-01', end_date = '2026-04-30' WHERE id = $1")
+                .bind(term_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let subject_id = insert_subject(&pool, tenant_id, "Geografia").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Heitor", "H1").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, token, tenant_id, class_id, term_id, subject_id, student_id }
+        }
+
+        fn lessons_path(&self) -> String {
+            format!("/classes/{}/lessons", self.class_id)
+        }
+
+        fn diary_path(&self) -> String {
+            format!("/classes/{}/diary?subject_id={}&term_id={}", self.class_id, self.subject_id, self.term_id)
+        }
+
+        async fn create_lesson(&self, number: i32, content: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", &self.lessons_path(), &self.token, Some(json!({
+                "subject_id": self.subject_id, "lesson_date": "2026-03-03", "lesson_number": number,
+                "content": content, "homework": "Página 12"
+            })))
+            .await
+        }
+
+        /// Duas aulas em 03/03 (presente na 1ª, falta na 2ª) e uma chamada avulsa em 05/03.
+        async fn record_week(&self) -> Vec<String> {
+            let mut lesson_ids = Vec::new();
+            for (number, content) in [(1, "Relevo; planaltos e \"planícies\""), (2, "Exercícios de relevo")] {
+                let (status, lesson) = self.create_lesson(number, content).await;
+                assert_eq!(status, StatusCode::OK);
+                lesson_ids.push(lesson["id"].as_str().unwrap().to_string());
+            }
+            for (lesson_id, present) in lesson_ids.iter().zip([true, false]) {
+                let path = format!("{}/{lesson_id}/attendance", self.lessons_path());
+                let (status, _) = call_json(&self.app, "PUT", &path, &self.token, Some(json!({
+                    "records": [{"student_id": self.student_id, "present": present}]
+                }))).await;
+                assert_eq!(status, StatusCode::OK);
+            }
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/attendance", self.class_id), &self.token, Some(json!({
+                "date": "2026-03-05", "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "present": true}]
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+            lesson_ids
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn lesson_number_is_unique_per_day() {
+        let f = Fixture::new().await;
+        let (status, _) = f.create_lesson(2, "Relevo").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = f.create_lesson(2, "Outra aula").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn each_lesson_keeps_its_own_attendance() {
+        let f = Fixture::new().await;
+        let lesson_ids = f.record_week().await;
+
+        let path = format!("{}/{}/attendance", f.lessons_path(), lesson_ids[1]);
+        let (_, attendance) = call_json(&f.app, "GET", &path, &f.token, None).await;
+        assert_eq!(attendance["items"][0]["present"], false);
+        assert_eq!(attendance["lesson"]["absences"], 1);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn loose_attendance_creates_the_days_lesson() {
+        let f = Fixture::new().await;
+        f.record_week().await;
+
+        let path = format!("{}?subject_id={}", f.lessons_path(), f.subject_id);
+        let (_, lessons) = call_json(&f.app, "GET", &path, &f.token, None).await;
+        assert_eq!(lessons.as_array().unwrap().len(), 3);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn diary_lists_lessons_and_marks_per_student() {
+        let f = Fixture::new().await;
+        f.record_week().await;
+
+        let (status, diary) = call_json(&f.app, "GET", &f.diary_path(), &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(diary["total_lessons"], 3);
+        assert_eq!(diary["lessons"][1]["lesson_number"], 2);
+        assert_eq!(diary["students"][0]["marks"], json!([true, false, true]));
+        assert_eq!(diary["students"][0]["absences"], 1);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn diary_csv_escapes_content_and_uses_brazilian_formats() {
+        let f = Fixture::new().await;
+        f.record_week().await;
+
+        let (status, csv) = call(&f.app, "GET", &format!("{}&format=csv", f.diary_path()), &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.contains("Heitor;H1;P;F;P;1;0;66,7"));
+        assert!(csv.contains("03/03/2026;1;;\"Relevo; planaltos e \"\"planícies\"\"\";Página 12"));
+
+        f.cleanup().await;
+    }
+}
@@ -1,17 +1,39 @@
 pub mod health;
 pub mod auth;
+pub mod identity;
+pub mod sso;
 pub mod platform_auth;
 pub mod admin;
 pub mod dashboard;
 pub mod teachers;
 pub mod session;
 pub mod school_settings;
+pub mod share_links;
 pub mod records;
+pub mod lessons;
+pub mod assessments;
+pub mod attendance_alerts;
+pub mod recovery;
+pub mod record_history;
+pub mod absence_justifications;
+pub mod guardian_alerts;
+pub mod guardian_portal;
+pub mod report_cards;
 pub mod subjects;
+pub mod teaching_assignments;
+pub mod transcripts;
 pub mod terms;
 //pub mod tenants;
 pub mod students;
+pub mod student_portal;
 pub mod classes;
+pub mod grading_scales;
 pub mod guardians;
 pub mod people;
 pub mod financial;
+pub mod offboarding;
+pub mod class_councils;
+pub mod grade_analytics;
+pub mod student_risk;
+pub mod timetable;
+pub mod timetable_generation;
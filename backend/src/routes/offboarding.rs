@@ -0,0 +1,273 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::PlatformUser;
+use crate::jobs::offboarding::{export_dir, export_tenant, hard_delete_tenant};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct StartOffboardingRequest {
+    pub retention_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OffboardingResponse {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub tenant_name: String,
+    pub tenant_slug: String,
+    pub status: String,
+    pub export_sha256: String,
+    pub manifest: serde_json::Value,
+    pub delete_after: NaiveDateTime,
+    pub requested_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_row_counts: Option<serde_json::Value>,
+    pub deletion_verified: Option<bool>,
+    pub error_message: Option<String>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/admin/clients/:tenant_id/offboarding",
+            get(list_offboardings).post(start_offboarding),
+        )
+        .route("/admin/offboardings/:offboarding_id", get(get_offboarding))
+        .route("/admin/offboardings/:offboarding_id/export", get(download_export))
+        .route("/admin/offboardings/:offboarding_id/cancel", post(cancel_offboarding))
+        .route("/admin/offboardings/:offboarding_id/execute", post(execute_offboarding))
+        .with_state(state)
+}
+
+/// Exporta todos os dados da escola e agenda a exclusão definitiva
+/// para depois da janela de retenção (padrão 30 dias).
+async fn start_offboarding(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(tenant_id): Path<Uuid>,
+    Json(req): Json<StartOffboardingRequest>,
+) -> Result<Json<OffboardingResponse>, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+
+    let retention_days = req.retention_days.unwrap_or(30);
+    if !(0..=365).contains(&retention_days) {
+        return Err((StatusCode::BAD_REQUEST, "Retenção deve estar entre 0 e 365 dias".into()));
+    }
+
+    let already_scheduled = sqlx::query(
+        r#"SELECT 1 FROM tenant_offboardings WHERE tenant_id = $1 AND status = 'scheduled'"#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if already_scheduled.is_some() {
+        return Err((StatusCode::CONFLICT, "Já existe exclusão agendada para esta escola".into()));
+    }
+
+    let export = export_tenant(&state.pool, tenant_id, &export_dir())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao exportar dados: {e}")))?;
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO tenant_offboardings (
+          id, tenant_id, tenant_name, tenant_slug, status,
+          export_file, export_sha256, manifest, delete_after
+        )
+        VALUES ($1, $2, $3, $4, 'scheduled', $5, $6, $7, NOW() + make_interval(days => $8))
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(tenant_id)
+    .bind(&export.manifest.tenant_name)
+    .bind(&export.manifest.tenant_slug)
+    .bind(&export.file_path)
+    .bind(&export.sha256)
+    .bind(sqlx::types::Json(&export.manifest))
+    .bind(retention_days as i32)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_offboarding(&state.pool, row.get("id")).await.map(Json)
+}
+
+async fn list_offboardings(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<Vec<OffboardingResponse>>, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE tenant_id = $1 ORDER BY requested_at DESC",
+        OFFBOARDING_SELECT
+    ))
+    .bind(tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.into_iter().map(map_offboarding_row).collect()))
+}
+
+async fn get_offboarding(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(offboarding_id): Path<Uuid>,
+) -> Result<Json<OffboardingResponse>, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+    load_offboarding(&state.pool, offboarding_id).await.map(Json)
+}
+
+async fn download_export(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(offboarding_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+
+    let row = sqlx::query(
+        r#"SELECT tenant_slug, export_file, status
+           FROM tenant_offboardings
+           WHERE id = $1"#,
+    )
+    .bind(offboarding_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Desligamento não encontrado".into()))?;
+
+    let status: String = row.get("status");
+    if status == "deleted" {
+        return Err((StatusCode::GONE, "Exportação removida após a exclusão definitiva".into()));
+    }
+
+    let slug: String = row.get("tenant_slug");
+    let export_file: String = row.get("export_file");
+    let bytes = tokio::fs::read(&export_file)
+        .await
+        .map_err(|_| (StatusCode::NOT_FOUND, "Arquivo de exportação não encontrado".into()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{slug}-export.zip\""),
+            ),
+        ],
+        bytes,
+    ))
+}
+
+async fn cancel_offboarding(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(offboarding_id): Path<Uuid>,
+) -> Result<Json<OffboardingResponse>, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+
+    let res = sqlx::query(
+        r#"UPDATE tenant_offboardings
+           SET status = 'cancelled'
+           WHERE id = $1 AND status = 'scheduled'"#,
+    )
+    .bind(offboarding_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::BAD_REQUEST, "Nenhuma exclusão agendada para cancelar".into()));
+    }
+
+    load_offboarding(&state.pool, offboarding_id).await.map(Json)
+}
+
+/// Executa a exclusão definitiva agora, desde que a janela de retenção já tenha vencido.
+async fn execute_offboarding(
+    State(state): State<AppState>,
+    platform_user: PlatformUser,
+    Path(offboarding_id): Path<Uuid>,
+) -> Result<Json<OffboardingResponse>, (StatusCode, String)> {
+    require_platform_admin(&platform_user)?;
+
+    let due = sqlx::query(
+        r#"SELECT delete_after <= NOW() AS due
+           FROM tenant_offboardings
+           WHERE id = $1 AND status = 'scheduled'"#,
+    )
+    .bind(offboarding_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Nenhuma exclusão agendada".into()))?;
+
+    if !due.get::<bool, _>("due") {
+        return Err((StatusCode::BAD_REQUEST, "Janela de retenção ainda não venceu".into()));
+    }
+
+    hard_delete_tenant(&state.pool, offboarding_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Erro ao excluir escola: {e}")))?;
+
+    load_offboarding(&state.pool, offboarding_id).await.map(Json)
+}
+
+const OFFBOARDING_SELECT: &str = r#"
+    SELECT
+      id, tenant_id, tenant_name, tenant_slug, status, export_sha256, manifest,
+      delete_after, requested_at, deleted_at, deleted_row_counts, deletion_verified, error_message
+    FROM tenant_offboardings
+"#;
+
+async fn load_offboarding(pool: &PgPool, offboarding_id: Uuid) -> Result<OffboardingResponse, (StatusCode, String)> {
+    let row = sqlx::query(&format!("{} WHERE id = $1", OFFBOARDING_SELECT))
+        .bind(offboarding_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Desligamento não encontrado".into()))?;
+
+    Ok(map_offboarding_row(row))
+}
+
+fn map_offboarding_row(r: sqlx::postgres::PgRow) -> OffboardingResponse {
+    OffboardingResponse {
+        id: r.get("id"),
+        tenant_id: r.get("tenant_id"),
+        tenant_name: r.get("tenant_name"),
+        tenant_slug: r.get("tenant_slug"),
+        status: r.get("status"),
+        export_sha256: r.get("export_sha256"),
+        manifest: r.get("manifest"),
+        delete_after: r.get("delete_after"),
+        requested_at: r.get("requested_at"),
+        deleted_at: r.get("deleted_at"),
+        deleted_row_counts: r.get("deleted_row_counts"),
+        deletion_verified: r.get("deletion_verified"),
+        error_message: r.get("error_message"),
+    }
+}
+
+fn require_platform_admin(platform_user: &PlatformUser) -> Result<(), (StatusCode, String)> {
+    if platform_user.role != "platform_admin" {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    Ok(())
+}
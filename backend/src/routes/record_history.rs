@@ -0,0 +1,321 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct RecordHistoryQuery {
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordHistoryItem {
+    /// `grade` ou `attendance`.
+    pub kind: String,
+    /// `insert` ou `update`.
+    pub operation: String,
+    pub class_id: Uuid,
+    pub attendance_date: Option<NaiveDate>,
+    pub changes: Vec<FieldChange>,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordHistoryResponse {
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+    pub items: Vec<RecordHistoryItem>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/students/:student_id/record-history", get(get_record_history))
+        .with_state(state)
+}
+
+/// Linha do tempo das notas e chamadas do aluno no período e disciplina, mais recente primeiro.
+async fn get_record_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Query(query): Query<RecordHistoryQuery>,
+) -> Result<Json<RecordHistoryResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let class_id: Option<Uuid> = sqlx::query_scalar("SELECT class_id FROM students WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(student_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))?;
+
+    // professor vê só o histórico da turma atual do aluno, se tiver a disciplina atribuída nela
+    let teacher_class_id = match restricted_teacher(&user) {
+        None => None,
+        Some(_) => {
+            let class_id = class_id.ok_or((
+                StatusCode::FORBIDDEN,
+                "Turma/disciplina não atribuída a este professor".to_string(),
+            ))?;
+            ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+            Some(class_id)
+        }
+    };
+
+    let term = sqlx::query("SELECT start_date, end_date FROM academic_terms WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(query.term_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
+    let start_date: Option<NaiveDate> = term.get("start_date");
+    let end_date: Option<NaiveDate> = term.get("end_date");
+
+    let grade_rows = sqlx::query(
+        r#"
+        SELECT h.operation, h.class_id,
+               h.old_score::float8 AS old_score, h.new_score::float8 AS new_score,
+               h.old_recovery_score::float8 AS old_recovery_score,
+               h.new_recovery_score::float8 AS new_recovery_score,
+               h.old_absences, h.new_absences, h.old_comments, h.new_comments,
+               h.changed_by, COALESCE(u.full_name, u.email) AS changed_by_name, h.changed_at
+        FROM student_grade_history h
+        LEFT JOIN users u ON u.id = h.changed_by AND u.tenant_id = h.tenant_id
+        WHERE h.tenant_id = $1 AND h.student_id = $2 AND h.term_id = $3 AND h.subject_id = $4
+          AND ($5::uuid IS NULL OR h.class_id = $5)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(query.term_id)
+    .bind(query.subject_id)
+    .bind(teacher_class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    // chamada não tem período: vale o intervalo de datas, quando definido
+    let attendance_rows = sqlx::query(
+        r#"
+        SELECT h.operation, h.class_id, h.attendance_date,
+               h.old_present, h.new_present, h.old_notes, h.new_notes,
+               h.changed_by, COALESCE(u.full_name, u.email) AS changed_by_name, h.changed_at
+        FROM student_attendance_history h
+        LEFT JOIN users u ON u.id = h.changed_by AND u.tenant_id = h.tenant_id
+        WHERE h.tenant_id = $1 AND h.student_id = $2 AND h.subject_id = $3
+          AND ($4::date IS NULL OR h.attendance_date >= $4)
+          AND ($5::date IS NULL OR h.attendance_date <= $5)
+          AND ($6::uuid IS NULL OR h.class_id = $6)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(query.subject_id)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(teacher_class_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut items: Vec<RecordHistoryItem> = Vec::with_capacity(grade_rows.len() + attendance_rows.len());
+    for r in grade_rows {
+        let mut changes = Vec::new();
+        push_change::<f64>(&mut changes, &r, "score");
+        push_change::<f64>(&mut changes, &r, "recovery_score");
+        push_change::<i32>(&mut changes, &r, "absences");
+        push_change::<String>(&mut changes, &r, "comments");
+        items.push(RecordHistoryItem {
+            kind: "grade".into(),
+            operation: r.get("operation"),
+            class_id: r.get("class_id"),
+            attendance_date: None,
+            changes,
+            changed_by: r.get("changed_by"),
+            changed_by_name: r.get("changed_by_name"),
+            changed_at: r.get("changed_at"),
+        });
+    }
+    for r in attendance_rows {
+        let mut changes = Vec::new();
+        push_change::<bool>(&mut changes, &r, "present");
+        push_change::<String>(&mut changes, &r, "notes");
+        items.push(RecordHistoryItem {
+            kind: "attendance".into(),
+            operation: r.get("operation"),
+            class_id: r.get("class_id"),
+            attendance_date: r.get("attendance_date"),
+            changes,
+            changed_by: r.get("changed_by"),
+            changed_by_name: r.get("changed_by_name"),
+            changed_at: r.get("changed_at"),
+        });
+    }
+    items.sort_by_key(|i| std::cmp::Reverse(i.changed_at));
+
+    Ok(Json(RecordHistoryResponse {
+        student_id,
+        term_id: query.term_id,
+        subject_id: query.subject_id,
+        items,
+    }))
+}
+
+/// Inclui o campo `old_<field>`/`new_<field>` só quando o valor mudou.
+fn push_change<T>(changes: &mut Vec<FieldChange>, row: &sqlx::postgres::PgRow, field: &str)
+where
+    T: for<'r> sqlx::Decode<'r, sqlx::Postgres> + sqlx::Type<sqlx::Postgres> + Serialize + PartialEq,
+{
+    let old_value: Option<T> = row.get(format!("old_{field}").as_str());
+    let new_value: Option<T> = row.get(format!("new_{field}").as_str());
+    if old_value != new_value {
+        changes.push(FieldChange {
+            field: field.to_string(),
+            old_value: serde_json::to_value(old_value).unwrap_or(Value::Null),
+            new_value: serde_json::to_value(new_value).unwrap_or(Value::Null),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, insert_user, make_token, test_pool, SECRET,
+    };
+    use serde_json::json;
+
+    /// Turma 6A com um aluno, um período e Ciências; `owner` lança as notas e chamadas.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner_id: Uuid,
+        owner: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Histórico").await;
+            let class_id = insert_class(&pool, tenant_id, "6A", "6 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Ciências").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Davi", "D1").await;
+
+            let owner_id = Uuid::new_v4();
+            let owner = make_token(tenant_id, owner_id, "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, owner_id, owner, tenant_id, class_id, term_id, subject_id, student_id }
+        }
+
+        fn history_path(&self) -> String {
+            format!(
+                "/students/{}/record-history?term_id={}&subject_id={}",
+                self.student_id, self.term_id, self.subject_id
+            )
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn timeline_lists_grade_and_attendance_changes_newest_first() {
+        let f = Fixture::new().await;
+
+        let gradebook_path = format!("/classes/{}/gradebook", f.class_id);
+        for score in [5.0, 5.0, 8.5] {
+            let (status, _) = call_json(&f.app, "PUT", &gradebook_path, &f.owner, Some(json!({
+                "term_id": f.term_id, "subject_id": f.subject_id,
+                "records": [{"student_id": f.student_id, "score": score}]
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        for present in [true, false] {
+            let (status, _) = call_json(&f.app, "PUT", &format!("/classes/{}/attendance", f.class_id), &f.owner, Some(json!({
+                "date": "2026-03-02", "subject_id": f.subject_id,
+                "records": [{"student_id": f.student_id, "present": present}]
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, body) = call_json(&f.app, "GET", &f.history_path(), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let items = body["items"].as_array().unwrap();
+        // a regravação da mesma nota não gera linha
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0]["kind"], "attendance");
+        assert_eq!(items[0]["changes"][0], json!({"field": "present", "old_value": true, "new_value": false}));
+        assert_eq!(items[2]["kind"], "grade");
+        assert_eq!(items[2]["operation"], "update");
+        assert_eq!(items[2]["changes"][0], json!({"field": "score", "old_value": 5.0, "new_value": 8.5}));
+        assert_eq!(items[2]["changed_by"], f.owner_id.to_string());
+        assert_eq!(items[3]["operation"], "insert");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_needs_the_subject_assigned_in_the_students_class() {
+        let f = Fixture::new().await;
+        let teacher_id = insert_user(&f.pool, f.tenant_id, "Prof. Lia", "teacher").await;
+        let teacher = make_token(f.tenant_id, teacher_id, "teacher");
+
+        let (status, _) = call_json(&f.app, "GET", &f.history_path(), &teacher, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        sqlx::query(
+            "INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year) VALUES ($1, $2, $3, $4, $5, 2026)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(f.tenant_id)
+        .bind(teacher_id)
+        .bind(f.class_id)
+        .bind(f.subject_id)
+        .execute(&f.pool)
+        .await
+        .unwrap();
+        let (status, _) = call_json(&f.app, "GET", &f.history_path(), &teacher, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.cleanup().await;
+    }
+}
@@ -1,23 +1,43 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
     routing::{get, post},
     Json, Router,
 };
 use chrono::NaiveDate;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use uuid::Uuid;
 
+use crate::auth::features::{ensure_feature_enabled, FEATURE_PUBLIC_REPORTS};
 use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::ensure_teaches;
+use crate::grading::council::{
+    decision_status, overall_label, student_decisions, subject_label, CouncilDecisionRef,
+};
+use crate::grading::history::set_change_author;
+use crate::grading::has_assessments;
+use crate::grading::scales::{scale_for_class, GradingScale};
+use crate::grading::term_lock::{ensure_writable, CORRECTION_GRADE};
+use crate::routes::guardian_alerts::alert_low_grades;
+use crate::routes::lessons::{ensure_lesson, save_lesson_attendance, validate_lesson_number};
+use crate::routes::share_links::{
+    create_share_link, resolve_share_link, ClientInfo, ShareTarget, REPORT_FULL, REPORT_GRADEBOOK, REPORT_TERM,
+};
+use crate::grading::promotion::{
+    final_status, overall_status, status_label, FinalStatusInput,
+    presence_percent, STATUS_APPROVED, STATUS_FAILED_ATTENDANCE,
+};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
 pub struct AttendanceQuery {
     pub date: NaiveDate,
     pub subject_id: Uuid,
+    /// Aula do dia (padrão: 1).
+    pub lesson_number: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -34,10 +54,11 @@ pub struct AttendanceResponse {
     pub class_id: Uuid,
     pub date: NaiveDate,
     pub subject_id: Uuid,
+    pub lesson_number: i32,
     pub items: Vec<AttendanceItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct AttendanceRecordInput {
     pub student_id: Uuid,
     pub present: bool,
@@ -48,7 +69,11 @@ pub struct AttendanceRecordInput {
 pub struct UpsertAttendanceRequest {
     pub date: NaiveDate,
     pub subject_id: Uuid,
+    /// Aula do dia (padrão: 1); a aula é criada no diário se ainda não existir.
+    pub lesson_number: Option<i32>,
     pub records: Vec<AttendanceRecordInput>,
+    /// Obrigatória (e só para owner/admin) quando a data cai num período encerrado.
+    pub correction_justification: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,12 +82,31 @@ pub struct GradebookQuery {
     pub subject_id: Uuid,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GradebookAssessmentColumn {
+    pub assessment_id: Uuid,
+    pub name: String,
+    pub assessment_date: Option<NaiveDate>,
+    pub weight: f64,
+    pub max_score: f64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct GradeItem {
     pub student_id: Uuid,
     pub student_name: String,
     pub registration: String,
+    /// Média do período; calculada pelas avaliações quando `derived_from_assessments`.
     pub score: Option<f64>,
+    /// `score` como aparece no boletim (conceito ou número na precisão da escala).
+    pub display_score: Option<String>,
+    pub recovery_score: Option<f64>,
+    /// Nota do período definida pelo conselho de classe.
+    pub council_adjusted_score: Option<f64>,
+    /// Nota do período após a recuperação, conforme a regra da escola, ou a do conselho.
+    pub effective_score: Option<f64>,
+    /// Uma posição por coluna de `assessments`, na mesma ordem.
+    pub assessment_scores: Vec<Option<f64>>,
     pub absences: i32,
     pub comments: Option<String>,
 }
@@ -74,13 +118,20 @@ pub struct GradebookResponse {
     pub term_name: String,
     pub subject_id: Uuid,
     pub subject_name: String,
+    pub grade_formula: String,
+    pub grading_scale: GradingScale,
+    pub derived_from_assessments: bool,
+    pub assessments: Vec<GradebookAssessmentColumn>,
     pub items: Vec<GradeItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct GradeRecordInput {
     pub student_id: Uuid,
-    pub score: f64,
+    /// Obrigatória sem avaliações; com avaliações a média é calculada e este campo deve vir vazio.
+    pub score: Option<f64>,
+    /// No lugar de `score` quando a série usa escala conceitual.
+    pub concept: Option<String>,
     pub absences: Option<i32>,
     pub comments: Option<String>,
 }
@@ -90,6 +141,8 @@ pub struct UpsertGradebookRequest {
     pub term_id: Uuid,
     pub subject_id: Uuid,
     pub records: Vec<GradeRecordInput>,
+    /// Obrigatória (e só para owner/admin) quando o período está encerrado.
+    pub correction_justification: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -112,6 +165,7 @@ pub struct ShareStudentTermReportRequest {
 
 #[derive(Debug, Serialize)]
 pub struct ShareReportResponse {
+    pub id: Uuid,
     pub token: String,
     pub expires_at: String,
 }
@@ -121,50 +175,26 @@ pub struct PublicReportQuery {
     pub token: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ParentReportClaims {
-    sub: String,
-    tenant_id: String,
-    class_id: String,
-    student_id: String,
-    term_id: String,
-    subject_id: String,
-    scope: String,
-    exp: usize,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ParentTermReportClaims {
-    sub: String,
-    tenant_id: String,
-    class_id: String,
-    student_id: String,
-    term_id: String,
-    scope: String,
-    exp: usize,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ParentFullReportClaims {
-    sub: String,
-    tenant_id: String,
-    class_id: String,
-    student_id: String,
-    scope: String,
-    exp: usize,
-}
-
 #[derive(Debug, Serialize)]
 pub struct GradebookReportStudent {
     pub student_id: Uuid,
     pub student_name: String,
     pub registration: String,
+    /// Nota lançada no diário.
     pub score: Option<f64>,
+    /// Nota que vale: a do conselho de classe ou a do diário após a recuperação.
+    pub effective_score: Option<f64>,
+    /// `effective_score` como aparece no boletim.
+    pub display_score: Option<String>,
+    /// Nota do período substituída pelo conselho de classe.
+    pub council_adjusted: bool,
     pub absences_gradebook: i32,
     pub comments: Option<String>,
     pub attendance_total_days: i32,
     pub attendance_present_days: i32,
     pub attendance_absent_days: i32,
+    /// Faltas cobertas por justificativa aprovada (já tratadas pela regra da escola).
+    pub attendance_justified_absences: i32,
     pub attendance_percent: f64,
 }
 
@@ -179,6 +209,7 @@ pub struct GradebookReportResponse {
     pub term_name: String,
     pub subject_id: Uuid,
     pub subject_name: String,
+    pub grading_scale: GradingScale,
     pub generated_at: String,
     pub students: Vec<GradebookReportStudent>,
 }
@@ -193,6 +224,14 @@ pub struct StudentTermSubjectGrade {
     pub subject_id: Uuid,
     pub subject_name: String,
     pub score: Option<f64>,
+    pub recovery_score: Option<f64>,
+    /// Nota do período definida pelo conselho de classe.
+    pub council_adjusted_score: Option<f64>,
+    /// Nota que vale no boletim: a do conselho de classe, quando houver, senão `score`
+    /// após a regra de recuperação.
+    pub effective_score: Option<f64>,
+    /// `effective_score` como aparece no boletim.
+    pub display_score: Option<String>,
     pub absences_gradebook: i32,
     pub comments: Option<String>,
 }
@@ -212,7 +251,10 @@ pub struct StudentTermReportResponse {
     pub attendance_total_days: i32,
     pub attendance_present_days: i32,
     pub attendance_absent_days: i32,
+    /// Faltas cobertas por justificativa aprovada (já tratadas pela regra da escola).
+    pub attendance_justified_absences: i32,
     pub attendance_percent: f64,
+    pub grading_scale: GradingScale,
     pub generated_at: String,
     pub subjects: Vec<StudentTermSubjectGrade>,
 }
@@ -230,8 +272,14 @@ pub struct StudentFullReportPeriodGrade {
     pub term_id: Uuid,
     pub term_name: String,
     pub score: Option<f64>,
+    pub recovery_score: Option<f64>,
+    /// Nota do período após aplicar a regra de recuperação.
+    pub effective_score: Option<f64>,
+    pub effective_display: Option<String>,
     pub absences_gradebook: i32,
     pub comments: Option<String>,
+    /// Nota do período substituída pelo conselho de classe.
+    pub council_adjusted: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -240,8 +288,18 @@ pub struct StudentFullReportSubject {
     pub subject_name: String,
     pub period_grades: Vec<StudentFullReportPeriodGrade>,
     pub average_score: Option<f64>,
+    pub average_display: Option<String>,
+    pub final_exam_score: Option<f64>,
+    /// Aulas com chamada nesta disciplina (`student_attendance.subject_id`).
+    pub attendance_total_lessons: i32,
+    pub attendance_absences: i32,
+    pub attendance_percent: Option<f64>,
     pub approved: bool,
+    /// approved | recovery | failed_grade | failed_attendance | no_grades
+    pub final_status: String,
     pub status: String,
+    /// Decisão do conselho de classe do último período que prevaleceu sobre o cálculo.
+    pub council_decision: Option<CouncilDecisionRef>,
 }
 
 #[derive(Debug, Serialize)]
@@ -255,10 +313,20 @@ pub struct StudentFullReportResponse {
     pub student_name: String,
     pub registration: String,
     pub min_passing_grade: f64,
+    pub grading_scale: GradingScale,
+    pub recovery_rule: String,
+    pub final_exam_min_grade: f64,
+    pub min_attendance_percent: f64,
     pub attendance_total_days: i32,
     pub attendance_present_days: i32,
     pub attendance_absent_days: i32,
+    /// Faltas cobertas por justificativa aprovada (já tratadas pela regra da escola).
+    pub attendance_justified_absences: i32,
     pub attendance_percent: f64,
+    pub final_status: String,
+    pub final_status_label: String,
+    /// Decisão geral do conselho de classe do último período que prevaleceu sobre o cálculo.
+    pub council_decision: Option<CouncilDecisionRef>,
     pub generated_at: String,
     pub periods: Vec<StudentFullReportPeriod>,
     pub subjects: Vec<StudentFullReportSubject>,
@@ -305,6 +373,8 @@ async fn get_attendance(
 ) -> Result<Json<AttendanceResponse>, (StatusCode, String)> {
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
     ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, query.subject_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+    let lesson_number = validate_lesson_number(query.lesson_number)?;
 
     let rows = sqlx::query(
         r#"
@@ -321,6 +391,7 @@ async fn get_attendance(
          AND a.student_id = s.id
          AND a.attendance_date = $3
          AND a.subject_id = $4
+         AND a.lesson_number = $5
         WHERE s.tenant_id = $1 AND s.class_id = $2
         ORDER BY s.name ASC
         "#,
@@ -329,6 +400,7 @@ async fn get_attendance(
     .bind(class_id)
     .bind(query.date)
     .bind(query.subject_id)
+    .bind(lesson_number)
     .fetch_all(&state.pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
@@ -348,6 +420,7 @@ async fn get_attendance(
         class_id,
         date: query.date,
         subject_id: query.subject_id,
+        lesson_number,
         items,
     }))
 }
@@ -361,38 +434,26 @@ async fn upsert_attendance(
     user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
     ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, req.subject_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+    let lesson_number = validate_lesson_number(req.lesson_number)?;
 
     let mut tx = state
         .pool
         .begin()
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
 
-    for r in &req.records {
-        ensure_student_belongs_to_class(&mut tx, user.tenant_id, class_id, r.student_id).await?;
-
-        sqlx::query(
-            r#"
-            INSERT INTO student_attendance
-              (id, tenant_id, class_id, student_id, attendance_date, subject_id, present, notes)
-            VALUES
-              ($1, $2, $3, $4, $5, $6, $7, $8)
-            ON CONFLICT (tenant_id, class_id, student_id, attendance_date, subject_id)
-            DO UPDATE SET present = EXCLUDED.present, notes = EXCLUDED.notes
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(user.tenant_id)
-        .bind(class_id)
-        .bind(r.student_id)
-        .bind(req.date)
-        .bind(req.subject_id)
-        .bind(r.present)
-        .bind(normalize_optional_text(r.notes.clone()))
-        .execute(&mut *tx)
-        .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
-    }
+    let lesson = ensure_lesson(&mut tx, &user, class_id, req.subject_id, req.date, lesson_number).await?;
+    save_lesson_attendance(
+        &mut tx,
+        &user,
+        class_id,
+        &lesson,
+        &req.records,
+        req.correction_justification.as_deref(),
+    )
+    .await?;
 
     tx.commit()
         .await
@@ -408,6 +469,7 @@ async fn get_gradebook(
     Query(query): Query<GradebookQuery>,
 ) -> Result<Json<GradebookResponse>, (StatusCode, String)> {
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
     let term_name = ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, query.term_id).await?;
     let subject_name =
         ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, query.subject_id).await?;
@@ -419,6 +481,9 @@ async fn get_gradebook(
           s.name AS student_name,
           s.registration,
           g.score::float8 AS score,
+          g.recovery_score::float8 AS recovery_score,
+          e.council_adjusted_score::float8 AS council_adjusted_score,
+          e.effective_score::float8 AS effective_score,
           COALESCE(g.absences, 0) AS absences,
           g.comments
         FROM students s
@@ -428,6 +493,12 @@ async fn get_gradebook(
          AND g.student_id = s.id
          AND g.term_id = $3
          AND g.subject_id = $4
+        LEFT JOIN student_grades_effective e
+          ON e.tenant_id = s.tenant_id
+         AND e.class_id = s.class_id
+         AND e.student_id = s.id
+         AND e.term_id = $3
+         AND e.subject_id = $4
         WHERE s.tenant_id = $1 AND s.class_id = $2
         ORDER BY s.name ASC
         "#,
@@ -440,15 +511,82 @@ async fn get_gradebook(
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
+    let assessment_rows = sqlx::query(
+        r#"
+        SELECT id, name, assessment_date, weight::float8 AS weight, max_score::float8 AS max_score
+        FROM assessments
+        WHERE tenant_id = $1 AND class_id = $2 AND term_id = $3 AND subject_id = $4
+        ORDER BY assessment_date ASC NULLS LAST, created_at ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.term_id)
+    .bind(query.subject_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let assessments: Vec<GradebookAssessmentColumn> = assessment_rows
+        .into_iter()
+        .map(|r| GradebookAssessmentColumn {
+            assessment_id: r.get("id"),
+            name: r.get("name"),
+            assessment_date: r.get("assessment_date"),
+            weight: r.get("weight"),
+            max_score: r.get("max_score"),
+        })
+        .collect();
+
+    let score_rows = sqlx::query(
+        r#"
+        SELECT sc.assessment_id, sc.student_id, sc.score::float8 AS score
+        FROM assessment_scores sc
+        JOIN assessments a ON a.id = sc.assessment_id AND a.tenant_id = sc.tenant_id
+        WHERE a.tenant_id = $1 AND a.class_id = $2 AND a.term_id = $3 AND a.subject_id = $4
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.term_id)
+    .bind(query.subject_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut assessment_scores: HashMap<(Uuid, Uuid), f64> = HashMap::new();
+    for r in score_rows {
+        assessment_scores.insert((r.get("student_id"), r.get("assessment_id")), r.get("score"));
+    }
+
+    let grade_formula: String = sqlx::query_scalar("SELECT grade_formula FROM tenants WHERE id = $1")
+        .bind(user.tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let grading_scale = scale_for_class(&state.pool, user.tenant_id, class_id).await?;
+
     let items = rows
         .into_iter()
-        .map(|r| GradeItem {
-            student_id: r.get("student_id"),
-            student_name: r.get("student_name"),
-            registration: r.get("registration"),
-            score: r.get("score"),
-            absences: r.get("absences"),
-            comments: r.get("comments"),
+        .map(|r| {
+            let student_id: Uuid = r.get("student_id");
+            let score: Option<f64> = r.get("score");
+            GradeItem {
+                student_id,
+                student_name: r.get("student_name"),
+                registration: r.get("registration"),
+                score,
+                display_score: grading_scale.display(score),
+                recovery_score: r.get("recovery_score"),
+                council_adjusted_score: r.get("council_adjusted_score"),
+                effective_score: r.get("effective_score"),
+                assessment_scores: assessments
+                    .iter()
+                    .map(|a| assessment_scores.get(&(student_id, a.assessment_id)).copied())
+                    .collect(),
+                absences: r.get("absences"),
+                comments: r.get("comments"),
+            }
         })
         .collect();
 
@@ -458,6 +596,10 @@ async fn get_gradebook(
         term_name,
         subject_id: query.subject_id,
         subject_name,
+        grade_formula,
+        grading_scale,
+        derived_from_assessments: !assessments.is_empty(),
+        assessments,
         items,
     }))
 }
@@ -470,6 +612,7 @@ async fn upsert_gradebook(
 ) -> Result<Json<OkResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
     let term_name = ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, req.term_id).await?;
     let subject_name =
         ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, req.subject_id).await?;
@@ -479,11 +622,34 @@ async fn upsert_gradebook(
         .begin()
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    ensure_writable(
+        &mut tx,
+        &user,
+        Some(req.term_id),
+        class_id,
+        CORRECTION_GRADE,
+        req.correction_justification.as_deref(),
+        serde_json::json!({ "subject_id": req.subject_id, "records": req.records }),
+    )
+    .await?;
+    let derived = has_assessments(&mut tx, user.tenant_id, class_id, req.term_id, req.subject_id).await?;
+    let grading_scale = scale_for_class(&mut *tx, user.tenant_id, class_id).await?;
 
     for r in &req.records {
-        if !(0.0..=10.0).contains(&r.score) {
-            return Err((StatusCode::BAD_REQUEST, "Nota deve estar entre 0 e 10".into()));
-        }
+        let score = if derived {
+            if r.score.is_some() || r.concept.is_some() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Nota do período é calculada pelas avaliações; lance as notas nas avaliações".into(),
+                ));
+            }
+            None
+        } else {
+            let score = grading_scale.input_value(r.score, r.concept.as_deref())?;
+            Some(score.ok_or((StatusCode::BAD_REQUEST, "Nota é obrigatória".to_string()))?)
+        };
         ensure_student_belongs_to_class(&mut tx, user.tenant_id, class_id, r.student_id).await?;
 
         sqlx::query(
@@ -496,7 +662,7 @@ async fn upsert_gradebook(
             DO UPDATE SET
               term = EXCLUDED.term,
               subject = EXCLUDED.subject,
-              score = EXCLUDED.score,
+              score = CASE WHEN $12 THEN student_grades.score ELSE EXCLUDED.score END,
               absences = EXCLUDED.absences,
               comments = EXCLUDED.comments
             "#,
@@ -509,14 +675,17 @@ async fn upsert_gradebook(
         .bind(&subject_name)
         .bind(req.term_id)
         .bind(req.subject_id)
-        .bind(r.score)
+        .bind(score)
         .bind(r.absences.unwrap_or(0))
         .bind(normalize_optional_text(r.comments.clone()))
+        .bind(derived)
         .execute(&mut *tx)
         .await
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro DB ao salvar notas: {e}")))?;
     }
 
+    alert_low_grades(&mut tx, user.tenant_id, class_id, req.term_id, req.subject_id).await?;
+
     tx.commit()
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
@@ -531,6 +700,7 @@ async fn get_gradebook_report(
     Query(query): Query<GradebookQuery>,
 ) -> Result<Json<GradebookReportResponse>, (StatusCode, String)> {
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
     let term_name = ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, query.term_id).await?;
     let subject_name =
         ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, query.subject_id).await?;
@@ -561,10 +731,13 @@ async fn get_gradebook_report(
           s.name AS student_name,
           s.registration,
           g.score::float8 AS score,
+          e.effective_score::float8 AS effective_score,
+          COALESCE(e.council_adjusted, FALSE) AS council_adjusted,
           COALESCE(g.absences, 0) AS absences_gradebook,
           g.comments,
           COALESCE(a.total_days, 0) AS attendance_total_days,
-          COALESCE(a.present_days, 0) AS attendance_present_days
+          COALESCE(a.present_days, 0) AS attendance_present_days,
+          COALESCE(a.justified_days, 0) AS attendance_justified_absences
         FROM students s
         LEFT JOIN student_grades g
           ON g.tenant_id = s.tenant_id
@@ -572,12 +745,19 @@ async fn get_gradebook_report(
          AND g.student_id = s.id
          AND g.term_id = $3
          AND g.subject_id = $4
+        LEFT JOIN student_grades_effective e
+          ON e.tenant_id = s.tenant_id
+         AND e.class_id = s.class_id
+         AND e.student_id = s.id
+         AND e.term_id = $3
+         AND e.subject_id = $4
         LEFT JOIN (
           SELECT
             student_id,
-            COUNT(*)::int AS total_days,
-            SUM(CASE WHEN present THEN 1 ELSE 0 END)::int AS present_days
-          FROM student_attendance
+            COUNT(*) FILTER (WHERE counted)::int AS total_days,
+            COUNT(*) FILTER (WHERE counted AND present)::int AS present_days,
+            COUNT(*) FILTER (WHERE justified)::int AS justified_days
+          FROM student_attendance_effective
           WHERE tenant_id = $1 AND class_id = $2 AND subject_id = $4
           GROUP BY student_id
         ) a ON a.student_id = s.id
@@ -594,9 +774,11 @@ async fn get_gradebook_report(
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
+    let grading_scale = scale_for_class(&state.pool, user.tenant_id, class_id).await?;
     let students = rows
         .into_iter()
         .map(|r| {
+            let effective_score: Option<f64> = r.get("effective_score");
             let total_days: i32 = r.get("attendance_total_days");
             let present_days: i32 = r.get("attendance_present_days");
             let absent_days = total_days.saturating_sub(present_days);
@@ -611,11 +793,15 @@ async fn get_gradebook_report(
                 student_name: r.get("student_name"),
                 registration: r.get("registration"),
                 score: r.get("score"),
+                effective_score,
+                display_score: grading_scale.display(effective_score),
+                council_adjusted: r.get("council_adjusted"),
                 absences_gradebook: r.get("absences_gradebook"),
                 comments: r.get("comments"),
                 attendance_total_days: total_days,
                 attendance_present_days: present_days,
                 attendance_absent_days: absent_days,
+                attendance_justified_absences: r.get("attendance_justified_absences"),
                 attendance_percent,
             }
         })
@@ -631,6 +817,7 @@ async fn get_gradebook_report(
         term_name,
         subject_id: query.subject_id,
         subject_name,
+        grading_scale,
         generated_at: chrono::Utc::now().to_rfc3339(),
         students,
     }))
@@ -643,63 +830,47 @@ async fn create_gradebook_report_share_link(
     Json(req): Json<ShareReportRequest>,
 ) -> Result<Json<ShareReportResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_PUBLIC_REPORTS).await?;
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
     ensure_student_belongs_to_class_by_pool(&state.pool, user.tenant_id, class_id, req.student_id).await?;
     ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, req.term_id).await?;
     ensure_subject_belongs_to_tenant(&state.pool, user.tenant_id, req.subject_id).await?;
-
-    let expires_days = req.expires_days.unwrap_or(30).clamp(1, 365);
-    let expires_at = chrono::Utc::now() + chrono::Duration::days(expires_days);
-
-    let claims = ParentReportClaims {
-        sub: user.user_id.to_string(),
-        tenant_id: user.tenant_id.to_string(),
-        class_id: class_id.to_string(),
-        student_id: req.student_id.to_string(),
-        term_id: req.term_id.to_string(),
-        subject_id: req.subject_id.to_string(),
-        scope: "parent_report".to_string(),
-        exp: expires_at.timestamp() as usize,
-    };
-
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_bytes()),
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+
+    create_share_link(
+        &state.pool,
+        &user,
+        ShareTarget {
+            report_type: REPORT_GRADEBOOK,
+            class_id,
+            student_id: req.student_id,
+            term_id: Some(req.term_id),
+            subject_id: Some(req.subject_id),
+        },
+        req.expires_days,
     )
-    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro ao gerar token".into()))?;
-
-    Ok(Json(ShareReportResponse {
-        token,
-        expires_at: expires_at.to_rfc3339(),
-    }))
+    .await
+    .map(Json)
 }
 
 async fn get_public_gradebook_report(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    addr: Option<ConnectInfo<SocketAddr>>,
     Query(query): Query<PublicReportQuery>,
 ) -> Result<Json<GradebookReportResponse>, (StatusCode, String)> {
-    let data = decode::<ParentReportClaims>(
+    let link = resolve_share_link(
+        &state.pool,
         &query.token,
-        &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
-        &Validation::default(),
+        REPORT_GRADEBOOK,
+        ClientInfo::from_request(&headers, addr),
     )
-    .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido ou expirado".into()))?;
-
-    if data.claims.scope != "parent_report" {
-        return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
-    }
-
-    let tenant_id = Uuid::parse_str(&data.claims.tenant_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "tenant inválido".into()))?;
-    let class_id =
-        Uuid::parse_str(&data.claims.class_id).map_err(|_| (StatusCode::BAD_REQUEST, "class inválida".into()))?;
-    let student_id = Uuid::parse_str(&data.claims.student_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "student inválido".into()))?;
-    let term_id =
-        Uuid::parse_str(&data.claims.term_id).map_err(|_| (StatusCode::BAD_REQUEST, "term inválido".into()))?;
-    let subject_id = Uuid::parse_str(&data.claims.subject_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "subject inválida".into()))?;
+    .await?;
+    let tenant_id = link.tenant_id;
+    let class_id = link.class_id;
+    let student_id = link.student_id;
+    let term_id = link.term_id.ok_or((StatusCode::UNAUTHORIZED, "Link inválido".into()))?;
+    let subject_id = link.subject_id.ok_or((StatusCode::UNAUTHORIZED, "Link inválido".into()))?;
 
     let term_name = ensure_term_belongs_to_tenant(&state.pool, tenant_id, term_id).await?;
     let subject_name = ensure_subject_belongs_to_tenant(&state.pool, tenant_id, subject_id).await?;
@@ -731,10 +902,13 @@ async fn get_public_gradebook_report(
           s.name AS student_name,
           s.registration,
           g.score::float8 AS score,
+          e.effective_score::float8 AS effective_score,
+          COALESCE(e.council_adjusted, FALSE) AS council_adjusted,
           COALESCE(g.absences, 0) AS absences_gradebook,
           g.comments,
           COALESCE(a.total_days, 0) AS attendance_total_days,
-          COALESCE(a.present_days, 0) AS attendance_present_days
+          COALESCE(a.present_days, 0) AS attendance_present_days,
+          COALESCE(a.justified_days, 0) AS attendance_justified_absences
         FROM students s
         LEFT JOIN student_grades g
           ON g.tenant_id = s.tenant_id
@@ -742,12 +916,19 @@ async fn get_public_gradebook_report(
          AND g.student_id = s.id
          AND g.term_id = $4
          AND g.subject_id = $5
+        LEFT JOIN student_grades_effective e
+          ON e.tenant_id = s.tenant_id
+         AND e.class_id = s.class_id
+         AND e.student_id = s.id
+         AND e.term_id = $4
+         AND e.subject_id = $5
         LEFT JOIN (
           SELECT
             student_id,
-            COUNT(*)::int AS total_days,
-            SUM(CASE WHEN present THEN 1 ELSE 0 END)::int AS present_days
-          FROM student_attendance
+            COUNT(*) FILTER (WHERE counted)::int AS total_days,
+            COUNT(*) FILTER (WHERE counted AND present)::int AS present_days,
+            COUNT(*) FILTER (WHERE justified)::int AS justified_days
+          FROM student_attendance_effective
           WHERE tenant_id = $1 AND class_id = $2 AND subject_id = $5
           GROUP BY student_id
         ) a ON a.student_id = s.id
@@ -775,16 +956,22 @@ async fn get_public_gradebook_report(
         0.0
     };
 
+    let grading_scale = scale_for_class(&state.pool, tenant_id, class_id).await?;
+    let effective_score: Option<f64> = row.get("effective_score");
     let student = GradebookReportStudent {
         student_id: row.get("student_id"),
         student_name: row.get("student_name"),
         registration: row.get("registration"),
         score: row.get("score"),
+        effective_score,
+        display_score: grading_scale.display(effective_score),
+        council_adjusted: row.get("council_adjusted"),
         absences_gradebook: row.get("absences_gradebook"),
         comments: row.get("comments"),
         attendance_total_days: total_days,
         attendance_present_days: present_days,
         attendance_absent_days: absent_days,
+        attendance_justified_absences: row.get("attendance_justified_absences"),
         attendance_percent,
     };
 
@@ -798,6 +985,7 @@ async fn get_public_gradebook_report(
         term_name,
         subject_id,
         subject_name,
+        grading_scale,
         generated_at: chrono::Utc::now().to_rfc3339(),
         students: vec![student],
     }))
@@ -810,6 +998,7 @@ async fn get_student_term_report(
     Query(query): Query<StudentTermReportQuery>,
 ) -> Result<Json<StudentTermReportResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
     fetch_student_term_report(&state.pool, user.tenant_id, class_id, student_id, query.term_id).await.map(Json)
 }
 
@@ -821,61 +1010,44 @@ async fn create_student_term_report_share_link(
     Json(req): Json<ShareStudentTermReportRequest>,
 ) -> Result<Json<ShareReportResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_PUBLIC_REPORTS).await?;
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
     ensure_student_belongs_to_class_by_pool(&state.pool, user.tenant_id, class_id, student_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
     ensure_term_belongs_to_tenant(&state.pool, user.tenant_id, query.term_id).await?;
 
-    let expires_days = req.expires_days.unwrap_or(30).clamp(1, 365);
-    let expires_at = chrono::Utc::now() + chrono::Duration::days(expires_days);
-
-    let claims = ParentTermReportClaims {
-        sub: user.user_id.to_string(),
-        tenant_id: user.tenant_id.to_string(),
-        class_id: class_id.to_string(),
-        student_id: student_id.to_string(),
-        term_id: query.term_id.to_string(),
-        scope: "parent_term_report".to_string(),
-        exp: expires_at.timestamp() as usize,
-    };
-
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_bytes()),
+    create_share_link(
+        &state.pool,
+        &user,
+        ShareTarget {
+            report_type: REPORT_TERM,
+            class_id,
+            student_id,
+            term_id: Some(query.term_id),
+            subject_id: None,
+        },
+        req.expires_days,
     )
-    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro ao gerar token".into()))?;
-
-    Ok(Json(ShareReportResponse {
-        token,
-        expires_at: expires_at.to_rfc3339(),
-    }))
+    .await
+    .map(Json)
 }
 
 async fn get_public_student_term_report(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    addr: Option<ConnectInfo<SocketAddr>>,
     Query(query): Query<PublicReportQuery>,
 ) -> Result<Json<StudentTermReportResponse>, (StatusCode, String)> {
-    let data = decode::<ParentTermReportClaims>(
+    let link = resolve_share_link(
+        &state.pool,
         &query.token,
-        &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
-        &Validation::default(),
+        REPORT_TERM,
+        ClientInfo::from_request(&headers, addr),
     )
-    .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido ou expirado".into()))?;
+    .await?;
+    let term_id = link.term_id.ok_or((StatusCode::UNAUTHORIZED, "Link inválido".into()))?;
 
-    if data.claims.scope != "parent_term_report" {
-        return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
-    }
-
-    let tenant_id = Uuid::parse_str(&data.claims.tenant_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "tenant inválido".into()))?;
-    let class_id =
-        Uuid::parse_str(&data.claims.class_id).map_err(|_| (StatusCode::BAD_REQUEST, "class inválida".into()))?;
-    let student_id = Uuid::parse_str(&data.claims.student_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "student inválido".into()))?;
-    let term_id =
-        Uuid::parse_str(&data.claims.term_id).map_err(|_| (StatusCode::BAD_REQUEST, "term inválido".into()))?;
-
-    fetch_student_term_report(&state.pool, tenant_id, class_id, student_id, term_id)
+    fetch_student_term_report(&state.pool, link.tenant_id, link.class_id, link.student_id, term_id)
         .await
         .map(Json)
 }
@@ -886,6 +1058,7 @@ async fn get_student_full_report(
     Path((class_id, student_id)): Path<(Uuid, Uuid)>,
 ) -> Result<Json<StudentFullReportResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
     fetch_student_full_report(&state.pool, user.tenant_id, class_id, student_id)
         .await
         .map(Json)
@@ -898,62 +1071,47 @@ async fn create_student_full_report_share_link(
     Json(req): Json<ShareStudentTermReportRequest>,
 ) -> Result<Json<ShareReportResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_feature_enabled(&state.pool, user.tenant_id, FEATURE_PUBLIC_REPORTS).await?;
     ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
     ensure_student_belongs_to_class_by_pool(&state.pool, user.tenant_id, class_id, student_id).await?;
-
-    let expires_days = req.expires_days.unwrap_or(30).clamp(1, 365);
-    let expires_at = chrono::Utc::now() + chrono::Duration::days(expires_days);
-
-    let claims = ParentFullReportClaims {
-        sub: user.user_id.to_string(),
-        tenant_id: user.tenant_id.to_string(),
-        class_id: class_id.to_string(),
-        student_id: student_id.to_string(),
-        scope: "parent_full_report".to_string(),
-        exp: expires_at.timestamp() as usize,
-    };
-
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_bytes()),
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
+
+    create_share_link(
+        &state.pool,
+        &user,
+        ShareTarget {
+            report_type: REPORT_FULL,
+            class_id,
+            student_id,
+            term_id: None,
+            subject_id: None,
+        },
+        req.expires_days,
     )
-    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro ao gerar token".into()))?;
-
-    Ok(Json(ShareReportResponse {
-        token,
-        expires_at: expires_at.to_rfc3339(),
-    }))
+    .await
+    .map(Json)
 }
 
 async fn get_public_student_full_report(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    addr: Option<ConnectInfo<SocketAddr>>,
     Query(query): Query<PublicReportQuery>,
 ) -> Result<Json<StudentFullReportResponse>, (StatusCode, String)> {
-    let data = decode::<ParentFullReportClaims>(
+    let link = resolve_share_link(
+        &state.pool,
         &query.token,
-        &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
-        &Validation::default(),
+        REPORT_FULL,
+        ClientInfo::from_request(&headers, addr),
     )
-    .map_err(|_| (StatusCode::UNAUTHORIZED, "Token inválido ou expirado".into()))?;
+    .await?;
 
-    if data.claims.scope != "parent_full_report" {
-        return Err((StatusCode::UNAUTHORIZED, "Escopo de token inválido".into()));
-    }
-
-    let tenant_id = Uuid::parse_str(&data.claims.tenant_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "tenant inválido".into()))?;
-    let class_id =
-        Uuid::parse_str(&data.claims.class_id).map_err(|_| (StatusCode::BAD_REQUEST, "class inválida".into()))?;
-    let student_id = Uuid::parse_str(&data.claims.student_id)
-        .map_err(|_| (StatusCode::BAD_REQUEST, "student inválido".into()))?;
-
-    fetch_student_full_report(&state.pool, tenant_id, class_id, student_id)
+    fetch_student_full_report(&state.pool, link.tenant_id, link.class_id, link.student_id)
         .await
         .map(Json)
 }
 
-async fn fetch_student_full_report(
+pub async fn fetch_student_full_report(
     pool: &PgPool,
     tenant_id: Uuid,
     class_id: Uuid,
@@ -961,10 +1119,23 @@ async fn fetch_student_full_report(
 ) -> Result<StudentFullReportResponse, (StatusCode, String)> {
     ensure_class_belongs_to_tenant(pool, tenant_id, class_id).await?;
     ensure_student_belongs_to_class_by_pool(pool, tenant_id, class_id, student_id).await?;
+    build_student_full_report(pool, tenant_id, class_id, student_id).await
+}
 
+/// Boletim anual do aluno numa turma, sem exigir que ele ainda esteja nela
+/// (anos anteriores do histórico escolar).
+pub async fn build_student_full_report(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    student_id: Uuid,
+) -> Result<StudentFullReportResponse, (StatusCode, String)> {
     let class_row = sqlx::query(
         r#"
-        SELECT c.name, c.grade, c.year, c.period, t.passing_min_grade::float8 AS passing_min_grade
+        SELECT c.name, c.grade, c.year, c.period,
+               t.recovery_rule,
+               t.final_exam_min_grade::float8 AS final_exam_min_grade,
+               t.min_attendance_percent::float8 AS min_attendance_percent
         FROM classes c
         JOIN tenants t ON t.id = c.tenant_id
         WHERE c.tenant_id = $1 AND c.id = $2
@@ -977,17 +1148,21 @@ async fn fetch_student_full_report(
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
     let class_row = class_row.ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))?;
     let class_year: i32 = class_row.get("year");
-    let min_passing_grade: f64 = class_row.get("passing_min_grade");
+    let recovery_rule: String = class_row.get("recovery_rule");
+    let grading_scale = scale_for_class(pool, tenant_id, class_id).await?;
+    let min_passing_grade = grading_scale.passing_value;
+    // a média da prova final é configurada de 0 a 10 e convertida para a escala da série
+    let final_exam_min_grade = grading_scale.rescale_from_ten(class_row.get("final_exam_min_grade"));
+    let min_attendance_percent: f64 = class_row.get("min_attendance_percent");
 
     let student_row = sqlx::query(
         r#"
         SELECT id, name, registration
         FROM students
-        WHERE tenant_id = $1 AND class_id = $2 AND id = $3
+        WHERE tenant_id = $1 AND id = $2
         "#,
     )
     .bind(tenant_id)
-    .bind(class_id)
     .bind(student_id)
     .fetch_optional(pool)
     .await
@@ -997,9 +1172,10 @@ async fn fetch_student_full_report(
     let attendance_row = sqlx::query(
         r#"
         SELECT
-          COUNT(*)::int AS total_days,
-          SUM(CASE WHEN present THEN 1 ELSE 0 END)::int AS present_days
-        FROM student_attendance
+          COUNT(*) FILTER (WHERE counted)::int AS total_days,
+          COUNT(*) FILTER (WHERE counted AND present)::int AS present_days,
+          COUNT(*) FILTER (WHERE justified)::int AS justified_days
+        FROM student_attendance_effective
         WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3
         "#,
     )
@@ -1059,21 +1235,30 @@ async fn fetch_student_full_report(
     let grades_rows = sqlx::query(
         r#"
         SELECT
-          g.subject_id,
+          e.subject_id,
           s.name AS subject_name,
-          g.term_id,
+          e.term_id,
           t.name AS term_name,
           t.school_year,
           t.sort_order,
           g.score::float8 AS score,
+          g.recovery_score::float8 AS recovery_score,
+          e.effective_score::float8 AS effective_score,
+          e.council_adjusted,
           COALESCE(g.absences, 0) AS absences_gradebook,
           g.comments
-        FROM student_grades g
-        JOIN subjects s ON s.id = g.subject_id AND s.tenant_id = g.tenant_id
-        JOIN academic_terms t ON t.id = g.term_id AND t.tenant_id = g.tenant_id
-        WHERE g.tenant_id = $1
-          AND g.class_id = $2
-          AND g.student_id = $3
+        FROM student_grades_effective e
+        LEFT JOIN student_grades g
+          ON g.tenant_id = e.tenant_id
+         AND g.class_id = e.class_id
+         AND g.student_id = e.student_id
+         AND g.term_id = e.term_id
+         AND g.subject_id = e.subject_id
+        JOIN subjects s ON s.id = e.subject_id AND s.tenant_id = e.tenant_id
+        JOIN academic_terms t ON t.id = e.term_id AND t.tenant_id = e.tenant_id
+        WHERE e.tenant_id = $1
+          AND e.class_id = $2
+          AND e.student_id = $3
         ORDER BY s.name ASC, t.school_year ASC, t.sort_order ASC
         "#,
     )
@@ -1094,52 +1279,114 @@ async fn fetch_student_full_report(
     for r in grades_rows {
         let subject_id: Uuid = r.get("subject_id");
         let subject_name: String = r.get("subject_name");
-        let term_id: Uuid = r.get("term_id");
-        let term_name: String = r.get("term_name");
+        let effective_score: Option<f64> = r.get("effective_score");
         let grade = StudentFullReportPeriodGrade {
-            term_id,
-            term_name,
+            term_id: r.get("term_id"),
+            term_name: r.get("term_name"),
             score: r.get("score"),
+            recovery_score: r.get("recovery_score"),
+            effective_score,
+            effective_display: grading_scale.display(effective_score),
             absences_gradebook: r.get("absences_gradebook"),
             comments: r.get("comments"),
+            council_adjusted: r.get("council_adjusted"),
         };
         subject_map
             .entry(subject_id)
-            .and_modify(|(_, list)| {
-                list.push(StudentFullReportPeriodGrade {
-                term_id: grade.term_id,
-                term_name: grade.term_name.clone(),
-                score: grade.score,
-                absences_gradebook: grade.absences_gradebook,
-                comments: grade.comments.clone(),
-                })
-            })
-            .or_insert((subject_name, vec![grade]));
+            .or_insert_with(|| (subject_name, Vec::new()))
+            .1
+            .push(grade);
+    }
+
+    // Decisões do conselho de fim de ano (os ajustes de nota já vêm de
+    // `student_grades_effective`).
+    let mut subject_decisions: HashMap<Uuid, CouncilDecisionRef> = HashMap::new();
+    let mut general_decision: Option<CouncilDecisionRef> = None;
+    for d in student_decisions(pool, tenant_id, class_id, student_id).await? {
+        if !d.year_end {
+            continue;
+        }
+        match d.subject_id {
+            Some(subject_id) => {
+                subject_decisions.insert(subject_id, d.info);
+            }
+            None => general_decision = Some(d.info),
+        }
     }
 
+    let final_exam_rows = sqlx::query(
+        r#"
+        SELECT subject_id, score::float8 AS score
+        FROM student_final_exams
+        WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let final_exams: HashMap<Uuid, f64> = final_exam_rows
+        .into_iter()
+        .map(|r| (r.get("subject_id"), r.get("score")))
+        .collect();
+
+    let subject_attendance_rows = sqlx::query(
+        r#"
+        SELECT
+          subject_id,
+          COUNT(*) FILTER (WHERE counted)::int AS total_lessons,
+          COUNT(*) FILTER (WHERE counted AND present)::int AS present_lessons
+        FROM student_attendance_effective
+        WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3 AND subject_id IS NOT NULL
+        GROUP BY subject_id
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let subject_attendance: HashMap<Uuid, (i32, i32)> = subject_attendance_rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.get("subject_id"),
+                (r.get("total_lessons"), r.get::<Option<i32>, _>("present_lessons").unwrap_or(0)),
+            )
+        })
+        .collect();
+
+    let overall_attendance = presence_percent(total_days, present_days);
+
     let mut subjects: Vec<StudentFullReportSubject> = subject_map
         .into_iter()
         .map(|(subject_id, (subject_name, period_grades))| {
-            let mut sum = 0.0_f64;
-            let mut count = 0_i32;
-            for g in &period_grades {
-                if let Some(score) = g.score {
-                    sum += score;
-                    count += 1;
-                }
-            }
-            let average_score = if count > 0 {
-                Some((sum / f64::from(count) * 100.0).round() / 100.0)
-            } else {
-                None
+            let effective: Vec<f64> = period_grades.iter().filter_map(|g| g.effective_score).collect();
+            let average_score = grading_scale.average(&effective);
+            let final_exam_score = final_exams.get(&subject_id).copied();
+            let (subject_lessons, subject_present) =
+                subject_attendance.get(&subject_id).copied().unwrap_or((0, 0));
+            let subject_percent = presence_percent(subject_lessons, subject_present);
+            // retém tanto pela frequência na disciplina quanto pela frequência geral
+            let worst_attendance = match (subject_percent, overall_attendance) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
             };
-            let approved = average_score.is_some_and(|avg| avg >= min_passing_grade);
-            let status = if average_score.is_none() {
-                "Sem notas".to_string()
-            } else if approved {
-                "Aprovado".to_string()
-            } else {
-                "Reprovado".to_string()
+            let final_status = final_status(&FinalStatusInput {
+                average: average_score,
+                min_passing_grade,
+                final_exam_score,
+                final_exam_min_grade,
+                attendance_percent: worst_attendance,
+                min_attendance_percent,
+            });
+            let council_decision = subject_decisions.remove(&subject_id);
+            let (final_status, status) = match &council_decision {
+                Some(d) => (decision_status(&d.decision), subject_label(&d.decision)),
+                None => (final_status, status_label(final_status)),
             };
 
             StudentFullReportSubject {
@@ -1147,12 +1394,30 @@ async fn fetch_student_full_report(
                 subject_name,
                 period_grades,
                 average_score,
-                approved,
-                status,
+                average_display: grading_scale.display(average_score),
+                final_exam_score,
+                attendance_total_lessons: subject_lessons,
+                attendance_absences: subject_lessons - subject_present,
+                attendance_percent: subject_percent,
+                approved: final_status == STATUS_APPROVED,
+                final_status: final_status.to_string(),
+                status: status.to_string(),
+                council_decision,
             }
         })
         .collect();
     subjects.sort_by(|a, b| a.subject_name.cmp(&b.subject_name));
+    let (final_status, final_status_label) = match &general_decision {
+        Some(d) => (decision_status(&d.decision), overall_label(&d.decision)),
+        None => {
+            let status = if overall_attendance.is_some_and(|p| p < min_attendance_percent) {
+                STATUS_FAILED_ATTENDANCE
+            } else {
+                overall_status(subjects.iter().map(|s| s.final_status.as_str()))
+            };
+            (status, status_label(status))
+        }
+    };
 
     Ok(StudentFullReportResponse {
         class_id,
@@ -1164,17 +1429,25 @@ async fn fetch_student_full_report(
         student_name: student_row.get("name"),
         registration: student_row.get("registration"),
         min_passing_grade,
+        grading_scale,
+        recovery_rule,
+        final_exam_min_grade,
+        min_attendance_percent,
         attendance_total_days: total_days,
         attendance_present_days: present_days,
         attendance_absent_days: absent_days,
+        attendance_justified_absences: attendance_row.get("justified_days"),
         attendance_percent,
+        final_status: final_status.to_string(),
+        final_status_label: final_status_label.to_string(),
+        council_decision: general_decision,
         generated_at: chrono::Utc::now().to_rfc3339(),
         periods,
         subjects,
     })
 }
 
-async fn fetch_student_term_report(
+pub async fn fetch_student_term_report(
     pool: &PgPool,
     tenant_id: Uuid,
     class_id: Uuid,
@@ -1217,9 +1490,10 @@ async fn fetch_student_term_report(
     let attendance_row = sqlx::query(
         r#"
         SELECT
-          COUNT(*)::int AS total_days,
-          SUM(CASE WHEN present THEN 1 ELSE 0 END)::int AS present_days
-        FROM student_attendance
+          COUNT(*) FILTER (WHERE counted)::int AS total_days,
+          COUNT(*) FILTER (WHERE counted AND present)::int AS present_days,
+          COUNT(*) FILTER (WHERE justified)::int AS justified_days
+        FROM student_attendance_effective
         WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3
         "#,
     )
@@ -1246,6 +1520,9 @@ async fn fetch_student_term_report(
           sbj.id AS subject_id,
           sbj.name AS subject_name,
           g.score::float8 AS score,
+          g.recovery_score::float8 AS recovery_score,
+          e.council_adjusted_score::float8 AS council_adjusted_score,
+          e.effective_score::float8 AS effective_score,
           COALESCE(g.absences, 0) AS absences_gradebook,
           g.comments
         FROM subjects sbj
@@ -1255,6 +1532,12 @@ async fn fetch_student_term_report(
          AND g.student_id = $3
          AND g.term_id = $4
          AND g.subject_id = sbj.id
+        LEFT JOIN student_grades_effective e
+          ON e.tenant_id = sbj.tenant_id
+         AND e.class_id = $2
+         AND e.student_id = $3
+         AND e.term_id = $4
+         AND e.subject_id = sbj.id
         WHERE sbj.tenant_id = $1
         ORDER BY sbj.name ASC
         "#,
@@ -1267,14 +1550,22 @@ async fn fetch_student_term_report(
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
+    let grading_scale = scale_for_class(pool, tenant_id, class_id).await?;
     let subjects = subject_rows
         .into_iter()
-        .map(|r| StudentTermSubjectGrade {
-            subject_id: r.get("subject_id"),
-            subject_name: r.get("subject_name"),
-            score: r.get("score"),
-            absences_gradebook: r.get("absences_gradebook"),
-            comments: r.get("comments"),
+        .map(|r| {
+            let effective_score: Option<f64> = r.get("effective_score");
+            StudentTermSubjectGrade {
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                score: r.get("score"),
+                recovery_score: r.get("recovery_score"),
+                council_adjusted_score: r.get("council_adjusted_score"),
+                effective_score,
+                display_score: grading_scale.display(effective_score),
+                absences_gradebook: r.get("absences_gradebook"),
+                comments: r.get("comments"),
+            }
         })
         .collect();
 
@@ -1292,7 +1583,9 @@ async fn fetch_student_term_report(
         attendance_total_days: total_days,
         attendance_present_days: present_days,
         attendance_absent_days: absent_days,
+        attendance_justified_absences: attendance_row.get("justified_days"),
         attendance_percent,
+        grading_scale,
         generated_at: chrono::Utc::now().to_rfc3339(),
         subjects,
     })
@@ -0,0 +1,431 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::ensure_teaches;
+use crate::grading::history::set_change_author;
+use crate::grading::scales::scale_for_class;
+use crate::grading::term_lock::{ensure_writable, CORRECTION_RECOVERY};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecoveryRecordInput {
+    pub student_id: Uuid,
+    /// `null` remove a nota de recuperação.
+    pub recovery_score: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertRecoveryRequest {
+    pub term_id: Uuid,
+    pub subject_id: Uuid,
+    pub records: Vec<RecoveryRecordInput>,
+    /// Obrigatória (e só para owner/admin) quando o período está encerrado.
+    pub correction_justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinalExamQuery {
+    pub subject_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FinalExamItem {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinalExamRecordInput {
+    pub student_id: Uuid,
+    /// `null` remove a nota da prova final.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertFinalExamsRequest {
+    pub subject_id: Uuid,
+    pub records: Vec<FinalExamRecordInput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/classes/:class_id/recovery", put(upsert_recovery))
+        .route(
+            "/classes/:class_id/final-exams",
+            get(list_final_exams).put(upsert_final_exams),
+        )
+        .with_state(state)
+}
+
+/// Recuperação paralela: uma nota por período, ligada à nota do período já lançada.
+async fn upsert_recovery(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<UpsertRecoveryRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    set_change_author(&mut tx, user.user_id).await?;
+
+    ensure_writable(
+        &mut tx,
+        &user,
+        Some(req.term_id),
+        class_id,
+        CORRECTION_RECOVERY,
+        req.correction_justification.as_deref(),
+        serde_json::json!({ "subject_id": req.subject_id, "records": req.records }),
+    )
+    .await?;
+    let grading_scale = scale_for_class(&mut *tx, user.tenant_id, class_id).await?;
+
+    for r in &req.records {
+        let recovery_score = r
+            .recovery_score
+            .map(|s| grading_scale.validate_score(s))
+            .transpose()?;
+
+        let res = sqlx::query(
+            r#"
+            UPDATE student_grades
+            SET recovery_score = $6
+            WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3
+              AND term_id = $4 AND subject_id = $5
+            "#,
+        )
+        .bind(user.tenant_id)
+        .bind(class_id)
+        .bind(r.student_id)
+        .bind(req.term_id)
+        .bind(req.subject_id)
+        .bind(recovery_score)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+        if res.rows_affected() == 0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Aluno sem nota lançada neste período/disciplina".into(),
+            ));
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn list_final_exams(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<FinalExamQuery>,
+) -> Result<Json<Vec<FinalExamItem>>, (StatusCode, String)> {
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(query.subject_id)).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT s.id AS student_id, s.name AS student_name, s.registration, f.score::float8 AS score
+        FROM students s
+        LEFT JOIN student_final_exams f
+          ON f.tenant_id = s.tenant_id
+         AND f.class_id = s.class_id
+         AND f.student_id = s.id
+         AND f.subject_id = $3
+        WHERE s.tenant_id = $1 AND s.class_id = $2
+        ORDER BY s.name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.subject_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| FinalExamItem {
+                student_id: r.get("student_id"),
+                student_name: r.get("student_name"),
+                registration: r.get("registration"),
+                score: r.get("score"),
+            })
+            .collect(),
+    ))
+}
+
+async fn upsert_final_exams(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<UpsertFinalExamsRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    ensure_teaches(&state.pool, &user, class_id, Some(req.subject_id)).await?;
+
+    let subject = sqlx::query("SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(req.subject_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if subject.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida".into()));
+    }
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let grading_scale = scale_for_class(&mut *tx, user.tenant_id, class_id).await?;
+
+    for r in &req.records {
+        let student = sqlx::query(
+            r#"SELECT 1 FROM students WHERE tenant_id = $1 AND id = $2 AND class_id = $3"#,
+        )
+        .bind(user.tenant_id)
+        .bind(r.student_id)
+        .bind(class_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        if student.is_none() {
+            return Err((StatusCode::BAD_REQUEST, "Aluno não pertence a esta turma".into()));
+        }
+
+        match r.score {
+            Some(score) => {
+                let score = grading_scale.validate_score(score)?;
+                sqlx::query(
+                    r#"
+                    INSERT INTO student_final_exams (id, tenant_id, class_id, student_id, subject_id, score)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (tenant_id, class_id, student_id, subject_id)
+                    DO UPDATE SET score = EXCLUDED.score, updated_at = NOW()
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(user.tenant_id)
+                .bind(class_id)
+                .bind(r.student_id)
+                .bind(req.subject_id)
+                .bind(score)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+            }
+            None => {
+                sqlx::query(
+                    r#"DELETE FROM student_final_exams
+                       WHERE tenant_id = $1 AND class_id = $2 AND student_id = $3 AND subject_id = $4"#,
+                )
+                .bind(user.tenant_id)
+                .bind(class_id)
+                .bind(r.student_id)
+                .bind(req.subject_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+            }
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query(r#"SELECT 1 FROM classes WHERE tenant_id = $1 AND id = $2"#)
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Turma não encontrada".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turma 7A com o aluno Bruno, Português e o 1º bimestre. A escola aprova com 6, usa a
+    /// regra "max" na recuperação e exige 5 na prova final.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Recuperação").await;
+            let class_id = insert_class(&pool, tenant_id, "7A", "7 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Português").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Bruno", "B1").await;
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::school_settings::routes(pool.clone(), SECRET.into()));
+
+            let (status, _) = call_json(&app, "PUT", "/school/settings", &token, Some(json!({
+                "passing_min_grade": 6, "recovery_rule": "max", "final_exam_min_grade": 5
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+
+            Fixture { pool, app, token, tenant_id, class_id, term_id, subject_id, student_id }
+        }
+
+        async fn set_term_score(&self, score: f64) {
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/gradebook", self.class_id), &self.token, Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "score": score}]
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn set_recovery(&self, score: Option<f64>) -> StatusCode {
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/recovery", self.class_id), &self.token, Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "recovery_score": score}]
+            }))).await;
+            status
+        }
+
+        fn final_exams_path(&self) -> String {
+            format!("/classes/{}/final-exams", self.class_id)
+        }
+
+        async fn set_final_exam(&self, score: f64) {
+            let (status, _) = call_json(&self.app, "PUT", &self.final_exams_path(), &self.token, Some(json!({
+                "subject_id": self.subject_id, "records": [{"student_id": self.student_id, "score": score}]
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn report(&self) -> Value {
+            let path = format!("/classes/{}/students/{}/full-report", self.class_id, self.student_id);
+            let (status, report) = call_json(&self.app, "GET", &path, &self.token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            report
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn recovery_requires_a_term_score() {
+        let f = Fixture::new().await;
+
+        assert_eq!(f.set_recovery(Some(7.0)).await, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn failing_term_score_puts_subject_in_recovery() {
+        let f = Fixture::new().await;
+        f.set_term_score(4.0).await;
+
+        assert_eq!(f.report().await["subjects"][0]["final_status"], "recovery");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn max_rule_keeps_the_better_score() {
+        let f = Fixture::new().await;
+        f.set_term_score(4.0).await;
+
+        // regra "max": vale a maior entre 4 e 7
+        assert_eq!(f.set_recovery(Some(7.0)).await, StatusCode::OK);
+        let report = f.report().await;
+        assert_eq!(report["subjects"][0]["period_grades"][0]["effective_score"], 7.0);
+        assert_eq!(report["subjects"][0]["final_status"], "approved");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn final_exam_decides_once_recovery_is_cleared() {
+        let f = Fixture::new().await;
+        f.set_term_score(4.0).await;
+        assert_eq!(f.set_recovery(Some(7.0)).await, StatusCode::OK);
+        assert_eq!(f.set_recovery(None).await, StatusCode::OK);
+
+        f.set_final_exam(3.0).await;
+        let report = f.report().await;
+        assert_eq!(report["subjects"][0]["period_grades"][0]["effective_score"], 4.0);
+        assert_eq!(report["subjects"][0]["final_exam_score"], 3.0);
+        assert_eq!(report["final_status"], "failed_grade");
+        assert_eq!(report["final_status_label"], "Reprovado por nota");
+
+        let path = format!("{}?subject_id={}", f.final_exams_path(), f.subject_id);
+        let (status, exams) = call_json(&f.app, "GET", &path, &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(exams[0]["score"], 3.0);
+
+        f.cleanup().await;
+    }
+}
@@ -0,0 +1,575 @@
+//! Boletins em PDF: do aluno (período e anual), identidade visual da escola e geração
+//! em lote por turma ou escola inteira (processada por `jobs::report_cards`).
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::ensure_teaches;
+use crate::reports::pdf::{JpegImage, Rgb};
+use crate::reports::report_cards::{load_branding, ReportCardDocument};
+use crate::routes::records::{fetch_student_full_report, fetch_student_term_report, StudentTermReportQuery};
+use crate::state::AppState;
+
+/// Logotipo: JPEG de até 512 KB.
+pub const MAX_LOGO_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Serialize)]
+pub struct ReportBrandingResponse {
+    pub school_name: String,
+    pub header_text: Option<String>,
+    pub brand_color: Option<String>,
+    pub school_city: Option<String>,
+    pub school_signature_name: Option<String>,
+    pub has_logo: bool,
+}
+
+/// Campo ausente mantém o valor atual; texto vazio limpa.
+#[derive(Debug, Deserialize)]
+pub struct UpdateReportBrandingRequest {
+    /// Endereço, CNPJ, autorização de funcionamento etc.
+    pub header_text: Option<String>,
+    /// `#RRGGBB`
+    pub brand_color: Option<String>,
+    pub school_city: Option<String>,
+    pub school_signature_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBatchRequest {
+    /// `term` (boletim do período) ou `full` (boletim anual)
+    pub kind: String,
+    pub term_id: Option<Uuid>,
+    /// Ausente: todas as turmas do ano letivo.
+    pub class_id: Option<Uuid>,
+    /// Obrigatório no boletim anual da escola inteira; nos demais vem da turma ou do período.
+    pub school_year: Option<i32>,
+    /// `pdf` (um arquivo com todos os alunos, padrão) ou `zip` (um PDF por aluno)
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub id: Uuid,
+    pub kind: String,
+    pub term_id: Option<Uuid>,
+    pub class_id: Option<Uuid>,
+    pub school_year: i32,
+    pub format: String,
+    pub status: String,
+    pub total_students: i32,
+    pub processed_students: i32,
+    pub file_size: Option<i64>,
+    pub error: Option<String>,
+    /// Presente quando o lote terminou.
+    pub download_url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/classes/:class_id/students/:student_id/term-report/pdf",
+            get(get_term_report_pdf),
+        )
+        .route(
+            "/classes/:class_id/students/:student_id/full-report/pdf",
+            get(get_full_report_pdf),
+        )
+        .route("/school/report-branding", get(get_branding).put(update_branding))
+        .route(
+            "/school/report-branding/logo",
+            put(upload_logo)
+                .delete(delete_logo)
+                .layer(DefaultBodyLimit::max(MAX_LOGO_BYTES)),
+        )
+        .route("/report-card-batches", post(create_batch).get(list_batches))
+        .route("/report-card-batches/:batch_id", get(get_batch))
+        .route("/report-card-batches/:batch_id/download", get(download_batch))
+        .with_state(state)
+}
+
+async fn get_term_report_pdf(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, student_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<StudentTermReportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
+
+    let report = fetch_student_term_report(&state.pool, user.tenant_id, class_id, student_id, query.term_id).await?;
+    let mut doc = ReportCardDocument::new(load_branding(&state.pool, user.tenant_id).await?);
+    doc.add_term_report(&report);
+
+    let filename = format!("boletim-{}-{}.pdf", report.registration, report.term_name);
+    Ok(pdf_response(&filename, doc.to_bytes()))
+}
+
+async fn get_full_report_pdf(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((class_id, student_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    ensure_teaches(&state.pool, &user, class_id, None).await?;
+
+    let report = fetch_student_full_report(&state.pool, user.tenant_id, class_id, student_id).await?;
+    let mut doc = ReportCardDocument::new(load_branding(&state.pool, user.tenant_id).await?);
+    doc.add_full_report(&report);
+
+    let filename = format!("boletim-anual-{}-{}.pdf", report.registration, report.class_year);
+    Ok(pdf_response(&filename, doc.to_bytes()))
+}
+
+async fn get_branding(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ReportBrandingResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    load_branding_settings(&state.pool, user.tenant_id).await.map(Json)
+}
+
+async fn update_branding(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<UpdateReportBrandingRequest>,
+) -> Result<Json<ReportBrandingResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    // campo ausente mantém o valor atual; texto vazio limpa
+    let brand_color = match req.brand_color.as_deref().map(str::trim) {
+        Some("") => Some(String::new()),
+        Some(color) => {
+            if Rgb::from_hex(color).is_none() {
+                return Err((StatusCode::BAD_REQUEST, "Cor inválida (use #RRGGBB)".into()));
+            }
+            Some(format!("#{}", color.trim_start_matches('#').to_uppercase()))
+        }
+        None => None,
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE tenants
+        SET report_header_text = CASE WHEN $2::text IS NULL THEN report_header_text ELSE NULLIF($2, '') END,
+            report_brand_color = CASE WHEN $3::text IS NULL THEN report_brand_color ELSE NULLIF($3, '') END,
+            school_city = CASE WHEN $4::text IS NULL THEN school_city ELSE NULLIF($4, '') END,
+            school_signature_name = CASE WHEN $5::text IS NULL THEN school_signature_name ELSE NULLIF($5, '') END
+        WHERE id = $1
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(req.header_text.map(|v| v.trim().to_string()))
+    .bind(brand_color)
+    .bind(req.school_city.map(|v| v.trim().to_string()))
+    .bind(req.school_signature_name.map(|v| v.trim().to_string()))
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_branding_settings(&state.pool, user.tenant_id).await.map(Json)
+}
+
+/// Logotipo em JPEG, enviado como corpo cru (`Content-Type: image/jpeg`).
+async fn upload_logo(
+    State(state): State<AppState>,
+    user: AuthUser,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ReportBrandingResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with("image/jpeg") || JpegImage::parse(body.to_vec()).is_none() {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Logotipo deve ser uma imagem JPEG".into(),
+        ));
+    }
+
+    sqlx::query("UPDATE tenants SET report_logo = $2 WHERE id = $1")
+        .bind(user.tenant_id)
+        .bind(body.to_vec())
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_branding_settings(&state.pool, user.tenant_id).await.map(Json)
+}
+
+async fn delete_logo(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<ReportBrandingResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    sqlx::query("UPDATE tenants SET report_logo = NULL WHERE id = $1")
+        .bind(user.tenant_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_branding_settings(&state.pool, user.tenant_id).await.map(Json)
+}
+
+async fn create_batch(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<CreateBatchRequest>,
+) -> Result<Json<BatchResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let kind = match req.kind.trim().to_lowercase().as_str() {
+        "term" => "term",
+        "full" => "full",
+        _ => return Err((StatusCode::BAD_REQUEST, "Tipo inválido (use term ou full)".into())),
+    };
+    let format = match req.format.as_deref().map(|f| f.trim().to_lowercase()).as_deref() {
+        None | Some("pdf") => "pdf",
+        Some("zip") => "zip",
+        _ => return Err((StatusCode::BAD_REQUEST, "Formato inválido (use pdf ou zip)".into())),
+    };
+
+    let class_year: Option<i32> = match req.class_id {
+        Some(class_id) => Some(
+            sqlx::query_scalar("SELECT year FROM classes WHERE tenant_id = $1 AND id = $2")
+                .bind(user.tenant_id)
+                .bind(class_id)
+                .fetch_optional(&state.pool)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+                .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))?,
+        ),
+        None => None,
+    };
+    let term_year: Option<i32> = match (kind, req.term_id) {
+        ("term", None) => {
+            return Err((StatusCode::BAD_REQUEST, "Informe o período (term_id)".into()));
+        }
+        ("term", Some(term_id)) => Some(
+            sqlx::query_scalar("SELECT school_year FROM academic_terms WHERE tenant_id = $1 AND id = $2")
+                .bind(user.tenant_id)
+                .bind(term_id)
+                .fetch_optional(&state.pool)
+                .await
+                .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+                .ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?,
+        ),
+        _ => None,
+    };
+    let school_year = class_year
+        .or(term_year)
+        .or(req.school_year)
+        .ok_or((StatusCode::BAD_REQUEST, "Informe o ano letivo (school_year)".into()))?;
+    if class_year.is_some_and(|y| term_year.is_some_and(|t| t != y)) {
+        return Err((StatusCode::BAD_REQUEST, "Período de outro ano letivo da turma".into()));
+    }
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO report_card_batches
+          (id, tenant_id, requested_by, kind, term_id, class_id, school_year, format)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(user.user_id)
+    .bind(kind)
+    .bind(if kind == "term" { req.term_id } else { None })
+    .bind(req.class_id)
+    .bind(school_year)
+    .bind(format)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_batch(&state.pool, user.tenant_id, id).await.map(Json)
+}
+
+async fn list_batches(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<Vec<BatchResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, kind, term_id, class_id, school_year, format, status, total_students,
+               processed_students, file_size, error, created_at, finished_at
+        FROM report_card_batches
+        WHERE tenant_id = $1
+        ORDER BY created_at DESC
+        LIMIT 100
+        "#,
+    )
+    .bind(user.tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(map_batch).collect()))
+}
+
+async fn get_batch(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BatchResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    load_batch(&state.pool, user.tenant_id, batch_id).await.map(Json)
+}
+
+async fn download_batch(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(batch_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let row = sqlx::query(
+        r#"SELECT status, format, file_path
+           FROM report_card_batches
+           WHERE tenant_id = $1 AND id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(batch_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Lote não encontrado".into()))?;
+
+    let status: String = row.get("status");
+    if status != "done" {
+        return Err((StatusCode::CONFLICT, "Lote ainda não concluído".into()));
+    }
+    let format: String = row.get("format");
+    let file_path: String = row.get("file_path");
+    let bytes = tokio::fs::read(&file_path)
+        .await
+        .map_err(|_| (StatusCode::NOT_FOUND, "Arquivo do lote não encontrado".into()))?;
+
+    let content_type = if format == "zip" { "application/zip" } else { "application/pdf" };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"boletins-{batch_id}.{format}\""),
+            ),
+        ],
+        bytes,
+    ))
+}
+
+async fn load_batch(pool: &PgPool, tenant_id: Uuid, batch_id: Uuid) -> Result<BatchResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, kind, term_id, class_id, school_year, format, status, total_students,
+               processed_students, file_size, error, created_at, finished_at
+        FROM report_card_batches
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(batch_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Lote não encontrado".into()))?;
+
+    Ok(map_batch(&row))
+}
+
+fn map_batch(r: &sqlx::postgres::PgRow) -> BatchResponse {
+    let id: Uuid = r.get("id");
+    let status: String = r.get("status");
+    BatchResponse {
+        id,
+        kind: r.get("kind"),
+        term_id: r.get("term_id"),
+        class_id: r.get("class_id"),
+        school_year: r.get("school_year"),
+        format: r.get("format"),
+        download_url: (status == "done").then(|| format!("/report-card-batches/{id}/download")),
+        status,
+        total_students: r.get("total_students"),
+        processed_students: r.get("processed_students"),
+        file_size: r.get("file_size"),
+        error: r.get("error"),
+        created_at: r.get("created_at"),
+        finished_at: r.get("finished_at"),
+    }
+}
+
+async fn load_branding_settings(pool: &PgPool, tenant_id: Uuid) -> Result<ReportBrandingResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT name, report_header_text, report_brand_color, school_city, school_signature_name,
+               report_logo IS NOT NULL AS has_logo
+        FROM tenants
+        WHERE id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
+
+    Ok(ReportBrandingResponse {
+        school_name: row.get("name"),
+        header_text: row.get("report_header_text"),
+        brand_color: row.get("report_brand_color"),
+        school_city: row.get("school_city"),
+        school_signature_name: row.get("school_signature_name"),
+        has_logo: row.get("has_logo"),
+    })
+}
+
+fn pdf_response(filename: &str, bytes: Vec<u8>) -> impl IntoResponse {
+    let filename: String = filename
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+        .collect();
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, format!("inline; filename=\"{filename}\"")),
+        ],
+        bytes,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call, call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::json;
+
+    /// Turma 8A com Júlia e Otávio, Geografia e o 1º bimestre.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        student_ids: Vec<Uuid>,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Boletim").await;
+            let class_id = insert_class(&pool, tenant_id, "8A", "8 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            insert_subject(&pool, tenant_id, "Geografia").await;
+            let student_ids = vec![
+                insert_student(&pool, tenant_id, class_id, "Júlia", "J1").await,
+                insert_student(&pool, tenant_id, class_id, "Otávio", "O1").await,
+            ];
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+
+            Fixture { pool, app, owner, tenant_id, class_id, term_id, student_ids }
+        }
+
+        fn pdf_path(&self) -> String {
+            format!("/classes/{}/students/{}/term-report/pdf?term_id={}", self.class_id, self.student_ids[0], self.term_id)
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn branding_rejects_invalid_color_and_normalizes_hex() {
+        let f = Fixture::new().await;
+        let (status, _) = call_json(&f.app, "PUT", "/school/report-branding", &f.owner, Some(json!({"brand_color": "verde"}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, branding) = call_json(&f.app, "PUT", "/school/report-branding", &f.owner, Some(json!({
+            "header_text": "Rua das Flores, 100", "brand_color": "#1f6f43", "school_city": "Curitiba"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(branding["brand_color"], "#1F6F43");
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn term_report_pdf_is_rendered_for_staff() {
+        let f = Fixture::new().await;
+        let (status, pdf) = call(&f.app, "GET", &f.pdf_path(), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn term_report_pdf_is_forbidden_to_teachers() {
+        let f = Fixture::new().await;
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+        let (status, _) = call(&f.app, "GET", &f.pdf_path(), &teacher, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn class_batch_zips_one_pdf_per_student() {
+        let f = Fixture::new().await;
+        let (status, batch) = call_json(&f.app, "POST", "/report-card-batches", &f.owner, Some(json!({
+            "kind": "term", "term_id": f.term_id, "class_id": f.class_id, "format": "zip"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(batch["status"], "queued");
+        let batch_path = format!("/report-card-batches/{}", batch["id"].as_str().unwrap());
+        // ainda não processado
+        let (status, _) = call(&f.app, "GET", &format!("{batch_path}/download"), &f.owner, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let dir = std::env::temp_dir().join("report-cards-test");
+        while crate::jobs::report_cards::process_next(&f.pool, dir.to_str().unwrap()).await.unwrap().is_some() {}
+
+        let (_, batch) = call_json(&f.app, "GET", &batch_path, &f.owner, None).await;
+        assert_eq!(batch["status"], "done");
+        assert_eq!(batch["total_students"], 2);
+        assert_eq!(batch["processed_students"], 2);
+        assert_eq!(batch["download_url"], format!("{batch_path}/download"));
+        let (status, zip_bytes) = call(&f.app, "GET", &format!("{batch_path}/download"), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let archive = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes)).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, vec!["8A/Júlia-J1.pdf", "8A/Otávio-O1.pdf"]);
+        f.cleanup().await;
+    }
+}
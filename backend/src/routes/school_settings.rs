@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::Row;
 
 use crate::auth::jwt::AuthUser;
+use crate::grading::promotion::{normalize_justified_absence_rule, normalize_recovery_rule};
+use crate::grading::scales::GradingScale;
+use crate::grading::{normalize_formula, recompute_term_scores};
+use crate::routes::guardian_alerts::alert_low_grades;
 use crate::state::AppState;
 
 #[derive(Debug, Serialize)]
@@ -11,11 +15,36 @@ pub struct SchoolSettingsResponse {
     pub school_name: String,
     pub school_code: String,
     pub passing_min_grade: f64,
+    pub grade_formula: String,
+    pub recovery_rule: String,
+    pub final_exam_min_grade: f64,
+    pub min_attendance_percent: f64,
+    pub justified_absence_rule: String,
+    pub alert_each_absence: bool,
+    pub alert_consecutive_absences: i32,
+    pub alert_low_grades: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateSchoolSettingsRequest {
+    /// Média mínima na escala padrão; séries com escala própria usam a média da escala.
     pub passing_min_grade: f64,
+    /// `weighted` (média ponderada) ou `arithmetic`; ausente mantém a atual.
+    pub grade_formula: Option<String>,
+    /// `max`, `average` ou `replace`: como a recuperação substitui a nota do período.
+    pub recovery_rule: Option<String>,
+    /// Na escala padrão; convertida para a escala de cada série ao fechar o ano.
+    pub final_exam_min_grade: Option<f64>,
+    /// Frequência mínima (%) para aprovação, geral e por disciplina.
+    pub min_attendance_percent: Option<f64>,
+    /// `excluded`, `present` ou `absence`: como a falta justificada entra na frequência.
+    pub justified_absence_rule: Option<String>,
+    /// Alertar os responsáveis a cada falta registrada.
+    pub alert_each_absence: Option<bool>,
+    /// Dias seguidos de falta que disparam alerta (0 desliga).
+    pub alert_consecutive_absences: Option<i32>,
+    /// Alertar quando a nota do período ficar abaixo da média.
+    pub alert_low_grades: Option<bool>,
 }
 
 pub fn routes(pool: sqlx::PgPool, jwt_secret: String) -> Router {
@@ -33,7 +62,10 @@ async fn get_school_settings(
 
     let row = sqlx::query(
         r#"
-        SELECT id, name, slug, passing_min_grade::float8 AS passing_min_grade
+        SELECT id, name, slug, passing_min_grade::float8 AS passing_min_grade, grade_formula,
+               recovery_rule, final_exam_min_grade::float8 AS final_exam_min_grade,
+               min_attendance_percent::float8 AS min_attendance_percent, justified_absence_rule,
+               alert_each_absence, alert_consecutive_absences, alert_low_grades
         FROM tenants
         WHERE id = $1
         "#,
@@ -50,6 +82,14 @@ async fn get_school_settings(
         school_name: row.get("name"),
         school_code: row.get("slug"),
         passing_min_grade: row.get("passing_min_grade"),
+        grade_formula: row.get("grade_formula"),
+        recovery_rule: row.get("recovery_rule"),
+        final_exam_min_grade: row.get("final_exam_min_grade"),
+        min_attendance_percent: row.get("min_attendance_percent"),
+        justified_absence_rule: row.get("justified_absence_rule"),
+        alert_each_absence: row.get("alert_each_absence"),
+        alert_consecutive_absences: row.get("alert_consecutive_absences"),
+        alert_low_grades: row.get("alert_low_grades"),
     }))
 }
 
@@ -60,24 +100,110 @@ async fn update_school_settings(
 ) -> Result<Json<SchoolSettingsResponse>, (StatusCode, String)> {
     user.require_any_role(&["owner", "admin"])?;
 
-    if !(0.0..=10.0).contains(&req.passing_min_grade) {
-        return Err((StatusCode::BAD_REQUEST, "Média mínima deve estar entre 0 e 10".into()));
+    let default_scale = GradingScale::default_numeric(req.passing_min_grade);
+    let passing_min_grade = default_scale.validate_setting("Média mínima", req.passing_min_grade)?;
+    let final_exam_min_grade = req
+        .final_exam_min_grade
+        .map(|g| default_scale.validate_setting("Média da prova final", g))
+        .transpose()?;
+
+    let grade_formula = req.grade_formula.as_deref().map(normalize_formula).transpose()?;
+    let recovery_rule = req.recovery_rule.as_deref().map(normalize_recovery_rule).transpose()?;
+    let justified_absence_rule = req
+        .justified_absence_rule
+        .as_deref()
+        .map(normalize_justified_absence_rule)
+        .transpose()?;
+    if req
+        .min_attendance_percent
+        .is_some_and(|p| !(0.0..=100.0).contains(&p))
+    {
+        return Err((StatusCode::BAD_REQUEST, "Frequência mínima deve estar entre 0 e 100".into()));
+    }
+    if req
+        .alert_consecutive_absences
+        .is_some_and(|n| !(0..=30).contains(&n))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Faltas seguidas para alerta devem estar entre 0 e 30".into(),
+        ));
     }
 
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let previous_formula: Option<String> =
+        sqlx::query_scalar("SELECT grade_formula FROM tenants WHERE id = $1")
+            .bind(user.tenant_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
     let row = sqlx::query(
         r#"
         UPDATE tenants
-        SET passing_min_grade = $2
+        SET passing_min_grade = $2,
+            grade_formula = COALESCE($3, grade_formula),
+            recovery_rule = COALESCE($4, recovery_rule),
+            final_exam_min_grade = COALESCE($5, final_exam_min_grade),
+            min_attendance_percent = COALESCE($6, min_attendance_percent),
+            justified_absence_rule = COALESCE($7, justified_absence_rule),
+            alert_each_absence = COALESCE($8, alert_each_absence),
+            alert_consecutive_absences = COALESCE($9, alert_consecutive_absences),
+            alert_low_grades = COALESCE($10, alert_low_grades)
         WHERE id = $1
-        RETURNING id, name, slug, passing_min_grade::float8 AS passing_min_grade
+        RETURNING id, name, slug, passing_min_grade::float8 AS passing_min_grade, grade_formula,
+                  recovery_rule, final_exam_min_grade::float8 AS final_exam_min_grade,
+                  min_attendance_percent::float8 AS min_attendance_percent, justified_absence_rule,
+                  alert_each_absence, alert_consecutive_absences, alert_low_grades
         "#,
     )
     .bind(user.tenant_id)
-    .bind(req.passing_min_grade)
-    .fetch_optional(&state.pool)
+    .bind(passing_min_grade)
+    .bind(grade_formula)
+    .bind(recovery_rule)
+    .bind(final_exam_min_grade)
+    .bind(req.min_attendance_percent)
+    .bind(justified_absence_rule)
+    .bind(req.alert_each_absence)
+    .bind(req.alert_consecutive_absences)
+    .bind(req.alert_low_grades)
+    .fetch_optional(&mut *tx)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
+    // trocar a fórmula muda as médias já calculadas pelas avaliações, mas só nos períodos
+    // abertos: os encerrados (e com eles os anos anteriores) ficam como fecharam
+    if grade_formula.is_some() && previous_formula.as_deref() != grade_formula {
+        let groups = sqlx::query(
+            r#"
+            SELECT DISTINCT a.class_id, a.term_id, a.subject_id
+            FROM assessments a
+            JOIN academic_terms t ON t.id = a.term_id AND t.tenant_id = a.tenant_id
+            WHERE a.tenant_id = $1
+              AND t.closed_at IS NULL
+            "#,
+        )
+        .bind(user.tenant_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+        for g in groups {
+            let (class_id, term_id, subject_id) = (g.get("class_id"), g.get("term_id"), g.get("subject_id"));
+            recompute_term_scores(&mut tx, user.tenant_id, class_id, term_id, subject_id).await?;
+            alert_low_grades(&mut tx, user.tenant_id, class_id, term_id, subject_id).await?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
     let row = row.ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
 
     Ok(Json(SchoolSettingsResponse {
@@ -85,5 +211,13 @@ async fn update_school_settings(
         school_name: row.get("name"),
         school_code: row.get("slug"),
         passing_min_grade: row.get("passing_min_grade"),
+        grade_formula: row.get("grade_formula"),
+        recovery_rule: row.get("recovery_rule"),
+        final_exam_min_grade: row.get("final_exam_min_grade"),
+        min_attendance_percent: row.get("min_attendance_percent"),
+        justified_absence_rule: row.get("justified_absence_rule"),
+        alert_each_absence: row.get("alert_each_absence"),
+        alert_consecutive_absences: row.get("alert_consecutive_absences"),
+        alert_low_grades: row.get("alert_low_grades"),
     }))
 }
@@ -3,6 +3,7 @@ use serde::Serialize;
 use sqlx::Row;
 use uuid::Uuid;
 
+use crate::auth::features::enabled_features;
 use crate::auth::jwt::AuthUser;
 use crate::state::AppState;
 
@@ -12,6 +13,7 @@ pub struct SessionMeResponse {
     pub school_name: String,
     pub school_code: String,
     pub role: String,
+    pub enabled_modules: Vec<String>,
 }
 
 pub fn routes(pool: sqlx::PgPool, jwt_secret: String) -> Router {
@@ -36,11 +38,13 @@ async fn me(
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
     let row = row.ok_or((StatusCode::NOT_FOUND, "Escola não encontrada".into()))?;
+    let enabled_modules = enabled_features(&state.pool, user.tenant_id).await?;
 
     Ok(Json(SessionMeResponse {
         tenant_id: user.tenant_id,
         school_name: row.get("name"),
         school_code: row.get("slug"),
         role: user.role,
+        enabled_modules,
     }))
 }
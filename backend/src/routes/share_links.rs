@@ -0,0 +1,555 @@
+//! Links públicos de boletim (`report_share_links`): token opaco guardado como hash,
+//! com expiração, revogação e registro de acessos (`report_share_link_accesses`).
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use uuid::Uuid;
+
+use crate::auth::features::{ensure_feature_enabled, FEATURE_PUBLIC_REPORTS};
+use crate::auth::jwt::AuthUser;
+use crate::auth::oidc::random_token;
+use crate::auth::teaching::restricted_teacher;
+use crate::routes::records::ShareReportResponse;
+use crate::state::AppState;
+
+pub const REPORT_GRADEBOOK: &str = "gradebook";
+pub const REPORT_TERM: &str = "term";
+pub const REPORT_FULL: &str = "full";
+
+const DEFAULT_EXPIRES_DAYS: i64 = 30;
+const MAX_EXPIRES_DAYS: i64 = 365;
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// O que o link dá acesso.
+pub struct ShareTarget {
+    pub report_type: &'static str,
+    pub class_id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Option<Uuid>,
+    pub subject_id: Option<Uuid>,
+}
+
+/// Link válido encontrado pelo token.
+pub struct ResolvedShareLink {
+    pub tenant_id: Uuid,
+    pub class_id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Option<Uuid>,
+    pub subject_id: Option<Uuid>,
+}
+
+/// Origem de um acesso público, para o registro.
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// IP da conexão. `X-Forwarded-For` só vale quando a conexão vem de um proxy listado em
+    /// `TRUSTED_PROXIES` (IPs separados por vírgula).
+    pub fn from_request(headers: &HeaderMap, addr: Option<ConnectInfo<SocketAddr>>) -> Self {
+        let peer = addr.map(|ConnectInfo(a)| a.ip());
+        let ip = client_ip(headers, peer, &trusted_proxies()).map(|ip| ip.to_string());
+        let user_agent = headers
+            .get(header::USER_AGENT)
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).chars().take(MAX_USER_AGENT_LEN).collect());
+        Self { ip, user_agent }
+    }
+}
+
+fn trusted_proxies() -> Vec<IpAddr> {
+    env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|v| v.trim().parse().ok())
+        .collect()
+}
+
+/// Atrás de proxy confiável, o cliente é o último endereço da cadeia `X-Forwarded-For` que não
+/// é um dos proxies (os anteriores podem ter sido forjados pelo próprio cliente).
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trusted: &[IpAddr]) -> Option<IpAddr> {
+    let forwarded = peer
+        .filter(|ip| trusted.contains(ip))
+        .and_then(|_| headers.get("x-forwarded-for"))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|chain| {
+            chain
+                .rsplit(',')
+                .filter_map(|v| v.trim().parse::<IpAddr>().ok())
+                .find(|ip| !trusted.contains(ip))
+        });
+    forwarded.or(peer)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListShareLinksQuery {
+    /// Inclui links expirados e revogados.
+    pub include_inactive: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareLinkResponse {
+    pub id: Uuid,
+    pub report_type: String,
+    pub student_id: Uuid,
+    pub class_id: Uuid,
+    pub term_id: Option<Uuid>,
+    pub term_name: Option<String>,
+    pub subject_id: Option<Uuid>,
+    pub subject_name: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_by_name: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub revoked: bool,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub active: bool,
+    pub access_count: i64,
+    pub last_accessed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareLinkAccessResponse {
+    pub accessed_at: NaiveDateTime,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/students/:student_id/share-links", get(list_student_share_links))
+        .route("/share-links/:link_id/revoke", post(revoke_share_link))
+        .route("/share-links/:link_id/accesses", get(list_share_link_accesses))
+        .with_state(state)
+}
+
+/// Cria o link e devolve o token em claro; só o hash fica no banco.
+pub async fn create_share_link(
+    pool: &PgPool,
+    user: &AuthUser,
+    target: ShareTarget,
+    expires_days: Option<i64>,
+) -> Result<ShareReportResponse, (StatusCode, String)> {
+    let expires_days = expires_days.unwrap_or(DEFAULT_EXPIRES_DAYS).clamp(1, MAX_EXPIRES_DAYS);
+    let expires_at = chrono::Utc::now() + chrono::Duration::days(expires_days);
+    let id = Uuid::new_v4();
+    let token = random_token();
+
+    sqlx::query(
+        r#"
+        INSERT INTO report_share_links
+          (id, tenant_id, token_hash, report_type, student_id, class_id, term_id, subject_id,
+           created_by, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(token_hash(&token))
+    .bind(target.report_type)
+    .bind(target.student_id)
+    .bind(target.class_id)
+    .bind(target.term_id)
+    .bind(target.subject_id)
+    .bind(user.user_id)
+    .bind(expires_at.naive_utc())
+    .execute(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(ShareReportResponse {
+        id,
+        token,
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+/// Valida o token do link público (tipo, expiração, revogação e o recurso `public_reports`
+/// da escola) e só então registra o acesso.
+pub async fn resolve_share_link(
+    pool: &PgPool,
+    token: &str,
+    report_type: &str,
+    client: ClientInfo,
+) -> Result<ResolvedShareLink, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, tenant_id, class_id, student_id, term_id, subject_id
+        FROM report_share_links
+        WHERE token_hash = $1
+          AND report_type = $2
+          AND NOT revoked
+          AND expires_at > (NOW() AT TIME ZONE 'UTC')
+        "#,
+    )
+    .bind(token_hash(token.trim()))
+    .bind(report_type)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Link inválido, expirado ou revogado".into()))?;
+
+    let tenant_id: Uuid = row.get("tenant_id");
+    ensure_feature_enabled(pool, tenant_id, FEATURE_PUBLIC_REPORTS).await?;
+
+    let link_id: Uuid = row.get("id");
+    sqlx::query("INSERT INTO report_share_link_accesses (link_id, ip, user_agent) VALUES ($1, $2, $3)")
+        .bind(link_id)
+        .bind(client.ip)
+        .bind(client.user_agent)
+        .execute(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(ResolvedShareLink {
+        tenant_id,
+        class_id: row.get("class_id"),
+        student_id: row.get("student_id"),
+        term_id: row.get("term_id"),
+        subject_id: row.get("subject_id"),
+    })
+}
+
+async fn list_student_share_links(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Query(query): Query<ListShareLinksQuery>,
+) -> Result<Json<Vec<ShareLinkResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let links = load_share_links(
+        &state.pool,
+        user.tenant_id,
+        Some(student_id),
+        None,
+        restricted_teacher(&user),
+        query.include_inactive.unwrap_or(false),
+    )
+    .await?;
+    Ok(Json(links))
+}
+
+async fn revoke_share_link(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(link_id): Path<Uuid>,
+) -> Result<Json<ShareLinkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    // professor só revoga os links que ele mesmo criou
+    let result = sqlx::query(
+        r#"
+        UPDATE report_share_links
+        SET revoked = TRUE,
+            revoked_by = $3,
+            revoked_at = COALESCE(revoked_at, NOW())
+        WHERE tenant_id = $1
+          AND id = $2
+          AND ($4::uuid IS NULL OR created_by = $4)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(link_id)
+    .bind(user.user_id)
+    .bind(restricted_teacher(&user))
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if result.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Link não encontrado".into()));
+    }
+
+    load_share_links(&state.pool, user.tenant_id, None, Some(link_id), None, true)
+        .await?
+        .into_iter()
+        .next()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "Link não encontrado".into()))
+}
+
+async fn list_share_link_accesses(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(link_id): Path<Uuid>,
+) -> Result<Json<Vec<ShareLinkAccessResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+
+    let exists: Option<i32> = sqlx::query_scalar(
+        r#"SELECT 1 FROM report_share_links
+           WHERE tenant_id = $1 AND id = $2 AND ($3::uuid IS NULL OR created_by = $3)"#,
+    )
+    .bind(user.tenant_id)
+    .bind(link_id)
+    .bind(restricted_teacher(&user))
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if exists.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Link não encontrado".into()));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT accessed_at, ip, user_agent
+        FROM report_share_link_accesses
+        WHERE link_id = $1
+        ORDER BY accessed_at DESC
+        LIMIT 500
+        "#,
+    )
+    .bind(link_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| ShareLinkAccessResponse {
+                accessed_at: r.get("accessed_at"),
+                ip: r.get("ip"),
+                user_agent: r.get("user_agent"),
+            })
+            .collect(),
+    ))
+}
+
+async fn load_share_links(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Option<Uuid>,
+    link_id: Option<Uuid>,
+    created_by: Option<Uuid>,
+    include_inactive: bool,
+) -> Result<Vec<ShareLinkResponse>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT l.id, l.report_type, l.student_id, l.class_id, l.term_id, t.name AS term_name,
+               l.subject_id, s.name AS subject_name, l.created_by, u.full_name AS created_by_name,
+               l.created_at, l.expires_at, l.revoked, l.revoked_at,
+               (NOT l.revoked AND l.expires_at > (NOW() AT TIME ZONE 'UTC')) AS active,
+               (SELECT COUNT(*) FROM report_share_link_accesses a WHERE a.link_id = l.id) AS access_count,
+               (SELECT MAX(a.accessed_at) FROM report_share_link_accesses a WHERE a.link_id = l.id) AS last_accessed_at
+        FROM report_share_links l
+        LEFT JOIN academic_terms t ON t.id = l.term_id
+        LEFT JOIN subjects s ON s.id = l.subject_id
+        LEFT JOIN users u ON u.id = l.created_by AND u.tenant_id = l.tenant_id
+        WHERE l.tenant_id = $1
+          AND ($2::uuid IS NULL OR l.student_id = $2)
+          AND ($3::uuid IS NULL OR l.id = $3)
+          AND ($4::uuid IS NULL OR l.created_by = $4)
+          AND ($5 OR (NOT l.revoked AND l.expires_at > (NOW() AT TIME ZONE 'UTC')))
+        ORDER BY l.created_at DESC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .bind(link_id)
+    .bind(created_by)
+    .bind(include_inactive)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| ShareLinkResponse {
+            id: r.get("id"),
+            report_type: r.get("report_type"),
+            student_id: r.get("student_id"),
+            class_id: r.get("class_id"),
+            term_id: r.get("term_id"),
+            term_name: r.get("term_name"),
+            subject_id: r.get("subject_id"),
+            subject_name: r.get("subject_name"),
+            created_by: r.get("created_by"),
+            created_by_name: r.get("created_by_name"),
+            created_at: r.get("created_at"),
+            expires_at: r.get("expires_at"),
+            revoked: r.get("revoked"),
+            revoked_at: r.get("revoked_at"),
+            active: r.get("active"),
+            access_count: r.get("access_count"),
+            last_accessed_at: r.get("last_accessed_at"),
+        })
+        .collect())
+}
+
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use axum::{body::Body, http::Request};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    const PEER: &str = "198.51.100.20";
+
+    /// Aluna Nina na 9A, com o 1º bimestre.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        term_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Links").await;
+            let class_id = insert_class(&pool, tenant_id, "9A", "9 ano", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Nina", "N1").await;
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into()).merge(crate::routes::records::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, owner, tenant_id, class_id, term_id, student_id }
+        }
+
+        /// Cria um link do boletim do período e devolve `(token, id)`.
+        async fn share_term_report(&self) -> (String, String) {
+            let share_path = format!(
+                "/classes/{}/students/{}/term-report/share?term_id={}",
+                self.class_id, self.student_id, self.term_id
+            );
+            let (status, shared) = call_json(&self.app, "POST", &share_path, &self.owner, Some(json!({"expires_days": 7}))).await;
+            assert_eq!(status, StatusCode::OK);
+            (shared["token"].as_str().unwrap().to_string(), shared["id"].as_str().unwrap().to_string())
+        }
+
+        /// Acesso anônimo vindo de `PEER`, com um `X-Forwarded-For` que o próprio cliente inventou.
+        async fn open_public(&self, path: &str) -> StatusCode {
+            let mut req = Request::builder()
+                .uri(path)
+                .header("x-forwarded-for", "203.0.113.7")
+                .header("user-agent", "Navegador do responsável")
+                .body(Body::empty())
+                .unwrap();
+            req.extensions_mut()
+                .insert(ConnectInfo(SocketAddr::new(PEER.parse().unwrap(), 40000)));
+            self.app.clone().oneshot(req).await.unwrap().status()
+        }
+
+        fn links_path(&self) -> String {
+            format!("/students/{}/share-links", self.student_id)
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_unless_peer_is_a_trusted_proxy() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7, 192.0.2.50, 10.0.0.1".parse().unwrap());
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        let proxies: Vec<IpAddr> = vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+
+        assert_eq!(client_ip(&headers, Some(peer), &[]), Some(peer));
+        // o primeiro endereço pode ter sido forjado; vale o último antes dos proxies
+        assert_eq!(client_ip(&headers, Some(peer), &proxies), Some("192.0.2.50".parse().unwrap()));
+        assert_eq!(client_ip(&HeaderMap::new(), Some(peer), &proxies), Some(peer));
+        assert_eq!(client_ip(&headers, None, &proxies), None);
+    }
+
+    #[tokio::test]
+    async fn public_link_only_opens_its_own_report() {
+        let f = Fixture::new().await;
+        let (token, _) = f.share_term_report().await;
+
+        // token JWT de sessão não serve como link público
+        assert_eq!(f.open_public(&format!("/public/student-term-report?token={}", f.owner)).await, StatusCode::UNAUTHORIZED);
+        // link de boletim do período não abre o boletim anual
+        assert_eq!(f.open_public(&format!("/public/student-full-report?token={token}")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(f.open_public(&format!("/public/student-term-report?token={token}")).await, StatusCode::OK);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn public_accesses_are_logged_with_connection_ip() {
+        let f = Fixture::new().await;
+        let (token, link_id) = f.share_term_report().await;
+        let public_path = format!("/public/student-term-report?token={token}");
+        assert_eq!(f.open_public(&public_path).await, StatusCode::OK);
+        assert_eq!(f.open_public(&public_path).await, StatusCode::OK);
+
+        let (status, links) = call_json(&f.app, "GET", &f.links_path(), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(links.as_array().unwrap().len(), 1);
+        assert_eq!(links[0]["report_type"], "term");
+        assert_eq!(links[0]["term_name"], "1º Bimestre");
+        assert_eq!(links[0]["access_count"], 2);
+
+        let (_, accesses) = call_json(&f.app, "GET", &format!("/share-links/{link_id}/accesses"), &f.owner, None).await;
+        assert_eq!(accesses.as_array().unwrap().len(), 2);
+        assert_eq!(accesses[0]["ip"], PEER);
+        assert_eq!(accesses[0]["user_agent"], "Navegador do responsável");
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn disabled_public_reports_refuse_links_without_logging() {
+        let f = Fixture::new().await;
+        let (token, link_id) = f.share_term_report().await;
+        sqlx::query("INSERT INTO tenant_features (tenant_id, feature_code, enabled) VALUES ($1, $2, FALSE)")
+            .bind(f.tenant_id)
+            .bind(FEATURE_PUBLIC_REPORTS)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(f.open_public(&format!("/public/student-term-report?token={token}")).await, StatusCode::FORBIDDEN);
+        let accesses: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM report_share_link_accesses WHERE link_id = $1::uuid")
+            .bind(&link_id)
+            .fetch_one(&f.pool)
+            .await
+            .unwrap();
+        assert_eq!(accesses, 0);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn revoked_link_stops_working_and_leaves_active_list() {
+        let f = Fixture::new().await;
+        let (token, link_id) = f.share_term_report().await;
+
+        let (status, revoked) = call_json(&f.app, "POST", &format!("/share-links/{link_id}/revoke"), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(revoked["revoked"], true);
+        assert_eq!(revoked["active"], false);
+        assert_eq!(f.open_public(&format!("/public/student-term-report?token={token}")).await, StatusCode::UNAUTHORIZED);
+
+        let (_, links) = call_json(&f.app, "GET", &f.links_path(), &f.owner, None).await;
+        assert!(links.as_array().unwrap().is_empty());
+        let (_, links) = call_json(&f.app, "GET", &format!("{}?include_inactive=true", f.links_path()), &f.owner, None).await;
+        assert_eq!(links.as_array().unwrap().len(), 1);
+        f.cleanup().await;
+    }
+}
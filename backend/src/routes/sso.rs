@@ -0,0 +1,792 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::identity::find_or_create_tenant_identity;
+use crate::auth::jwt::{AuthUser, SCOPE_TENANT_SSO};
+use crate::auth::oidc::{
+    authorization_url, discover, email_domain_allowed, exchange_code, http_client, pkce_challenge,
+    random_token, verify_id_token,
+};
+use crate::routes::auth::{make_scoped_jwt, record_login, AuthResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+pub struct SsoConfigResponse {
+    pub issuer: String,
+    pub client_id: String,
+    pub has_client_secret: bool,
+    pub redirect_uri: String,
+    pub allowed_domains: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertSsoConfigRequest {
+    pub issuer: String,
+    pub client_id: String,
+    /// Em branco mantém o segredo atual.
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub allowed_domains: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SsoStartResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoCallbackRequest {
+    pub state: String,
+    pub code: String,
+}
+
+struct SsoConfig {
+    issuer: String,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    allowed_domains: Vec<String>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/school/sso",
+            get(get_sso_config).put(upsert_sso_config).delete(delete_sso_config),
+        )
+        .route("/auth/sso/:school_code/start", get(start_sso))
+        .route("/auth/sso/callback", post(sso_callback))
+        .with_state(state)
+}
+
+async fn get_sso_config(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<SsoConfigResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+    load_sso_config_response(&state.pool, user.tenant_id).await.map(Json)
+}
+
+async fn upsert_sso_config(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<UpsertSsoConfigRequest>,
+) -> Result<Json<SsoConfigResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let issuer = req.issuer.trim().trim_end_matches('/').to_string();
+    if !issuer.starts_with("https://") {
+        return Err((StatusCode::BAD_REQUEST, "Issuer precisa usar https".into()));
+    }
+    let client_id = req.client_id.trim().to_string();
+    if client_id.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Client ID é obrigatório".into()));
+    }
+    let redirect_uri = req.redirect_uri.trim().to_string();
+    if url::Url::parse(&redirect_uri).is_err() {
+        return Err((StatusCode::BAD_REQUEST, "Redirect URI inválida".into()));
+    }
+    let client_secret = req
+        .client_secret
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let allowed_domains: Vec<String> = req
+        .allowed_domains
+        .iter()
+        .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    if allowed_domains.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Informe ao menos um domínio permitido".into()));
+    }
+    if allowed_domains.iter().any(|d| !d.contains('.')) {
+        return Err((StatusCode::BAD_REQUEST, "Domínio permitido inválido".into()));
+    }
+
+    let res = sqlx::query(
+        r#"
+        INSERT INTO tenant_sso_configs (
+          tenant_id, issuer, client_id, client_secret, redirect_uri, allowed_domains, enabled
+        )
+        SELECT $1, $2, $3, $4, $5, $6, $7
+        WHERE $4::text IS NOT NULL
+           OR EXISTS (SELECT 1 FROM tenant_sso_configs WHERE tenant_id = $1)
+        ON CONFLICT (tenant_id) DO UPDATE
+        SET issuer = EXCLUDED.issuer,
+            client_id = EXCLUDED.client_id,
+            client_secret = COALESCE($4, tenant_sso_configs.client_secret),
+            redirect_uri = EXCLUDED.redirect_uri,
+            allowed_domains = EXCLUDED.allowed_domains,
+            enabled = EXCLUDED.enabled,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(&issuer)
+    .bind(&client_id)
+    .bind(&client_secret)
+    .bind(&redirect_uri)
+    .bind(&allowed_domains)
+    .bind(req.enabled)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::BAD_REQUEST, "Client secret é obrigatório".into()));
+    }
+
+    load_sso_config_response(&state.pool, user.tenant_id).await.map(Json)
+}
+
+async fn delete_sso_config(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<StatusCode, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    sqlx::query("DELETE FROM tenant_sso_configs WHERE tenant_id = $1")
+        .bind(user.tenant_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Início do fluxo: guarda state/nonce/code_verifier e devolve a URL do provedor.
+async fn start_sso(
+    State(state): State<AppState>,
+    Path(school_code): Path<String>,
+) -> Result<Json<SsoStartResponse>, (StatusCode, String)> {
+    let school_code = school_code.trim().to_lowercase();
+    let (tenant_id, config) = load_enabled_sso_config(&state.pool, &school_code).await?;
+
+    let http = http_client().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let metadata = discover(&http, &config.issuer)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Provedor SSO indisponível: {e}")))?;
+
+    let login_state = random_token();
+    let nonce = random_token();
+    let code_verifier = random_token();
+
+    sqlx::query("DELETE FROM sso_login_states WHERE expires_at < NOW()")
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO sso_login_states (state, tenant_id, code_verifier, nonce, expires_at)
+        VALUES ($1, $2, $3, $4, NOW() + INTERVAL '10 minutes')
+        "#,
+    )
+    .bind(&login_state)
+    .bind(tenant_id)
+    .bind(&code_verifier)
+    .bind(&nonce)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let authorization_url = authorization_url(
+        &metadata,
+        &config.client_id,
+        &config.redirect_uri,
+        &login_state,
+        &nonce,
+        &pkce_challenge(&code_verifier),
+    )
+    .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+
+    Ok(Json(SsoStartResponse { authorization_url }))
+}
+
+/// Retorno do provedor: troca o código, valida o id_token e vincula ao cadastro da escola pelo e-mail.
+async fn sso_callback(
+    State(state): State<AppState>,
+    Json(req): Json<SsoCallbackRequest>,
+) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+    // o state é de uso único: consumido antes de falar com o provedor
+    let row = sqlx::query(
+        r#"
+        DELETE FROM sso_login_states
+        WHERE state = $1
+        RETURNING tenant_id, code_verifier, nonce, expires_at > NOW() AS valid
+        "#,
+    )
+    .bind(req.state.trim())
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Sessão SSO inválida ou expirada".into()))?;
+
+    if !row.get::<bool, _>("valid") {
+        return Err((StatusCode::UNAUTHORIZED, "Sessão SSO inválida ou expirada".into()));
+    }
+    let tenant_id: Uuid = row.get("tenant_id");
+    let code_verifier: String = row.get("code_verifier");
+    let nonce: String = row.get("nonce");
+
+    let school_code: String = sqlx::query_scalar("SELECT slug FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let (_, config) = load_enabled_sso_config(&state.pool, &school_code).await?;
+
+    let http = http_client().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let metadata = discover(&http, &config.issuer)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Provedor SSO indisponível: {e}")))?;
+    let id_token = exchange_code(
+        &http,
+        &metadata,
+        &config.client_id,
+        &config.client_secret,
+        &config.redirect_uri,
+        req.code.trim(),
+        &code_verifier,
+    )
+    .await
+    .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Falha no SSO: {e}")))?;
+    let claims = verify_id_token(&http, &metadata, &config.client_id, &id_token, &nonce)
+        .await
+        .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Falha no SSO: {e}")))?;
+
+    let email = claims
+        .login_email()
+        .ok_or((StatusCode::UNAUTHORIZED, "Provedor não informou o e-mail".into()))?;
+    if !email_domain_allowed(&email, &config.allowed_domains) {
+        return Err((StatusCode::FORBIDDEN, "Domínio de e-mail não permitido para esta escola".into()));
+    }
+
+    let (user_id, role) = link_sso_user(&state.pool, tenant_id, &claims.sub, &email).await?;
+
+    let token = make_scoped_jwt(&state.jwt_secret, user_id, tenant_id, &role, SCOPE_TENANT_SSO)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
+    record_login(&state.pool, tenant_id, user_id).await;
+
+    let school_name: String = sqlx::query_scalar("SELECT name FROM tenants WHERE id = $1")
+        .bind(tenant_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(AuthResponse {
+        tenant_id,
+        user_id,
+        token,
+        school_name,
+        school_code,
+    }))
+}
+
+/// Vínculo just-in-time: primeiro pelo `sub` já gravado, depois pelo e-mail do usuário
+/// da escola e, por fim, criando o acesso para um professor/funcionário já cadastrado em
+/// `people`. Usuário com identidade global também é vinculado: o token do SSO tem escopo
+/// `tenant_sso` e não alcança as outras escolas dessa identidade.
+async fn link_sso_user(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject: &str,
+    email: &str,
+) -> Result<(Uuid, String), (StatusCode, String)> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let by_subject = sqlx::query(r#"SELECT id, role FROM users WHERE tenant_id = $1 AND sso_subject = $2"#)
+        .bind(tenant_id)
+        .bind(subject)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if let Some(row) = by_subject {
+        return Ok((row.get("id"), row.get("role")));
+    }
+
+    let by_email = sqlx::query(
+        r#"
+        SELECT u.id, u.role, u.sso_subject
+        FROM users u
+        JOIN identities i ON i.id = u.identity_id
+        WHERE u.tenant_id = $1 AND lower(i.email) = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(email)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let (user_id, role) = if let Some(row) = by_email {
+        let current_subject: Option<String> = row.get("sso_subject");
+        if current_subject.is_some() {
+            return Err((StatusCode::CONFLICT, "Usuário já vinculado a outra conta SSO".into()));
+        }
+        let user_id: Uuid = row.get("id");
+        sqlx::query("UPDATE users SET sso_subject = $3 WHERE tenant_id = $1 AND id = $2")
+            .bind(tenant_id)
+            .bind(user_id)
+            .bind(subject)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        (user_id, row.get::<String, _>("role"))
+    } else {
+        let person = sqlx::query(
+            r#"
+            SELECT id, full_name, phone, person_type
+            FROM people
+            WHERE tenant_id = $1
+              AND lower(email) = $2
+              AND person_type IN ('teacher', 'staff')
+              AND is_active = TRUE
+            LIMIT 1
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(email)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::FORBIDDEN, "Nenhum cadastro com este e-mail na escola".into()))?;
+
+        let person_id: Uuid = person.get("id");
+        let role: String = person.get("person_type");
+        let full_name: String = person.get("full_name");
+        let phone: Option<String> = person.get("phone");
+
+        // senha aleatória: o acesso é só pelo SSO desta escola
+        let identity_id = find_or_create_tenant_identity(&mut tx, tenant_id, email, &random_token()).await?;
+
+        let user_id = Uuid::new_v4();
+        sqlx::query(
+            r#"INSERT INTO users (id, tenant_id, person_id, identity_id, full_name, email, role, phone, sso_subject)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+        )
+        .bind(user_id)
+        .bind(tenant_id)
+        .bind(person_id)
+        .bind(identity_id)
+        .bind(&full_name)
+        .bind(email)
+        .bind(&role)
+        .bind(&phone)
+        .bind(subject)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::CONFLICT, "Cadastro já possui outro usuário".into()))?;
+        (user_id, role)
+    };
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok((user_id, role))
+}
+
+async fn load_enabled_sso_config(
+    pool: &PgPool,
+    school_code: &str,
+) -> Result<(Uuid, SsoConfig), (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT c.tenant_id, c.issuer, c.client_id, c.client_secret, c.redirect_uri, c.allowed_domains
+        FROM tenant_sso_configs c
+        JOIN tenants t ON t.id = c.tenant_id
+        WHERE t.slug = $1 AND c.enabled = TRUE
+        "#,
+    )
+    .bind(school_code)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "SSO não configurado para esta escola".into()))?;
+
+    Ok((
+        row.get("tenant_id"),
+        SsoConfig {
+            issuer: row.get("issuer"),
+            client_id: row.get("client_id"),
+            client_secret: row.get("client_secret"),
+            redirect_uri: row.get("redirect_uri"),
+            allowed_domains: row.get("allowed_domains"),
+        },
+    ))
+}
+
+async fn load_sso_config_response(pool: &PgPool, tenant_id: Uuid) -> Result<SsoConfigResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT issuer, client_id, client_secret <> '' AS has_client_secret,
+               redirect_uri, allowed_domains, enabled
+        FROM tenant_sso_configs
+        WHERE tenant_id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "SSO não configurado".into()))?;
+
+    Ok(SsoConfigResponse {
+        issuer: row.get("issuer"),
+        client_id: row.get("client_id"),
+        has_client_secret: row.get("has_client_secret"),
+        redirect_uri: row.get("redirect_uri"),
+        allowed_domains: row.get("allowed_domains"),
+        enabled: row.get("enabled"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{call_json, insert_tenant, insert_user, make_token, test_pool, SECRET};
+    use axum::Form;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const MOCK_KID: &str = "mock-key";
+    const MOCK_N: &str = "wsRaL7qprdaEYItAVFK9qw8j1OSwG3GdNqHBnctaYWwFKrsU3Y3mQxQ5hkb6nWeGd7u5QK2hDp2wOq0PT_Ctx0u0Ly39bznWmA1g2N-VGb1ECyOw7JcYHOQfNy_EgNpiuDO-QVPlwZ88qIqJjWdHDfZIJJ0O37HzuMkkJMnYW2REVZ96-cUrskdgwaVkzt3s5hxaBriqM9vM8Iz6QReFLRu0XC8Gp7sZfKNt57QWDCXYJ-ix2AOjVjpRvv8F8Yu8a8CEMxiCKQTdODqK2zY1MOjFy0UPYTXRN5lF0yDENfu6V6F1Q0i9ilcG0_uDmC8KyWlGUib0F8qx-WmvPOlrnw";
+
+    /// Código emitido pelo "login" no provedor fake: challenge + nonce + conta.
+    #[derive(Clone)]
+    struct IssuedCode {
+        challenge: String,
+        nonce: String,
+        sub: String,
+        email: String,
+        email_verified: bool,
+    }
+
+    #[derive(Clone)]
+    struct MockProvider {
+        issuer: String,
+        codes: Arc<Mutex<HashMap<String, IssuedCode>>>,
+    }
+
+    async fn spawn_mock_provider() -> MockProvider {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let provider = MockProvider {
+            issuer: issuer.clone(),
+            codes: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        let app = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(|State(p): State<MockProvider>| async move {
+                    Json(serde_json::json!({
+                        "issuer": p.issuer,
+                        "authorization_endpoint": format!("{}/authorize", p.issuer),
+                        "token_endpoint": format!("{}/token", p.issuer),
+                        "jwks_uri": format!("{}/jwks", p.issuer),
+                    }))
+                }),
+            )
+            .route(
+                "/jwks",
+                get(|| async {
+                    Json(serde_json::json!({"keys": [{
+                        "kty": "RSA", "use": "sig", "alg": "RS256",
+                        "kid": MOCK_KID, "n": MOCK_N, "e": "AQAB"
+                    }]}))
+                }),
+            )
+            .route("/token", post(mock_token))
+            .with_state(provider.clone());
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        provider
+    }
+
+    async fn mock_token(
+        State(p): State<MockProvider>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<serde_json::Value>, StatusCode> {
+        if form.get("client_secret").map(String::as_str) != Some("segredo-do-cliente") {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        let issued = p
+            .codes
+            .lock()
+            .unwrap()
+            .remove(form.get("code").ok_or(StatusCode::BAD_REQUEST)?)
+            .ok_or(StatusCode::BAD_REQUEST)?;
+        let verifier = form.get("code_verifier").ok_or(StatusCode::BAD_REQUEST)?;
+        if pkce_challenge(verifier) != issued.challenge {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(MOCK_KID.into());
+        let claims = serde_json::json!({
+            "iss": p.issuer,
+            "aud": "client-escola",
+            "sub": issued.sub,
+            "email": issued.email,
+            "email_verified": issued.email_verified,
+            "nonce": issued.nonce,
+            "exp": (chrono::Utc::now() + chrono::Duration::minutes(5)).timestamp(),
+        });
+        let key = EncodingKey::from_rsa_pem(include_bytes!("../../testdata/mock_oidc_rsa.pem")).unwrap();
+        let id_token = encode(&header, &claims, &key).unwrap();
+        Ok(Json(serde_json::json!({"access_token": "x", "token_type": "Bearer", "id_token": id_token})))
+    }
+
+    /// Simula o navegador: inicia o fluxo, "loga" no provedor e chama o callback.
+    async fn sso_login(
+        app: &Router,
+        provider: &MockProvider,
+        slug: &str,
+        sub: &str,
+        email: &str,
+        email_verified: bool,
+    ) -> (StatusCode, Value) {
+        let (status, start) = call_json(app, "GET", &format!("/auth/sso/{slug}/start"), "", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let url = url::Url::parse(start["authorization_url"].as_str().unwrap()).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        let code = random_token();
+        provider.codes.lock().unwrap().insert(
+            code.clone(),
+            IssuedCode {
+                challenge: params["code_challenge"].clone(),
+                nonce: params["nonce"].clone(),
+                sub: sub.into(),
+                email: email.into(),
+                email_verified,
+            },
+        );
+
+        call_json(app, "POST", "/auth/sso/callback", "", Some(json!({"state": params["state"], "code": code}))).await
+    }
+
+    fn app(pool: &PgPool) -> Router {
+        routes(pool.clone(), SECRET.to_string())
+            .merge(crate::routes::auth::routes(pool.clone(), SECRET.to_string()))
+            .merge(crate::routes::identity::routes(pool.clone(), SECRET.to_string()))
+    }
+
+    /// Escola com SSO ativo para `rede.edu.br` e um professor cadastrado em `people`.
+    async fn school_with_sso(pool: &PgPool, provider: &MockProvider) -> (Uuid, String, String) {
+        let tenant_id = insert_tenant(pool, "Rede SSO").await;
+        let slug: String = sqlx::query_scalar("SELECT slug FROM tenants WHERE id = $1")
+            .bind(tenant_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let email = format!("prof-{}@rede.edu.br", Uuid::new_v4().simple());
+
+        sqlx::query(
+            r#"INSERT INTO people (id, tenant_id, person_type, full_name, email, is_active)
+               VALUES ($1, $2, 'teacher', 'Professora SSO', $3, TRUE)"#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(tenant_id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .unwrap();
+        // issuer http só aqui, direto no banco: o provedor fake não tem TLS
+        sqlx::query(
+            r#"INSERT INTO tenant_sso_configs (tenant_id, issuer, client_id, client_secret, redirect_uri, allowed_domains)
+               VALUES ($1, $2, 'client-escola', 'segredo-do-cliente', 'http://localhost:3000/sso/callback', ARRAY['rede.edu.br'])"#,
+        )
+        .bind(tenant_id)
+        .bind(&provider.issuer)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        (tenant_id, slug, email)
+    }
+
+    async fn cleanup(pool: &PgPool, tenants: &[Uuid], email: &str) {
+        for tenant_id in tenants {
+            sqlx::query("DELETE FROM tenants WHERE id = $1").bind(tenant_id).execute(pool).await.unwrap();
+        }
+        sqlx::query("DELETE FROM identities WHERE lower(email) = lower($1)")
+            .bind(email)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sso_creates_access_for_registered_teacher_and_reuses_it() {
+        let pool = test_pool().await;
+        let provider = spawn_mock_provider().await;
+        let (tenant_id, slug, email) = school_with_sso(&pool, &provider).await;
+        let app = app(&pool);
+
+        let (status, first) = sso_login(&app, &provider, &slug, "sub-123", &email, true).await;
+        assert_eq!(status, StatusCode::OK, "{first}");
+        let (status, second) = sso_login(&app, &provider, &slug, "sub-123", &email, true).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["user_id"], second["user_id"]);
+
+        let row = sqlx::query(
+            "SELECT u.role, i.tenant_id AS identity_tenant FROM users u JOIN identities i ON i.id = u.identity_id WHERE u.tenant_id = $1",
+        )
+        .bind(tenant_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row.get::<String, _>("role"), "teacher");
+        assert_eq!(row.get::<Option<Uuid>, _>("identity_tenant"), Some(tenant_id));
+
+        cleanup(&pool, &[tenant_id], &email).await;
+    }
+
+    #[tokio::test]
+    async fn sso_rejects_email_outside_allowed_domains() {
+        let pool = test_pool().await;
+        let provider = spawn_mock_provider().await;
+        let (tenant_id, slug, email) = school_with_sso(&pool, &provider).await;
+
+        let (status, _) = sso_login(&app(&pool), &provider, &slug, "sub-999", "alguem@gmail.com", true).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        cleanup(&pool, &[tenant_id], &email).await;
+    }
+
+    #[tokio::test]
+    async fn sso_rejects_unverified_email() {
+        let pool = test_pool().await;
+        let provider = spawn_mock_provider().await;
+        let (tenant_id, slug, email) = school_with_sso(&pool, &provider).await;
+
+        let (status, _) = sso_login(&app(&pool), &provider, &slug, "sub-123", &email, false).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        cleanup(&pool, &[tenant_id], &email).await;
+    }
+
+    #[tokio::test]
+    async fn sso_never_reaches_identity_from_another_school() {
+        let pool = test_pool().await;
+        let provider = spawn_mock_provider().await;
+        let (tenant_id, slug, email) = school_with_sso(&pool, &provider).await;
+        let app = app(&pool);
+
+        // a mesma pessoa já tem conta por senha em outra escola
+        let (status, other) = call_json(
+            &app,
+            "POST",
+            "/auth/register",
+            "",
+            Some(json!({
+                "school_name": "Outra escola",
+                "school_code": format!("outra-{}", &Uuid::new_v4().simple().to_string()[..8]),
+                "email": email,
+                "password": "senha-forte-1"
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, session) = sso_login(&app, &provider, &slug, "sub-123", &email, true).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = call_json(
+            &app,
+            "POST",
+            "/auth/switch-tenant",
+            session["token"].as_str().unwrap(),
+            Some(json!({"tenant_id": other["tenant_id"]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let other_tenant = Uuid::parse_str(other["tenant_id"].as_str().unwrap()).unwrap();
+        cleanup(&pool, &[tenant_id, other_tenant], &email).await;
+    }
+
+    #[tokio::test]
+    async fn sso_links_existing_user_who_signs_in_with_password() {
+        let pool = test_pool().await;
+        let provider = spawn_mock_provider().await;
+        let (tenant_id, slug, email) = school_with_sso(&pool, &provider).await;
+        let user_id = insert_user(&pool, tenant_id, "Secretaria", "admin").await;
+        let user_email = format!("{user_id}@escola.test");
+        sqlx::query("UPDATE tenant_sso_configs SET allowed_domains = ARRAY['escola.test'] WHERE tenant_id = $1")
+            .bind(tenant_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let app = app(&pool);
+
+        let (status, session) = sso_login(&app, &provider, &slug, "sub-456", &user_email, true).await;
+        assert_eq!(status, StatusCode::OK, "{session}");
+        assert_eq!(session["user_id"], user_id.to_string());
+        let subject: Option<String> = sqlx::query_scalar("SELECT sso_subject FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(subject.as_deref(), Some("sub-456"));
+
+        // a sessão do SSO não chega à identidade global
+        let token = session["token"].as_str().unwrap();
+        let (status, _) = call_json(&app, "GET", "/auth/memberships", token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call_json(&app, "POST", "/auth/2fa/setup", token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        cleanup(&pool, &[tenant_id], &email).await;
+        cleanup(&pool, &[], &user_email).await;
+    }
+
+    #[tokio::test]
+    async fn sso_config_requires_https_issuer_and_allowed_domains() {
+        let pool = test_pool().await;
+        let tenant_id = insert_tenant(&pool, "Escola config SSO").await;
+        let admin = insert_user(&pool, tenant_id, "Admin", "admin").await;
+        let token = make_token(tenant_id, admin, "admin");
+        let app = app(&pool);
+        let config = |issuer: &str, domains: Vec<&str>| {
+            json!({
+                "issuer": issuer,
+                "client_id": "client-escola",
+                "client_secret": "segredo",
+                "redirect_uri": "https://escola.test/sso/callback",
+                "allowed_domains": domains,
+                "enabled": true
+            })
+        };
+
+        let (status, _) = call_json(&app, "PUT", "/school/sso", &token, Some(config("http://idp.escola.test", vec!["escola.test"]))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call_json(&app, "PUT", "/school/sso", &token, Some(config("https://idp.escola.test", vec![]))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, saved) =
+            call_json(&app, "PUT", "/school/sso", &token, Some(config("https://idp.escola.test", vec!["escola.test"]))).await;
+        assert_eq!(status, StatusCode::OK, "{saved}");
+
+        cleanup(&pool, &[tenant_id], &format!("{admin}@escola.test")).await;
+    }
+}
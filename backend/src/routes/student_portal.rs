@@ -0,0 +1,832 @@
+//! Portal do aluno: login pela matrícula com senha definida pela secretaria e JWT de
+//! escopo `student` preso a `students.person_id`. Só leitura, e só do próprio aluno:
+//! boletins, aulas da turma e tarefas. Nada de saúde (`allergies`, `medications`...)
+//! nem financeiro passa por aqui.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::identity::{hash_password, verify_password};
+use crate::auth::jwt::{AuthUser, Claims, StudentUser};
+use crate::routes::records::{
+    fetch_student_full_report, fetch_student_term_report, StudentFullReportResponse,
+    StudentTermReportQuery, StudentTermReportResponse,
+};
+use crate::routes::timetable::{class_slots, SlotResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StudentLoginRequest {
+    #[validate(length(min = 3))]
+    pub school_code: String,
+    #[validate(length(min = 1))]
+    pub registration: String,
+    #[validate(length(min = 1))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentAuthResponse {
+    pub tenant_id: Uuid,
+    pub student_id: Uuid,
+    pub token: String,
+    pub school_name: String,
+    pub school_code: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeStudentPasswordRequest {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+    #[validate(length(min = 8))]
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+/// Dados do próprio aluno; lista fechada de campos, sem ficha de saúde.
+#[derive(Debug, Serialize)]
+pub struct StudentMeResponse {
+    pub student_id: Uuid,
+    pub name: String,
+    pub social_name: Option<String>,
+    pub registration: String,
+    pub class_id: Option<Uuid>,
+    pub class_name: Option<String>,
+    pub class_grade: Option<String>,
+    pub class_period: Option<String>,
+    pub school_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DateRangeQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentLessonItem {
+    pub lesson_date: NaiveDate,
+    pub lesson_number: i32,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub teacher_name: Option<String>,
+    pub content: Option<String>,
+    pub homework: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentTimetableResponse {
+    pub class_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Grade horária semanal da turma.
+    pub weekly_slots: Vec<SlotResponse>,
+    pub lessons: Vec<StudentLessonItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentHomeworkItem {
+    pub lesson_date: NaiveDate,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub homework: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentAssessmentItem {
+    pub assessment_id: Uuid,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub name: String,
+    pub assessment_date: Option<NaiveDate>,
+    pub weight: f64,
+    pub max_score: f64,
+    /// Nota do próprio aluno, quando já lançada.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentAssignmentsResponse {
+    pub class_id: Uuid,
+    pub homework: Vec<StudentHomeworkItem>,
+    pub assessments: Vec<StudentAssessmentItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StudentPortalAccessRequest {
+    pub is_active: bool,
+    /// Obrigatória ao liberar o primeiro acesso; depois, redefine a senha.
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentPortalAccessResponse {
+    pub student_id: Uuid,
+    pub registration: String,
+    pub enabled: bool,
+    pub is_active: bool,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+/// Aluno do token, conferido a cada requisição.
+struct PortalStudent {
+    student_id: Uuid,
+    class_id: Option<Uuid>,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/student-portal/auth/login", post(login))
+        .route("/student-portal/me", get(get_me))
+        .route("/student-portal/me/password", put(change_password))
+        .route("/student-portal/term-report", get(get_term_report))
+        .route("/student-portal/full-report", get(get_full_report))
+        .route("/student-portal/timetable", get(get_timetable))
+        .route("/student-portal/assignments", get(get_assignments))
+        .route(
+            "/students/:student_id/portal-access",
+            get(get_portal_access).put(update_portal_access),
+        )
+        .with_state(state)
+}
+
+async fn login(
+    State(state): State<AppState>,
+    Json(req): Json<StudentLoginRequest>,
+) -> Result<Json<StudentAuthResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let school_code = req.school_code.trim().to_lowercase();
+    let registration = req.registration.trim().to_lowercase();
+
+    let row = sqlx::query(
+        r#"
+        SELECT a.id AS account_id, a.password_hash, s.id AS student_id, s.person_id, s.tenant_id,
+               t.name AS school_name, t.slug AS school_code
+        FROM students s
+        JOIN tenants t ON t.id = s.tenant_id
+        JOIN student_portal_accounts a ON a.person_id = s.person_id AND a.tenant_id = s.tenant_id
+        WHERE t.slug = $1 AND lower(s.registration) = $2 AND a.is_active
+        "#,
+    )
+    .bind(&school_code)
+    .bind(&registration)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()))?;
+
+    let password_hash: String = row.get("password_hash");
+    if !verify_password(&password_hash, &req.password)? {
+        return Err((StatusCode::UNAUTHORIZED, "Credenciais inválidas".into()));
+    }
+
+    let account_id: Uuid = row.get("account_id");
+    let person_id: Uuid = row.get("person_id");
+    let tenant_id: Uuid = row.get("tenant_id");
+
+    sqlx::query("UPDATE student_portal_accounts SET last_login_at = NOW() WHERE id = $1")
+        .bind(account_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let token = make_student_jwt(&state.jwt_secret, person_id, tenant_id)
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro token".into()))?;
+
+    Ok(Json(StudentAuthResponse {
+        tenant_id,
+        student_id: row.get("student_id"),
+        token,
+        school_name: row.get("school_name"),
+        school_code: row.get("school_code"),
+    }))
+}
+
+async fn get_me(
+    State(state): State<AppState>,
+    user: StudentUser,
+) -> Result<Json<StudentMeResponse>, (StatusCode, String)> {
+    let student = load_portal_student(&state.pool, &user).await?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT s.id, s.name, s.social_name, s.registration, s.class_id,
+               c.name AS class_name, c.grade AS class_grade, c.period AS class_period,
+               t.name AS school_name
+        FROM students s
+        JOIN tenants t ON t.id = s.tenant_id
+        LEFT JOIN classes c ON c.id = s.class_id AND c.tenant_id = s.tenant_id
+        WHERE s.tenant_id = $1 AND s.id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student.student_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(StudentMeResponse {
+        student_id: row.get("id"),
+        name: row.get("name"),
+        social_name: row.get("social_name"),
+        registration: row.get("registration"),
+        class_id: row.get("class_id"),
+        class_name: row.get("class_name"),
+        class_grade: row.get("class_grade"),
+        class_period: row.get("class_period"),
+        school_name: row.get("school_name"),
+    }))
+}
+
+async fn change_password(
+    State(state): State<AppState>,
+    user: StudentUser,
+    Json(req): Json<ChangeStudentPasswordRequest>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    load_portal_student(&state.pool, &user).await?;
+
+    let current_hash: String = sqlx::query_scalar(
+        "SELECT password_hash FROM student_portal_accounts WHERE tenant_id = $1 AND person_id = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(user.person_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if !verify_password(&current_hash, &req.current_password)? {
+        return Err((StatusCode::UNAUTHORIZED, "Senha atual incorreta".into()));
+    }
+
+    sqlx::query(
+        r#"UPDATE student_portal_accounts
+           SET password_hash = $3, updated_at = NOW()
+           WHERE tenant_id = $1 AND person_id = $2"#,
+    )
+    .bind(user.tenant_id)
+    .bind(user.person_id)
+    .bind(hash_password(&req.new_password)?)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn get_term_report(
+    State(state): State<AppState>,
+    user: StudentUser,
+    Query(query): Query<StudentTermReportQuery>,
+) -> Result<Json<StudentTermReportResponse>, (StatusCode, String)> {
+    let student = load_portal_student(&state.pool, &user).await?;
+    let class_id = current_class(&student)?;
+    let data = fetch_student_term_report(&state.pool, user.tenant_id, class_id, student.student_id, query.term_id).await?;
+    Ok(Json(data))
+}
+
+async fn get_full_report(
+    State(state): State<AppState>,
+    user: StudentUser,
+) -> Result<Json<StudentFullReportResponse>, (StatusCode, String)> {
+    let student = load_portal_student(&state.pool, &user).await?;
+    let class_id = current_class(&student)?;
+    let data = fetch_student_full_report(&state.pool, user.tenant_id, class_id, student.student_id).await?;
+    Ok(Json(data))
+}
+
+/// Grade horária da turma e aulas registradas no diário no intervalo (padrão: semana corrente).
+async fn get_timetable(
+    State(state): State<AppState>,
+    user: StudentUser,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Json<StudentTimetableResponse>, (StatusCode, String)> {
+    let student = load_portal_student(&state.pool, &user).await?;
+    let class_id = current_class(&student)?;
+
+    let today = chrono::Local::now().date_naive();
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let from = query.from.unwrap_or(week_start);
+    let to = query.to.unwrap_or(from + Duration::days(6));
+    if to < from {
+        return Err((StatusCode::BAD_REQUEST, "Período inválido".into()));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT l.lesson_date, l.lesson_number, l.subject_id, sub.name AS subject_name,
+               COALESCE(u.full_name, u.email) AS teacher_name, l.content, l.homework
+        FROM lessons l
+        JOIN subjects sub ON sub.id = l.subject_id
+        LEFT JOIN users u ON u.id = l.teacher_user_id
+        WHERE l.tenant_id = $1 AND l.class_id = $2
+          AND l.lesson_date BETWEEN $3 AND $4
+        ORDER BY l.lesson_date ASC, l.lesson_number ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let weekly_slots = class_slots(&state.pool, user.tenant_id, class_id).await?;
+
+    Ok(Json(StudentTimetableResponse {
+        class_id,
+        from,
+        to,
+        weekly_slots,
+        lessons: rows
+            .into_iter()
+            .map(|r| StudentLessonItem {
+                lesson_date: r.get("lesson_date"),
+                lesson_number: r.get("lesson_number"),
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                teacher_name: r.get("teacher_name"),
+                content: r.get("content"),
+                homework: r.get("homework"),
+            })
+            .collect(),
+    }))
+}
+
+/// Tarefas de casa do diário e avaliações da turma com a nota do próprio aluno.
+async fn get_assignments(
+    State(state): State<AppState>,
+    user: StudentUser,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Json<StudentAssignmentsResponse>, (StatusCode, String)> {
+    let student = load_portal_student(&state.pool, &user).await?;
+    let class_id = current_class(&student)?;
+
+    let homework_rows = sqlx::query(
+        r#"
+        SELECT l.lesson_date, l.subject_id, sub.name AS subject_name, l.homework
+        FROM lessons l
+        JOIN subjects sub ON sub.id = l.subject_id
+        WHERE l.tenant_id = $1 AND l.class_id = $2
+          AND NULLIF(btrim(l.homework), '') IS NOT NULL
+          AND ($3::date IS NULL OR l.lesson_date >= $3)
+          AND ($4::date IS NULL OR l.lesson_date <= $4)
+        ORDER BY l.lesson_date DESC, l.lesson_number ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let assessment_rows = sqlx::query(
+        r#"
+        SELECT a.id, a.subject_id, sub.name AS subject_name, a.term_id, t.name AS term_name,
+               a.name, a.assessment_date, a.weight::float8 AS weight, a.max_score::float8 AS max_score,
+               sc.score::float8 AS score
+        FROM assessments a
+        JOIN subjects sub ON sub.id = a.subject_id
+        JOIN academic_terms t ON t.id = a.term_id
+        LEFT JOIN assessment_scores sc ON sc.assessment_id = a.id AND sc.student_id = $3
+        WHERE a.tenant_id = $1 AND a.class_id = $2
+          AND ($4::date IS NULL OR a.assessment_date >= $4)
+          AND ($5::date IS NULL OR a.assessment_date <= $5)
+        ORDER BY a.assessment_date DESC NULLS LAST, sub.name ASC, a.name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(student.student_id)
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(StudentAssignmentsResponse {
+        class_id,
+        homework: homework_rows
+            .into_iter()
+            .map(|r| StudentHomeworkItem {
+                lesson_date: r.get("lesson_date"),
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                homework: r.get("homework"),
+            })
+            .collect(),
+        assessments: assessment_rows
+            .into_iter()
+            .map(|r| StudentAssessmentItem {
+                assessment_id: r.get("id"),
+                subject_id: r.get("subject_id"),
+                subject_name: r.get("subject_name"),
+                term_id: r.get("term_id"),
+                term_name: r.get("term_name"),
+                name: r.get("name"),
+                assessment_date: r.get("assessment_date"),
+                weight: r.get("weight"),
+                max_score: r.get("max_score"),
+                score: r.get("score"),
+            })
+            .collect(),
+    }))
+}
+
+async fn get_portal_access(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<Json<StudentPortalAccessResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    let data = load_portal_access(&state.pool, user.tenant_id, student_id).await?;
+    Ok(Json(data))
+}
+
+async fn update_portal_access(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Json(req): Json<StudentPortalAccessRequest>,
+) -> Result<Json<StudentPortalAccessResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let person_id: Uuid = sqlx::query_scalar("SELECT person_id FROM students WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(student_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))?;
+
+    let password_hash = match normalize_optional_text(req.password) {
+        Some(password) if password.chars().count() < 8 => {
+            return Err((StatusCode::BAD_REQUEST, "Senha deve ter ao menos 8 caracteres".into()));
+        }
+        Some(password) => Some(hash_password(&password)?),
+        None => None,
+    };
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE student_portal_accounts
+        SET is_active = $3,
+            password_hash = COALESCE($4, password_hash),
+            updated_at = NOW()
+        WHERE tenant_id = $1 AND person_id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(person_id)
+    .bind(req.is_active)
+    .bind(&password_hash)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if updated.rows_affected() == 0 {
+        let password_hash =
+            password_hash.ok_or((StatusCode::BAD_REQUEST, "Informe a senha inicial do aluno".into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO student_portal_accounts (id, tenant_id, person_id, password_hash, is_active)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user.tenant_id)
+        .bind(person_id)
+        .bind(password_hash)
+        .bind(req.is_active)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    let data = load_portal_access(&state.pool, user.tenant_id, student_id).await?;
+    Ok(Json(data))
+}
+
+/// Conta ativa e aluno ainda cadastrado: desativar o acesso derruba também os tokens
+/// já emitidos.
+async fn load_portal_student(pool: &PgPool, user: &StudentUser) -> Result<PortalStudent, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT s.id, s.class_id
+        FROM student_portal_accounts a
+        JOIN students s ON s.person_id = a.person_id AND s.tenant_id = a.tenant_id
+        WHERE a.tenant_id = $1 AND a.person_id = $2 AND a.is_active
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(user.person_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Acesso ao portal desativado".into()))?;
+
+    Ok(PortalStudent {
+        student_id: row.get("id"),
+        class_id: row.get("class_id"),
+    })
+}
+
+fn current_class(student: &PortalStudent) -> Result<Uuid, (StatusCode, String)> {
+    student
+        .class_id
+        .ok_or((StatusCode::NOT_FOUND, "Aluno sem turma atual".into()))
+}
+
+async fn load_portal_access(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+) -> Result<StudentPortalAccessResponse, (StatusCode, String)> {
+    let row = sqlx::query(
+        r#"
+        SELECT s.registration, a.id AS account_id, a.is_active, a.last_login_at
+        FROM students s
+        LEFT JOIN student_portal_accounts a ON a.person_id = s.person_id AND a.tenant_id = s.tenant_id
+        WHERE s.tenant_id = $1 AND s.id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))?;
+
+    let account_id: Option<Uuid> = row.get("account_id");
+    let is_active: Option<bool> = row.get("is_active");
+    Ok(StudentPortalAccessResponse {
+        student_id,
+        registration: row.get("registration"),
+        enabled: account_id.is_some(),
+        is_active: is_active.unwrap_or(false),
+        last_login_at: row.get("last_login_at"),
+    })
+}
+
+fn make_student_jwt(jwt_secret: &str, person_id: Uuid, tenant_id: Uuid) -> Result<String, ()> {
+    // 7 dias em segundos
+    let exp = (chrono::Utc::now() + chrono::Duration::days(7)).timestamp() as usize;
+
+    let claims = Claims {
+        sub: person_id.to_string(),
+        tenant_id: Some(tenant_id.to_string()),
+        role: "student".to_string(),
+        scope: "student".to_string(),
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_bytes()),
+    )
+    .map_err(|_| ())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    const PASSWORD: &str = "senha-do-caio";
+
+    /// Caio e Bia na 1EM (ambos com dados de saúde), Física com uma aula de casa e a Prova 1
+    /// do 1º bimestre já corrigida.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        school_code: String,
+        term_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Aluno").await;
+            let school_code = format!("teste-{tenant_id}");
+            let assessment_id = Uuid::new_v4();
+            let class_id = insert_class(&pool, tenant_id, "1EM", "1 serie", 2026).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Física").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Caio", "EM-Caio").await;
+            let classmate_id = insert_student(&pool, tenant_id, class_id, "Bia", "EM-Bia").await;
+            sqlx::query("UPDATE students SET allergies = 'Amendoim', medications = 'Insulina' WHERE tenant_id = $1")
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO lessons (id, tenant_id, class_id, subject_id, lesson_date, content, homework)
+                 VALUES ($1, $2, $3, $4, '2026-03-02', 'Cinemática', 'Lista 1, exercícios 1 a 5')",
+            )
+            .bind(Uuid::new_v4())
+            .bind(tenant_id)
+            .bind(class_id)
+            .bind(subject_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO assessments (id, tenant_id, class_id, subject_id, term_id, name, assessment_date)
+                 VALUES ($1, $2, $3, $4, $5, 'Prova 1', '2026-03-20')",
+            )
+            .bind(assessment_id)
+            .bind(tenant_id)
+            .bind(class_id)
+            .bind(subject_id)
+            .bind(term_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            for (id, score) in [(student_id, 7.5), (classmate_id, 9.0)] {
+                sqlx::query("INSERT INTO assessment_scores (id, tenant_id, assessment_id, student_id, score) VALUES ($1, $2, $3, $4, $5)")
+                    .bind(Uuid::new_v4())
+                    .bind(tenant_id)
+                    .bind(assessment_id)
+                    .bind(id)
+                    .bind(score)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+
+            Fixture { pool, app, owner, tenant_id, school_code, term_id, student_id }
+        }
+
+        fn access_path(&self) -> String {
+            format!("/students/{}/portal-access", self.student_id)
+        }
+
+        async fn set_access(&self, body: Value) -> (StatusCode, Value) {
+            call_json(&self.app, "PUT", &self.access_path(), &self.owner, Some(body)).await
+        }
+
+        async fn login(&self, password: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", "/student-portal/auth/login", "", Some(json!({
+                "school_code": self.school_code, "registration": "em-caio", "password": password
+            })))
+            .await
+        }
+
+        /// Libera o acesso do Caio e devolve o token da sessão dele.
+        async fn student_session(&self) -> String {
+            let (status, _) = self.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, session) = self.login(PASSWORD).await;
+            assert_eq!(status, StatusCode::OK);
+            session["token"].as_str().unwrap().to_string()
+        }
+
+        async fn get(&self, path: &str, token: &str) -> Value {
+            let (status, body) = call_json(&self.app, "GET", path, token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            body
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn enabling_access_requires_a_password() {
+        let f = Fixture::new().await;
+        let (status, _) = f.set_access(json!({"is_active": true})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, access) = f.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(access["enabled"], true);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn login_matches_registration_case_insensitively() {
+        let f = Fixture::new().await;
+        f.set_access(json!({"is_active": true, "password": PASSWORD})).await;
+
+        let (status, _) = f.login("senha-errada").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, session) = f.login(PASSWORD).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["student_id"], f.student_id.to_string());
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn profile_hides_health_data() {
+        let f = Fixture::new().await;
+        let student = f.student_session().await;
+
+        let me = f.get("/student-portal/me", &student).await;
+        assert_eq!(me["name"], "Caio");
+        assert_eq!(me["class_name"], "1EM");
+        let me_text = me.to_string();
+        assert!(!me_text.contains("Amendoim") && !me_text.contains("Insulina"));
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_sees_own_report_lessons_and_scores() {
+        let f = Fixture::new().await;
+        let student = f.student_session().await;
+
+        let report = f.get(&format!("/student-portal/term-report?term_id={}", f.term_id), &student).await;
+        assert_eq!(report["student_id"], f.student_id.to_string());
+        let timetable = f.get("/student-portal/timetable?from=2026-03-02&to=2026-03-08", &student).await;
+        assert_eq!(timetable["lessons"][0]["content"], "Cinemática");
+        let assignments = f.get("/student-portal/assignments", &student).await;
+        assert_eq!(assignments["homework"][0]["homework"], "Lista 1, exercícios 1 a 5");
+        assert_eq!(assignments["assessments"].as_array().unwrap().len(), 1);
+        assert_eq!(assignments["assessments"][0]["score"], 7.5);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn password_change_requires_current_password() {
+        let f = Fixture::new().await;
+        let student = f.student_session().await;
+
+        let (status, _) = call_json(&f.app, "PUT", "/student-portal/me/password", &student, Some(json!({
+            "current_password": "senha-errada", "new_password": "nova-senha-1"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call_json(&f.app, "PUT", "/student-portal/me/password", &student, Some(json!({
+            "current_password": PASSWORD, "new_password": "nova-senha-1"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(f.login("nova-senha-1").await.0, StatusCode::OK);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn tokens_of_other_scopes_are_rejected() {
+        let f = Fixture::new().await;
+        let student = f.student_session().await;
+
+        let (status, _) = call_json(&f.app, "GET", "/student-portal/me", &f.owner, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call_json(&f.app, "GET", &f.access_path(), &student, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn disabling_access_revokes_issued_tokens() {
+        let f = Fixture::new().await;
+        let student = f.student_session().await;
+
+        let (status, _) = f.set_access(json!({"is_active": false})).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call_json(&f.app, "GET", "/student-portal/me", &student, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        f.cleanup().await;
+    }
+}
@@ -0,0 +1,665 @@
+//! Indicador de risco por aluno (ranking da turma ou da escola, com os fatores que
+//! pesaram), histórico dos snapshots diários e registro de ocorrências disciplinares.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::grading::risk::{assess_students, level_label, StudentRisk, LEVEL_HIGH, LEVEL_LOW, LEVEL_MEDIUM};
+use crate::state::AppState;
+
+const MANAGE_ROLES: [&str; 3] = ["owner", "admin", "staff"];
+
+#[derive(Debug, Deserialize)]
+pub struct StudentRiskQuery {
+    /// Sem turma: a escola toda.
+    pub class_id: Option<Uuid>,
+    /// Só hoje (padrão): datas anteriores ficam em `/students/:student_id/risk-history`.
+    pub as_of: Option<NaiveDate>,
+    /// low | medium | high: só alunos a partir deste nível.
+    pub min_level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RiskHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateIncidentRequest {
+    pub occurred_on: NaiveDate,
+    /// low | medium | high
+    pub severity: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentRiskItem {
+    #[serde(flatten)]
+    pub risk: StudentRisk,
+    /// Último snapshot antes da data consultada, para ver a tendência.
+    pub previous_score: Option<i32>,
+    pub previous_snapshot_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentRiskResponse {
+    pub as_of: NaiveDate,
+    pub class_id: Option<Uuid>,
+    pub items: Vec<StudentRiskItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RiskSnapshotResponse {
+    pub snapshot_date: NaiveDate,
+    pub class_id: Option<Uuid>,
+    pub score: i32,
+    pub level: String,
+    pub level_label: String,
+    pub factors: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncidentResponse {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub occurred_on: NaiveDate,
+    pub severity: String,
+    pub description: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/student-risk", get(list_student_risk))
+        .route("/students/:student_id/risk-history", get(get_risk_history))
+        .route(
+            "/students/:student_id/discipline-incidents",
+            get(list_incidents).post(create_incident),
+        )
+        .route("/discipline-incidents/:incident_id", delete(delete_incident))
+        .with_state(state)
+}
+
+async fn list_student_risk(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<StudentRiskQuery>,
+) -> Result<Json<StudentRiskResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let min_rank = match query.min_level.as_deref().map(str::trim) {
+        None | Some("") => 0,
+        Some(level) => level_rank(level)
+            .ok_or((StatusCode::BAD_REQUEST, "Nível inválido (use low, medium ou high)".into()))?,
+    };
+    if let Some(class_id) = query.class_id {
+        ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    }
+    let today = chrono::Local::now().date_naive();
+    let as_of = query.as_of.unwrap_or(today);
+    if as_of != today {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "O indicador é calculado só para hoje; datas anteriores estão no histórico do aluno".into(),
+        ));
+    }
+
+    let students = assess_students(&state.pool, user.tenant_id, query.class_id, as_of).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT ON (student_id) student_id, snapshot_date, score
+        FROM student_risk_snapshots
+        WHERE tenant_id = $1 AND snapshot_date < $2
+        ORDER BY student_id, snapshot_date DESC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(as_of)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let previous: HashMap<Uuid, (NaiveDate, i32)> = rows
+        .into_iter()
+        .map(|r| (r.get("student_id"), (r.get("snapshot_date"), r.get("score"))))
+        .collect();
+
+    let items = students
+        .into_iter()
+        .filter(|s| level_rank(&s.level).unwrap_or(0) >= min_rank)
+        .map(|risk| {
+            let prev = previous.get(&risk.student_id);
+            StudentRiskItem {
+                previous_score: prev.map(|p| p.1),
+                previous_snapshot_date: prev.map(|p| p.0),
+                risk,
+            }
+        })
+        .collect();
+
+    Ok(Json(StudentRiskResponse {
+        as_of,
+        class_id: query.class_id,
+        items,
+    }))
+}
+
+async fn get_risk_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Query(query): Query<RiskHistoryQuery>,
+) -> Result<Json<Vec<RiskSnapshotResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, student_id).await?;
+    let limit = query.limit.unwrap_or(90).clamp(1, 366);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT snapshot_date, class_id, score, level, factors
+        FROM student_risk_snapshots
+        WHERE tenant_id = $1 AND student_id = $2
+        ORDER BY snapshot_date DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| {
+                let level: String = r.get("level");
+                RiskSnapshotResponse {
+                    snapshot_date: r.get("snapshot_date"),
+                    class_id: r.get("class_id"),
+                    score: r.get("score"),
+                    level_label: level_label(&level).to_string(),
+                    level,
+                    factors: r.get("factors"),
+                }
+            })
+            .collect(),
+    ))
+}
+
+async fn list_incidents(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<Json<Vec<IncidentResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, student_id).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, student_id, occurred_on, severity, description, created_by, created_at
+        FROM discipline_incidents
+        WHERE tenant_id = $1 AND student_id = $2
+        ORDER BY occurred_on DESC, created_at DESC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(incident_from_row).collect()))
+}
+
+/// Professores registram ocorrências dos alunos das turmas em que lecionam.
+async fn create_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Json(req): Json<CreateIncidentRequest>,
+) -> Result<Json<IncidentResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    let class_id = ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, student_id).await?;
+    if restricted_teacher(&user).is_some() {
+        let class_id = class_id.ok_or((StatusCode::FORBIDDEN, "Aluno sem turma".into()))?;
+        ensure_teaches(&state.pool, &user, class_id, None).await?;
+    }
+
+    let severity = req.severity.trim().to_lowercase();
+    if level_rank(&severity).is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Gravidade inválida (use low, medium ou high)".into()));
+    }
+    let description = req.description.trim();
+    if description.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Descrição é obrigatória".into()));
+    }
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO discipline_incidents (id, tenant_id, student_id, occurred_on, severity, description, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, student_id, occurred_on, severity, description, created_by, created_at
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(req.occurred_on)
+    .bind(severity)
+    .bind(description)
+    .bind(user.user_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(incident_from_row(&row)))
+}
+
+async fn delete_incident(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(incident_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let deleted = sqlx::query("DELETE FROM discipline_incidents WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(incident_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if deleted.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Ocorrência não encontrada".into()));
+    }
+    Ok(Json(OkResponse { ok: true }))
+}
+
+fn incident_from_row(r: &sqlx::postgres::PgRow) -> IncidentResponse {
+    IncidentResponse {
+        id: r.get("id"),
+        student_id: r.get("student_id"),
+        occurred_on: r.get("occurred_on"),
+        severity: r.get("severity"),
+        description: r.get("description"),
+        created_by: r.get("created_by"),
+        created_at: r.get("created_at"),
+    }
+}
+
+/// Mesma escala para nível de risco e gravidade da ocorrência.
+fn level_rank(level: &str) -> Option<i32> {
+    match level {
+        LEVEL_LOW => Some(0),
+        LEVEL_MEDIUM => Some(1),
+        LEVEL_HIGH => Some(2),
+        _ => None,
+    }
+}
+
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let row = sqlx::query("SELECT 1 FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if row.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Turma não encontrada".into()));
+    }
+    Ok(())
+}
+
+/// Devolve a turma atual do aluno.
+async fn ensure_student_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+) -> Result<Option<Uuid>, (StatusCode, String)> {
+    sqlx::query_scalar::<_, Option<Uuid>>("SELECT class_id FROM students WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(student_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use chrono::Duration;
+    use serde_json::{json, Value};
+
+    /// Turma 7A com História em dois bimestres. Enzo caiu de 8 para 4, faltou às três aulas
+    /// mais recentes e tem uma parcela vencida há 40 dias; Lara foi de 8 para 8,5 e veio a
+    /// todas. As datas são relativas a hoje, a única data que o indicador aceita.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        subject_id: Uuid,
+        second_term_id: Uuid,
+        at_risk_id: Uuid,
+        doing_well_id: Uuid,
+        today: NaiveDate,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Risco").await;
+            let today = chrono::Local::now().date_naive();
+            let class_id = insert_class(&pool, tenant_id, "7A", "7º ano", 2026).await;
+            let subject_id = insert_subject(&pool, tenant_id, "História").await;
+            let first_term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let second_term_id = insert_term(&pool, tenant_id, "2º Bimestre", 2026, 2).await;
+            let at_risk_id = insert_student(&pool, tenant_id, class_id, "Enzo", "Enzo").await;
+            let doing_well_id = insert_student(&pool, tenant_id, class_id, "Lara", "Lara").await;
+
+            // (aluno, nota 1º bim, nota 2º bim, presente nas aulas recentes)
+            for (id, first, second, recent_present) in [
+                (at_risk_id, 8.0, 4.0, false),
+                (doing_well_id, 8.0, 8.5, true),
+            ] {
+                for (term_id, score) in [(first_term_id, first), (second_term_id, second)] {
+                    sqlx::query(
+                        "INSERT INTO student_grades (id, tenant_id, class_id, student_id, term, subject, term_id, subject_id, score)
+                         VALUES ($1, $2, $3, $4, 'Bimestre', 'História', $5, $6, $7)",
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(tenant_id)
+                    .bind(class_id)
+                    .bind(id)
+                    .bind(term_id)
+                    .bind(subject_id)
+                    .bind(score)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                }
+                for (days_ago, present) in [(45, true), (44, true), (43, true), (3, recent_present), (2, recent_present), (1, recent_present)] {
+                    sqlx::query(
+                        "INSERT INTO student_attendance (id, tenant_id, class_id, student_id, attendance_date, subject_id, lesson_number, present)
+                         VALUES ($1, $2, $3, $4, $5, $6, 1, $7)",
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(tenant_id)
+                    .bind(class_id)
+                    .bind(id)
+                    .bind(today - Duration::days(days_ago))
+                    .bind(subject_id)
+                    .bind(present)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                }
+            }
+
+            let contract_id = Uuid::new_v4();
+            let due_date = today - Duration::days(40);
+            sqlx::query(
+                "INSERT INTO financial_contracts (id, tenant_id, student_id, description, total_amount, installments_count, first_due_date)
+                 VALUES ($1, $2, $3, 'Anuidade', 1200, 1, $4)",
+            )
+            .bind(contract_id)
+            .bind(tenant_id)
+            .bind(at_risk_id)
+            .bind(due_date)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO financial_installments (id, contract_id, tenant_id, installment_number, due_date, amount, status)
+                 VALUES ($1, $2, $3, 1, $4, 1200, 'pending')",
+            )
+            .bind(Uuid::new_v4())
+            .bind(contract_id)
+            .bind(tenant_id)
+            .bind(due_date)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+            let f = Fixture {
+                pool,
+                app,
+                token,
+                tenant_id,
+                class_id,
+                subject_id,
+                second_term_id,
+                at_risk_id,
+                doing_well_id,
+                today,
+            };
+            let (status, _) = f.create_incident(f.at_risk_id, &f.token, "high").await;
+            assert_eq!(status, StatusCode::OK);
+            f
+        }
+
+        fn risk_path(&self) -> String {
+            format!("/student-risk?class_id={}", self.class_id)
+        }
+
+        async fn risk(&self, query: &str) -> (StatusCode, Value) {
+            call_json(&self.app, "GET", &format!("{}{query}", self.risk_path()), &self.token, None).await
+        }
+
+        async fn create_incident(&self, student_id: Uuid, token: &str, severity: &str) -> (StatusCode, Value) {
+            let occurred_on = self.today - Duration::days(10);
+            call_json(
+                &self.app,
+                "POST",
+                &format!("/students/{student_id}/discipline-incidents"),
+                token,
+                Some(json!({"occurred_on": occurred_on, "severity": severity, "description": "Briga no intervalo"})),
+            )
+            .await
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn factor_codes(item: &Value) -> Vec<&str> {
+        item["factors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["code"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn student_risk_ranks_students_and_explains_factors() {
+        let f = Fixture::new().await;
+
+        let (status, data) = f.risk("").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(data["as_of"], f.today.to_string());
+        let items = data["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        let top = &items[0];
+        assert_eq!(top["student_id"], f.at_risk_id.to_string());
+        assert_eq!(top["level"], "high");
+        let codes = factor_codes(top);
+        for code in [
+            "grade_decline",
+            "low_grades",
+            "low_attendance",
+            "attendance_trend",
+            "consecutive_absences",
+            "financial_debt",
+            "discipline",
+        ] {
+            assert!(codes.contains(&code), "fator {code} ausente em {codes:?}");
+        }
+        let points: i64 = top["factors"].as_array().unwrap().iter().map(|f| f["points"].as_i64().unwrap()).sum();
+        assert_eq!(top["score"].as_i64().unwrap(), points.min(100));
+        assert_eq!(items[1]["student_id"], f.doing_well_id.to_string());
+        assert_eq!(items[1]["score"], 0);
+        assert_eq!(items[1]["level"], "low");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_risk_filters_by_minimum_level() {
+        let f = Fixture::new().await;
+
+        let (status, high_only) = f.risk("&min_level=high").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(high_only["items"].as_array().unwrap().len(), 1);
+        let (status, _) = f.risk("&min_level=grave").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_risk_is_only_computed_for_today() {
+        let f = Fixture::new().await;
+
+        let (status, _) = f.risk(&format!("&as_of={}", f.today)).await;
+        assert_eq!(status, StatusCode::OK);
+        for date in [f.today - Duration::days(1), f.today + Duration::days(1)] {
+            let (status, _) = f.risk(&format!("&as_of={date}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{date}");
+        }
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_risk_uses_council_adjusted_grades_from_the_report_card() {
+        let f = Fixture::new().await;
+        let council_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO class_councils (id, tenant_id, class_id, term_id, held_on) VALUES ($1, $2, $3, $4, $5)")
+            .bind(council_id)
+            .bind(f.tenant_id)
+            .bind(f.class_id)
+            .bind(f.second_term_id)
+            .bind(f.today)
+            .execute(&f.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO class_council_decisions (id, tenant_id, council_id, student_id, subject_id, decision, justification, adjusted_score)
+             VALUES ($1, $2, $3, $4, $5, 'approve', 'Recuperou nos trabalhos', 8)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(f.tenant_id)
+        .bind(council_id)
+        .bind(f.at_risk_id)
+        .bind(f.subject_id)
+        .execute(&f.pool)
+        .await
+        .unwrap();
+
+        let (_, data) = f.risk("").await;
+        let enzo = data["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|i| i["student_id"] == f.at_risk_id.to_string())
+            .unwrap();
+        let codes = factor_codes(enzo);
+        assert!(!codes.contains(&"grade_decline"), "{codes:?}");
+        assert!(!codes.contains(&"low_grades"), "{codes:?}");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_risk_shows_previous_snapshot_and_history() {
+        let f = Fixture::new().await;
+        let yesterday = f.today - Duration::days(1);
+        let count = crate::jobs::student_risk::snapshot_tenant(&f.pool, f.tenant_id, yesterday)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let (_, data) = f.risk("").await;
+        assert_eq!(data["items"][0]["previous_snapshot_date"], yesterday.to_string());
+        assert_eq!(data["items"][0]["previous_score"], data["items"][0]["score"]);
+
+        let (status, history) =
+            call_json(&f.app, "GET", &format!("/students/{}/risk-history", f.at_risk_id), &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(history[0]["snapshot_date"], yesterday.to_string());
+        assert_eq!(history[0]["level"], "high");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn discipline_incidents_validate_severity_and_teaching_assignment() {
+        let f = Fixture::new().await;
+
+        let (status, _) = f.create_incident(f.doing_well_id, &f.token, "grave").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+        let (status, _) = f.create_incident(f.doing_well_id, &teacher, "low").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, incidents) = call_json(
+            &f.app,
+            "GET",
+            &format!("/students/{}/discipline-incidents", f.at_risk_id),
+            &f.token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(incidents.as_array().unwrap().len(), 1);
+        assert_eq!(incidents[0]["severity"], "high");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn student_risk_is_forbidden_to_teachers() {
+        let f = Fixture::new().await;
+
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+        let (status, _) = call_json(&f.app, "GET", &f.risk_path(), &teacher, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        f.cleanup().await;
+    }
+}
@@ -10,6 +10,7 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::restricted_teacher;
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize, Validate)]
@@ -18,6 +19,8 @@ pub struct CreateSubjectRequest {
     pub name: String,
     pub code: Option<String>,
     pub teacher_user_id: Option<Uuid>,
+    #[validate(range(min = 1))]
+    pub workload_hours: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,6 +36,30 @@ pub struct SubjectResponse {
     pub code: Option<String>,
     pub teacher_user_id: Option<Uuid>,
     pub teacher_name: Option<String>,
+    /// Carga horária anual padrão (horas), usada no histórico escolar.
+    pub workload_hours: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GradeWorkload {
+    /// Série como cadastrada na turma (`classes.grade`).
+    pub grade: String,
+    pub workload_hours: i32,
+}
+
+/// Substitui a carga horária padrão e a lista por série.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSubjectWorkloadRequest {
+    pub workload_hours: Option<i32>,
+    #[serde(default)]
+    pub grades: Vec<GradeWorkload>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubjectWorkloadResponse {
+    pub subject_id: Uuid,
+    pub workload_hours: Option<i32>,
+    pub grades: Vec<GradeWorkload>,
 }
 
 pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
@@ -40,6 +67,10 @@ pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
     Router::new()
         .route("/subjects", get(list_subjects).post(create_subject))
         .route("/subjects/:subject_id/teacher", put(assign_teacher))
+        .route(
+            "/subjects/:subject_id/workload",
+            get(get_subject_workload).put(update_subject_workload),
+        )
         .with_state(state)
 }
 
@@ -57,16 +88,22 @@ async fn list_subjects(
           s.name,
           s.code,
           s.teacher_user_id,
+          s.workload_hours,
           u.full_name AS teacher_name
         FROM subjects s
         LEFT JOIN users u
           ON u.id = s.teacher_user_id
          AND u.tenant_id = s.tenant_id
         WHERE s.tenant_id = $1
+          AND ($2::uuid IS NULL OR EXISTS (
+            SELECT 1 FROM teaching_assignments ta
+            WHERE ta.tenant_id = s.tenant_id AND ta.subject_id = s.id AND ta.teacher_user_id = $2
+          ))
         ORDER BY s.name ASC
         "#,
     )
     .bind(user.tenant_id)
+    .bind(restricted_teacher(&user))
     .fetch_all(&state.pool)
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
@@ -80,6 +117,7 @@ async fn list_subjects(
             code: r.get("code"),
             teacher_user_id: r.get("teacher_user_id"),
             teacher_name: r.get("teacher_name"),
+            workload_hours: r.get("workload_hours"),
         })
         .collect();
 
@@ -104,9 +142,9 @@ async fn create_subject(
 
     let row = sqlx::query(
         r#"
-        INSERT INTO subjects (id, tenant_id, name, code, teacher_user_id)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, tenant_id, name, code, teacher_user_id
+        INSERT INTO subjects (id, tenant_id, name, code, teacher_user_id, workload_hours)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, tenant_id, name, code, teacher_user_id, workload_hours
         "#,
     )
     .bind(id)
@@ -114,6 +152,7 @@ async fn create_subject(
     .bind(&name)
     .bind(&code)
     .bind(req.teacher_user_id)
+    .bind(req.workload_hours)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro DB: {e}")))?;
@@ -131,6 +170,7 @@ async fn create_subject(
         code: row.get("code"),
         teacher_user_id: row.get("teacher_user_id"),
         teacher_name,
+        workload_hours: row.get("workload_hours"),
     }))
 }
 
@@ -151,7 +191,7 @@ async fn assign_teacher(
         UPDATE subjects
         SET teacher_user_id = $3
         WHERE tenant_id = $1 AND id = $2
-        RETURNING id, tenant_id, name, code, teacher_user_id
+        RETURNING id, tenant_id, name, code, teacher_user_id, workload_hours
         "#,
     )
     .bind(user.tenant_id)
@@ -176,9 +216,125 @@ async fn assign_teacher(
         code: row.get("code"),
         teacher_user_id,
         teacher_name,
+        workload_hours: row.get("workload_hours"),
     }))
 }
 
+async fn get_subject_workload(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(subject_id): Path<Uuid>,
+) -> Result<Json<SubjectWorkloadResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    Ok(Json(load_subject_workload(&state.pool, user.tenant_id, subject_id).await?))
+}
+
+async fn update_subject_workload(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(subject_id): Path<Uuid>,
+    Json(req): Json<UpdateSubjectWorkloadRequest>,
+) -> Result<Json<SubjectWorkloadResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    if req.workload_hours.is_some_and(|h| h <= 0) || req.grades.iter().any(|g| g.workload_hours <= 0) {
+        return Err((StatusCode::BAD_REQUEST, "Carga horária deve ser maior que zero".into()));
+    }
+    let mut grades: Vec<(String, i32)> = Vec::new();
+    for g in req.grades {
+        let grade = g.grade.trim().to_string();
+        if grade.is_empty() {
+            return Err((StatusCode::BAD_REQUEST, "Série obrigatória na carga horária".into()));
+        }
+        if grades.iter().any(|(existing, _)| existing == &grade) {
+            return Err((StatusCode::BAD_REQUEST, format!("Série repetida: {grade}")));
+        }
+        grades.push((grade, g.workload_hours));
+    }
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let updated = sqlx::query("UPDATE subjects SET workload_hours = $3 WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(subject_id)
+        .bind(req.workload_hours)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if updated.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Disciplina não encontrada".into()));
+    }
+
+    sqlx::query("DELETE FROM subject_grade_workloads WHERE tenant_id = $1 AND subject_id = $2")
+        .bind(user.tenant_id)
+        .bind(subject_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    for (grade, hours) in &grades {
+        sqlx::query(
+            r#"INSERT INTO subject_grade_workloads (tenant_id, subject_id, grade, workload_hours)
+               VALUES ($1, $2, $3, $4)"#,
+        )
+        .bind(user.tenant_id)
+        .bind(subject_id)
+        .bind(grade)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(load_subject_workload(&state.pool, user.tenant_id, subject_id).await?))
+}
+
+async fn load_subject_workload(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    subject_id: Uuid,
+) -> Result<SubjectWorkloadResponse, (StatusCode, String)> {
+    let workload_hours: Option<i32> =
+        sqlx::query_scalar("SELECT workload_hours FROM subjects WHERE tenant_id = $1 AND id = $2")
+            .bind(tenant_id)
+            .bind(subject_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+            .ok_or((StatusCode::NOT_FOUND, "Disciplina não encontrada".into()))?;
+
+    let rows = sqlx::query(
+        r#"SELECT grade, workload_hours
+           FROM subject_grade_workloads
+           WHERE tenant_id = $1 AND subject_id = $2
+           ORDER BY grade ASC"#,
+    )
+    .bind(tenant_id)
+    .bind(subject_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(SubjectWorkloadResponse {
+        subject_id,
+        workload_hours,
+        grades: rows
+            .into_iter()
+            .map(|r| GradeWorkload {
+                grade: r.get("grade"),
+                workload_hours: r.get("workload_hours"),
+            })
+            .collect(),
+    })
+}
+
 async fn ensure_teacher_belongs_to_tenant(
     pool: &PgPool,
     tenant_id: Uuid,
@@ -1,7 +1,3 @@
-use argon2::{
-    password_hash::{PasswordHasher, SaltString},
-    Argon2,
-};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
@@ -13,6 +9,7 @@ use sqlx::{PgPool, Row};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::auth::identity::link_or_create_identity;
 use crate::auth::jwt::AuthUser;
 use crate::state::AppState;
 
@@ -123,7 +120,6 @@ async fn create_teacher(
     }
     let email = req.email.trim().to_lowercase();
     let phone = normalize_optional_text(req.phone);
-    let password_hash = hash_password(&req.password)?;
 
     let person_type = role_to_person_type(role);
     let mut tx = state
@@ -232,16 +228,20 @@ async fn create_teacher(
     .await
     .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro DB: {e}")))?;
 
+    // Se o e-mail já tem identidade (outra escola), só vincula com a senha atual dela;
+    // do contrário 409, para a escola não tomar a conta de outra pessoa.
+    let identity_id = link_or_create_identity(&mut tx, &email, &req.password, true).await?;
+
     sqlx::query(
-        r#"INSERT INTO users (id, tenant_id, person_id, full_name, email, password_hash, role, phone)
+        r#"INSERT INTO users (id, tenant_id, person_id, identity_id, full_name, email, role, phone)
            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
     )
     .bind(id)
     .bind(user.tenant_id)
     .bind(person_id)
+    .bind(identity_id)
     .bind(&full_name)
     .bind(&email)
-    .bind(password_hash)
     .bind(role)
     .bind(&phone)
     .execute(&mut *tx)
@@ -398,15 +398,6 @@ async fn delete_teacher(
     Ok(Json(OkResponse { ok: true }))
 }
 
-fn hash_password(password: &str) -> Result<String, (StatusCode, String)> {
-    let salt = SaltString::generate(&mut rand::thread_rng());
-    let argon2 = Argon2::default();
-    argon2
-        .hash_password(password.as_bytes(), &salt)
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro hash".into()))
-        .map(|h| h.to_string())
-}
-
 fn normalize_role(role: &str) -> Result<&str, (StatusCode, String)> {
     match role.trim().to_lowercase().as_str() {
         "admin" => Ok("admin"),
@@ -0,0 +1,364 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::restricted_teacher;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct TeachingAssignmentsQuery {
+    pub teacher_user_id: Option<Uuid>,
+    pub class_id: Option<Uuid>,
+    pub school_year: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTeachingAssignmentRequest {
+    pub teacher_user_id: Uuid,
+    pub class_id: Uuid,
+    pub subject_id: Uuid,
+    /// Padrão: ano da turma.
+    pub school_year: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TeachingAssignmentResponse {
+    pub id: Uuid,
+    pub teacher_user_id: Uuid,
+    pub teacher_name: Option<String>,
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub school_year: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/teaching-assignments",
+            get(list_teaching_assignments).post(create_teaching_assignment),
+        )
+        .route("/teaching-assignments/:assignment_id", delete(delete_teaching_assignment))
+        .with_state(state)
+}
+
+async fn list_teaching_assignments(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<TeachingAssignmentsQuery>,
+) -> Result<Json<Vec<TeachingAssignmentResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "teacher", "staff"])?;
+    // professor só enxerga as próprias atribuições
+    let teacher_user_id = restricted_teacher(&user).or(query.teacher_user_id);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT ta.id, ta.teacher_user_id, COALESCE(u.full_name, u.email) AS teacher_name,
+               ta.class_id, c.name AS class_name, ta.subject_id, s.name AS subject_name,
+               ta.school_year
+        FROM teaching_assignments ta
+        JOIN classes c ON c.id = ta.class_id AND c.tenant_id = ta.tenant_id
+        JOIN subjects s ON s.id = ta.subject_id AND s.tenant_id = ta.tenant_id
+        LEFT JOIN users u ON u.id = ta.teacher_user_id AND u.tenant_id = ta.tenant_id
+        WHERE ta.tenant_id = $1
+          AND ($2::uuid IS NULL OR ta.teacher_user_id = $2)
+          AND ($3::uuid IS NULL OR ta.class_id = $3)
+          AND ($4::int IS NULL OR ta.school_year = $4)
+        ORDER BY ta.school_year DESC, c.name ASC, s.name ASC, teacher_name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .bind(query.class_id)
+    .bind(query.school_year)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(map_assignment_row).collect()))
+}
+
+async fn create_teaching_assignment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<CreateTeachingAssignmentRequest>,
+) -> Result<Json<TeachingAssignmentResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let teacher: Option<i32> = sqlx::query_scalar(
+        r#"SELECT 1 FROM users
+           WHERE tenant_id = $1 AND id = $2 AND role IN ('owner', 'admin', 'teacher')"#,
+    )
+    .bind(user.tenant_id)
+    .bind(req.teacher_user_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if teacher.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Professor inválido para este tenant".into()));
+    }
+
+    let class_year: i32 = sqlx::query_scalar("SELECT year FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(req.class_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::BAD_REQUEST, "Turma inválida para este tenant".into()))?;
+    let school_year = req.school_year.unwrap_or(class_year);
+    if school_year != class_year {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("A turma é do ano letivo {class_year}"),
+        ));
+    }
+
+    let subject: Option<i32> = sqlx::query_scalar("SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(req.subject_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if subject.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()));
+    }
+
+    let id = Uuid::new_v4();
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (tenant_id, teacher_user_id, class_id, subject_id, school_year) DO NOTHING
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(req.teacher_user_id)
+    .bind(req.class_id)
+    .bind(req.subject_id)
+    .bind(school_year)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if inserted.rows_affected() == 0 {
+        return Err((StatusCode::CONFLICT, "Atribuição já cadastrada".into()));
+    }
+
+    let row = sqlx::query(
+        r#"
+        SELECT ta.id, ta.teacher_user_id, COALESCE(u.full_name, u.email) AS teacher_name,
+               ta.class_id, c.name AS class_name, ta.subject_id, s.name AS subject_name,
+               ta.school_year
+        FROM teaching_assignments ta
+        JOIN classes c ON c.id = ta.class_id AND c.tenant_id = ta.tenant_id
+        JOIN subjects s ON s.id = ta.subject_id AND s.tenant_id = ta.tenant_id
+        LEFT JOIN users u ON u.id = ta.teacher_user_id AND u.tenant_id = ta.tenant_id
+        WHERE ta.tenant_id = $1 AND ta.id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(map_assignment_row(&row)))
+}
+
+async fn delete_teaching_assignment(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(assignment_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let res = sqlx::query("DELETE FROM teaching_assignments WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(assignment_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Atribuição não encontrada".into()));
+    }
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+fn map_assignment_row(r: &sqlx::postgres::PgRow) -> TeachingAssignmentResponse {
+    TeachingAssignmentResponse {
+        id: r.get("id"),
+        teacher_user_id: r.get("teacher_user_id"),
+        teacher_name: r.get("teacher_name"),
+        class_id: r.get("class_id"),
+        class_name: r.get("class_name"),
+        subject_id: r.get("subject_id"),
+        subject_name: r.get("subject_name"),
+        school_year: r.get("school_year"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, insert_user, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turmas 9A/9B, Matemática/Artes, uma aluna na 9A e um professor ainda sem atribuição.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        teacher: String,
+        tenant_id: Uuid,
+        teacher_id: Uuid,
+        class_a: Uuid,
+        class_b: Uuid,
+        math: Uuid,
+        art: Uuid,
+        term_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Atribuição").await;
+            let class_a = insert_class(&pool, tenant_id, "9A", "9 ano", 2026).await;
+            let class_b = insert_class(&pool, tenant_id, "9B", "9 ano", 2026).await;
+            let math = insert_subject(&pool, tenant_id, "Matemática").await;
+            let art = insert_subject(&pool, tenant_id, "Artes").await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", 2026, 1).await;
+            let student_id = insert_student(&pool, tenant_id, class_a, "Elisa", "E1").await;
+            let teacher_id = insert_user(&pool, tenant_id, "Prof. Fábio", "teacher").await;
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let teacher = make_token(tenant_id, teacher_id, "teacher");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::classes::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::subjects::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, owner, teacher, tenant_id, teacher_id, class_a, class_b, math, art, term_id, student_id }
+        }
+
+        /// Atribui Matemática na 9A ao professor e devolve a atribuição criada.
+        async fn assign_math(&self) -> Value {
+            let (status, created) = call_json(&self.app, "POST", "/teaching-assignments", &self.owner, Some(json!({
+                "teacher_user_id": self.teacher_id, "class_id": self.class_a, "subject_id": self.math
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+            created
+        }
+
+        async fn teacher_grades(&self, subject_id: Uuid) -> StatusCode {
+            let path = format!("/classes/{}/gradebook", self.class_a);
+            call_json(&self.app, "PUT", &path, &self.teacher, Some(json!({
+                "term_id": self.term_id, "subject_id": subject_id,
+                "records": [{"student_id": self.student_id, "score": 8}]
+            })))
+            .await
+            .0
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn assignment_uses_class_year_and_rejects_duplicates() {
+        let f = Fixture::new().await;
+        let created = f.assign_math().await;
+        assert_eq!(created["school_year"], 2026);
+
+        let (status, _) = call_json(&f.app, "POST", "/teaching-assignments", &f.owner, Some(json!({
+            "teacher_user_id": f.teacher_id, "class_id": f.class_a, "subject_id": f.math
+        }))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_cannot_assign_classes_to_themselves() {
+        let f = Fixture::new().await;
+
+        let (status, _) = call_json(&f.app, "POST", "/teaching-assignments", &f.teacher, Some(json!({
+            "teacher_user_id": f.teacher_id, "class_id": f.class_b, "subject_id": f.art
+        }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_lists_only_assigned_classes_and_subjects() {
+        let f = Fixture::new().await;
+        f.assign_math().await;
+
+        let (_, classes) = call_json(&f.app, "GET", "/classes", &f.teacher, None).await;
+        assert_eq!(classes.as_array().unwrap().len(), 1);
+        assert_eq!(classes[0]["id"], f.class_a.to_string());
+        let (_, subjects) = call_json(&f.app, "GET", "/subjects", &f.teacher, None).await;
+        assert_eq!(subjects.as_array().unwrap().len(), 1);
+        let (_, mine) = call_json(&f.app, "GET", "/teaching-assignments", &f.teacher, None).await;
+        assert_eq!(mine.as_array().unwrap().len(), 1);
+        let (_, classes) = call_json(&f.app, "GET", "/classes", &f.owner, None).await;
+        assert_eq!(classes.as_array().unwrap().len(), 2);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_records_only_in_assigned_class_and_subject() {
+        let f = Fixture::new().await;
+        f.assign_math().await;
+
+        assert_eq!(f.teacher_grades(f.math).await, StatusCode::OK);
+        assert_eq!(f.teacher_grades(f.art).await, StatusCode::FORBIDDEN);
+        let path = format!("/classes/{}/attendance?date=2026-03-02&subject_id={}", f.class_b, f.math);
+        let (status, _) = call_json(&f.app, "GET", &path, &f.teacher, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let path = format!("/classes/{}/students/{}/full-report", f.class_a, f.student_id);
+        let (status, _) = call_json(&f.app, "GET", &path, &f.teacher, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn removing_assignment_revokes_access() {
+        let f = Fixture::new().await;
+        let created = f.assign_math().await;
+
+        let path = format!("/teaching-assignments/{}", created["id"].as_str().unwrap());
+        let (status, _) = call_json(&f.app, "DELETE", &path, &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(f.teacher_grades(f.math).await, StatusCode::FORBIDDEN);
+
+        f.cleanup().await;
+    }
+}
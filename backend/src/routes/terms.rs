@@ -1,15 +1,17 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    routing::{get, put},
+    routing::{get, post, put},
     Json, Router,
 };
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::Row;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::auth::jwt::AuthUser;
+use crate::grading::term_lock::{record_correction, validate_justification, CORRECTION_REOPEN};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize, Validate)]
@@ -18,6 +20,8 @@ pub struct CreateTermRequest {
     pub name: String,
     pub school_year: i32,
     pub sort_order: Option<i32>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,6 +32,10 @@ pub struct TermResponse {
     pub school_year: i32,
     pub sort_order: i32,
     pub is_active: bool,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub is_closed: bool,
+    pub closed_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -36,6 +44,8 @@ pub struct UpdateTermRequest {
     pub name: String,
     pub school_year: i32,
     pub sort_order: Option<i32>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,6 +53,24 @@ pub struct UpdateTermStatusRequest {
     pub is_active: bool,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReopenTermRequest {
+    pub justification: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TermCorrectionItem {
+    pub id: Uuid,
+    pub class_id: Option<Uuid>,
+    pub class_name: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub user_name: Option<String>,
+    pub kind: String,
+    pub justification: String,
+    pub payload: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
 #[derive(Debug, Serialize)]
 pub struct OkResponse {
     pub ok: bool,
@@ -55,6 +83,9 @@ pub fn routes(pool: sqlx::PgPool, jwt_secret: String) -> Router {
         .route("/terms", get(list_terms).post(create_term))
         .route("/terms/:term_id", put(update_term).delete(delete_term))
         .route("/terms/:term_id/status", put(update_term_status))
+        .route("/terms/:term_id/close", post(close_term))
+        .route("/terms/:term_id/reopen", post(reopen_term))
+        .route("/terms/:term_id/corrections", get(list_term_corrections))
         .with_state(state)
 }
 
@@ -66,7 +97,7 @@ async fn list_terms(
 
     let rows = sqlx::query(
         r#"
-        SELECT id, tenant_id, name, school_year, sort_order, is_active
+        SELECT id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
         FROM academic_terms
         WHERE tenant_id = $1
         ORDER BY school_year DESC, sort_order ASC, name ASC
@@ -77,17 +108,7 @@ async fn list_terms(
     .await
     .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
 
-    let out = rows
-        .into_iter()
-        .map(|r| TermResponse {
-            id: r.get("id"),
-            tenant_id: r.get("tenant_id"),
-            name: r.get("name"),
-            school_year: r.get("school_year"),
-            sort_order: r.get("sort_order"),
-            is_active: r.get("is_active"),
-        })
-        .collect();
+    let out = rows.iter().map(map_term_row).collect();
 
     Ok(Json(out))
 }
@@ -107,12 +128,13 @@ async fn create_term(
     let id = Uuid::new_v4();
     let name = req.name.trim().to_string();
     let sort_order = req.sort_order.unwrap_or(1);
+    validate_term_dates(&state.pool, user.tenant_id, None, req.start_date, req.end_date).await?;
 
     let row = sqlx::query(
         r#"
-        INSERT INTO academic_terms (id, tenant_id, name, school_year, sort_order, is_active)
-        VALUES ($1, $2, $3, $4, $5, true)
-        RETURNING id, tenant_id, name, school_year, sort_order, is_active
+        INSERT INTO academic_terms (id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date)
+        VALUES ($1, $2, $3, $4, $5, true, $6, $7)
+        RETURNING id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
         "#,
     )
     .bind(id)
@@ -120,18 +142,13 @@ async fn create_term(
     .bind(&name)
     .bind(req.school_year)
     .bind(sort_order)
+    .bind(req.start_date)
+    .bind(req.end_date)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro DB: {e}")))?;
 
-    Ok(Json(TermResponse {
-        id: row.get("id"),
-        tenant_id: row.get("tenant_id"),
-        name: row.get("name"),
-        school_year: row.get("school_year"),
-        sort_order: row.get("sort_order"),
-        is_active: row.get("is_active"),
-    }))
+    Ok(Json(map_term_row(&row)))
 }
 
 async fn update_term(
@@ -152,16 +169,39 @@ async fn update_term(
     if sort_order < 1 {
         return Err((StatusCode::BAD_REQUEST, "sort_order inválido".into()));
     }
+    validate_term_dates(&state.pool, user.tenant_id, Some(term_id), req.start_date, req.end_date).await?;
+
+    // datas de período encerrado definem quais chamadas estão travadas
+    let current = sqlx::query(
+        "SELECT start_date, end_date, closed_at FROM academic_terms WHERE tenant_id = $1 AND id = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
+    let closed_at: Option<NaiveDateTime> = current.get("closed_at");
+    let dates_changed = current.get::<Option<NaiveDate>, _>("start_date") != req.start_date
+        || current.get::<Option<NaiveDate>, _>("end_date") != req.end_date;
+    if closed_at.is_some() && dates_changed {
+        return Err((
+            StatusCode::CONFLICT,
+            "Período encerrado; reabra antes de alterar as datas".into(),
+        ));
+    }
 
     let row = sqlx::query(
         r#"
         UPDATE academic_terms
            SET name = $3,
                school_year = $4,
-               sort_order = $5
+               sort_order = $5,
+               start_date = $6,
+               end_date = $7
          WHERE tenant_id = $1
            AND id = $2
-         RETURNING id, tenant_id, name, school_year, sort_order, is_active
+         RETURNING id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
         "#,
     )
     .bind(user.tenant_id)
@@ -169,20 +209,15 @@ async fn update_term(
     .bind(&name)
     .bind(req.school_year)
     .bind(sort_order)
+    .bind(req.start_date)
+    .bind(req.end_date)
     .fetch_optional(&state.pool)
     .await
     .map_err(|e| (StatusCode::BAD_REQUEST, format!("Erro DB: {e}")))?;
 
     let row = row.ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
 
-    Ok(Json(TermResponse {
-        id: row.get("id"),
-        tenant_id: row.get("tenant_id"),
-        name: row.get("name"),
-        school_year: row.get("school_year"),
-        sort_order: row.get("sort_order"),
-        is_active: row.get("is_active"),
-    }))
+    Ok(Json(map_term_row(&row)))
 }
 
 async fn update_term_status(
@@ -199,7 +234,7 @@ async fn update_term_status(
            SET is_active = $3
          WHERE tenant_id = $1
            AND id = $2
-         RETURNING id, tenant_id, name, school_year, sort_order, is_active
+         RETURNING id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
         "#,
     )
     .bind(user.tenant_id)
@@ -211,14 +246,7 @@ async fn update_term_status(
 
     let row = row.ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
 
-    Ok(Json(TermResponse {
-        id: row.get("id"),
-        tenant_id: row.get("tenant_id"),
-        name: row.get("name"),
-        school_year: row.get("school_year"),
-        sort_order: row.get("sort_order"),
-        is_active: row.get("is_active"),
-    }))
+    Ok(Json(map_term_row(&row)))
 }
 
 async fn delete_term(
@@ -269,3 +297,385 @@ async fn delete_term(
 
     Ok(Json(OkResponse { ok: true }))
 }
+
+/// Encerra o período: diário, recuperação e chamadas dentro das datas ficam travados.
+/// Sem início e fim o bloqueio das chamadas não teria intervalo, então as datas são obrigatórias.
+async fn close_term(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(term_id): Path<Uuid>,
+) -> Result<Json<TermResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let has_dates: bool = sqlx::query_scalar(
+        r#"
+        SELECT start_date IS NOT NULL AND end_date IS NOT NULL
+        FROM academic_terms
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
+    if !has_dates {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Informe as datas de início e fim antes de encerrar o período".into(),
+        ));
+    }
+
+    let row = sqlx::query(
+        r#"
+        UPDATE academic_terms
+           SET closed_at = COALESCE(closed_at, NOW()),
+               closed_by = COALESCE(closed_by, $3)
+         WHERE tenant_id = $1
+           AND id = $2
+         RETURNING id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .bind(user.user_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let row = row.ok_or((StatusCode::NOT_FOUND, "Período não encontrado".into()))?;
+    Ok(Json(map_term_row(&row)))
+}
+
+async fn reopen_term(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(term_id): Path<Uuid>,
+    Json(req): Json<ReopenTermRequest>,
+) -> Result<Json<TermResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+    let justification = validate_justification(Some(&req.justification))?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let row = sqlx::query(
+        r#"
+        UPDATE academic_terms
+           SET closed_at = NULL,
+               closed_by = NULL
+         WHERE tenant_id = $1
+           AND id = $2
+           AND closed_at IS NOT NULL
+         RETURNING id, tenant_id, name, school_year, sort_order, is_active, start_date, end_date, closed_at
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let row = row.ok_or((StatusCode::NOT_FOUND, "Período não encontrado ou já aberto".into()))?;
+    record_correction(
+        &mut tx,
+        &user,
+        term_id,
+        None,
+        CORRECTION_REOPEN,
+        justification,
+        serde_json::json!({}),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(map_term_row(&row)))
+}
+
+async fn list_term_corrections(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(term_id): Path<Uuid>,
+) -> Result<Json<Vec<TermCorrectionItem>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin"])?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT tc.id, tc.class_id, c.name AS class_name, tc.user_id,
+               COALESCE(u.full_name, u.email) AS user_name,
+               tc.kind, tc.justification, tc.payload, tc.created_at
+        FROM term_corrections tc
+        LEFT JOIN classes c ON c.id = tc.class_id AND c.tenant_id = tc.tenant_id
+        LEFT JOIN users u ON u.id = tc.user_id AND u.tenant_id = tc.tenant_id
+        WHERE tc.tenant_id = $1 AND tc.term_id = $2
+        ORDER BY tc.created_at DESC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(term_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| TermCorrectionItem {
+                id: r.get("id"),
+                class_id: r.get("class_id"),
+                class_name: r.get("class_name"),
+                user_id: r.get("user_id"),
+                user_name: r.get("user_name"),
+                kind: r.get("kind"),
+                justification: r.get("justification"),
+                payload: r.get("payload"),
+                created_at: r.get("created_at"),
+            })
+            .collect(),
+    ))
+}
+
+fn map_term_row(row: &sqlx::postgres::PgRow) -> TermResponse {
+    let closed_at: Option<NaiveDateTime> = row.get("closed_at");
+    TermResponse {
+        id: row.get("id"),
+        tenant_id: row.get("tenant_id"),
+        name: row.get("name"),
+        school_year: row.get("school_year"),
+        sort_order: row.get("sort_order"),
+        is_active: row.get("is_active"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        is_closed: closed_at.is_some(),
+        closed_at,
+    }
+}
+
+/// Datas coerentes e sem sobreposição com outros períodos da escola.
+async fn validate_term_dates(
+    pool: &sqlx::PgPool,
+    tenant_id: Uuid,
+    term_id: Option<Uuid>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<(), (StatusCode, String)> {
+    let (start_date, end_date) = match (start_date, end_date) {
+        (None, None) => return Ok(()),
+        (Some(s), Some(e)) if s <= e => (s, e),
+        (Some(_), Some(_)) => {
+            return Err((StatusCode::BAD_REQUEST, "Data inicial deve ser anterior à final".into()))
+        }
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Informe data inicial e final do período".into(),
+            ))
+        }
+    };
+
+    let overlapping: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT name FROM academic_terms
+        WHERE tenant_id = $1
+          AND ($2::uuid IS NULL OR id <> $2)
+          AND start_date <= $4 AND end_date >= $3
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(term_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if let Some(name) = overlapping {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Datas se sobrepõem ao período \"{name}\""),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_user, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    /// Turma 8A com uma aluna, professor atribuído a História e o 1º bimestre (fev-abr/2026).
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        teacher: String,
+        tenant_id: Uuid,
+        class_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+        term_id: String,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Encerramento").await;
+            let class_id = insert_class(&pool, tenant_id, "8A", "8 ano", 2026).await;
+            let subject_id = insert_subject(&pool, tenant_id, "História").await;
+            let student_id = insert_student(&pool, tenant_id, class_id, "Carla", "C1").await;
+
+            // professor com aula atribuída: o bloqueio vem do encerramento, não da atribuição
+            let teacher_id = insert_user(&pool, tenant_id, "Prof. Gil", "teacher").await;
+            sqlx::query(
+                "INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year) VALUES ($1, $2, $3, $4, $5, 2026)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(tenant_id)
+            .bind(teacher_id)
+            .bind(class_id)
+            .bind(subject_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let teacher = make_token(tenant_id, teacher_id, "teacher");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()));
+
+            let (status, term) = call_json(&app, "POST", "/terms", &owner, Some(json!({
+                "name": "1º Bimestre", "school_year": 2026,
+                "start_date": "2026-02-01", "end_date": "2026-04-30"
+            }))).await;
+            assert_eq!(status, StatusCode::OK);
+            let term_id = term["id"].as_str().unwrap().to_string();
+
+            Fixture { pool, app, owner, teacher, tenant_id, class_id, subject_id, student_id, term_id }
+        }
+
+        fn grade(&self, score: f64, justification: Option<&str>) -> Option<Value> {
+            Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "score": score}],
+                "correction_justification": justification
+            }))
+        }
+
+        async fn put_grade(&self, token: &str, score: f64, justification: Option<&str>) -> StatusCode {
+            let path = format!("/classes/{}/gradebook", self.class_id);
+            call_json(&self.app, "PUT", &path, token, self.grade(score, justification)).await.0
+        }
+
+        async fn close(&self) {
+            let (status, closed) =
+                call_json(&self.app, "POST", &format!("/terms/{}/close", self.term_id), &self.owner, None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(closed["is_closed"], true);
+        }
+
+        async fn corrections(&self) -> Value {
+            let path = format!("/terms/{}/corrections", self.term_id);
+            let (status, corrections) = call_json(&self.app, "GET", &path, &self.owner, None).await;
+            assert_eq!(status, StatusCode::OK);
+            corrections
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn terms_of_the_same_year_cannot_overlap() {
+        let f = Fixture::new().await;
+
+        let (status, _) = call_json(&f.app, "POST", "/terms", &f.owner, Some(json!({
+            "name": "2º Bimestre", "school_year": 2026,
+            "start_date": "2026-04-15", "end_date": "2026-06-30"
+        }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn term_without_dates_cannot_be_closed() {
+        let f = Fixture::new().await;
+
+        let (status, term) = call_json(&f.app, "POST", "/terms", &f.owner, Some(json!({
+            "name": "Recuperação final", "school_year": 2026
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let path = format!("/terms/{}/close", term["id"].as_str().unwrap());
+        let (status, _) = call_json(&f.app, "POST", &path, &f.owner, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn closed_term_accepts_grades_only_as_justified_staff_correction() {
+        let f = Fixture::new().await;
+        assert_eq!(f.put_grade(&f.teacher, 5.0, None).await, StatusCode::OK);
+        f.close().await;
+
+        assert_eq!(f.put_grade(&f.teacher, 6.0, None).await, StatusCode::CONFLICT);
+        assert_eq!(f.put_grade(&f.teacher, 6.0, Some("Erro de digitação na prova")).await, StatusCode::FORBIDDEN);
+        assert_eq!(f.put_grade(&f.owner, 6.0, Some("curta")).await, StatusCode::BAD_REQUEST);
+        assert_eq!(f.put_grade(&f.owner, 6.0, Some("Erro de digitação na prova")).await, StatusCode::OK);
+
+        let corrections = f.corrections().await;
+        assert_eq!(corrections.as_array().unwrap().len(), 1);
+        assert_eq!(corrections[0]["kind"], "grade");
+        assert_eq!(corrections[0]["payload"]["records"][0]["score"], 6.0);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn closed_term_locks_attendance_within_its_dates() {
+        let f = Fixture::new().await;
+        f.close().await;
+
+        let attendance = json!({
+            "date": "2026-03-10", "subject_id": f.subject_id,
+            "records": [{"student_id": f.student_id, "present": false}]
+        });
+        let path = format!("/classes/{}/attendance", f.class_id);
+        let (status, _) = call_json(&f.app, "PUT", &path, &f.teacher, Some(attendance)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn reopened_term_accepts_writes_and_logs_the_reopening() {
+        let f = Fixture::new().await;
+        f.close().await;
+
+        let path = format!("/terms/{}/reopen", f.term_id);
+        let (status, _) = call_json(&f.app, "POST", &path, &f.owner, Some(json!({
+            "justification": "Conselho pediu revisão das notas"
+        }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(f.put_grade(&f.teacher, 7.0, None).await, StatusCode::OK);
+        assert_eq!(f.corrections().await[0]["kind"], "reopen");
+
+        f.cleanup().await;
+    }
+}
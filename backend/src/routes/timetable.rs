@@ -0,0 +1,836 @@
+//! Grade horária semanal por turma, com visões por professor e por sala. Um horário não
+//! pode sobrepor outro da mesma turma, nem ocupar duas vezes o mesmo professor ou a mesma
+//! sala no mesmo ano letivo.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::state::AppState;
+
+const MANAGE_ROLES: [&str; 3] = ["owner", "admin", "staff"];
+
+#[derive(Debug, Deserialize)]
+pub struct SlotRequest {
+    /// 1 = segunda ... 7 = domingo.
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub subject_id: Uuid,
+    /// Padrão: o professor atribuído à disciplina na turma, se houver só um.
+    pub teacher_user_id: Option<Uuid>,
+    pub room: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YearQuery {
+    /// Padrão: ano atual.
+    pub year: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrentSlotQuery {
+    /// Padrão: agora.
+    pub at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotResponse {
+    pub id: Uuid,
+    pub class_id: Uuid,
+    pub class_name: String,
+    pub weekday: i32,
+    pub weekday_name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub teacher_user_id: Option<Uuid>,
+    pub teacher_name: Option<String>,
+    pub room: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurrentSlotResponse {
+    pub date: NaiveDate,
+    pub weekday: i32,
+    /// Horário em andamento; `None` fora da grade.
+    pub slot: Option<SlotResponse>,
+    /// Posição do horário no dia (1ª aula, 2ª...), para o `lesson_number` da chamada.
+    pub lesson_number: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoomResponse {
+    pub room: String,
+    pub slots: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/classes/:class_id/timetable",
+            get(list_class_slots).post(create_slot),
+        )
+        .route("/classes/:class_id/timetable/current", get(get_current_slot))
+        .route(
+            "/timetable-slots/:slot_id",
+            put(update_slot).delete(delete_slot),
+        )
+        .route("/teachers/:user_id/timetable", get(list_teacher_slots))
+        .route("/timetable/rooms", get(list_rooms))
+        .route("/timetable/rooms/:room", get(list_room_slots))
+        .with_state(state)
+}
+
+pub fn weekday_name(weekday: i32) -> &'static str {
+    match weekday {
+        1 => "Segunda-feira",
+        2 => "Terça-feira",
+        3 => "Quarta-feira",
+        4 => "Quinta-feira",
+        5 => "Sexta-feira",
+        6 => "Sábado",
+        _ => "Domingo",
+    }
+}
+
+const SLOT_SELECT: &str = r#"
+    SELECT ts.id, ts.class_id, c.name AS class_name, ts.weekday, ts.start_time, ts.end_time,
+           ts.subject_id, s.name AS subject_name, ts.teacher_user_id,
+           COALESCE(u.full_name, u.email) AS teacher_name, ts.room
+    FROM timetable_slots ts
+    JOIN classes c ON c.id = ts.class_id
+    JOIN subjects s ON s.id = ts.subject_id
+    LEFT JOIN users u ON u.id = ts.teacher_user_id
+"#;
+
+async fn list_class_slots(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+) -> Result<Json<Vec<SlotResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    if restricted_teacher(&user).is_some() {
+        ensure_teaches(&state.pool, &user, class_id, None).await?;
+    }
+
+    let slots = class_slots(&state.pool, user.tenant_id, class_id).await?;
+    Ok(Json(slots))
+}
+
+/// Horários da turma na semana, por dia e hora.
+pub async fn class_slots(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<Vec<SlotResponse>, (StatusCode, String)> {
+    let rows = sqlx::query(&format!(
+        "{SLOT_SELECT} WHERE ts.tenant_id = $1 AND ts.class_id = $2 ORDER BY ts.weekday, ts.start_time"
+    ))
+    .bind(tenant_id)
+    .bind(class_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows.iter().map(slot_from_row).collect())
+}
+
+async fn create_slot(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<SlotRequest>,
+) -> Result<Json<SlotResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let class_year = ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let slot_id = Uuid::new_v4();
+    let (teacher_user_id, room) =
+        validate_slot(&mut tx, user.tenant_id, class_id, class_year, None, &req).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO timetable_slots
+          (id, tenant_id, class_id, weekday, start_time, end_time, subject_id, teacher_user_id, room)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(slot_id)
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(req.weekday)
+    .bind(req.start_time)
+    .bind(req.end_time)
+    .bind(req.subject_id)
+    .bind(teacher_user_id)
+    .bind(room)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let slot = load_slot(&state.pool, user.tenant_id, slot_id).await?;
+    Ok(Json(slot))
+}
+
+async fn update_slot(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(slot_id): Path<Uuid>,
+    Json(req): Json<SlotRequest>,
+) -> Result<Json<SlotResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+
+    let row = sqlx::query(
+        r#"
+        SELECT ts.class_id, c.year
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        WHERE ts.tenant_id = $1 AND ts.id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(slot_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Horário não encontrado".into()))?;
+    let class_id: Uuid = row.get("class_id");
+    let class_year: i32 = row.get("year");
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let (teacher_user_id, room) =
+        validate_slot(&mut tx, user.tenant_id, class_id, class_year, Some(slot_id), &req).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE timetable_slots
+        SET weekday = $3, start_time = $4, end_time = $5, subject_id = $6,
+            teacher_user_id = $7, room = $8, updated_at = NOW()
+        WHERE tenant_id = $1 AND id = $2
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(slot_id)
+    .bind(req.weekday)
+    .bind(req.start_time)
+    .bind(req.end_time)
+    .bind(req.subject_id)
+    .bind(teacher_user_id)
+    .bind(room)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let slot = load_slot(&state.pool, user.tenant_id, slot_id).await?;
+    Ok(Json(slot))
+}
+
+async fn delete_slot(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(slot_id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+
+    let deleted = sqlx::query("DELETE FROM timetable_slots WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(slot_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if deleted.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Horário não encontrado".into()));
+    }
+    Ok(Json(OkResponse { ok: true }))
+}
+
+/// Horário em andamento, para a chamada já abrir na disciplina certa.
+async fn get_current_slot(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Query(query): Query<CurrentSlotQuery>,
+) -> Result<Json<CurrentSlotResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    if restricted_teacher(&user).is_some() {
+        ensure_teaches(&state.pool, &user, class_id, None).await?;
+    }
+
+    let at = query.at.unwrap_or_else(|| chrono::Local::now().naive_local());
+    let weekday = at.weekday().number_from_monday() as i32;
+    let time = at.time();
+
+    let day_slots: Vec<SlotResponse> = class_slots(&state.pool, user.tenant_id, class_id)
+        .await?
+        .into_iter()
+        .filter(|s| s.weekday == weekday)
+        .collect();
+    let position = day_slots
+        .iter()
+        .position(|s| s.start_time <= time && time < s.end_time);
+    let lesson_number = position.map(|p| p as i32 + 1);
+    let slot = position.and_then(|p| day_slots.into_iter().nth(p));
+
+    Ok(Json(CurrentSlotResponse {
+        date: at.date(),
+        weekday,
+        slot,
+        lesson_number,
+    }))
+}
+
+async fn list_teacher_slots(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(teacher_user_id): Path<Uuid>,
+    Query(query): Query<YearQuery>,
+) -> Result<Json<Vec<SlotResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    // professor só enxerga a própria grade
+    if restricted_teacher(&user).is_some_and(|own| own != teacher_user_id) {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    let year = query.year.unwrap_or_else(|| chrono::Local::now().year());
+
+    let rows = sqlx::query(&format!(
+        r#"{SLOT_SELECT}
+        WHERE ts.tenant_id = $1 AND ts.teacher_user_id = $2 AND c.year = $3
+        ORDER BY ts.weekday, ts.start_time"#
+    ))
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .bind(year)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(slot_from_row).collect()))
+}
+
+async fn list_rooms(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Query(query): Query<YearQuery>,
+) -> Result<Json<Vec<RoomResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let year = query.year.unwrap_or_else(|| chrono::Local::now().year());
+
+    let rows = sqlx::query(
+        r#"
+        SELECT MIN(ts.room) AS room, COUNT(*) AS slots
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        WHERE ts.tenant_id = $1 AND c.year = $2 AND ts.room IS NOT NULL
+        GROUP BY lower(ts.room)
+        ORDER BY lower(ts.room)
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(year)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| RoomResponse {
+                room: r.get("room"),
+                slots: r.get("slots"),
+            })
+            .collect(),
+    ))
+}
+
+async fn list_room_slots(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(room): Path<String>,
+    Query(query): Query<YearQuery>,
+) -> Result<Json<Vec<SlotResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let year = query.year.unwrap_or_else(|| chrono::Local::now().year());
+
+    let rows = sqlx::query(&format!(
+        r#"{SLOT_SELECT}
+        WHERE ts.tenant_id = $1 AND lower(ts.room) = lower($2) AND c.year = $3
+        ORDER BY ts.weekday, ts.start_time"#
+    ))
+    .bind(user.tenant_id)
+    .bind(room.trim())
+    .bind(year)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(slot_from_row).collect()))
+}
+
+/// Valida o horário e procura conflitos com a grade já gravada. O lock por escola
+/// serializa as gravações, então duas requisições simultâneas não passam juntas.
+/// Devolve o professor (com o padrão aplicado) e a sala normalizada.
+async fn validate_slot(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: Uuid,
+    class_id: Uuid,
+    class_year: i32,
+    slot_id: Option<Uuid>,
+    req: &SlotRequest,
+) -> Result<(Option<Uuid>, Option<String>), (StatusCode, String)> {
+    if !(1..=7).contains(&req.weekday) {
+        return Err((StatusCode::BAD_REQUEST, "Dia da semana inválido (1 = segunda ... 7 = domingo)".into()));
+    }
+    if req.start_time >= req.end_time {
+        return Err((StatusCode::BAD_REQUEST, "Início deve ser antes do fim".into()));
+    }
+
+    let subject = sqlx::query("SELECT 1 FROM subjects WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(req.subject_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if subject.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()));
+    }
+
+    let assigned: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT teacher_user_id
+        FROM teaching_assignments
+        WHERE tenant_id = $1 AND class_id = $2 AND subject_id = $3 AND school_year = $4
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .bind(req.subject_id)
+    .bind(class_year)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let teacher_user_id = match req.teacher_user_id {
+        // só quem está atribuído consegue fazer a chamada desse horário
+        Some(id) if !assigned.contains(&id) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Professor não está atribuído a esta disciplina na turma".into(),
+            ));
+        }
+        Some(id) => Some(id),
+        None if assigned.len() == 1 => Some(assigned[0]),
+        None => None,
+    };
+    let room = req
+        .room
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(str::to_string);
+
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('timetable:' || $1::text))")
+        .bind(tenant_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let conflict = sqlx::query(
+        r#"
+        SELECT c.name AS class_name, s.name AS subject_name, ts.start_time, ts.end_time,
+               CASE
+                 WHEN ts.class_id = $3 THEN 'class'
+                 WHEN ts.teacher_user_id = $7 THEN 'teacher'
+                 ELSE 'room'
+               END AS kind
+        FROM timetable_slots ts
+        JOIN classes c ON c.id = ts.class_id
+        JOIN subjects s ON s.id = ts.subject_id
+        WHERE ts.tenant_id = $1
+          AND ($2::uuid IS NULL OR ts.id <> $2)
+          AND c.year = $4
+          AND ts.weekday = $5
+          AND ts.start_time < $8
+          AND $6 < ts.end_time
+          AND (
+            ts.class_id = $3
+            OR ts.teacher_user_id = $7
+            OR lower(ts.room) = lower($9)
+          )
+        ORDER BY ts.start_time
+        LIMIT 1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(slot_id)
+    .bind(class_id)
+    .bind(class_year)
+    .bind(req.weekday)
+    .bind(req.start_time)
+    .bind(teacher_user_id)
+    .bind(req.end_time)
+    .bind(&room)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    if let Some(c) = conflict {
+        let kind: String = c.get("kind");
+        let who = match kind.as_str() {
+            "class" => "A turma já tem aula",
+            "teacher" => "O professor já tem aula",
+            _ => "A sala já está ocupada",
+        };
+        let start: NaiveTime = c.get("start_time");
+        let end: NaiveTime = c.get("end_time");
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "{who} neste horário: {} ({}), {} das {} às {}",
+                c.get::<String, _>("class_name"),
+                c.get::<String, _>("subject_name"),
+                weekday_name(req.weekday).to_lowercase(),
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            ),
+        ));
+    }
+
+    Ok((teacher_user_id, room))
+}
+
+async fn load_slot(pool: &PgPool, tenant_id: Uuid, slot_id: Uuid) -> Result<SlotResponse, (StatusCode, String)> {
+    let row = sqlx::query(&format!("{SLOT_SELECT} WHERE ts.tenant_id = $1 AND ts.id = $2"))
+        .bind(tenant_id)
+        .bind(slot_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Horário não encontrado".into()))?;
+    Ok(slot_from_row(&row))
+}
+
+fn slot_from_row(r: &sqlx::postgres::PgRow) -> SlotResponse {
+    let weekday: i32 = r.get("weekday");
+    SlotResponse {
+        id: r.get("id"),
+        class_id: r.get("class_id"),
+        class_name: r.get("class_name"),
+        weekday,
+        weekday_name: weekday_name(weekday).to_string(),
+        start_time: r.get("start_time"),
+        end_time: r.get("end_time"),
+        subject_id: r.get("subject_id"),
+        subject_name: r.get("subject_name"),
+        teacher_user_id: r.get("teacher_user_id"),
+        teacher_name: r.get("teacher_name"),
+        room: r.get("room"),
+    }
+}
+
+/// Devolve o ano letivo da turma.
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<i32, (StatusCode, String)> {
+    sqlx::query_scalar("SELECT year FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_subject, insert_tenant, insert_user, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turmas 6A e 6B (2026). Prof. Ana dá Matemática nas duas e Prof. Beto dá Geografia
+    /// na 6A.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_a: Uuid,
+        class_b: Uuid,
+        math_id: Uuid,
+        geo_id: Uuid,
+        math_teacher: Uuid,
+        geo_teacher: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Grade").await;
+            let class_a = insert_class(&pool, tenant_id, "6A", "6º ano", 2026).await;
+            let class_b = insert_class(&pool, tenant_id, "6B", "6º ano", 2026).await;
+            let math_id = insert_subject(&pool, tenant_id, "Matemática").await;
+            let geo_id = insert_subject(&pool, tenant_id, "Geografia").await;
+            let math_teacher = insert_user(&pool, tenant_id, "Prof. Ana", "teacher").await;
+            let geo_teacher = insert_user(&pool, tenant_id, "Prof. Beto", "teacher").await;
+            for (teacher, class_id, subject_id) in [
+                (math_teacher, class_a, math_id),
+                (math_teacher, class_b, math_id),
+                (geo_teacher, class_a, geo_id),
+            ] {
+                sqlx::query(
+                    "INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year)
+                     VALUES ($1, $2, $3, $4, $5, 2026)",
+                )
+                .bind(Uuid::new_v4())
+                .bind(tenant_id)
+                .bind(teacher)
+                .bind(class_id)
+                .bind(subject_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+            Fixture { pool, app, token, tenant_id, class_a, class_b, math_id, geo_id, math_teacher, geo_teacher }
+        }
+
+        async fn add_slot(&self, class_id: Uuid, slot: Value) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", &format!("/classes/{class_id}/timetable"), &self.token, Some(slot)).await
+        }
+
+        /// Matemática na 6A (segunda, 07:30) e na 6B (terça, 08:20) e Geografia na 6A
+        /// (segunda, 08:20), todas na Sala 1 com grafias diferentes.
+        async fn weekly_schedule(&self) {
+            let (status, _) = self
+                .add_slot(self.class_a, json!({"weekday": 1, "start_time": "07:30", "end_time": "08:20", "subject_id": self.math_id, "room": "Sala 1"}))
+                .await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, _) = self
+                .add_slot(self.class_b, json!({"weekday": 2, "start_time": "08:20", "end_time": "09:10", "subject_id": self.math_id, "room": "sala 1"}))
+                .await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, _) = self
+                .add_slot(self.class_a, json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": self.geo_id, "room": "SALA 1"}))
+                .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn slot_without_teacher_takes_the_only_assigned_one() {
+        let f = Fixture::new().await;
+
+        let (status, slot) = f
+            .add_slot(f.class_a, json!({"weekday": 1, "start_time": "07:30", "end_time": "08:20", "subject_id": f.math_id}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(slot["teacher_user_id"], f.math_teacher.to_string());
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn slot_refuses_teacher_double_booking_but_allows_back_to_back() {
+        let f = Fixture::new().await;
+        let (status, _) = f
+            .add_slot(f.class_a, json!({"weekday": 1, "start_time": "07:30", "end_time": "08:20", "subject_id": f.math_id}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = f
+            .add_slot(f.class_b, json!({"weekday": 1, "start_time": "08:00", "end_time": "08:50", "subject_id": f.math_id}))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = f
+            .add_slot(f.class_b, json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": f.math_id}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn slot_refuses_room_and_class_double_booking() {
+        let f = Fixture::new().await;
+        let (status, _) = f
+            .add_slot(f.class_a, json!({"weekday": 1, "start_time": "07:30", "end_time": "08:20", "subject_id": f.math_id}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = f
+            .add_slot(f.class_b, json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": f.math_id, "room": "sala 1"}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // mesma sala, grafia diferente
+        let (status, _) = f
+            .add_slot(f.class_a, json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": f.geo_id, "room": "SALA 1"}))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        // a própria turma já tem aula nesse horário
+        let (status, _) = f
+            .add_slot(f.class_a, json!({"weekday": 1, "start_time": "07:40", "end_time": "08:00", "subject_id": f.geo_id}))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn slot_requires_a_teacher_assigned_to_the_subject() {
+        let f = Fixture::new().await;
+
+        let (status, _) = f
+            .add_slot(
+                f.class_a,
+                json!({"weekday": 2, "start_time": "07:30", "end_time": "08:20", "subject_id": f.geo_id, "teacher_user_id": f.math_teacher}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn moving_a_slot_frees_its_room() {
+        let f = Fixture::new().await;
+        let (status, b_slot) = f
+            .add_slot(f.class_b, json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": f.math_id, "room": "Sala 1"}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let geo_slot = json!({"weekday": 1, "start_time": "08:20", "end_time": "09:10", "subject_id": f.geo_id, "room": "Sala 1"});
+        let (status, _) = f.add_slot(f.class_a, geo_slot.clone()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, moved) = call_json(
+            &f.app,
+            "PUT",
+            &format!("/timetable-slots/{}", b_slot["id"].as_str().unwrap()),
+            &f.token,
+            Some(json!({"weekday": 2, "start_time": "08:20", "end_time": "09:10", "subject_id": f.math_id, "room": "Sala 1"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(moved["weekday_name"], "Terça-feira");
+        let (status, _) = f.add_slot(f.class_a, geo_slot).await;
+        assert_eq!(status, StatusCode::OK);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn teacher_timetable_is_visible_to_staff_and_to_the_teacher_only() {
+        let f = Fixture::new().await;
+        f.weekly_schedule().await;
+        let path = format!("/teachers/{}/timetable?year=2026", f.math_teacher);
+
+        let (status, slots) = call_json(&f.app, "GET", &path, &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(slots.as_array().unwrap().len(), 2);
+        let own = make_token(f.tenant_id, f.math_teacher, "teacher");
+        let (status, _) = call_json(&f.app, "GET", &path, &own, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let other = make_token(f.tenant_id, f.geo_teacher, "teacher");
+        let (status, _) = call_json(&f.app, "GET", &path, &other, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn room_views_group_slots_by_normalized_room_name() {
+        let f = Fixture::new().await;
+        f.weekly_schedule().await;
+
+        let (status, rooms) = call_json(&f.app, "GET", "/timetable/rooms?year=2026", &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(rooms.as_array().unwrap().len(), 1);
+        assert_eq!(rooms[0]["slots"], 3);
+        let (status, room_slots) = call_json(&f.app, "GET", "/timetable/rooms/sala%201?year=2026", &f.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(room_slots.as_array().unwrap().len(), 3);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn current_slot_preselects_the_lesson_being_taught() {
+        let f = Fixture::new().await;
+        f.weekly_schedule().await;
+        let geo_token = make_token(f.tenant_id, f.geo_teacher, "teacher");
+
+        // 2026-03-02 é segunda-feira
+        let (status, current) = call_json(
+            &f.app,
+            "GET",
+            &format!("/classes/{}/timetable/current?at=2026-03-02T08:30:00", f.class_a),
+            &geo_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(current["weekday"], 1);
+        assert_eq!(current["slot"]["subject_id"], f.geo_id.to_string());
+        assert_eq!(current["lesson_number"], 2);
+
+        let (status, free) = call_json(
+            &f.app,
+            "GET",
+            &format!("/classes/{}/timetable/current?at=2026-03-02T12:00:00", f.class_a),
+            &f.token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(free["slot"].is_null());
+
+        f.cleanup().await;
+    }
+}
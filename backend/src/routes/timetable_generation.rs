@@ -0,0 +1,909 @@
+//! Geração automática da grade horária: aulas semanais por disciplina em cada turma,
+//! janelas de indisponibilidade dos professores e as execuções do gerador, processadas em
+//! segundo plano pelo job de horários.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::auth::teaching::{ensure_teaches, restricted_teacher};
+use crate::routes::timetable::weekday_name;
+use crate::scheduling::Period;
+use crate::state::AppState;
+
+const MANAGE_ROLES: [&str; 3] = ["owner", "admin", "staff"];
+
+/// Dias da grade quando a geração não informa: segunda a sexta.
+const DEFAULT_WEEKDAYS: [i32; 5] = [1, 2, 3, 4, 5];
+
+#[derive(Debug, Deserialize)]
+pub struct RequirementRequest {
+    pub subject_id: Uuid,
+    pub lessons_per_week: i32,
+    /// Quantas das aulas vêm em dupla (dois horários seguidos).
+    pub double_lessons: Option<i32>,
+    /// Padrão: o professor atribuído à disciplina na turma, se houver só um.
+    pub teacher_user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceRequirementsRequest {
+    pub requirements: Vec<RequirementRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequirementResponse {
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub lessons_per_week: i32,
+    pub double_lessons: i32,
+    pub teacher_user_id: Option<Uuid>,
+    pub teacher_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnavailabilityRequest {
+    pub weekday: i32,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnavailabilityResponse {
+    pub id: Uuid,
+    pub teacher_user_id: Uuid,
+    pub weekday: i32,
+    pub weekday_name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGenerationRequest {
+    /// Padrão: ano atual.
+    pub school_year: Option<i32>,
+    /// Padrão: todas as turmas do ano com aulas semanais configuradas.
+    pub class_ids: Option<Vec<Uuid>>,
+    /// Padrão: segunda a sexta.
+    pub weekdays: Option<Vec<i32>>,
+    /// Horários de aula do dia, iguais para todos os dias da grade.
+    pub periods: Vec<Period>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GenerationResponse {
+    pub id: Uuid,
+    pub school_year: i32,
+    pub class_ids: Option<Vec<Uuid>>,
+    pub weekdays: Vec<i32>,
+    pub periods: Vec<Period>,
+    pub status: String,
+    pub progress: i32,
+    pub total_lessons: i32,
+    pub placed_lessons: i32,
+    /// Horários vagos entre aulas na agenda dos professores.
+    pub teacher_gaps: Option<i32>,
+    pub double_lessons_requested: Option<i32>,
+    pub double_lessons_met: Option<i32>,
+    /// Aulas que não couberam, por turma e disciplina.
+    pub unplaced: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route(
+            "/classes/:class_id/timetable/requirements",
+            get(list_requirements).put(replace_requirements),
+        )
+        .route(
+            "/teachers/:user_id/unavailability",
+            get(list_unavailability).post(create_unavailability),
+        )
+        .route("/teacher-unavailability/:id", delete(delete_unavailability))
+        .route("/timetable-generations", post(create_generation).get(list_generations))
+        .route("/timetable-generations/:generation_id", get(get_generation))
+        .with_state(state)
+}
+
+async fn list_requirements(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+) -> Result<Json<Vec<RequirementResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+    if restricted_teacher(&user).is_some() {
+        ensure_teaches(&state.pool, &user, class_id, None).await?;
+    }
+
+    load_requirements(&state.pool, user.tenant_id, class_id).await.map(Json)
+}
+
+/// Substitui as aulas semanais da turma pela lista enviada.
+async fn replace_requirements(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(class_id): Path<Uuid>,
+    Json(req): Json<ReplaceRequirementsRequest>,
+) -> Result<Json<Vec<RequirementResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let class_year = ensure_class_belongs_to_tenant(&state.pool, user.tenant_id, class_id).await?;
+
+    let mut seen = Vec::new();
+    for r in &req.requirements {
+        if seen.contains(&r.subject_id) {
+            return Err((StatusCode::BAD_REQUEST, "Disciplina repetida na lista".into()));
+        }
+        seen.push(r.subject_id);
+        if !(1..=40).contains(&r.lessons_per_week) {
+            return Err((StatusCode::BAD_REQUEST, "Aulas por semana devem estar entre 1 e 40".into()));
+        }
+        let doubles = r.double_lessons.unwrap_or(0);
+        if doubles < 0 || doubles * 2 > r.lessons_per_week {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Aulas duplas não podem passar da metade das aulas da semana".into(),
+            ));
+        }
+    }
+
+    let known: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM subjects WHERE tenant_id = $1 AND id = ANY($2)")
+        .bind(user.tenant_id)
+        .bind(&seen)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if known != seen.len() as i64 {
+        return Err((StatusCode::BAD_REQUEST, "Disciplina inválida para este tenant".into()));
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT subject_id, teacher_user_id
+        FROM teaching_assignments
+        WHERE tenant_id = $1 AND class_id = $2 AND school_year = $3
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(class_id)
+    .bind(class_year)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    let mut assigned: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for r in rows {
+        assigned.entry(r.get("subject_id")).or_default().push(r.get("teacher_user_id"));
+    }
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    sqlx::query("DELETE FROM timetable_requirements WHERE tenant_id = $1 AND class_id = $2")
+        .bind(user.tenant_id)
+        .bind(class_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    for r in &req.requirements {
+        let teachers = assigned.get(&r.subject_id).map(Vec::as_slice).unwrap_or_default();
+        let teacher_user_id = match r.teacher_user_id {
+            Some(id) if !teachers.contains(&id) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Professor não está atribuído a esta disciplina na turma".into(),
+                ));
+            }
+            Some(id) => Some(id),
+            None if teachers.len() == 1 => Some(teachers[0]),
+            None if teachers.is_empty() => None,
+            // com mais de um professor o gerador não tem como escolher
+            None => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "Disciplina com mais de um professor na turma: informe teacher_user_id".into(),
+                ));
+            }
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO timetable_requirements
+              (tenant_id, class_id, subject_id, lessons_per_week, double_lessons, teacher_user_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(user.tenant_id)
+        .bind(class_id)
+        .bind(r.subject_id)
+        .bind(r.lessons_per_week)
+        .bind(r.double_lessons.unwrap_or(0))
+        .bind(teacher_user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_requirements(&state.pool, user.tenant_id, class_id).await.map(Json)
+}
+
+async fn load_requirements(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<Vec<RequirementResponse>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.subject_id, s.name AS subject_name, r.lessons_per_week, r.double_lessons,
+               r.teacher_user_id, COALESCE(u.full_name, u.email) AS teacher_name
+        FROM timetable_requirements r
+        JOIN subjects s ON s.id = r.subject_id
+        LEFT JOIN users u ON u.id = r.teacher_user_id
+        WHERE r.tenant_id = $1 AND r.class_id = $2
+        ORDER BY s.name ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(class_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows
+        .iter()
+        .map(|r| RequirementResponse {
+            subject_id: r.get("subject_id"),
+            subject_name: r.get("subject_name"),
+            lessons_per_week: r.get("lessons_per_week"),
+            double_lessons: r.get("double_lessons"),
+            teacher_user_id: r.get("teacher_user_id"),
+            teacher_name: r.get("teacher_name"),
+        })
+        .collect())
+}
+
+async fn list_unavailability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(teacher_user_id): Path<Uuid>,
+) -> Result<Json<Vec<UnavailabilityResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_own_or_manager(&user, teacher_user_id)?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, teacher_user_id, weekday, start_time, end_time, reason
+        FROM teacher_unavailability
+        WHERE tenant_id = $1 AND teacher_user_id = $2
+        ORDER BY weekday, start_time
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(unavailability_from_row).collect()))
+}
+
+async fn create_unavailability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(teacher_user_id): Path<Uuid>,
+    Json(req): Json<UnavailabilityRequest>,
+) -> Result<Json<UnavailabilityResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+    ensure_own_or_manager(&user, teacher_user_id)?;
+    if !(1..=7).contains(&req.weekday) {
+        return Err((StatusCode::BAD_REQUEST, "Dia da semana inválido (1 = segunda ... 7 = domingo)".into()));
+    }
+    if req.start_time >= req.end_time {
+        return Err((StatusCode::BAD_REQUEST, "Início deve ser antes do fim".into()));
+    }
+
+    let exists = sqlx::query("SELECT 1 FROM users WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(teacher_user_id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if exists.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Professor não encontrado".into()));
+    }
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO teacher_unavailability (id, tenant_id, teacher_user_id, weekday, start_time, end_time, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, teacher_user_id, weekday, start_time, end_time, reason
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user.tenant_id)
+    .bind(teacher_user_id)
+    .bind(req.weekday)
+    .bind(req.start_time)
+    .bind(req.end_time)
+    .bind(normalize_optional_text(req.reason))
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(unavailability_from_row(&row)))
+}
+
+async fn delete_unavailability(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff", "teacher"])?;
+
+    let teacher_user_id: Uuid = sqlx::query_scalar(
+        "SELECT teacher_user_id FROM teacher_unavailability WHERE tenant_id = $1 AND id = $2",
+    )
+    .bind(user.tenant_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Indisponibilidade não encontrada".into()))?;
+    ensure_own_or_manager(&user, teacher_user_id)?;
+
+    sqlx::query("DELETE FROM teacher_unavailability WHERE tenant_id = $1 AND id = $2")
+        .bind(user.tenant_id)
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    Ok(Json(OkResponse { ok: true }))
+}
+
+async fn create_generation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<CreateGenerationRequest>,
+) -> Result<Json<GenerationResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    let school_year = req.school_year.unwrap_or_else(|| chrono::Local::now().year());
+
+    let mut weekdays = req.weekdays.unwrap_or_else(|| DEFAULT_WEEKDAYS.to_vec());
+    weekdays.sort_unstable();
+    weekdays.dedup();
+    if weekdays.is_empty() || weekdays.iter().any(|w| !(1..=7).contains(w)) {
+        return Err((StatusCode::BAD_REQUEST, "Dias da semana inválidos (1 = segunda ... 7 = domingo)".into()));
+    }
+
+    let mut periods = req.periods;
+    periods.sort_by_key(|p| p.start_time);
+    if periods.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Informe os horários de aula do dia (periods)".into()));
+    }
+    if periods.iter().any(|p| p.start_time >= p.end_time) {
+        return Err((StatusCode::BAD_REQUEST, "Início deve ser antes do fim".into()));
+    }
+    if periods.windows(2).any(|w| w[1].start_time < w[0].end_time) {
+        return Err((StatusCode::BAD_REQUEST, "Horários de aula sobrepostos".into()));
+    }
+
+    if let Some(class_ids) = &req.class_ids {
+        let found: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM classes WHERE tenant_id = $1 AND year = $2 AND id = ANY($3)",
+        )
+        .bind(user.tenant_id)
+        .bind(school_year)
+        .bind(class_ids)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+        let mut unique = class_ids.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        if class_ids.is_empty() || found != unique.len() as i64 {
+            return Err((StatusCode::BAD_REQUEST, "Turma inválida ou de outro ano letivo".into()));
+        }
+    }
+
+    // turma com mais aulas do que horários na semana nunca vai caber
+    let loads = sqlx::query(
+        r#"
+        SELECT c.name, SUM(r.lessons_per_week)::int AS lessons
+        FROM timetable_requirements r
+        JOIN classes c ON c.id = r.class_id AND c.tenant_id = r.tenant_id
+        WHERE r.tenant_id = $1
+          AND c.year = $2
+          AND ($3::uuid[] IS NULL OR r.class_id = ANY($3))
+        GROUP BY c.id, c.name
+        ORDER BY c.name ASC
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(school_year)
+    .bind(&req.class_ids)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if loads.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Nenhuma turma com aulas semanais configuradas".into()));
+    }
+    let capacity = (weekdays.len() * periods.len()) as i32;
+    for l in &loads {
+        let lessons: i32 = l.get("lessons");
+        if lessons > capacity {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "A turma {} tem {lessons} aulas por semana, mas a grade só tem {capacity} horários",
+                    l.get::<String, _>("name")
+                ),
+            ));
+        }
+    }
+
+    let pending = sqlx::query(
+        "SELECT 1 FROM timetable_generations WHERE tenant_id = $1 AND status IN ('queued', 'running') LIMIT 1",
+    )
+    .bind(user.tenant_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if pending.is_some() {
+        return Err((StatusCode::CONFLICT, "Já existe uma geração de horário em andamento".into()));
+    }
+
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO timetable_generations
+          (id, tenant_id, requested_by, school_year, class_ids, weekdays, periods)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(id)
+    .bind(user.tenant_id)
+    .bind(user.user_id)
+    .bind(school_year)
+    .bind(&req.class_ids)
+    .bind(&weekdays)
+    .bind(sqlx::types::Json(&periods))
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_generation(&state.pool, user.tenant_id, id).await.map(Json)
+}
+
+async fn list_generations(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> Result<Json<Vec<GenerationResponse>>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+
+    let rows = sqlx::query(&format!(
+        "{GENERATION_SELECT} WHERE tenant_id = $1 ORDER BY created_at DESC LIMIT 100"
+    ))
+    .bind(user.tenant_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(Json(rows.iter().map(generation_from_row).collect()))
+}
+
+async fn get_generation(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(generation_id): Path<Uuid>,
+) -> Result<Json<GenerationResponse>, (StatusCode, String)> {
+    user.require_any_role(&MANAGE_ROLES)?;
+    load_generation(&state.pool, user.tenant_id, generation_id).await.map(Json)
+}
+
+const GENERATION_SELECT: &str = r#"
+    SELECT id, school_year, class_ids, weekdays, periods, status, progress, total_lessons,
+           placed_lessons, teacher_gaps, double_lessons_requested, double_lessons_met,
+           unplaced, error, created_at, finished_at
+    FROM timetable_generations
+"#;
+
+async fn load_generation(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    generation_id: Uuid,
+) -> Result<GenerationResponse, (StatusCode, String)> {
+    let row = sqlx::query(&format!("{GENERATION_SELECT} WHERE tenant_id = $1 AND id = $2"))
+        .bind(tenant_id)
+        .bind(generation_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Geração não encontrada".into()))?;
+    Ok(generation_from_row(&row))
+}
+
+fn generation_from_row(r: &sqlx::postgres::PgRow) -> GenerationResponse {
+    GenerationResponse {
+        id: r.get("id"),
+        school_year: r.get("school_year"),
+        class_ids: r.get("class_ids"),
+        weekdays: r.get("weekdays"),
+        periods: r.get::<sqlx::types::Json<Vec<Period>>, _>("periods").0,
+        status: r.get("status"),
+        progress: r.get("progress"),
+        total_lessons: r.get("total_lessons"),
+        placed_lessons: r.get("placed_lessons"),
+        teacher_gaps: r.get("teacher_gaps"),
+        double_lessons_requested: r.get("double_lessons_requested"),
+        double_lessons_met: r.get("double_lessons_met"),
+        unplaced: r.get("unplaced"),
+        error: r.get("error"),
+        created_at: r.get("created_at"),
+        finished_at: r.get("finished_at"),
+    }
+}
+
+fn unavailability_from_row(r: &sqlx::postgres::PgRow) -> UnavailabilityResponse {
+    let weekday: i32 = r.get("weekday");
+    UnavailabilityResponse {
+        id: r.get("id"),
+        teacher_user_id: r.get("teacher_user_id"),
+        weekday,
+        weekday_name: weekday_name(weekday).to_string(),
+        start_time: r.get("start_time"),
+        end_time: r.get("end_time"),
+        reason: r.get("reason"),
+    }
+}
+
+/// Professor só mexe nas próprias indisponibilidades.
+fn ensure_own_or_manager(user: &AuthUser, teacher_user_id: Uuid) -> Result<(), (StatusCode, String)> {
+    if restricted_teacher(user).is_some_and(|own| own != teacher_user_id) {
+        return Err((StatusCode::FORBIDDEN, "Sem permissão".into()));
+    }
+    Ok(())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+/// Devolve o ano letivo da turma.
+async fn ensure_class_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    class_id: Uuid,
+) -> Result<i32, (StatusCode, String)> {
+    sqlx::query_scalar("SELECT year FROM classes WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(class_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+        .ok_or((StatusCode::NOT_FOUND, "Turma não encontrada".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call_json, insert_class, insert_subject, insert_tenant, insert_user, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Turmas 7A e 7B (2026) com Matemática (Prof. Ana), Português (Prof. Beto) e Arte,
+    /// sem professor. Cada turma pede 4 aulas de Matemática (uma dupla), 3 de Português e 2
+    /// de Arte.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        token: String,
+        tenant_id: Uuid,
+        class_a: Uuid,
+        class_b: Uuid,
+        math_id: Uuid,
+        port_id: Uuid,
+        art_id: Uuid,
+        math_teacher: Uuid,
+        port_teacher: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let tenant_id = insert_tenant(&pool, "Escola Gerador").await;
+            let class_a = insert_class(&pool, tenant_id, "7A", "7º ano", 2026).await;
+            let class_b = insert_class(&pool, tenant_id, "7B", "7º ano", 2026).await;
+            let math_id = insert_subject(&pool, tenant_id, "Matemática").await;
+            let port_id = insert_subject(&pool, tenant_id, "Português").await;
+            let art_id = insert_subject(&pool, tenant_id, "Arte").await;
+            let math_teacher = insert_user(&pool, tenant_id, "Prof. Ana", "teacher").await;
+            let port_teacher = insert_user(&pool, tenant_id, "Prof. Beto", "teacher").await;
+            for class_id in [class_a, class_b] {
+                for (teacher, subject_id) in [(math_teacher, math_id), (port_teacher, port_id)] {
+                    sqlx::query(
+                        "INSERT INTO teaching_assignments (id, tenant_id, teacher_user_id, class_id, subject_id, school_year)
+                         VALUES ($1, $2, $3, $4, $5, 2026)",
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(tenant_id)
+                    .bind(teacher)
+                    .bind(class_id)
+                    .bind(subject_id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                }
+            }
+
+            let token = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into());
+            Fixture {
+                pool,
+                app,
+                token,
+                tenant_id,
+                class_a,
+                class_b,
+                math_id,
+                port_id,
+                art_id,
+                math_teacher,
+                port_teacher,
+            }
+        }
+
+        async fn set_requirements(&self, class_id: Uuid) -> (StatusCode, Value) {
+            call_json(
+                &self.app,
+                "PUT",
+                &format!("/classes/{class_id}/timetable/requirements"),
+                &self.token,
+                Some(json!({"requirements": [
+                    {"subject_id": self.math_id, "lessons_per_week": 4, "double_lessons": 1},
+                    {"subject_id": self.port_id, "lessons_per_week": 3},
+                    {"subject_id": self.art_id, "lessons_per_week": 2}
+                ]})),
+            )
+            .await
+        }
+
+        async fn add_unavailability(&self, teacher_user_id: Uuid, token: &str) -> (StatusCode, Value) {
+            call_json(
+                &self.app,
+                "POST",
+                &format!("/teachers/{teacher_user_id}/unavailability"),
+                token,
+                Some(json!({"weekday": 1, "start_time": "07:00", "end_time": "12:00", "reason": "Outra escola"})),
+            )
+            .await
+        }
+
+        /// Três horários por dia, com intervalo antes do último.
+        fn periods() -> Value {
+            json!([
+                {"start_time": "07:30:00", "end_time": "08:20:00"},
+                {"start_time": "08:20:00", "end_time": "09:10:00"},
+                {"start_time": "09:30:00", "end_time": "10:20:00"}
+            ])
+        }
+
+        async fn request_generation(&self, body: Value) -> (StatusCode, Value) {
+            call_json(&self.app, "POST", "/timetable-generations", &self.token, Some(body)).await
+        }
+
+        /// Enfileira, roda o job e devolve a geração concluída.
+        async fn generate(&self, body: Value) -> Value {
+            let (status, generation) = self.request_generation(body).await;
+            assert_eq!(status, StatusCode::OK, "{generation}");
+            assert_eq!(generation["status"], "queued");
+            while crate::jobs::timetable_generation::process_next(&self.pool).await.unwrap().is_some() {}
+            let path = format!("/timetable-generations/{}", generation["id"].as_str().unwrap());
+            let (status, generation) = call_json(&self.app, "GET", &path, &self.token, None).await;
+            assert_eq!(status, StatusCode::OK);
+            generation
+        }
+
+        async fn slots(&self, class_id: Uuid) -> Vec<sqlx::postgres::PgRow> {
+            sqlx::query(
+                "SELECT weekday, start_time, end_time, subject_id, teacher_user_id, room
+                 FROM timetable_slots WHERE tenant_id = $1 AND class_id = $2",
+            )
+            .bind(self.tenant_id)
+            .bind(class_id)
+            .fetch_all(&self.pool)
+            .await
+            .unwrap()
+        }
+
+        async fn insert_slot(&self, class_id: Uuid, weekday: i32, start: &str, end: &str, subject_id: Uuid, room: &str) {
+            sqlx::query(
+                "INSERT INTO timetable_slots (id, tenant_id, class_id, weekday, start_time, end_time, subject_id, room)
+                 VALUES ($1, $2, $3, $4, $5::time, $6::time, $7, $8)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(self.tenant_id)
+            .bind(class_id)
+            .bind(weekday)
+            .bind(start)
+            .bind(end)
+            .bind(subject_id)
+            .bind(room)
+            .execute(&self.pool)
+            .await
+            .unwrap();
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn requirements_default_to_the_assigned_teacher() {
+        let f = Fixture::new().await;
+
+        let (status, _) = call_json(
+            &f.app,
+            "PUT",
+            &format!("/classes/{}/timetable/requirements", f.class_a),
+            &f.token,
+            Some(json!({"requirements": [{"subject_id": f.math_id, "lessons_per_week": 4, "teacher_user_id": f.port_teacher}]})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, list) = f.set_requirements(f.class_a).await;
+        assert_eq!(status, StatusCode::OK);
+        let by_subject = |id: Uuid| list.as_array().unwrap().iter().find(|r| r["subject_id"] == id.to_string()).unwrap();
+        assert_eq!(by_subject(f.math_id)["teacher_user_id"], f.math_teacher.to_string());
+        assert_eq!(by_subject(f.math_id)["double_lessons"], 1);
+        assert!(by_subject(f.art_id)["teacher_user_id"].is_null());
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn unavailability_is_managed_by_staff_or_the_teacher() {
+        let f = Fixture::new().await;
+
+        let port_token = make_token(f.tenant_id, f.port_teacher, "teacher");
+        let (status, _) = f.add_unavailability(f.math_teacher, &port_token).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = f.add_unavailability(f.port_teacher, &port_token).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, window) = f.add_unavailability(f.math_teacher, &f.token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(window["weekday_name"], "Segunda-feira");
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn generation_checks_capacity_and_runs_one_at_a_time() {
+        let f = Fixture::new().await;
+        f.set_requirements(f.class_a).await;
+
+        // 2 dias x 3 horários não comportam as 9 aulas da turma
+        let (status, _) = f
+            .request_generation(json!({"school_year": 2026, "weekdays": [1, 2], "periods": Fixture::periods()}))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = f.request_generation(json!({"school_year": 2026, "periods": Fixture::periods()})).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = f.request_generation(json!({"school_year": 2026, "periods": Fixture::periods()})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn generation_builds_a_conflict_free_grid() {
+        let f = Fixture::new().await;
+        for class_id in [f.class_a, f.class_b] {
+            f.set_requirements(class_id).await;
+        }
+        // Ana não dá aula às segundas
+        f.add_unavailability(f.math_teacher, &f.token).await;
+
+        let generation = f.generate(json!({"school_year": 2026, "periods": Fixture::periods()})).await;
+        assert_eq!(generation["status"], "done", "{generation}");
+        assert_eq!(generation["progress"], 100);
+        assert_eq!(generation["total_lessons"], 18);
+        assert_eq!(generation["placed_lessons"], 18);
+        assert_eq!(generation["double_lessons_requested"], 2);
+        assert_eq!(generation["double_lessons_met"], 2);
+
+        let mut taken: Vec<(Uuid, i32, NaiveTime)> = Vec::new();
+        for class_id in [f.class_a, f.class_b] {
+            let slots = f.slots(class_id).await;
+            assert_eq!(slots.len(), 9);
+            for s in &slots {
+                let teacher: Option<Uuid> = s.get("teacher_user_id");
+                let weekday: i32 = s.get("weekday");
+                let start: NaiveTime = s.get("start_time");
+                assert!(!(teacher == Some(f.math_teacher) && weekday == 1), "aula da Ana na segunda");
+                for owner_id in [Some(class_id), teacher].into_iter().flatten() {
+                    let key = (owner_id, weekday, start);
+                    assert!(!taken.contains(&key), "horário ocupado duas vezes");
+                    taken.push(key);
+                }
+            }
+            // a dupla de matemática ocupa dois horários seguidos do mesmo dia
+            let math: Vec<(i32, NaiveTime, NaiveTime)> = slots
+                .iter()
+                .filter(|s| s.get::<Uuid, _>("subject_id") == f.math_id)
+                .map(|s| (s.get("weekday"), s.get("start_time"), s.get("end_time")))
+                .collect();
+            assert!(math.iter().any(|a| math.iter().any(|b| b.0 == a.0 && b.1 == a.2)));
+        }
+
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn regeneration_keeps_rooms_and_avoids_rooms_taken_by_other_classes() {
+        let f = Fixture::new().await;
+        f.set_requirements(f.class_a).await;
+        // a 7A já tinha Matemática no laboratório; a 7B, fora da geração, ocupa o
+        // laboratório a terça inteira
+        f.insert_slot(f.class_a, 1, "07:30", "08:20", f.math_id, "Laboratório").await;
+        for (start, end) in [("07:30", "08:20"), ("08:20", "09:10"), ("09:30", "10:20")] {
+            f.insert_slot(f.class_b, 2, start, end, f.art_id, "laboratório").await;
+        }
+
+        let generation = f
+            .generate(json!({"school_year": 2026, "class_ids": [f.class_a], "periods": Fixture::periods()}))
+            .await;
+        assert_eq!(generation["status"], "done", "{generation}");
+
+        let slots = f.slots(f.class_a).await;
+        assert_eq!(slots.len(), 9);
+        for s in &slots {
+            let room: Option<String> = s.get("room");
+            if s.get::<Uuid, _>("subject_id") == f.math_id {
+                assert_eq!(room.as_deref(), Some("Laboratório"));
+                assert_ne!(s.get::<i32, _>("weekday"), 2, "laboratório ocupado pela 7B na terça");
+            } else {
+                assert!(room.is_none());
+            }
+        }
+        assert_eq!(f.slots(f.class_b).await.len(), 3);
+
+        f.cleanup().await;
+    }
+}
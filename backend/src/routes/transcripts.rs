@@ -0,0 +1,857 @@
+//! Histórico escolar: todos os anos cursados pelo aluno, tanto nas turmas desta escola
+//! (`student_enrollments`) quanto em escolas anteriores (registros lançados à mão).
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::auth::jwt::AuthUser;
+use crate::grading::promotion::{
+    presence_percent, status_label, STATUS_APPROVED, STATUS_FAILED_ATTENDANCE, STATUS_FAILED_GRADE,
+};
+use crate::reports::report_cards::{load_branding, ReportCardDocument};
+use crate::routes::records::build_student_full_report;
+use crate::state::AppState;
+
+pub const SOURCE_SCHOOL: &str = "school";
+pub const SOURCE_EXTERNAL: &str = "external";
+/// Ano em andamento: matrícula ativa no ano letivo corrente ou futuro.
+pub const STATUS_IN_PROGRESS: &str = "in_progress";
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptSubject {
+    pub subject_name: String,
+    pub workload_hours: Option<i32>,
+    /// Média final na escala da série; nos registros externos, como consta no documento.
+    pub final_grade: Option<String>,
+    pub attendance_percent: Option<f64>,
+    pub final_status: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptYear {
+    /// `school` (turma desta escola) ou `external` (escola anterior)
+    pub source: String,
+    pub school_year: i32,
+    pub grade: String,
+    pub school_name: String,
+    pub school_city: Option<String>,
+    pub class_id: Option<Uuid>,
+    pub class_name: Option<String>,
+    pub external_record_id: Option<Uuid>,
+    pub total_workload_hours: i32,
+    pub attendance_percent: Option<f64>,
+    /// approved | recovery | failed_grade | failed_attendance | no_grades | in_progress
+    pub final_status: String,
+    pub final_status_label: String,
+    pub notes: Option<String>,
+    pub subjects: Vec<TranscriptSubject>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptResponse {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub registration: String,
+    pub birth_date: Option<NaiveDate>,
+    pub place_of_birth: Option<String>,
+    pub nationality: Option<String>,
+    pub current_class_name: Option<String>,
+    pub school_name: String,
+    pub total_workload_hours: i32,
+    pub generated_at: String,
+    pub years: Vec<TranscriptYear>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalSubjectRequest {
+    pub subject_name: String,
+    pub workload_hours: Option<i32>,
+    pub final_grade: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalRecordRequest {
+    pub school_year: i32,
+    pub grade: String,
+    pub school_name: String,
+    pub school_city: Option<String>,
+    pub attendance_percent: Option<f64>,
+    /// approved | failed_grade | failed_attendance
+    pub final_status: String,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub subjects: Vec<ExternalSubjectRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExternalSubjectResponse {
+    pub id: Uuid,
+    pub subject_name: String,
+    pub workload_hours: Option<i32>,
+    pub final_grade: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExternalRecordResponse {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub school_year: i32,
+    pub grade: String,
+    pub school_name: String,
+    pub school_city: Option<String>,
+    pub attendance_percent: Option<f64>,
+    pub final_status: String,
+    pub notes: Option<String>,
+    pub subjects: Vec<ExternalSubjectResponse>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkResponse {
+    pub ok: bool,
+}
+
+pub fn routes(pool: PgPool, jwt_secret: String) -> Router {
+    let state = AppState { pool, jwt_secret };
+
+    Router::new()
+        .route("/students/:student_id/transcript", get(get_transcript))
+        .route("/students/:student_id/transcript/pdf", get(get_transcript_pdf))
+        .route(
+            "/students/:student_id/transcript/external-records",
+            get(list_external_records).post(create_external_record),
+        )
+        .route(
+            "/students/:student_id/transcript/external-records/:record_id",
+            put(update_external_record).delete(delete_external_record),
+        )
+        .with_state(state)
+}
+
+async fn get_transcript(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<Json<TranscriptResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    Ok(Json(build_transcript(&state.pool, user.tenant_id, student_id).await?))
+}
+
+async fn get_transcript_pdf(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let transcript = build_transcript(&state.pool, user.tenant_id, student_id).await?;
+    let branding = load_branding(&state.pool, user.tenant_id).await?;
+    let mut doc = ReportCardDocument::new(branding);
+    doc.add_transcript(&transcript);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"historico-{}.pdf\"", transcript.registration),
+            ),
+        ],
+        doc.to_bytes(),
+    ))
+}
+
+/// Monta o histórico: um ano por turma em que o aluno esteve, mais os anos externos,
+/// em ordem cronológica.
+pub async fn build_transcript(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+) -> Result<TranscriptResponse, (StatusCode, String)> {
+    let student = sqlx::query(
+        r#"
+        SELECT s.name, s.registration, s.birth_date, s.place_of_birth, s.nationality,
+               c.name AS current_class_name, t.name AS school_name, t.school_city
+        FROM students s
+        JOIN tenants t ON t.id = s.tenant_id
+        LEFT JOIN classes c ON c.id = s.class_id AND c.tenant_id = s.tenant_id
+        WHERE s.tenant_id = $1 AND s.id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?
+    .ok_or((StatusCode::NOT_FOUND, "Aluno não encontrado".into()))?;
+    let school_name: String = student.get("school_name");
+    let school_city: Option<String> = student.get("school_city");
+
+    let enrollments = sqlx::query(
+        r#"
+        SELECT e.class_id, e.school_year, e.ended_at, c.grade
+        FROM student_enrollments e
+        JOIN classes c ON c.id = e.class_id AND c.tenant_id = e.tenant_id
+        WHERE e.tenant_id = $1 AND e.student_id = $2
+        ORDER BY e.school_year ASC, e.started_at ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let current_year = chrono::Local::now().year();
+    let mut years = Vec::new();
+    for enrollment in enrollments {
+        let class_id: Uuid = enrollment.get("class_id");
+        let grade: String = enrollment.get("grade");
+        let ended_at: Option<NaiveDateTime> = enrollment.get("ended_at");
+        let report = build_student_full_report(pool, tenant_id, class_id, student_id).await?;
+        let workloads = workloads_for_grade(pool, tenant_id, &grade).await?;
+
+        let subjects: Vec<TranscriptSubject> = report
+            .subjects
+            .into_iter()
+            .filter(|s| !s.period_grades.is_empty() || s.attendance_total_lessons > 0 || s.final_exam_score.is_some())
+            .map(|s| TranscriptSubject {
+                workload_hours: workloads.get(&s.subject_id).copied().flatten(),
+                subject_name: s.subject_name,
+                final_grade: s.average_display,
+                attendance_percent: s.attendance_percent,
+                final_status: Some(s.final_status),
+                status: Some(s.status),
+            })
+            .collect();
+
+        let in_progress = ended_at.is_none() && report.class_year >= current_year;
+        let (final_status, final_status_label) = if in_progress {
+            (STATUS_IN_PROGRESS.to_string(), "Cursando".to_string())
+        } else {
+            (report.final_status, report.final_status_label)
+        };
+        years.push(TranscriptYear {
+            source: SOURCE_SCHOOL.into(),
+            school_year: report.class_year,
+            grade,
+            school_name: school_name.clone(),
+            school_city: school_city.clone(),
+            class_id: Some(class_id),
+            class_name: Some(report.class_name),
+            external_record_id: None,
+            total_workload_hours: subjects.iter().filter_map(|s| s.workload_hours).sum(),
+            attendance_percent: presence_percent(report.attendance_total_days, report.attendance_present_days),
+            final_status,
+            final_status_label,
+            notes: None,
+            subjects,
+        });
+    }
+
+    for record in load_external_records(pool, tenant_id, student_id).await? {
+        let subjects: Vec<TranscriptSubject> = record
+            .subjects
+            .into_iter()
+            .map(|s| TranscriptSubject {
+                subject_name: s.subject_name,
+                workload_hours: s.workload_hours,
+                final_grade: s.final_grade,
+                attendance_percent: None,
+                final_status: None,
+                status: None,
+            })
+            .collect();
+        years.push(TranscriptYear {
+            source: SOURCE_EXTERNAL.into(),
+            school_year: record.school_year,
+            grade: record.grade,
+            school_name: record.school_name,
+            school_city: record.school_city,
+            class_id: None,
+            class_name: None,
+            external_record_id: Some(record.id),
+            total_workload_hours: subjects.iter().filter_map(|s| s.workload_hours).sum(),
+            attendance_percent: record.attendance_percent,
+            final_status_label: status_label(&record.final_status).to_string(),
+            final_status: record.final_status,
+            notes: record.notes,
+            subjects,
+        });
+    }
+    // estável: no mesmo ano (transferência no meio do ano) a escola anterior vem antes
+    years.sort_by_key(|y| (y.school_year, y.source == SOURCE_SCHOOL));
+
+    Ok(TranscriptResponse {
+        student_id,
+        student_name: student.get("name"),
+        registration: student.get("registration"),
+        birth_date: student.get("birth_date"),
+        place_of_birth: student.get("place_of_birth"),
+        nationality: student.get("nationality"),
+        current_class_name: student.get("current_class_name"),
+        school_name,
+        total_workload_hours: years.iter().map(|y| y.total_workload_hours).sum(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        years,
+    })
+}
+
+/// Carga horária de cada disciplina na série: a da série, senão a padrão da disciplina.
+async fn workloads_for_grade(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    grade: &str,
+) -> Result<HashMap<Uuid, Option<i32>>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT s.id, COALESCE(w.workload_hours, s.workload_hours) AS workload_hours
+        FROM subjects s
+        LEFT JOIN subject_grade_workloads w
+          ON w.subject_id = s.id AND w.tenant_id = s.tenant_id AND w.grade = $2
+        WHERE s.tenant_id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(grade)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    Ok(rows.into_iter().map(|r| (r.get("id"), r.get("workload_hours"))).collect())
+}
+
+async fn list_external_records(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+) -> Result<Json<Vec<ExternalRecordResponse>>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, student_id).await?;
+    Ok(Json(load_external_records(&state.pool, user.tenant_id, student_id).await?))
+}
+
+async fn create_external_record(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(student_id): Path<Uuid>,
+    Json(req): Json<ExternalRecordRequest>,
+) -> Result<Json<ExternalRecordResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    ensure_student_belongs_to_tenant(&state.pool, user.tenant_id, student_id).await?;
+    let req = validate_external_record(req)?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let record_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO transcript_external_records
+          (id, tenant_id, student_id, school_year, grade, school_name, school_city,
+           attendance_percent, final_status, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+    )
+    .bind(record_id)
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(req.school_year)
+    .bind(&req.grade)
+    .bind(&req.school_name)
+    .bind(&req.school_city)
+    .bind(req.attendance_percent)
+    .bind(&req.final_status)
+    .bind(&req.notes)
+    .bind(user.user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    insert_external_subjects(&mut tx, record_id, &req.subjects).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_external_record(&state.pool, user.tenant_id, student_id, record_id).await.map(Json)
+}
+
+async fn update_external_record(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((student_id, record_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<ExternalRecordRequest>,
+) -> Result<Json<ExternalRecordResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+    let req = validate_external_record(req)?;
+
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE transcript_external_records
+        SET school_year = $4, grade = $5, school_name = $6, school_city = $7,
+            attendance_percent = $8, final_status = $9, notes = $10, updated_at = NOW()
+        WHERE tenant_id = $1 AND student_id = $2 AND id = $3
+        "#,
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(record_id)
+    .bind(req.school_year)
+    .bind(&req.grade)
+    .bind(&req.school_name)
+    .bind(&req.school_city)
+    .bind(req.attendance_percent)
+    .bind(&req.final_status)
+    .bind(&req.notes)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if updated.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Registro não encontrado".into()));
+    }
+
+    // as disciplinas são substituídas por inteiro
+    sqlx::query("DELETE FROM transcript_external_subjects WHERE record_id = $1")
+        .bind(record_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    insert_external_subjects(&mut tx, record_id, &req.subjects).await?;
+
+    tx.commit()
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    load_external_record(&state.pool, user.tenant_id, student_id, record_id).await.map(Json)
+}
+
+async fn delete_external_record(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path((student_id, record_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<OkResponse>, (StatusCode, String)> {
+    user.require_any_role(&["owner", "admin", "staff"])?;
+
+    let deleted = sqlx::query(
+        "DELETE FROM transcript_external_records WHERE tenant_id = $1 AND student_id = $2 AND id = $3",
+    )
+    .bind(user.tenant_id)
+    .bind(student_id)
+    .bind(record_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if deleted.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, "Registro não encontrado".into()));
+    }
+
+    Ok(Json(OkResponse { ok: true }))
+}
+
+struct ValidExternalSubject {
+    subject_name: String,
+    workload_hours: Option<i32>,
+    final_grade: Option<String>,
+}
+
+struct ValidExternalRecord {
+    school_year: i32,
+    grade: String,
+    school_name: String,
+    school_city: Option<String>,
+    attendance_percent: Option<f64>,
+    final_status: String,
+    notes: Option<String>,
+    subjects: Vec<ValidExternalSubject>,
+}
+
+fn validate_external_record(req: ExternalRecordRequest) -> Result<ValidExternalRecord, (StatusCode, String)> {
+    if !(1900..=2100).contains(&req.school_year) {
+        return Err((StatusCode::BAD_REQUEST, "Ano letivo inválido".into()));
+    }
+    let grade = req.grade.trim().to_string();
+    let school_name = req.school_name.trim().to_string();
+    if grade.is_empty() || school_name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Série e escola são obrigatórias".into()));
+    }
+    if req.attendance_percent.is_some_and(|p| !(0.0..=100.0).contains(&p)) {
+        return Err((StatusCode::BAD_REQUEST, "Frequência deve estar entre 0 e 100".into()));
+    }
+    let final_status = match req.final_status.trim().to_lowercase().as_str() {
+        STATUS_APPROVED => STATUS_APPROVED,
+        STATUS_FAILED_GRADE => STATUS_FAILED_GRADE,
+        STATUS_FAILED_ATTENDANCE => STATUS_FAILED_ATTENDANCE,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Situação inválida (use approved, failed_grade ou failed_attendance)".into(),
+            ))
+        }
+    };
+
+    let mut subjects = Vec::with_capacity(req.subjects.len());
+    for s in req.subjects {
+        let subject_name = s.subject_name.trim().to_string();
+        if subject_name.is_empty() {
+            return Err((StatusCode::BAD_REQUEST, "Nome da disciplina obrigatório".into()));
+        }
+        if s.workload_hours.is_some_and(|h| h <= 0) {
+            return Err((StatusCode::BAD_REQUEST, "Carga horária deve ser maior que zero".into()));
+        }
+        subjects.push(ValidExternalSubject {
+            subject_name,
+            workload_hours: s.workload_hours,
+            final_grade: normalize_optional_text(s.final_grade),
+        });
+    }
+
+    Ok(ValidExternalRecord {
+        school_year: req.school_year,
+        grade,
+        school_name,
+        school_city: normalize_optional_text(req.school_city),
+        attendance_percent: req.attendance_percent,
+        final_status: final_status.to_string(),
+        notes: normalize_optional_text(req.notes),
+        subjects,
+    })
+}
+
+async fn insert_external_subjects(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    record_id: Uuid,
+    subjects: &[ValidExternalSubject],
+) -> Result<(), (StatusCode, String)> {
+    for (i, s) in subjects.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO transcript_external_subjects
+              (id, record_id, subject_name, workload_hours, final_grade, sort_order)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(record_id)
+        .bind(&s.subject_name)
+        .bind(s.workload_hours)
+        .bind(&s.final_grade)
+        .bind(i as i32)
+        .execute(&mut **tx)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    }
+    Ok(())
+}
+
+async fn load_external_records(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+) -> Result<Vec<ExternalRecordResponse>, (StatusCode, String)> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, student_id, school_year, grade, school_name, school_city,
+               attendance_percent::float8 AS attendance_percent, final_status, notes,
+               created_at, updated_at
+        FROM transcript_external_records
+        WHERE tenant_id = $1 AND student_id = $2
+        ORDER BY school_year ASC, created_at ASC
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let record_ids: Vec<Uuid> = rows.iter().map(|r| r.get("id")).collect();
+    let subject_rows = sqlx::query(
+        r#"
+        SELECT id, record_id, subject_name, workload_hours, final_grade
+        FROM transcript_external_subjects
+        WHERE record_id = ANY($1)
+        ORDER BY sort_order ASC
+        "#,
+    )
+    .bind(&record_ids)
+    .fetch_all(pool)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+
+    let mut subjects: HashMap<Uuid, Vec<ExternalSubjectResponse>> = HashMap::new();
+    for r in subject_rows {
+        subjects.entry(r.get("record_id")).or_default().push(ExternalSubjectResponse {
+            id: r.get("id"),
+            subject_name: r.get("subject_name"),
+            workload_hours: r.get("workload_hours"),
+            final_grade: r.get("final_grade"),
+        });
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            let id: Uuid = r.get("id");
+            ExternalRecordResponse {
+                id,
+                student_id: r.get("student_id"),
+                school_year: r.get("school_year"),
+                grade: r.get("grade"),
+                school_name: r.get("school_name"),
+                school_city: r.get("school_city"),
+                attendance_percent: r.get("attendance_percent"),
+                final_status: r.get("final_status"),
+                notes: r.get("notes"),
+                subjects: subjects.remove(&id).unwrap_or_default(),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+            }
+        })
+        .collect())
+}
+
+async fn load_external_record(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+    record_id: Uuid,
+) -> Result<ExternalRecordResponse, (StatusCode, String)> {
+    load_external_records(pool, tenant_id, student_id)
+        .await?
+        .into_iter()
+        .find(|r| r.id == record_id)
+        .ok_or((StatusCode::NOT_FOUND, "Registro não encontrado".into()))
+}
+
+async fn ensure_student_belongs_to_tenant(
+    pool: &PgPool,
+    tenant_id: Uuid,
+    student_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let exists: Option<i32> = sqlx::query_scalar("SELECT 1 FROM students WHERE tenant_id = $1 AND id = $2")
+        .bind(tenant_id)
+        .bind(student_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Erro DB".into()))?;
+    if exists.is_none() {
+        return Err((StatusCode::NOT_FOUND, "Aluno não encontrado".into()));
+    }
+    Ok(())
+}
+
+fn normalize_optional_text(input: Option<String>) -> Option<String> {
+    input
+        .map(|v| v.trim().to_string())
+        .and_then(|v| if v.is_empty() { None } else { Some(v) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        call, call_json, insert_class, insert_student, insert_subject, insert_tenant, insert_term, make_token, test_pool, SECRET,
+    };
+    use serde_json::{json, Value};
+
+    /// Aluna Iara matriculada na 6A do ano passado, com a 7A deste ano já criada, Matemática e
+    /// um bimestre do ano passado.
+    struct Fixture {
+        pool: PgPool,
+        app: Router,
+        owner: String,
+        tenant_id: Uuid,
+        year: i32,
+        old_class_id: Uuid,
+        new_class_id: Uuid,
+        term_id: Uuid,
+        subject_id: Uuid,
+        student_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let pool = test_pool().await;
+            let year = chrono::Local::now().year();
+            let tenant_id = insert_tenant(&pool, "Escola Histórico").await;
+            sqlx::query("UPDATE tenants SET school_city = 'Recife' WHERE id = $1")
+                .bind(tenant_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            let old_class_id = insert_class(&pool, tenant_id, "6A", "6 ano", year - 1).await;
+            let new_class_id = insert_class(&pool, tenant_id, "7A", "7 ano", year).await;
+            let term_id = insert_term(&pool, tenant_id, "1º Bimestre", year - 1, 1).await;
+            let subject_id = insert_subject(&pool, tenant_id, "Matemática").await;
+            let student_id = insert_student(&pool, tenant_id, old_class_id, "Iara", "I1").await;
+
+            let owner = make_token(tenant_id, Uuid::new_v4(), "owner");
+            let app = routes(pool.clone(), SECRET.into())
+                .merge(crate::routes::records::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::students::routes(pool.clone(), SECRET.into()))
+                .merge(crate::routes::subjects::routes(pool.clone(), SECRET.into()));
+
+            Fixture { pool, app, owner, tenant_id, year, old_class_id, new_class_id, term_id, subject_id, student_id }
+        }
+
+        fn transcript_path(&self) -> String {
+            format!("/students/{}/transcript", self.student_id)
+        }
+
+        fn external_path(&self) -> String {
+            format!("/students/{}/transcript/external-records", self.student_id)
+        }
+
+        async fn set_workload(&self) -> (StatusCode, Value) {
+            call_json(&self.app, "PUT", &format!("/subjects/{}/workload", self.subject_id), &self.owner, Some(json!({
+                "workload_hours": 160, "grades": [{"grade": "7 ano", "workload_hours": 200}]
+            })))
+            .await
+        }
+
+        /// Nota 8 na 6A e promoção para a 7A; a matrícula antiga continua no histórico.
+        async fn grade_and_promote(&self) {
+            let (status, _) = call_json(&self.app, "PUT", &format!("/classes/{}/gradebook", self.old_class_id), &self.owner, Some(json!({
+                "term_id": self.term_id, "subject_id": self.subject_id,
+                "records": [{"student_id": self.student_id, "score": 8.0}]
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let (status, _) = call_json(&self.app, "PUT", &format!("/students/{}/class", self.student_id), &self.owner, Some(json!({
+                "class_id": self.new_class_id
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        async fn add_external_record(&self) -> Value {
+            let (status, external) = call_json(&self.app, "POST", &self.external_path(), &self.owner, Some(json!({
+                "school_year": self.year - 2, "grade": "5 ano", "school_name": "EM Anterior", "school_city": "Olinda",
+                "attendance_percent": 92.5, "final_status": "approved",
+                "subjects": [
+                    {"subject_name": "Matemática", "workload_hours": 150, "final_grade": "B"},
+                    {"subject_name": "Língua Portuguesa", "workload_hours": 150, "final_grade": "A"}
+                ]
+            })))
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            external
+        }
+
+        async fn transcript(&self) -> Value {
+            let (status, transcript) = call_json(&self.app, "GET", &self.transcript_path(), &self.owner, None).await;
+            assert_eq!(status, StatusCode::OK);
+            transcript
+        }
+
+        async fn cleanup(self) {
+            sqlx::query("DELETE FROM tenants WHERE id = $1")
+                .bind(self.tenant_id)
+                .execute(&self.pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn subject_workload_accepts_per_grade_overrides() {
+        let f = Fixture::new().await;
+        let (status, workload) = f.set_workload().await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(workload["workload_hours"], 160);
+        assert_eq!(workload["grades"][0]["workload_hours"], 200);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn external_record_rejects_unknown_final_status() {
+        let f = Fixture::new().await;
+        let (status, _) = call_json(&f.app, "POST", &f.external_path(), &f.owner, Some(json!({
+            "school_year": f.year - 2, "grade": "5 ano", "school_name": "EM Anterior", "final_status": "promoted"
+        })))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn transcript_lists_previous_school_past_and_current_years() {
+        let f = Fixture::new().await;
+        f.set_workload().await;
+        f.grade_and_promote().await;
+        let external = f.add_external_record().await;
+        assert_eq!(external["subjects"].as_array().unwrap().len(), 2);
+
+        let transcript = f.transcript().await;
+        let years = transcript["years"].as_array().unwrap();
+        assert_eq!(years.len(), 3);
+        assert_eq!(years[0]["source"], "external");
+        assert_eq!(years[0]["final_status_label"], "Aprovado");
+        assert_eq!(years[0]["total_workload_hours"], 300);
+        assert_eq!(years[1]["source"], "school");
+        assert_eq!(years[1]["grade"], "6 ano");
+        assert_eq!(years[1]["subjects"][0]["workload_hours"], 160);
+        assert_eq!(years[1]["subjects"][0]["final_grade"], "8,00");
+        assert_eq!(years[1]["final_status"], "approved");
+        assert_eq!(years[2]["class_name"], "7A");
+        assert_eq!(years[2]["final_status"], "in_progress");
+        assert_eq!(transcript["total_workload_hours"], 460);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn transcript_is_forbidden_to_teachers() {
+        let f = Fixture::new().await;
+        let teacher = make_token(f.tenant_id, Uuid::new_v4(), "teacher");
+        let (status, _) = call_json(&f.app, "GET", &f.transcript_path(), &teacher, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn transcript_pdf_is_rendered() {
+        let f = Fixture::new().await;
+        f.grade_and_promote().await;
+        f.add_external_record().await;
+
+        let (status, pdf) = call(&f.app, "GET", &format!("{}/pdf", f.transcript_path()), &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        f.cleanup().await;
+    }
+
+    #[tokio::test]
+    async fn deleting_external_record_removes_its_year() {
+        let f = Fixture::new().await;
+        f.grade_and_promote().await;
+        let external = f.add_external_record().await;
+        assert_eq!(f.transcript().await["years"].as_array().unwrap().len(), 3);
+
+        let record_path = format!("{}/{}", f.external_path(), external["id"].as_str().unwrap());
+        let (status, _) = call_json(&f.app, "DELETE", &record_path, &f.owner, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(f.transcript().await["years"].as_array().unwrap().len(), 2);
+        f.cleanup().await;
+    }
+}
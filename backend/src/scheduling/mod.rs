@@ -0,0 +1,14 @@
+//! Montagem automática da grade horária.
+
+pub mod solver;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+/// Um horário de aula do dia. Dois horários seguidos (fim de um = início do outro) aceitam
+/// aula dupla; um intervalo entre eles separa a dupla.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Period {
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
@@ -0,0 +1,716 @@
+//! Gerador de grade horária. Restrições obrigatórias: turma, professor e sala têm no máximo
+//! uma aula por horário, o professor não recebe aula quando está indisponível e professor e
+//! sala não recebem aula quando estão ocupados em turma fora da geração. Entre as grades válidas, procura deixar poucas janelas (horários
+//! vagos entre duas aulas) para professores e turmas, não repetir a disciplina no mesmo dia
+//! e manter as aulas duplas pedidas.
+//!
+//! Primeiro encaixa as aulas da mais difícil para a mais fácil; quando uma aula não cabe em
+//! lugar nenhum, ocupa o horário com menos conflitos e as aulas desalojadas voltam para a
+//! fila; a aula desalojada vezes demais fica sem horário. Depois melhora a grade movendo e trocando aulas enquanto o custo não piora.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Peso de cada janela na agenda de um professor.
+const TEACHER_GAP_COST: i64 = 4;
+/// Peso de cada janela na agenda da turma.
+const CLASS_GAP_COST: i64 = 1;
+/// Peso de cada aula repetida da disciplina no mesmo dia (a dupla conta como uma aula).
+const SAME_DAY_COST: i64 = 3;
+/// Uma aula dupla desalojada mais vezes que isso vira duas aulas simples.
+const SPLIT_DOUBLE_AFTER: u32 = 8;
+/// Uma aula desalojada mais vezes que isso desiste de desalojar as outras: sem isso, aulas
+/// que disputam poucos horários se revezam para sempre e as demais nunca saem da fila.
+const GIVE_UP_AFTER: u32 = 30;
+/// Parte do progresso reservada para o encaixe; o restante é da melhoria.
+const PLACING_SHARE: usize = 70;
+
+pub struct Requirement {
+    pub class: usize,
+    pub teacher: Option<usize>,
+    pub room: Option<usize>,
+    pub lessons: usize,
+    pub doubles: usize,
+}
+
+pub struct Problem {
+    pub days: usize,
+    pub periods: usize,
+    /// `joined[p]`: os horários `p` e `p + 1` são seguidos e aceitam aula dupla.
+    pub joined: Vec<bool>,
+    pub classes: usize,
+    /// `teacher_unavailable[t][day * periods + p]`.
+    pub teacher_unavailable: Vec<Vec<bool>>,
+    /// Aulas do professor em turmas fora da geração, no mesmo formato.
+    pub teacher_busy: Vec<Vec<bool>>,
+    /// Aulas na sala em turmas fora da geração, no mesmo formato; uma linha por sala.
+    pub room_busy: Vec<Vec<bool>>,
+    pub requirements: Vec<Requirement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub requirement: usize,
+    pub day: usize,
+    pub period: usize,
+    /// 1 = aula simples, 2 = aula dupla.
+    pub length: usize,
+}
+
+#[derive(Debug)]
+pub struct Solution {
+    pub placements: Vec<Placement>,
+    /// Aulas sem horário: (requisito, quantidade).
+    pub unplaced: Vec<(usize, usize)>,
+    pub teacher_gaps: usize,
+    pub doubles_requested: usize,
+    pub doubles_met: usize,
+}
+
+struct Unit {
+    requirement: usize,
+    class: usize,
+    teacher: Option<usize>,
+    room: Option<usize>,
+    length: usize,
+    pos: Option<(usize, usize)>,
+    evictions: u32,
+}
+
+struct Solver<'a> {
+    problem: &'a Problem,
+    units: Vec<Unit>,
+    class_grid: Vec<Vec<Option<usize>>>,
+    teacher_grid: Vec<Vec<Option<usize>>>,
+    room_grid: Vec<Vec<Option<usize>>>,
+    rng: StdRng,
+}
+
+/// Monta a grade. `progress` recebe (aulas encaixadas, percentual) ao longo da execução.
+pub fn solve(problem: &Problem, seed: u64, progress: &dyn Fn(usize, usize)) -> Solution {
+    let teachers = problem.teacher_unavailable.len();
+    let slots = problem.days * problem.periods;
+    let mut solver = Solver {
+        problem,
+        units: Vec::new(),
+        class_grid: vec![vec![None; slots]; problem.classes],
+        teacher_grid: vec![vec![None; slots]; teachers],
+        room_grid: vec![vec![None; slots]; problem.room_busy.len()],
+        rng: StdRng::seed_from_u64(seed),
+    };
+
+    for (i, r) in problem.requirements.iter().enumerate() {
+        let doubles = r.doubles.min(r.lessons / 2);
+        for n in 0..(r.lessons - doubles) {
+            solver.units.push(Unit {
+                requirement: i,
+                class: r.class,
+                teacher: r.teacher,
+                room: r.room,
+                length: if n < doubles { 2 } else { 1 },
+                pos: None,
+                evictions: 0,
+            });
+        }
+    }
+    let total: usize = problem.requirements.iter().map(|r| r.lessons).sum();
+
+    let leftover = solver.place_all(total, progress);
+    solver.improve(total, progress);
+    progress(solver.placed_lessons(), 100);
+
+    let mut unplaced: Vec<(usize, usize)> = Vec::new();
+    for u in leftover {
+        let unit = &solver.units[u];
+        match unplaced.iter_mut().find(|(r, _)| *r == unit.requirement) {
+            Some((_, n)) => *n += unit.length,
+            None => unplaced.push((unit.requirement, unit.length)),
+        }
+    }
+
+    let mut teacher_gaps = 0;
+    for t in 0..teachers {
+        for day in 0..problem.days {
+            teacher_gaps += solver.teacher_day_gaps(t, day);
+        }
+    }
+    let placements: Vec<Placement> = solver
+        .units
+        .iter()
+        .filter_map(|u| {
+            u.pos.map(|(day, period)| Placement {
+                requirement: u.requirement,
+                day,
+                period,
+                length: u.length,
+            })
+        })
+        .collect();
+
+    Solution {
+        doubles_requested: problem.requirements.iter().map(|r| r.doubles.min(r.lessons / 2)).sum(),
+        doubles_met: placements.iter().filter(|p| p.length == 2).count(),
+        placements,
+        unplaced,
+        teacher_gaps,
+    }
+}
+
+impl Solver<'_> {
+    fn slot(&self, day: usize, period: usize) -> usize {
+        day * self.problem.periods + period
+    }
+
+    fn placed_lessons(&self) -> usize {
+        self.units.iter().filter(|u| u.pos.is_some()).map(|u| u.length).sum()
+    }
+
+    /// A aula cabe no formato do dia e professor e sala estão livres fora da geração?
+    fn fits_shape(&self, u: usize, day: usize, period: usize) -> bool {
+        let unit = &self.units[u];
+        if period + unit.length > self.problem.periods {
+            return false;
+        }
+        if unit.length == 2 && !self.problem.joined[period] {
+            return false;
+        }
+        (0..unit.length).all(|k| {
+            let s = self.slot(day, period + k);
+            let teacher_ok = unit
+                .teacher
+                .is_none_or(|t| !self.problem.teacher_unavailable[t][s] && !self.problem.teacher_busy[t][s]);
+            teacher_ok && unit.room.is_none_or(|r| !self.problem.room_busy[r][s])
+        })
+    }
+
+    /// Aulas já encaixadas que ocupam a turma, o professor ou a sala nesses horários.
+    fn conflicts(&self, u: usize, day: usize, period: usize) -> Vec<usize> {
+        let unit = &self.units[u];
+        let mut found = Vec::new();
+        for k in 0..unit.length {
+            let s = self.slot(day, period + k);
+            let teacher_cell = unit.teacher.and_then(|t| self.teacher_grid[t][s]);
+            let room_cell = unit.room.and_then(|r| self.room_grid[r][s]);
+            for other in [self.class_grid[unit.class][s], teacher_cell, room_cell].into_iter().flatten() {
+                if other != u && !found.contains(&other) {
+                    found.push(other);
+                }
+            }
+        }
+        found
+    }
+
+    fn is_free(&self, u: usize, day: usize, period: usize) -> bool {
+        self.fits_shape(u, day, period) && self.conflicts(u, day, period).is_empty()
+    }
+
+    fn place(&mut self, u: usize, day: usize, period: usize) {
+        for k in 0..self.units[u].length {
+            let s = self.slot(day, period + k);
+            self.class_grid[self.units[u].class][s] = Some(u);
+            if let Some(t) = self.units[u].teacher {
+                self.teacher_grid[t][s] = Some(u);
+            }
+            if let Some(r) = self.units[u].room {
+                self.room_grid[r][s] = Some(u);
+            }
+        }
+        self.units[u].pos = Some((day, period));
+    }
+
+    fn remove(&mut self, u: usize) {
+        let Some((day, period)) = self.units[u].pos.take() else {
+            return;
+        };
+        for k in 0..self.units[u].length {
+            let s = self.slot(day, period + k);
+            self.class_grid[self.units[u].class][s] = None;
+            if let Some(t) = self.units[u].teacher {
+                self.teacher_grid[t][s] = None;
+            }
+            if let Some(r) = self.units[u].room {
+                self.room_grid[r][s] = None;
+            }
+        }
+    }
+
+    fn teacher_day_gaps(&self, t: usize, day: usize) -> usize {
+        let busy: Vec<bool> = (0..self.problem.periods)
+            .map(|p| {
+                let s = self.slot(day, p);
+                self.teacher_grid[t][s].is_some() || self.problem.teacher_busy[t][s]
+            })
+            .collect();
+        gaps(&busy)
+    }
+
+    fn class_day_cost(&self, c: usize, day: usize) -> i64 {
+        let cells: Vec<Option<usize>> = (0..self.problem.periods)
+            .map(|p| self.class_grid[c][self.slot(day, p)])
+            .collect();
+        let busy: Vec<bool> = cells.iter().map(Option::is_some).collect();
+
+        // aulas distintas da mesma disciplina no dia
+        let mut per_requirement: Vec<(usize, i64)> = Vec::new();
+        for (p, cell) in cells.iter().enumerate() {
+            let Some(u) = *cell else { continue };
+            if p > 0 && cells[p - 1] == Some(u) {
+                continue;
+            }
+            let r = self.units[u].requirement;
+            match per_requirement.iter_mut().find(|(req, _)| *req == r) {
+                Some((_, n)) => *n += 1,
+                None => per_requirement.push((r, 1)),
+            }
+        }
+        let repeated: i64 = per_requirement.iter().map(|(_, n)| n - 1).sum();
+
+        gaps(&busy) as i64 * CLASS_GAP_COST + repeated * SAME_DAY_COST
+    }
+
+    /// Custo das agendas tocadas por uma mudança.
+    fn cost(&self, classes: &[usize], teachers: &[Option<usize>], days: &[usize]) -> i64 {
+        let mut total = 0;
+        for (i, &day) in days.iter().enumerate() {
+            if days[..i].contains(&day) {
+                continue;
+            }
+            for (j, &c) in classes.iter().enumerate() {
+                if !classes[..j].contains(&c) {
+                    total += self.class_day_cost(c, day);
+                }
+            }
+            for (j, t) in teachers.iter().enumerate() {
+                if let Some(t) = *t {
+                    if !teachers[..j].contains(&Some(t)) {
+                        total += self.teacher_day_gaps(t, day) as i64 * TEACHER_GAP_COST;
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    fn placement_cost(&mut self, u: usize, day: usize, period: usize) -> i64 {
+        let classes = [self.units[u].class];
+        let teachers = [self.units[u].teacher];
+        let before = self.cost(&classes, &teachers, &[day]);
+        self.place(u, day, period);
+        let after = self.cost(&classes, &teachers, &[day]);
+        self.remove(u);
+        after - before
+    }
+
+    /// Encaixe inicial; devolve as aulas que não couberam.
+    fn place_all(&mut self, total: usize, progress: &dyn Fn(usize, usize)) -> Vec<usize> {
+        let teachers = self.problem.teacher_unavailable.len();
+        let mut load = vec![0usize; teachers];
+        for unit in &self.units {
+            if let Some(t) = unit.teacher {
+                load[t] += unit.length;
+            }
+        }
+        let difficulty = |unit: &Unit| {
+            let blocked = unit.teacher.map_or(0, |t| {
+                self.problem.teacher_unavailable[t].iter().filter(|b| **b).count()
+            });
+            (unit.length, unit.teacher.map_or(0, |t| load[t]) + blocked)
+        };
+        // a fila é uma pilha: as aulas mais difíceis saem primeiro
+        let mut order: Vec<(usize, (usize, usize))> =
+            self.units.iter().enumerate().map(|(i, u)| (i, difficulty(u))).collect();
+        order.sort_by_key(|(i, d)| (*d, std::cmp::Reverse(*i)));
+        let mut queue: Vec<usize> = order.into_iter().map(|(i, _)| i).collect();
+
+        let mut leftover = Vec::new();
+        let max_steps = self.units.len() * 100 + 1000;
+        let mut steps = 0;
+        while let Some(u) = queue.pop() {
+            steps += 1;
+            if steps > max_steps {
+                queue.push(u);
+                break;
+            }
+            if steps % 50 == 0 {
+                let placed = self.placed_lessons();
+                progress(placed, placed * PLACING_SHARE / total.max(1));
+            }
+
+            let mut best: Option<((usize, usize), i64)> = None;
+            for day in 0..self.problem.days {
+                for period in 0..self.problem.periods {
+                    if !self.is_free(u, day, period) {
+                        continue;
+                    }
+                    let cost = self.placement_cost(u, day, period) * 4 + self.rng.gen_range(0..4);
+                    if best.is_none_or(|(_, c)| cost < c) {
+                        best = Some(((day, period), cost));
+                    }
+                }
+            }
+            if let Some(((day, period), _)) = best {
+                self.place(u, day, period);
+                continue;
+            }
+
+            // sem horário livre: desaloja o mínimo possível, evitando quem já saiu muitas vezes
+            if self.units[u].length == 2 && self.units[u].evictions > SPLIT_DOUBLE_AFTER {
+                self.split(u, &mut queue);
+                continue;
+            }
+            if self.units[u].evictions > GIVE_UP_AFTER {
+                leftover.push(u);
+                continue;
+            }
+            let mut target: Option<((usize, usize), u32)> = None;
+            for day in 0..self.problem.days {
+                for period in 0..self.problem.periods {
+                    if !self.fits_shape(u, day, period) {
+                        continue;
+                    }
+                    let weight: u32 = self
+                        .conflicts(u, day, period)
+                        .iter()
+                        .map(|&o| 1 + self.units[o].evictions)
+                        .sum::<u32>()
+                        * 4
+                        + self.rng.gen_range(0..4);
+                    if target.is_none_or(|(_, w)| weight < w) {
+                        target = Some(((day, period), weight));
+                    }
+                }
+            }
+            match target {
+                Some(((day, period), _)) => {
+                    for other in self.conflicts(u, day, period) {
+                        self.remove(other);
+                        self.units[other].evictions += 1;
+                        queue.push(other);
+                    }
+                    self.place(u, day, period);
+                }
+                None if self.units[u].length == 2 => self.split(u, &mut queue),
+                None => leftover.push(u),
+            }
+        }
+
+        // o que sobrou ainda ocupa horários que tenham ficado livres
+        leftover.extend(queue);
+        leftover.retain(|&u| match self.first_free(u) {
+            Some((day, period)) => {
+                self.place(u, day, period);
+                false
+            }
+            None => true,
+        });
+        leftover
+    }
+
+    fn first_free(&self, u: usize) -> Option<(usize, usize)> {
+        (0..self.problem.days)
+            .flat_map(|day| (0..self.problem.periods).map(move |period| (day, period)))
+            .find(|&(day, period)| self.is_free(u, day, period))
+    }
+
+    /// Desiste da aula dupla: vira duas aulas simples.
+    fn split(&mut self, u: usize, queue: &mut Vec<usize>) {
+        self.units[u].length = 1;
+        self.units.push(Unit {
+            requirement: self.units[u].requirement,
+            class: self.units[u].class,
+            teacher: self.units[u].teacher,
+            room: self.units[u].room,
+            length: 1,
+            pos: None,
+            evictions: 0,
+        });
+        queue.push(self.units.len() - 1);
+        queue.push(u);
+    }
+
+    /// Busca local: move uma aula para um horário livre ou troca duas aulas da mesma turma,
+    /// mantendo a mudança quando o custo não piora.
+    fn improve(&mut self, total: usize, progress: &dyn Fn(usize, usize)) {
+        let placed: Vec<usize> = (0..self.units.len()).filter(|&u| self.units[u].pos.is_some()).collect();
+        if placed.is_empty() {
+            return;
+        }
+        let iterations = (placed.len() * 300).min(300_000);
+        let report_every = (iterations / 20).max(1);
+        let placed_lessons = self.placed_lessons();
+
+        for i in 0..iterations {
+            if i % report_every == 0 {
+                let percent = PLACING_SHARE + i * (100 - PLACING_SHARE) / iterations;
+                progress(placed_lessons.min(total), percent);
+            }
+            let a = placed[self.rng.gen_range(0..placed.len())];
+            if self.rng.gen_bool(0.5) {
+                self.try_move(a);
+            } else {
+                let b = placed[self.rng.gen_range(0..placed.len())];
+                if a != b
+                    && self.units[a].class == self.units[b].class
+                    && self.units[a].length == self.units[b].length
+                {
+                    self.try_swap(a, b);
+                }
+            }
+        }
+    }
+
+    fn try_move(&mut self, u: usize) {
+        let Some((old_day, old_period)) = self.units[u].pos else {
+            return;
+        };
+        let day = self.rng.gen_range(0..self.problem.days);
+        let period = self.rng.gen_range(0..self.problem.periods);
+        let classes = [self.units[u].class];
+        let teachers = [self.units[u].teacher];
+        let days = [old_day, day];
+
+        let before = self.cost(&classes, &teachers, &days);
+        self.remove(u);
+        if !self.is_free(u, day, period) {
+            self.place(u, old_day, old_period);
+            return;
+        }
+        self.place(u, day, period);
+        if self.cost(&classes, &teachers, &days) > before {
+            self.remove(u);
+            self.place(u, old_day, old_period);
+        }
+    }
+
+    fn try_swap(&mut self, a: usize, b: usize) {
+        let (Some(pos_a), Some(pos_b)) = (self.units[a].pos, self.units[b].pos) else {
+            return;
+        };
+        let classes = [self.units[a].class];
+        let teachers = [self.units[a].teacher, self.units[b].teacher];
+        let days = [pos_a.0, pos_b.0];
+
+        let before = self.cost(&classes, &teachers, &days);
+        self.remove(a);
+        self.remove(b);
+        let swapped = if self.is_free(a, pos_b.0, pos_b.1) {
+            self.place(a, pos_b.0, pos_b.1);
+            if self.is_free(b, pos_a.0, pos_a.1) {
+                self.place(b, pos_a.0, pos_a.1);
+                true
+            } else {
+                self.remove(a);
+                false
+            }
+        } else {
+            false
+        };
+        if swapped && self.cost(&classes, &teachers, &days) <= before {
+            return;
+        }
+        if swapped {
+            self.remove(a);
+            self.remove(b);
+        }
+        self.place(a, pos_a.0, pos_a.1);
+        self.place(b, pos_b.0, pos_b.1);
+    }
+}
+
+/// Horários vagos entre a primeira e a última aula do dia.
+fn gaps(busy: &[bool]) -> usize {
+    let Some(first) = busy.iter().position(|b| *b) else {
+        return 0;
+    };
+    let last = busy.iter().rposition(|b| *b).unwrap_or(first);
+    busy[first..=last].iter().filter(|b| !**b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 3] = [1, 42, 2026];
+
+    fn requirement(class: usize, teacher: Option<usize>, room: Option<usize>, lessons: usize, doubles: usize) -> Requirement {
+        Requirement {
+            class,
+            teacher,
+            room,
+            lessons,
+            doubles,
+        }
+    }
+
+    /// Todos os horários seguidos, sem indisponibilidades nem aulas fora da geração.
+    fn problem(days: usize, periods: usize, classes: usize, teachers: usize, rooms: usize, requirements: Vec<Requirement>) -> Problem {
+        let slots = days * periods;
+        Problem {
+            days,
+            periods,
+            joined: (0..periods).map(|p| p + 1 < periods).collect(),
+            classes,
+            teacher_unavailable: vec![vec![false; slots]; teachers],
+            teacher_busy: vec![vec![false; slots]; teachers],
+            room_busy: vec![vec![false; slots]; rooms],
+            requirements,
+        }
+    }
+
+    /// (requisito, horário) de cada aula encaixada; a dupla ocupa dois horários.
+    fn cells(problem: &Problem, solution: &Solution) -> Vec<(usize, usize)> {
+        solution
+            .placements
+            .iter()
+            .flat_map(|p| (0..p.length).map(move |k| (p.requirement, p.day * problem.periods + p.period + k)))
+            .collect()
+    }
+
+    /// Nenhum horário com duas aulas do mesmo recurso (turma, professor ou sala).
+    fn assert_no_double_booking(problem: &Problem, solution: &Solution, resource: impl Fn(&Requirement) -> Option<usize>) {
+        let mut seen: Vec<(usize, usize)> = Vec::new();
+        for (r, slot) in cells(problem, solution) {
+            if let Some(id) = resource(&problem.requirements[r]) {
+                assert!(!seen.contains(&(id, slot)), "recurso {id} com duas aulas no horário {slot}");
+                seen.push((id, slot));
+            }
+        }
+    }
+
+    fn placed_per_requirement(problem: &Problem, solution: &Solution) -> Vec<usize> {
+        let mut placed = vec![0; problem.requirements.len()];
+        for p in &solution.placements {
+            placed[p.requirement] += p.length;
+        }
+        placed
+    }
+
+    #[test]
+    fn never_double_books_classes_teachers_or_rooms() {
+        // 3 turmas x 5 dias x 5 horários, 2 professores que dão aula nas três e um laboratório
+        let problem = problem(
+            5,
+            5,
+            3,
+            2,
+            1,
+            vec![
+                requirement(0, Some(0), None, 8, 2),
+                requirement(1, Some(0), None, 8, 0),
+                requirement(2, Some(1), None, 8, 1),
+                requirement(0, Some(1), Some(0), 5, 0),
+                requirement(1, Some(1), Some(0), 4, 0),
+                requirement(2, None, Some(0), 6, 0),
+            ],
+        );
+        for seed in SEEDS {
+            let solution = solve(&problem, seed, &|_, _| {});
+            assert!(solution.unplaced.is_empty(), "seed {seed}: {:?}", solution.unplaced);
+            assert_eq!(placed_per_requirement(&problem, &solution), vec![8, 8, 8, 5, 4, 6]);
+            assert_no_double_booking(&problem, &solution, |r| Some(r.class));
+            assert_no_double_booking(&problem, &solution, |r| r.teacher);
+            assert_no_double_booking(&problem, &solution, |r| r.room);
+        }
+    }
+
+    #[test]
+    fn respects_teacher_unavailability_and_outside_lessons() {
+        let mut problem = problem(
+            5,
+            4,
+            2,
+            1,
+            1,
+            vec![requirement(0, Some(0), None, 6, 0), requirement(1, Some(0), Some(0), 6, 0)],
+        );
+        // professor indisponível às segundas e ocupado no 1º horário dos outros dias;
+        // a sala está ocupada no último horário de todos os dias
+        for day in 0..5 {
+            for period in 0..4 {
+                let s = day * 4 + period;
+                problem.teacher_unavailable[0][s] = day == 0;
+                problem.teacher_busy[0][s] = period == 0;
+                problem.room_busy[0][s] = period == 3;
+            }
+        }
+        for seed in SEEDS {
+            let solution = solve(&problem, seed, &|_, _| {});
+            assert!(solution.unplaced.is_empty(), "seed {seed}: {:?}", solution.unplaced);
+            for (r, s) in cells(&problem, &solution) {
+                assert!(!problem.teacher_unavailable[0][s], "seed {seed}: aula no horário indisponível {s}");
+                assert!(!problem.teacher_busy[0][s], "seed {seed}: aula no horário ocupado {s}");
+                if r == 1 {
+                    assert!(!problem.room_busy[0][s], "seed {seed}: sala ocupada no horário {s}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn places_double_lessons_only_on_joined_periods() {
+        // intervalo entre o 2º e o 3º horário: a dupla só cabe em 0-1 ou 2-3
+        let mut problem = problem(3, 4, 1, 1, 0, vec![requirement(0, Some(0), None, 6, 3)]);
+        problem.joined = vec![true, false, true, false];
+        for seed in SEEDS {
+            let solution = solve(&problem, seed, &|_, _| {});
+            assert!(solution.unplaced.is_empty(), "seed {seed}: {:?}", solution.unplaced);
+            assert_eq!(solution.doubles_requested, 3);
+            assert_eq!(solution.doubles_met, 3);
+            for p in solution.placements.iter().filter(|p| p.length == 2) {
+                assert!(problem.joined[p.period], "seed {seed}: dupla em {p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reports_lessons_that_do_not_fit() {
+        // 2 dias x 3 horários para 8 aulas da turma; o professor da segunda disciplina só
+        // pode no 1º dia
+        let mut problem = problem(
+            2,
+            3,
+            1,
+            2,
+            0,
+            vec![requirement(0, Some(0), None, 3, 0), requirement(0, Some(1), None, 5, 0)],
+        );
+        for s in 3..6 {
+            problem.teacher_unavailable[1][s] = true;
+        }
+        for seed in SEEDS {
+            let solution = solve(&problem, seed, &|_, _| {});
+            let placed = placed_per_requirement(&problem, &solution);
+            let mut missing = vec![0; problem.requirements.len()];
+            for &(r, lessons) in &solution.unplaced {
+                missing[r] += lessons;
+            }
+            for (r, req) in problem.requirements.iter().enumerate() {
+                assert_eq!(placed[r] + missing[r], req.lessons, "seed {seed}: requisito {r}");
+            }
+            assert_eq!(placed.iter().sum::<usize>(), 6, "seed {seed}: a grade tem 6 horários");
+            assert!(missing[1] >= 2, "seed {seed}: o 2º professor só tem 3 horários");
+            assert_no_double_booking(&problem, &solution, |r| Some(r.class));
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_grid() {
+        let problem = problem(
+            5,
+            5,
+            2,
+            2,
+            0,
+            vec![
+                requirement(0, Some(0), None, 10, 2),
+                requirement(1, Some(0), None, 10, 2),
+                requirement(0, Some(1), None, 8, 0),
+                requirement(1, Some(1), None, 8, 0),
+            ],
+        );
+        let first = solve(&problem, 7, &|_, _| {});
+        let second = solve(&problem, 7, &|_, _| {});
+        assert_eq!(first.placements, second.placements);
+        assert_eq!(first.teacher_gaps, second.teacher_gaps);
+    }
+}
@@ -0,0 +1,232 @@
+//! Apoio aos testes HTTP: banco de teste, cadastros mínimos, tokens e chamadas às rotas.
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use tower::util::ServiceExt;
+use uuid::Uuid;
+
+use crate::auth::jwt::Claims;
+
+pub const SECRET: &str = "test-secret";
+
+/// A fila `guardian_notifications` é uma só para todos os testes: quem chama a entrega
+/// segura esta trava para que a rodada de outro teste não leve as suas mensagens.
+pub static NOTIFICATION_DELIVERY: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Gateway de e-mail falso: devolve a URL de envio e os payloads que chegaram nela.
+pub async fn spawn_gateway() -> (String, Arc<Mutex<Vec<Value>>>) {
+    let received: Arc<Mutex<Vec<Value>>> = Arc::default();
+    let sink = received.clone();
+    let gateway = Router::new().route(
+        "/send",
+        post(move |Json(payload): Json<Value>| {
+            let sink = sink.clone();
+            async move {
+                sink.lock().unwrap().push(payload);
+                StatusCode::OK
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, gateway).await.unwrap() });
+    (format!("http://{addr}/send"), received)
+}
+
+pub async fn test_pool() -> PgPool {
+    dotenvy::dotenv().ok();
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL precisa estar definido para rodar os testes");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&url)
+        .await
+        .expect("falha ao conectar no banco de teste");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("falha ao rodar migrations");
+
+    pool
+}
+
+pub async fn insert_tenant(pool: &PgPool, name: &str) -> Uuid {
+    let tenant_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO tenants (id, name, slug) VALUES ($1, $2, $3)")
+        .bind(tenant_id)
+        .bind(name)
+        .bind(format!("teste-{tenant_id}"))
+        .execute(pool)
+        .await
+        .expect("falha ao criar tenant de teste");
+    tenant_id
+}
+
+/// Usuário da escola com pessoa e identidade próprias (mesmo id nas três tabelas).
+pub async fn insert_user(pool: &PgPool, tenant_id: Uuid, name: &str, role: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    let email = format!("{id}@escola.test");
+    let person_type = if role == "teacher" { "teacher" } else { "staff" };
+    sqlx::query("INSERT INTO people (id, tenant_id, person_type, full_name) VALUES ($1, $2, $3, $4)")
+        .bind(id)
+        .bind(tenant_id)
+        .bind(person_type)
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("falha ao criar pessoa de teste");
+    sqlx::query("INSERT INTO identities (id, email, password_hash) VALUES ($1, $2, 'x')")
+        .bind(id)
+        .bind(&email)
+        .execute(pool)
+        .await
+        .expect("falha ao criar identidade de teste");
+    sqlx::query(
+        "INSERT INTO users (id, tenant_id, person_id, identity_id, full_name, email, role) VALUES ($1, $2, $1, $1, $3, $4, $5)",
+    )
+    .bind(id)
+    .bind(tenant_id)
+    .bind(name)
+    .bind(&email)
+    .bind(role)
+    .execute(pool)
+    .await
+    .expect("falha ao criar usuário de teste");
+    id
+}
+
+pub async fn insert_class(pool: &PgPool, tenant_id: Uuid, name: &str, grade: &str, year: i32) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO classes (id, tenant_id, name, grade, year) VALUES ($1, $2, $3, $4, $5)")
+        .bind(id)
+        .bind(tenant_id)
+        .bind(name)
+        .bind(grade)
+        .bind(year)
+        .execute(pool)
+        .await
+        .expect("falha ao criar turma de teste");
+    id
+}
+
+pub async fn insert_term(pool: &PgPool, tenant_id: Uuid, name: &str, school_year: i32, sort_order: i32) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO academic_terms (id, tenant_id, name, school_year, sort_order) VALUES ($1, $2, $3, $4, $5)")
+        .bind(id)
+        .bind(tenant_id)
+        .bind(name)
+        .bind(school_year)
+        .bind(sort_order)
+        .execute(pool)
+        .await
+        .expect("falha ao criar período de teste");
+    id
+}
+
+pub async fn insert_subject(pool: &PgPool, tenant_id: Uuid, name: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO subjects (id, tenant_id, name) VALUES ($1, $2, $3)")
+        .bind(id)
+        .bind(tenant_id)
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("falha ao criar disciplina de teste");
+    id
+}
+
+/// Aluno matriculado na turma, com a pessoa de mesmo id.
+pub async fn insert_student(pool: &PgPool, tenant_id: Uuid, class_id: Uuid, name: &str, registration: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO people (id, tenant_id, person_type, full_name) VALUES ($1, $2, 'student', $3)")
+        .bind(id)
+        .bind(tenant_id)
+        .bind(name)
+        .execute(pool)
+        .await
+        .expect("falha ao criar pessoa de teste");
+    sqlx::query(
+        "INSERT INTO students (id, tenant_id, person_id, name, registration, class_id) VALUES ($1, $2, $1, $3, $4, $5)",
+    )
+    .bind(id)
+    .bind(tenant_id)
+    .bind(name)
+    .bind(registration)
+    .bind(class_id)
+    .execute(pool)
+    .await
+    .expect("falha ao criar aluno de teste");
+    id
+}
+
+pub fn make_token(tenant_id: Uuid, user_id: Uuid, role: &str) -> String {
+    sign(Claims {
+        sub: user_id.to_string(),
+        tenant_id: Some(tenant_id.to_string()),
+        role: role.to_string(),
+        scope: "tenant".to_string(),
+        exp: (chrono::Utc::now() + chrono::Duration::days(1)).timestamp() as usize,
+    })
+}
+
+pub fn platform_token() -> String {
+    sign(Claims {
+        sub: Uuid::new_v4().to_string(),
+        tenant_id: None,
+        role: "platform_admin".to_string(),
+        scope: "platform".to_string(),
+        exp: (chrono::Utc::now() + chrono::Duration::days(1)).timestamp() as usize,
+    })
+}
+
+fn sign(claims: Claims) -> String {
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(SECRET.as_bytes()))
+        .expect("falha ao gerar token de teste")
+}
+
+/// Chamada crua; `token` vazio manda a requisição sem Authorization.
+pub async fn call(app: &Router, method: &str, path: &str, token: &str, body: Option<Value>) -> (StatusCode, Vec<u8>) {
+    let body = body.map(|b| b.to_string().into_bytes()).unwrap_or_default();
+    call_bytes(app, method, path, token, "application/json", body).await
+}
+
+/// Chamada com corpo binário (uploads de documentos).
+pub async fn call_bytes(
+    app: &Router,
+    method: &str,
+    path: &str,
+    token: &str,
+    content_type: &str,
+    body: Vec<u8>,
+) -> (StatusCode, Vec<u8>) {
+    let mut req = Request::builder()
+        .method(method)
+        .uri(path)
+        .header("content-type", content_type);
+    if !token.is_empty() {
+        req = req.header("authorization", format!("Bearer {token}"));
+    }
+    let request = req.body(Body::from(body)).expect("falha ao construir request");
+
+    let resp = app.clone().oneshot(request).await.expect("falha ao executar request");
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), usize::MAX).await.expect("falha ao ler body");
+    (status, bytes.to_vec())
+}
+
+/// Chamada com resposta JSON; respostas de erro em texto viram `Value::String`.
+pub async fn call_json(app: &Router, method: &str, path: &str, token: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let (status, bytes) = call(app, method, path, token, body).await;
+    let value = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    (status, value)
+}
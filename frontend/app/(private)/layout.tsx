@@ -96,6 +96,7 @@ export default function PrivateLayout({ children }: { children: React.ReactNode
   const pathname = usePathname();
   const [tenantLabel, setTenantLabel] = useState("");
   const [menuOpen, setMenuOpen] = useState(false);
+  const [enabledModules, setEnabledModules] = useState<string[] | null>(null);
 
   function getLocalTenantLabel(currentPath: string) {
     if (typeof window === "undefined") return "";
@@ -147,12 +148,14 @@ export default function PrivateLayout({ children }: { children: React.ReactNode
         return (await res.json()) as {
           school_name?: string;
           school_code?: string;
+          enabled_modules?: string[];
         };
       })
       .then((data) => {
         if (!data) return;
         if (data.school_name) localStorage.setItem("school_name", data.school_name);
         if (data.school_code) localStorage.setItem("school_code", data.school_code);
+        if (data.enabled_modules) setEnabledModules(data.enabled_modules);
         refreshTenantLabel();
       })
       .catch(() => {
@@ -178,7 +181,12 @@ export default function PrivateLayout({ children }: { children: React.ReactNode
         { href: "/reports", label: "Relatórios" },
         { href: "/cadastros", label: "Cadastros" },
         { href: "/settings", label: "Configurações" },
-      ];
+      ].filter((item) => {
+        // Módulos contratados por escola; enquanto /auth/me não responde, mostra tudo.
+        if (!enabledModules) return true;
+        if (item.href === "/financial") return enabledModules.includes("financial");
+        return true;
+      });
 
   function itemClass(href: string) {
     const active = pathname === href;
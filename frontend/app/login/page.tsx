@@ -5,12 +5,34 @@ import { useRouter } from "next/navigation";
 
 const API = process.env.NEXT_PUBLIC_API_BASE;
 
+type Membership = {
+  tenant_id: string;
+  user_id: string;
+  role: string;
+  school_name: string;
+  school_code: string;
+};
+
+type AuthResponse = {
+  tenant_id: string;
+  user_id: string;
+  token: string;
+  school_name?: string;
+  school_code?: string;
+};
+
 export default function LoginPage() {
   const router = useRouter();
 
   const [schoolCode, setSchoolCode] = useState("");
   const [email, setEmail] = useState("");
   const [password, setPassword] = useState("");
+  const [totpCode, setTotpCode] = useState("");
+  const [needsTotp, setNeedsTotp] = useState(false);
+
+  // seletor de escola (login sem código, e-mail vinculado a várias escolas)
+  const [identityToken, setIdentityToken] = useState<string | null>(null);
+  const [memberships, setMemberships] = useState<Membership[]>([]);
 
   const [busy, setBusy] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -23,6 +45,14 @@ export default function LoginPage() {
       .replace(/(^-|-$)+/g, "");
   }
 
+  function saveSession(data: AuthResponse, fallbackCode: string) {
+    localStorage.setItem("tenant_id", data.tenant_id);
+    localStorage.setItem("token", data.token);
+    localStorage.setItem("school_code", data.school_code ?? fallbackCode);
+    if (data.school_name) localStorage.setItem("school_name", data.school_name);
+    router.replace("/students");
+  }
+
   async function onSubmit(e: React.FormEvent) {
     e.preventDefault();
     setError(null);
@@ -31,35 +61,83 @@ export default function LoginPage() {
     const eemail = email.trim().toLowerCase();
 
     if (!API) return setError("NEXT_PUBLIC_API_BASE não está definido no .env.local");
-    if (school_code.length < 3) return setError("Informe o código da escola.");
+    if (school_code.length > 0 && school_code.length < 3) return setError("Código da escola inválido.");
     if (!eemail.includes("@")) return setError("Informe um e-mail válido.");
     if (password.length < 1) return setError("Informe a senha.");
 
+    const totp_code = totpCode.trim() || undefined;
+
     setBusy(true);
     try {
-      const res = await fetch(`${API}/auth/login`, {
+      // sem código da escola: login pela identidade e seletor de escolas
+      const res = school_code
+        ? await fetch(`${API}/auth/login`, {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            body: JSON.stringify({ school_code, email: eemail, password, totp_code }),
+          })
+        : await fetch(`${API}/auth/identity/login`, {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            body: JSON.stringify({ email: eemail, password, totp_code }),
+          });
+
+      const txt = await res.text();
+      if (!res.ok) {
+        if (txt.includes("2FA")) setNeedsTotp(true);
+        throw new Error(txt || "Erro no login");
+      }
+
+      if (school_code) {
+        saveSession(JSON.parse(txt) as AuthResponse, school_code);
+        return;
+      }
+
+      const data = JSON.parse(txt) as { identity_token: string; memberships: Membership[] };
+      if (data.memberships.length === 1) {
+        await selectTenant(data.identity_token, data.memberships[0]);
+        return;
+      }
+      setIdentityToken(data.identity_token);
+      setMemberships(data.memberships);
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : "Erro inesperado");
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function startSso() {
+    setError(null);
+    const school_code = slugify(schoolCode);
+    if (!API) return setError("NEXT_PUBLIC_API_BASE não está definido no .env.local");
+    if (school_code.length < 3) return setError("Informe o código da escola para entrar com SSO.");
+
+    setBusy(true);
+    try {
+      const res = await fetch(`${API}/auth/sso/${school_code}/start`);
+      const txt = await res.text();
+      if (!res.ok) throw new Error(txt || "SSO indisponível");
+      const data = JSON.parse(txt) as { authorization_url: string };
+      window.location.href = data.authorization_url;
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : "Erro inesperado");
+      setBusy(false);
+    }
+  }
+
+  async function selectTenant(token: string, membership: Membership) {
+    setError(null);
+    setBusy(true);
+    try {
+      const res = await fetch(`${API}/auth/select-tenant`, {
         method: "POST",
-        headers: { "Content-Type": "application/json" },
-        body: JSON.stringify({ school_code, email: eemail, password }),
+        headers: { "Content-Type": "application/json", Authorization: `Bearer ${token}` },
+        body: JSON.stringify({ tenant_id: membership.tenant_id }),
       });
-
       const txt = await res.text();
-      if (!res.ok) throw new Error(txt || "Erro no login");
-
-      const data = JSON.parse(txt) as {
-        tenant_id: string;
-        user_id: string;
-        token: string;
-        school_name?: string;
-        school_code?: string;
-      };
-
-      localStorage.setItem("tenant_id", data.tenant_id);
-      localStorage.setItem("token", data.token);
-      localStorage.setItem("school_code", data.school_code ?? school_code);
-      if (data.school_name) localStorage.setItem("school_name", data.school_name);
-
-      router.replace("/students");
+      if (!res.ok) throw new Error(txt || "Erro ao escolher a escola");
+      saveSession(JSON.parse(txt) as AuthResponse, membership.school_code);
     } catch (err: unknown) {
       setError(err instanceof Error ? err.message : "Erro inesperado");
     } finally {
@@ -72,7 +150,9 @@ export default function LoginPage() {
       <div className="mx-auto max-w-md">
         <div className="rounded-2xl border border-neutral-200 bg-white p-6 shadow">
           <h1 className="text-2xl font-semibold tracking-tight">Entrar</h1>
-          <p className="mt-2 text-sm text-neutral-600">Acesse sua escola usando o código.</p>
+          <p className="mt-2 text-sm text-neutral-600">
+            Acesse sua escola usando o código, ou deixe em branco para escolher entre as suas escolas.
+          </p>
 
           {error && (
             <div className="mt-4 rounded-2xl border border-red-200 bg-red-50 p-3 text-sm text-red-700">
@@ -80,7 +160,27 @@ export default function LoginPage() {
             </div>
           )}
 
-          <form className="mt-6 space-y-4" onSubmit={onSubmit}>
+          {identityToken && memberships.length > 0 && (
+            <div className="mt-6 space-y-2">
+              <div className="text-xs font-semibold text-neutral-700">Escolha a escola</div>
+              {memberships.map((m) => (
+                <button
+                  key={m.tenant_id}
+                  type="button"
+                  className="w-full rounded-2xl border border-neutral-300 bg-white px-4 py-3 text-left text-sm hover:bg-neutral-50 disabled:opacity-60"
+                  onClick={() => selectTenant(identityToken, m)}
+                  disabled={busy}
+                >
+                  <div className="font-semibold text-neutral-900">{m.school_name}</div>
+                  <div className="text-xs text-neutral-500">
+                    {m.school_code} · {m.role}
+                  </div>
+                </button>
+              ))}
+            </div>
+          )}
+
+          <form className={`mt-6 space-y-4 ${identityToken ? "hidden" : ""}`} onSubmit={onSubmit}>
             <div>
               <label className="text-xs font-semibold text-neutral-700">Código da escola</label>
               <input
@@ -115,6 +215,20 @@ export default function LoginPage() {
               />
             </div>
 
+            {needsTotp && (
+              <div>
+                <label className="text-xs font-semibold text-neutral-700">Código 2FA</label>
+                <input
+                  inputMode="numeric"
+                  className="mt-2 h-12 w-full rounded-2xl border border-neutral-300 bg-white px-4 text-base font-medium text-neutral-900 outline-none focus:ring-4 focus:ring-black/10"
+                  value={totpCode}
+                  onChange={(e) => setTotpCode(e.target.value.replace(/\D/g, "").slice(0, 6))}
+                  placeholder="000000"
+                  disabled={busy}
+                />
+              </div>
+            )}
+
             <button
               className="w-full rounded-2xl bg-black px-4 py-3 text-sm font-semibold text-white shadow hover:opacity-90 disabled:opacity-60"
               disabled={busy}
@@ -122,6 +236,15 @@ export default function LoginPage() {
               {busy ? "Entrando..." : "Entrar"}
             </button>
 
+            <button
+              type="button"
+              className="w-full rounded-2xl border border-neutral-300 bg-white px-4 py-3 text-sm font-semibold text-neutral-900 hover:bg-neutral-50 disabled:opacity-60"
+              onClick={startSso}
+              disabled={busy}
+            >
+              Entrar com SSO da escola (Google / Microsoft)
+            </button>
+
             <div className="text-center text-xs text-neutral-500">
               Não tem escola ainda?{" "}
               <a className="font-semibold text-black hover:underline" href="/register">
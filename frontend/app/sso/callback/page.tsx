@@ -0,0 +1,70 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { useRouter } from "next/navigation";
+
+const API = process.env.NEXT_PUBLIC_API_BASE;
+
+export default function SsoCallbackPage() {
+  const router = useRouter();
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const params = new URLSearchParams(window.location.search);
+    const state = params.get("state");
+    const code = params.get("code");
+    const providerError = params.get("error_description") || params.get("error");
+
+    if (providerError) {
+      setError(providerError);
+      return;
+    }
+    if (!API || !state || !code) {
+      setError("Retorno do SSO inválido.");
+      return;
+    }
+
+    (async () => {
+      try {
+        const res = await fetch(`${API}/auth/sso/callback`, {
+          method: "POST",
+          headers: { "Content-Type": "application/json" },
+          body: JSON.stringify({ state, code }),
+        });
+        const txt = await res.text();
+        if (!res.ok) throw new Error(txt || "Falha no SSO");
+
+        const data = JSON.parse(txt) as {
+          tenant_id: string;
+          token: string;
+          school_name: string;
+          school_code: string;
+        };
+        localStorage.setItem("tenant_id", data.tenant_id);
+        localStorage.setItem("token", data.token);
+        localStorage.setItem("school_code", data.school_code);
+        localStorage.setItem("school_name", data.school_name);
+        router.replace("/students");
+      } catch (err: unknown) {
+        setError(err instanceof Error ? err.message : "Erro inesperado");
+      }
+    })();
+  }, [router]);
+
+  return (
+    <div className="min-h-screen bg-neutral-50 px-4 py-10">
+      <div className="mx-auto max-w-md rounded-2xl border border-neutral-200 bg-white p-6 shadow">
+        {error ? (
+          <>
+            <div className="rounded-2xl border border-red-200 bg-red-50 p-3 text-sm text-red-700">{error}</div>
+            <a className="mt-4 inline-block text-sm font-semibold text-black hover:underline" href="/login">
+              Voltar ao login
+            </a>
+          </>
+        ) : (
+          <p className="text-sm text-neutral-600">Concluindo login pela sua conta institucional...</p>
+        )}
+      </div>
+    </div>
+  );
+}